@@ -1,7 +1,15 @@
 //! Cache cleanup via the `clean` command and shared orphan detection helpers.
 //!
 //! Removes orphaned repo-cache directories under `storage/.repos` and stale
-//! temporary discovery checkouts under the system temp directory.
+//! temporary discovery checkouts under the system temp directory. Caches
+//! still pinned by a recorded generation are not orphans. Sparse cache
+//! entries that still check out subpaths of removed skills are narrowed to
+//! the subpaths the config references, since syncs only widen them.
+//!
+//! The shared object stores under `storage/.objects` back the repo-cache
+//! worktrees: a store is removed once none of its worktrees is left, and
+//! otherwise garbage-collected so objects only removed entries needed
+//! are dropped.
 
 use std::collections::HashSet;
 use std::fs;
@@ -9,8 +17,10 @@ use std::path::{Path, PathBuf};
 
 use eden_skills_core::config::{config_dir_from_path, Config};
 use eden_skills_core::error::EdenError;
+use eden_skills_core::generations::generation_repo_cache_keys;
+use eden_skills_core::git;
 use eden_skills_core::paths::resolve_path_string;
-use eden_skills_core::source::repo_cache_key;
+use eden_skills_core::source::{narrow_sparse_checkouts, repo_cache_key, OBJECT_STORE_DIR};
 
 use super::common::{load_config_with_context, print_warning, remove_path, resolve_config_path};
 use super::CommandOptions;
@@ -22,19 +32,23 @@ pub(crate) const DISCOVERY_TEMP_DIR_PREFIX: &str = "eden-skills-discovery-";
 pub(crate) struct CleanReport {
     pub(crate) dry_run: bool,
     pub(crate) removed_cache_entries: Vec<String>,
+    pub(crate) narrowed_cache_entries: Vec<String>,
     pub(crate) removed_discovery_dirs: Vec<String>,
     pub(crate) freed_bytes: u64,
 }
 
 impl CleanReport {
     pub(crate) fn is_empty(&self) -> bool {
-        self.removed_cache_entries.is_empty() && self.removed_discovery_dirs.is_empty()
+        self.removed_cache_entries.is_empty()
+            && self.narrowed_cache_entries.is_empty()
+            && self.removed_discovery_dirs.is_empty()
     }
 
     pub(crate) fn nested_json_value(&self) -> serde_json::Value {
         serde_json::json!({
             "dry_run": self.dry_run,
             "removed_cache_entries": self.removed_cache_entries,
+            "narrowed_cache_entries": self.narrowed_cache_entries,
             "removed_discovery_dirs": self.removed_discovery_dirs,
             "freed_bytes": self.freed_bytes,
         })
@@ -45,6 +59,7 @@ impl CleanReport {
             "action": "clean",
             "dry_run": self.dry_run,
             "removed_cache_entries": self.removed_cache_entries,
+            "narrowed_cache_entries": self.narrowed_cache_entries,
             "removed_discovery_dirs": self.removed_discovery_dirs,
             "freed_bytes": self.freed_bytes,
         })
@@ -60,7 +75,11 @@ impl CleanReport {
 pub fn clean(config_path: &str, dry_run: bool, options: CommandOptions) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
@@ -90,6 +109,12 @@ pub(crate) fn clean_with_loaded_config(
         ..CleanReport::default()
     };
 
+    let object_stores = collect_object_stores(&storage_root)?;
+    let removed_worktrees = orphan_cache_entries
+        .iter()
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect::<HashSet<_>>();
+
     for path in orphan_cache_entries {
         report.freed_bytes += path_size_bytes(&path)?;
         if !dry_run {
@@ -100,6 +125,47 @@ pub(crate) fn clean_with_loaded_config(
             .push(path.display().to_string());
     }
 
+    let narrowable =
+        narrow_sparse_checkouts(config, &storage_root, true).map_err(EdenError::Runtime)?;
+    if !narrowable.is_empty() {
+        let size_before = narrowable
+            .iter()
+            .map(|path| path_size_bytes(path))
+            .sum::<Result<u64, _>>()?;
+        if !dry_run {
+            narrow_sparse_checkouts(config, &storage_root, false).map_err(EdenError::Runtime)?;
+            let size_after = narrowable
+                .iter()
+                .map(|path| path_size_bytes(path))
+                .sum::<Result<u64, _>>()?;
+            report.freed_bytes += size_before.saturating_sub(size_after);
+        }
+        report
+            .narrowed_cache_entries
+            .extend(narrowable.iter().map(|path| path.display().to_string()));
+    }
+
+    for store in object_stores {
+        let in_use = git::store_worktrees(&store).iter().any(|worktree| {
+            fs::canonicalize(worktree).is_ok_and(|worktree| !removed_worktrees.contains(&worktree))
+        });
+        if in_use {
+            if !dry_run {
+                let size_before = path_size_bytes(&store)?;
+                git::gc_object_store(&store).map_err(EdenError::Runtime)?;
+                report.freed_bytes += size_before.saturating_sub(path_size_bytes(&store)?);
+            }
+            continue;
+        }
+        report.freed_bytes += path_size_bytes(&store)?;
+        if !dry_run {
+            remove_clean_path(&store)?;
+        }
+        report
+            .removed_cache_entries
+            .push(store.display().to_string());
+    }
+
     for path in stale_discovery_dirs {
         report.freed_bytes += path_size_bytes(&path)?;
         if !dry_run {
@@ -117,7 +183,8 @@ pub(crate) fn collect_orphan_repo_cache_entries(
     config: &Config,
     storage_root: &Path,
 ) -> Result<Vec<PathBuf>, EdenError> {
-    let referenced = referenced_repo_cache_keys(config);
+    let mut referenced = referenced_repo_cache_keys(config);
+    referenced.extend(generation_repo_cache_keys(storage_root)?);
     let repo_cache_root = storage_root.join(".repos");
     let mut orphans = Vec::new();
     match fs::read_dir(&repo_cache_root) {
@@ -140,6 +207,18 @@ pub(crate) fn collect_orphan_repo_cache_entries(
     Ok(orphans)
 }
 
+fn collect_object_stores(storage_root: &Path) -> Result<Vec<PathBuf>, EdenError> {
+    let mut stores = match fs::read_dir(storage_root.join(OBJECT_STORE_DIR)) {
+        Ok(entries) => entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(EdenError::Io(err)),
+    };
+    stores.sort();
+    Ok(stores)
+}
+
 pub(crate) fn orphan_cache_target_path(path: &Path) -> String {
     let file_name = path.file_name().map_or_else(
         || path.display().to_string(),
@@ -181,6 +260,15 @@ fn print_clean_apply_summary(ui: &UiContext, report: &CleanReport) {
             "orphaned cache entries removed",
         );
     }
+    if !report.narrowed_cache_entries.is_empty() {
+        print_clean_count_line(
+            ui,
+            &mut first_line,
+            report.narrowed_cache_entries.len(),
+            "sparse cache entry narrowed",
+            "sparse cache entries narrowed",
+        );
+    }
     if !report.removed_discovery_dirs.is_empty() {
         print_clean_count_line(
             ui,
@@ -204,6 +292,7 @@ fn print_clean_dry_run_summary(ui: &UiContext, report: &CleanReport) {
         print_clean_dry_run_heading(
             ui,
             &mut first_line,
+            "remove",
             report.removed_cache_entries.len(),
             "orphaned cache entry",
             "orphaned cache entries",
@@ -212,6 +301,22 @@ fn print_clean_dry_run_summary(ui: &UiContext, report: &CleanReport) {
             println!("           {}", ui.styled_path(path));
         }
     }
+    if !report.narrowed_cache_entries.is_empty() {
+        if !first_line {
+            println!();
+        }
+        print_clean_dry_run_heading(
+            ui,
+            &mut first_line,
+            "narrow",
+            report.narrowed_cache_entries.len(),
+            "sparse cache entry",
+            "sparse cache entries",
+        );
+        for path in &report.narrowed_cache_entries {
+            println!("           {}", ui.styled_path(path));
+        }
+    }
     if !report.removed_discovery_dirs.is_empty() {
         if !first_line {
             println!();
@@ -219,6 +324,7 @@ fn print_clean_dry_run_summary(ui: &UiContext, report: &CleanReport) {
         print_clean_dry_run_heading(
             ui,
             &mut first_line,
+            "remove",
             report.removed_discovery_dirs.len(),
             "stale discovery directory",
             "stale discovery directories",
@@ -254,6 +360,7 @@ fn print_clean_count_line(
 fn print_clean_dry_run_heading(
     ui: &UiContext,
     first_line: &mut bool,
+    verb: &str,
     count: usize,
     singular: &str,
     plural: &str,
@@ -265,7 +372,7 @@ fn print_clean_dry_run_heading(
         "          ".to_string()
     };
     let label = if count == 1 { singular } else { plural };
-    println!("{prefix}would remove {count} {label}:");
+    println!("{prefix}would {verb} {count} {label}:");
 }
 
 fn print_clean_json(report: &CleanReport) -> Result<(), EdenError> {
@@ -4,33 +4,59 @@
 //! checks, plan execution, lock file writes, output formatting helpers,
 //! and registry-mode skill resolution used across multiple commands.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::ui::{abbreviate_home_path, UiContext};
+use comfy_table::{Cell, Table};
 use eden_skills_core::config::{
-    decode_registry_mode_repo, is_registry_mode_repo, load_from_file, LoadOptions, LoadedConfig,
-    SourceConfig,
+    decode_registry_mode_repo, is_registry_mode_repo, load_from_file, load_layered_from_files,
+    load_user_layer, LoadOptions, LoadedConfig, SourceConfig,
 };
-use eden_skills_core::config::{AgentKind, Config, InstallMode, SkillConfig, TargetConfig};
+use eden_skills_core::config::{
+    AgentKind, Config, GitConfig, InstallMode, ReactorConfig, SkillConfig, TargetConfig,
+    TargetScope,
+};
+use eden_skills_core::config_layers::{merge_config_layers, ConfigLayer};
 use eden_skills_core::error::EdenError;
-use eden_skills_core::lock::{build_lock_from_config, lock_path_for_config, write_lock_file};
+use eden_skills_core::generations::record_generation;
+use eden_skills_core::git::{self, git_bin, GitBackend};
+use eden_skills_core::lock::{
+    build_lock_from_config, carry_implicit_entries, carry_resolved_versions, lock_path_for_config,
+    read_lock_file, record_content_digests, write_lock_file, DigestRefresh, LockFile, LOCK_VERSION,
+};
 use eden_skills_core::paths::resolve_path_string;
 use eden_skills_core::plan::PlanItem;
 use eden_skills_core::reactor::{MAX_CONCURRENCY_LIMIT, MIN_CONCURRENCY_LIMIT};
 use eden_skills_core::registry::{
-    parse_registry_specs_from_toml, resolve_skill_from_registry_sources,
-    sort_registry_specs_by_priority, RegistrySource,
+    parse_registry_specs_from_toml, resolve_skill_from_registry_sources, resolve_skill_set,
+    sort_registry_specs_by_priority, RegistrySource, SkillRequirement,
+    SUPPORTED_INDEX_FORMAT_VERSIONS,
 };
 use eden_skills_core::safety::{LicenseStatus, SkillSafetyReport};
-use eden_skills_core::source::SyncSummary;
+use eden_skills_core::source::{
+    resolve_skill_storage_root, SyncFailure, SyncFailureStage, SyncSummary,
+};
 use owo_colors::OwoColorize;
+use toml_edit::DocumentMut;
+
+use super::config_edit::edit_config_document;
 
 pub(crate) const REGISTRY_SYNC_MARKER_FILE: &str = ".eden-last-sync";
 
+/// `user_config_path` when it should be merged beneath `config_path`:
+/// it exists and is not `config_path` itself.
+pub(crate) fn user_config_layer_for(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+) -> Option<PathBuf> {
+    let layer = user_config_path?;
+    (layer != config_path && layer.is_file()).then(|| layer.to_path_buf())
+}
+
 /// Resolve a possibly-relative or tilde-prefixed config path against `cwd`.
 ///
 /// # Errors
@@ -48,7 +74,9 @@ pub(crate) fn with_hint(message: impl Into<String>, hint: impl Into<String>) ->
 /// Load and parse `skills.toml`, wrapping common I/O errors with
 /// user-friendly messages and hints (e.g. "Run `eden-skills init`").
 ///
-/// In strict mode, config warnings are promoted to errors.
+/// In strict mode, config warnings are promoted to errors. When
+/// `user_config_path` applies (see [`user_config_layer_for`]), the config
+/// is merged over it.
 ///
 /// # Errors
 ///
@@ -57,8 +85,14 @@ pub(crate) fn with_hint(message: impl Into<String>, hint: impl Into<String>) ->
 pub(crate) fn load_config_with_context(
     config_path: &Path,
     strict: bool,
+    user_config_path: Option<&Path>,
 ) -> Result<LoadedConfig, EdenError> {
-    match load_from_file(config_path, LoadOptions { strict }) {
+    let options = LoadOptions { strict };
+    let loaded = match user_config_layer_for(config_path, user_config_path) {
+        Some(user_config_path) => load_layered_from_files(&user_config_path, config_path, options),
+        None => load_from_file(config_path, options),
+    };
+    match loaded {
         Ok(loaded) => Ok(loaded),
         Err(EdenError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
             let display_path = abbreviate_home_path(&config_path.display().to_string());
@@ -78,19 +112,17 @@ pub(crate) fn load_config_with_context(
     }
 }
 
-pub(crate) fn git_bin() -> String {
-    std::env::var("EDEN_SKILLS_GIT_BIN")
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| "git".to_string())
-}
-
 /// Verify that `git` is available on `$PATH` before operations that require it.
 ///
+/// Skipped for the embedded backend, which needs no `git` binary.
+///
 /// # Errors
 ///
 /// Returns [`EdenError::Runtime`] with an install hint when git is absent.
-pub(crate) fn ensure_git_available() -> Result<(), EdenError> {
+pub(crate) fn ensure_git_available(backend: GitBackend) -> Result<(), EdenError> {
+    if backend == GitBackend::Embedded {
+        return Ok(());
+    }
     let git = git_bin();
     match Command::new(&git).arg("--version").output() {
         Ok(output) if output.status.success() => Ok(()),
@@ -104,7 +136,7 @@ pub(crate) fn ensure_git_available() -> Result<(), EdenError> {
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
             Err(EdenError::Runtime(with_hint(
                 "git executable not found",
-                "Install Git: https://git-scm.com/downloads",
+                "Install Git: https://git-scm.com/downloads, or set `backend = \"embedded\"` under `[git]` in the config.",
             )))
         }
         Err(err) => Err(EdenError::Runtime(with_hint(
@@ -196,23 +228,17 @@ where
     runtime.block_on(future)
 }
 
-pub(crate) fn run_git_command(command: &mut Command, context: &str) -> Result<String, String> {
-    let output = command
-        .output()
-        .map_err(|err| format!("git invocation failed while trying to {context}: {err}"))?;
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if output.status.success() {
-        return Ok(stdout);
-    }
-    Err(format!(
-        "git command failed while trying to {context}: status={} stderr=`{}` stdout=`{}`",
-        output.status, stderr, stdout
-    ))
-}
-
 pub(crate) fn extract_git_clone_failure_reason(stderr: &str) -> &str {
     let lower = stderr.to_ascii_lowercase();
+    if lower.contains("network access is disabled") {
+        return "network access is disabled";
+    }
+    if lower.contains("was not found in the remote") {
+        return "locked commit not found";
+    }
+    if lower.contains("no version matched") || lower.contains("no semver tags found") {
+        return "no matching version tag";
+    }
     if lower.contains("repository not found") {
         return "repository not found";
     }
@@ -260,28 +286,32 @@ pub(crate) fn git_clone_failure_hint(reason: &str, repo_url: &str) -> String {
             "Check that the branch, tag, or commit exists in the repository.".to_string()
         }
         "SSL certificate error" => "Check your system's SSL/TLS certificates.".to_string(),
+        "untrusted signature" => {
+            "Check that the commit or its tag is signed by a key in `git.allowed_signers` or `git.gpg_home`."
+                .to_string()
+        }
+        "no matching version tag" => {
+            "Check that `version` matches a tag of the repository (e.g. `v1.2.0`).".to_string()
+        }
+        "network access is disabled" => {
+            "Run once with network access (without --offline or --frozen) to populate the cache."
+                .to_string()
+        }
+        "checksum mismatch" => {
+            "Check that `sha256` matches the checksum published for the archive.".to_string()
+        }
+        "archive download failed" => "Check that the archive URL is reachable.".to_string(),
+        "archive extraction failed" => {
+            "Archives must not contain links or entries outside their root directory.".to_string()
+        }
+        "locked commit not found" => {
+            "Run `eden-skills apply` without --locked to re-resolve refs and update skills.lock."
+                .to_string()
+        }
         _ => format!("Run `git clone {repo_url}` manually to diagnose the issue."),
     }
 }
 
-pub(crate) fn read_head_sha(repo_dir: &Path) -> Option<String> {
-    let stdout = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("rev-parse")
-            .arg("HEAD"),
-        &format!("read HEAD for `{}`", repo_dir.display()),
-    )
-    .ok()?;
-    let sha = stdout.lines().next()?.trim();
-    if sha.is_empty() {
-        None
-    } else {
-        Some(sha.to_string())
-    }
-}
-
 pub(crate) fn agent_kind_label(agent: &AgentKind) -> &'static str {
     agent.as_str()
 }
@@ -295,6 +325,7 @@ pub(crate) fn parse_target_specs(specs: &[String]) -> Result<Vec<TargetConfig>,
                 expected_path: None,
                 path: None,
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
             });
             continue;
         }
@@ -310,6 +341,7 @@ pub(crate) fn parse_target_specs(specs: &[String]) -> Result<Vec<TargetConfig>,
                 expected_path: None,
                 path: Some(rest.to_string()),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
             });
             continue;
         }
@@ -321,6 +353,14 @@ pub(crate) fn parse_target_specs(specs: &[String]) -> Result<Vec<TargetConfig>,
     Ok(targets)
 }
 
+/// Append a cell to the most recently added row (optional trailing columns).
+pub(crate) fn push_last_row_cell(table: &mut Table, content: String) {
+    let last = table.row_count().saturating_sub(1);
+    if let Some(row) = table.row_mut(last) {
+        row.add_cell(Cell::new(content));
+    }
+}
+
 pub(crate) fn format_quoted_ids(ids: &[String]) -> String {
     ids.iter()
         .map(|id| format!("'{id}'"))
@@ -360,7 +400,43 @@ pub(crate) fn print_source_sync_summary_human(ui: &UiContext, summary: &SyncSumm
     );
 }
 
-pub(crate) fn source_sync_failure_error(summary: &SyncSummary) -> Option<EdenError> {
+/// Fail with [`source_sync_failure_error`] when a source did not sync. In
+/// `--json` mode the failures are first printed as a JSON payload on
+/// stdout.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Runtime`] listing every failed source.
+pub(crate) fn check_source_sync(
+    summary: &SyncSummary,
+    action: &str,
+    json: bool,
+) -> Result<(), EdenError> {
+    let Some(err) = source_sync_failure_error(summary) else {
+        return Ok(());
+    };
+    if json {
+        let payload = serde_json::json!({
+            "action": action,
+            "status": "failed",
+            "sync_failures": summary.failures.iter().map(|failure| {
+                serde_json::json!({
+                    "skill_id": failure.skill_id,
+                    "stage": failure.stage.as_str(),
+                    "repo_dir": failure.repo_dir,
+                    "detail": failure.detail,
+                    "attempts": failure.attempts,
+                })
+            }).collect::<Vec<_>>(),
+        });
+        let encoded = serde_json::to_string_pretty(&payload)
+            .map_err(|err| EdenError::Runtime(format!("failed to encode {action} json: {err}")))?;
+        println!("{encoded}");
+    }
+    Err(err)
+}
+
+fn source_sync_failure_error(summary: &SyncSummary) -> Option<EdenError> {
     if summary.failed == 0 {
         return None;
     }
@@ -369,9 +445,14 @@ pub(crate) fn source_sync_failure_error(summary: &SyncSummary) -> Option<EdenErr
         .failures
         .iter()
         .map(|failure| {
-            let reason = extract_git_clone_failure_reason(&failure.detail);
+            let reason = sync_failure_reason(failure);
+            let attempts = if failure.attempts > 1 {
+                format!(", failed after {} attempts", failure.attempts)
+            } else {
+                String::new()
+            };
             format!(
-                "'{}' ({} — {reason})",
+                "'{}' ({} — {reason}{attempts})",
                 failure.skill_id,
                 failure.stage.as_str()
             )
@@ -382,10 +463,7 @@ pub(crate) fn source_sync_failure_error(summary: &SyncSummary) -> Option<EdenErr
     let hint = summary
         .failures
         .first()
-        .map(|f| {
-            let reason = extract_git_clone_failure_reason(&f.detail);
-            git_clone_failure_hint(reason, &f.repo_dir)
-        })
+        .map(|f| git_clone_failure_hint(sync_failure_reason(f), &f.repo_dir))
         .unwrap_or_default();
 
     Some(EdenError::Runtime(format!(
@@ -398,6 +476,19 @@ pub(crate) fn source_sync_failure_error(summary: &SyncSummary) -> Option<EdenErr
     )))
 }
 
+fn sync_failure_reason(failure: &SyncFailure) -> &str {
+    match failure.stage {
+        SyncFailureStage::Verify => "checksum mismatch",
+        SyncFailureStage::Signature => "untrusted signature",
+        SyncFailureStage::Extract => "archive extraction failed",
+        SyncFailureStage::Download => match extract_git_clone_failure_reason(&failure.detail) {
+            "git clone failed" => "archive download failed",
+            reason => reason,
+        },
+        _ => extract_git_clone_failure_reason(&failure.detail),
+    }
+}
+
 pub(crate) fn print_safety_summary_human(ui: &UiContext, reports: &[SkillSafetyReport]) {
     let permissive = reports
         .iter()
@@ -429,6 +520,15 @@ pub(crate) fn print_warning(ui: &UiContext, warning: &str) {
     eprintln!("  {prefix} {warning}");
 }
 
+pub(crate) fn print_note(ui: &UiContext, note: &str) {
+    let prefix = if ui.colors_enabled() {
+        "note:".blue().bold().to_string()
+    } else {
+        "note:".to_string()
+    };
+    eprintln!("  {prefix} {note}");
+}
+
 pub(crate) fn style_count_for_action(ui: &UiContext, action: &str, count: usize) -> String {
     let style = match action {
         "create" => CountStyle::GreenIfNonZero,
@@ -466,40 +566,53 @@ fn style_count(ui: &UiContext, count: usize, style: CountStyle) -> String {
     }
 }
 
+/// Replace registry-mode sources with the git sources their index entries
+/// point at, and append the registry skills pulled in through `requires`
+/// as implicit skills.
 pub(crate) fn resolve_registry_mode_skills_for_execution(
     config_path: &Path,
+    user_config_path: Option<&Path>,
     config: &Config,
     config_dir: &Path,
     ui: &UiContext,
+) -> Result<Config, EdenError> {
+    resolve_skill_subset_for_execution(
+        config_path,
+        user_config_path,
+        config,
+        config,
+        config_dir,
+        ui,
+    )
+}
+
+/// [`resolve_registry_mode_skills_for_execution`] for `config`, a subset of
+/// the skills in `configured`. Requirements on configured skills outside
+/// the subset count as satisfied rather than becoming implicit skills.
+pub(crate) fn resolve_skill_subset_for_execution(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+    config: &Config,
+    configured: &Config,
+    config_dir: &Path,
+    ui: &UiContext,
 ) -> Result<Config, EdenError> {
     if !config
         .skills
         .iter()
-        .any(|skill| is_registry_mode_repo(&skill.source.repo))
+        .any(|skill| is_registry_mode_repo(&skill.source.repo) || !skill.requires.is_empty())
     {
         return Ok(config.clone());
     }
 
-    let raw_toml = fs::read_to_string(config_path)?;
-    let sorted_specs = sort_registry_specs_by_priority(
-        &parse_registry_specs_from_toml(&raw_toml).map_err(EdenError::from)?,
-    );
-    if sorted_specs.is_empty() {
+    let registry_sources =
+        configured_registry_sources(config_path, user_config_path, config, config_dir)?;
+    if registry_sources.is_empty() {
         return Err(EdenError::Runtime(
             "Registry index not found. Run `eden-skills update` first.".to_string(),
         ));
     }
-
     let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
-    let registries_root = storage_root.join("registries");
-    let registry_sources = sorted_specs
-        .into_iter()
-        .map(|spec| RegistrySource {
-            name: spec.name.clone(),
-            priority: spec.priority,
-            root: registries_root.join(spec.name),
-        })
-        .collect::<Vec<_>>();
 
     let mut resolved = config.clone();
     for skill in &mut resolved.skills {
@@ -560,13 +673,181 @@ pub(crate) fn resolve_registry_mode_skills_for_execution(
             repo: resolved_skill.repo,
             subpath: resolved_skill.subpath,
             r#ref: resolved_skill.git_ref,
+            verify_signature: skill.source.verify_signature,
         };
+        for (name, constraint) in resolved_skill.requires {
+            skill.requires.entry(name).or_insert(constraint);
+        }
     }
 
+    if resolved
+        .skills
+        .iter()
+        .any(|skill| !skill.requires.is_empty())
+    {
+        expand_skill_dependencies(&mut resolved, config, configured, &registry_sources)?;
+    }
     Ok(resolved)
 }
 
-fn validate_registry_manifest_for_resolution(
+/// Resolve the dependency set of `config` as a whole and fold it into
+/// `resolved`: configured registry skills move to the version picked for
+/// the set, and every other required skill is appended as an implicit skill
+/// installed like the first configured skill that needs it.
+fn expand_skill_dependencies(
+    resolved: &mut Config,
+    config: &Config,
+    configured: &Config,
+    registry_sources: &[RegistrySource],
+) -> Result<(), EdenError> {
+    let mut requirements = Vec::new();
+    let mut provided = configured
+        .skills
+        .iter()
+        .filter(|skill| !config.skills.iter().any(|own| own.id == skill.id))
+        .map(|skill| skill.id.clone())
+        .collect::<BTreeSet<_>>();
+    for skill in &config.skills {
+        if is_registry_mode_repo(&skill.source.repo) {
+            requirements.push(SkillRequirement {
+                name: skill.id.clone(),
+                constraint: skill.source.r#ref.clone(),
+                required_by: None,
+                registry: decode_registry_mode_repo(&skill.source.repo)
+                    .unwrap_or(None)
+                    .filter(|name| !name.is_empty()),
+            });
+        } else {
+            provided.insert(skill.id.clone());
+        }
+        for (name, constraint) in &skill.requires {
+            requirements.push(SkillRequirement {
+                name: name.clone(),
+                constraint: constraint.clone(),
+                required_by: Some(skill.id.clone()),
+                registry: None,
+            });
+        }
+    }
+
+    let dependencies = resolve_skill_set(registry_sources, &requirements, &provided)
+        .map_err(|err| EdenError::Runtime(format!("dependency resolution failed: {err}")))?;
+    let mut implicit = Vec::new();
+    for dependency in dependencies {
+        let source = SourceConfig {
+            repo: dependency.skill.repo,
+            subpath: dependency.skill.subpath,
+            r#ref: dependency.skill.git_ref,
+            verify_signature: false,
+        };
+        match resolved
+            .skills
+            .iter_mut()
+            .find(|skill| skill.id == dependency.name)
+        {
+            Some(skill) => {
+                skill.source = SourceConfig {
+                    verify_signature: skill.source.verify_signature,
+                    ..source
+                };
+                for (name, constraint) in dependency.skill.requires {
+                    skill.requires.entry(name).or_insert(constraint);
+                }
+            }
+            None => implicit.push((dependency.name, source, dependency.skill.requires)),
+        }
+    }
+
+    let mut edges = resolved
+        .skills
+        .iter()
+        .map(|skill| (skill.id.as_str(), &skill.requires))
+        .collect::<BTreeMap<_, _>>();
+    edges.extend(
+        implicit
+            .iter()
+            .map(|(name, _, requires)| (name.as_str(), requires)),
+    );
+    let anchors = implicit
+        .iter()
+        .filter_map(|(name, _, _)| {
+            let anchor = resolved
+                .skills
+                .iter()
+                .find(|skill| requires_transitively(&edges, &skill.id, name))?;
+            Some((name.clone(), anchor.clone()))
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (name, source, requires) in implicit {
+        let Some(anchor) = anchors.get(&name) else {
+            continue;
+        };
+        resolved.skills.push(SkillConfig {
+            id: name,
+            source,
+            install: anchor.install,
+            targets: anchor.targets.clone(),
+            verify: anchor.verify.clone(),
+            safety: anchor.safety,
+            requires,
+            implicit: true,
+        });
+    }
+    Ok(())
+}
+
+fn requires_transitively(
+    edges: &BTreeMap<&str, &BTreeMap<String, String>>,
+    from: &str,
+    target: &str,
+) -> bool {
+    let mut seen = HashSet::new();
+    let mut pending = vec![from];
+    while let Some(id) = pending.pop() {
+        let Some(requires) = edges.get(id) else {
+            continue;
+        };
+        for name in requires.keys() {
+            if name == target {
+                return true;
+            }
+            if seen.insert(name.as_str()) {
+                pending.push(name.as_str());
+            }
+        }
+    }
+    false
+}
+
+/// The registries configured for `config_path`, highest priority first,
+/// each rooted at its cache under `<storage_root>/registries/`. The caches
+/// may not have been synced yet.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the `[registries]` table is malformed.
+pub(crate) fn configured_registry_sources(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+    config: &Config,
+    config_dir: &Path,
+) -> Result<Vec<RegistrySource>, EdenError> {
+    let raw_toml = read_effective_config_toml(config_path, user_config_path)?;
+    let sorted_specs = sort_registry_specs_by_priority(
+        &parse_registry_specs_from_toml(&raw_toml).map_err(EdenError::from)?,
+    );
+    let registries_root = resolve_path_string(&config.storage_root, config_dir)?.join("registries");
+    Ok(sorted_specs
+        .into_iter()
+        .map(|spec| RegistrySource {
+            name: spec.name.clone(),
+            priority: spec.priority,
+            root: registries_root.join(spec.name),
+        })
+        .collect())
+}
+
+pub(crate) fn validate_registry_manifest_for_resolution(
     source: &RegistrySource,
     ui: &UiContext,
 ) -> Result<(), EdenError> {
@@ -599,21 +880,88 @@ fn validate_registry_manifest_for_resolution(
             source.name
         )));
     };
-    if format_version != 1 {
+    if !SUPPORTED_INDEX_FORMAT_VERSIONS.contains(&format_version) {
         print_warning(
             ui,
             &format!(
-                "registry `{}` manifest format_version={} (expected 1); continuing",
-                source.name, format_version
+                "registry `{}` manifest format_version={} is not supported (expected {}); continuing",
+                source.name,
+                format_version,
+                supported_index_format_versions()
             ),
         );
     }
     Ok(())
 }
 
-pub(crate) fn write_normalized_config(path: &Path, config: &Config) -> Result<(), EdenError> {
-    let registries = read_existing_registries(path)?;
-    let toml = normalized_config_toml(config, registries.as_ref());
+/// [`SUPPORTED_INDEX_FORMAT_VERSIONS`] as `1 or 2` for messages.
+pub(crate) fn supported_index_format_versions() -> String {
+    SUPPORTED_INDEX_FORMAT_VERSIONS
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Read the config TOML that registry lookups should see: the merged
+/// layers when `user_config_path` applies, otherwise the file itself.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when a layer cannot be read or parsed.
+pub(crate) fn read_effective_config_toml(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+) -> Result<String, EdenError> {
+    let Some(user_config_path) = user_config_layer_for(config_path, user_config_path) else {
+        return Ok(fs::read_to_string(config_path)?);
+    };
+    let parse = |path: &Path| -> Result<toml::Value, EdenError> {
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| EdenError::Validation(format!("root: invalid toml: {err}")))
+    };
+    let user_dir = user_config_path.parent().unwrap_or(Path::new("."));
+    let merged = merge_config_layers(parse(&user_config_path)?, user_dir, parse(config_path)?);
+    toml::to_string(&merged.value)
+        .map_err(|err| EdenError::Runtime(format!("failed to serialize layered config: {err}")))
+}
+
+/// Write `config` back to `path`.
+///
+/// An existing file is edited in place (see [`edit_config_document`]) so
+/// comments, ordering, and untouched entries survive; a missing or
+/// unparsable file is rendered in normalized form. When `path` is a
+/// project layer over `user_config_path`, only the settings and skills that differ from the user
+/// layer are written, so user-global definitions are not copied into the
+/// project file.
+pub(crate) fn write_normalized_config(
+    path: &Path,
+    config: &Config,
+    user_config_path: Option<&Path>,
+) -> Result<(), EdenError> {
+    let user_layer = user_config_layer_for(path, user_config_path)
+        .and_then(|user_config_path| load_user_layer(&user_config_path, path).ok());
+    let existing = match fs::read_to_string(path) {
+        Ok(raw) => raw.parse::<DocumentMut>().ok(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(EdenError::Io(err)),
+    };
+    let edited = existing.and_then(|mut doc| {
+        edit_config_document(&mut doc, config, user_layer.as_ref())?;
+        Some(doc.to_string())
+    });
+    let toml = match edited {
+        Some(toml) => toml,
+        None => {
+            let registries = read_existing_registries(path)?;
+            match user_layer {
+                Some(user_layer) => {
+                    project_layer_config_toml(config, &user_layer, registries.as_ref())
+                }
+                None => normalized_config_toml(config, registries.as_ref()),
+            }
+        }
+    };
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
@@ -628,28 +976,142 @@ pub(crate) fn normalized_config_toml(
     let mut out = String::new();
 
     out.push_str(&format!("version = {}\n\n", config.version));
+    push_storage_toml(&mut out, config);
+    if config.reactor != ReactorConfig::default() {
+        push_reactor_toml(&mut out, config, &ReactorConfig::default());
+    }
+    if config.git != GitConfig::default() {
+        push_git_toml(&mut out, config);
+    }
+    if !config.mirrors.is_empty() {
+        push_mirrors_toml(&mut out, config);
+    }
+    push_registries_toml(&mut out, registries);
+
+    for skill in &config.skills {
+        out.push_str(&normalized_skill_toml(skill));
+        out.push('\n');
+    }
+
+    out
+}
+
+fn project_layer_config_toml(
+    config: &Config,
+    user_layer: &Config,
+    registries: Option<&BTreeMap<String, ExistingRegistryConfig>>,
+) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("version = {}\n\n", config.version));
+    if config.storage_root != user_layer.storage_root {
+        push_storage_toml(&mut out, config);
+    }
+    if config.reactor != user_layer.reactor {
+        push_reactor_toml(&mut out, config, &user_layer.reactor);
+    }
+    if config.git != user_layer.git {
+        push_git_toml(&mut out, config);
+    }
+    if config.mirrors != user_layer.mirrors {
+        push_mirrors_toml(&mut out, config);
+    }
+    push_registries_toml(&mut out, registries);
+
+    for skill in &config.skills {
+        if user_layer.skills.contains(skill) {
+            continue;
+        }
+        out.push_str(&normalized_skill_toml(skill));
+        out.push('\n');
+    }
+
+    out
+}
+
+fn push_storage_toml(out: &mut String, config: &Config) {
     out.push_str("[storage]\n");
     out.push_str(&format!(
         "root = \"{}\"\n\n",
         toml_escape_str(&config.storage_root)
     ));
-    if config.reactor.concurrency != eden_skills_core::reactor::DEFAULT_CONCURRENCY_LIMIT {
-        out.push_str("[reactor]\n");
-        out.push_str(&format!("concurrency = {}\n\n", config.reactor.concurrency));
+}
+
+/// `concurrency` is always written; the other keys only when they differ
+/// from `base`.
+fn push_reactor_toml(out: &mut String, config: &Config, base: &ReactorConfig) {
+    out.push_str("[reactor]\n");
+    let base_fields = reactor_toml_fields(base);
+    for (index, (key, value)) in reactor_toml_fields(&config.reactor).into_iter().enumerate() {
+        if index == 0 || value != base_fields[index].1 {
+            out.push_str(&format!("{key} = {value}\n"));
+        }
     }
+    out.push('\n');
+}
+
+/// The `[reactor]` keys of `reactor` in file order, `concurrency` first.
+pub(crate) fn reactor_toml_fields(reactor: &ReactorConfig) -> [(&'static str, toml::Value); 5] {
+    let backoff = reactor.backoff;
+    let backoff = if backoff.subsec_nanos() == 0 {
+        toml::Value::Integer(backoff.as_secs() as i64)
+    } else {
+        toml::Value::Float(backoff.as_secs_f64())
+    };
+    [
+        (
+            "concurrency",
+            toml::Value::Integer(reactor.concurrency as i64),
+        ),
+        ("retries", toml::Value::Integer(i64::from(reactor.retries))),
+        ("backoff", backoff),
+        (
+            "timeout_secs",
+            toml::Value::Integer(reactor.timeout_secs as i64),
+        ),
+        (
+            "host_concurrency",
+            toml::Value::Integer(reactor.host_concurrency as i64),
+        ),
+    ]
+}
+
+fn push_git_toml(out: &mut String, config: &Config) {
+    out.push_str("[git]\n");
+    out.push_str(&format!("backend = \"{}\"\n", config.git.backend.as_str()));
+    for (key, path) in [
+        ("allowed_signers", &config.git.allowed_signers),
+        ("gpg_home", &config.git.gpg_home),
+    ] {
+        if let Some(path) = path {
+            out.push_str(&format!("{key} = \"{}\"\n", toml_escape_str(path)));
+        }
+    }
+    out.push('\n');
+}
+
+fn push_mirrors_toml(out: &mut String, config: &Config) {
+    out.push_str("[mirrors]\n");
+    for rule in &config.mirrors {
+        out.push_str(&format!(
+            "\"{}\" = \"{}\"\n",
+            toml_escape_str(&rule.prefix),
+            toml_escape_str(&rule.replacement)
+        ));
+    }
+    out.push('\n');
+}
+
+fn push_registries_toml(
+    out: &mut String,
+    registries: Option<&BTreeMap<String, ExistingRegistryConfig>>,
+) {
     if let Some(registries) = registries {
         if !registries.is_empty() {
             out.push_str(&render_registries_toml(registries));
             out.push('\n');
         }
     }
-
-    for skill in &config.skills {
-        out.push_str(&normalized_skill_toml(skill));
-        out.push('\n');
-    }
-
-    out
 }
 
 pub(crate) fn read_existing_registries(
@@ -703,7 +1165,7 @@ fn render_registries_toml(registries: &BTreeMap<String, ExistingRegistryConfig>)
     out
 }
 
-fn normalized_skill_toml(skill: &SkillConfig) -> String {
+pub(crate) fn normalized_skill_toml(skill: &SkillConfig) -> String {
     let mut out = String::new();
 
     out.push_str("[[skills]]\n");
@@ -719,9 +1181,27 @@ fn normalized_skill_toml(skill: &SkillConfig) -> String {
                 toml_escape_str(&registry_name)
             ));
         }
+        push_requires_toml(&mut out, skill);
+        out.push('\n');
+    } else if let Some(sha256) = skill.source.archive_sha256() {
+        out.push_str(&format!("id = \"{}\"\n", toml_escape_str(&skill.id)));
+        push_requires_toml(&mut out, skill);
         out.push('\n');
+
+        out.push_str("[skills.source]\n");
+        out.push_str(&format!(
+            "archive = \"{}\"\n",
+            toml_escape_str(&skill.source.repo)
+        ));
+        out.push_str(&format!("sha256 = \"{}\"\n", toml_escape_str(sha256)));
+        out.push_str(&format!(
+            "subpath = \"{}\"\n\n",
+            toml_escape_str(&skill.source.subpath)
+        ));
     } else {
-        out.push_str(&format!("id = \"{}\"\n\n", toml_escape_str(&skill.id)));
+        out.push_str(&format!("id = \"{}\"\n", toml_escape_str(&skill.id)));
+        push_requires_toml(&mut out, skill);
+        out.push('\n');
 
         out.push_str("[skills.source]\n");
         out.push_str(&format!(
@@ -732,10 +1212,19 @@ fn normalized_skill_toml(skill: &SkillConfig) -> String {
             "subpath = \"{}\"\n",
             toml_escape_str(&skill.source.subpath)
         ));
-        out.push_str(&format!(
-            "ref = \"{}\"\n\n",
-            toml_escape_str(&skill.source.r#ref)
-        ));
+        match skill.source.version_constraint() {
+            Some(constraint) => {
+                out.push_str(&format!("version = \"{}\"\n", toml_escape_str(constraint)))
+            }
+            None => out.push_str(&format!(
+                "ref = \"{}\"\n",
+                toml_escape_str(&skill.source.r#ref)
+            )),
+        }
+        if skill.source.verify_signature {
+            out.push_str("verify_signature = true\n");
+        }
+        out.push('\n');
     }
 
     out.push_str("[skills.install]\n");
@@ -772,6 +1261,29 @@ fn normalized_skill_toml(skill: &SkillConfig) -> String {
     out
 }
 
+/// `requires = { name = "constraint", ... }`, when the skill has any.
+fn push_requires_toml(out: &mut String, skill: &SkillConfig) {
+    if skill.requires.is_empty() {
+        return;
+    }
+    let entries = skill
+        .requires
+        .iter()
+        .map(|(name, constraint)| {
+            let bare = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            let key = if bare {
+                name.clone()
+            } else {
+                format!("\"{}\"", toml_escape_str(name))
+            };
+            format!("{key} = \"{}\"", toml_escape_str(constraint))
+        })
+        .collect::<Vec<_>>();
+    out.push_str(&format!("requires = {{ {} }}\n", entries.join(", ")));
+}
+
 fn normalized_target_toml(target: &TargetConfig) -> String {
     let mut out = String::new();
     out.push_str("[[skills.targets]]\n");
@@ -794,6 +1306,9 @@ fn normalized_target_toml(target: &TargetConfig) -> String {
             toml_escape_str(&target.environment)
         ));
     }
+    if !target.scope.is_global() {
+        out.push_str(&format!("scope = \"{}\"\n", target.scope.as_str()));
+    }
     out
 }
 
@@ -801,42 +1316,346 @@ fn toml_escape_str(value: &str) -> String {
     value.replace('\\', "\\\\").replace('\"', "\\\"")
 }
 
+/// Rebuild `skills.lock` from `config`.
+///
+/// `refresh` selects the skills that were just installed and whose
+/// content digests are recomputed; all other digests are carried over
+/// from the previous lock. See [`write_lock_for_config_with_commits`]
+/// for layered configs.
 pub(crate) fn write_lock_for_config(
     config_path: &Path,
+    user_config_path: Option<&Path>,
     config: &Config,
     config_dir: &Path,
+    refresh: DigestRefresh<'_>,
 ) -> Result<(), EdenError> {
-    write_lock_for_config_with_commits(config_path, config, config_dir, &HashMap::new())
+    write_lock_for_config_with_commits(
+        config_path,
+        user_config_path,
+        config,
+        config_dir,
+        &HashMap::new(),
+        refresh,
+    )
+}
+
+/// The user-global config and the ids of the skills only it defines,
+/// when `config_path` is a project layer over `user_config_path`.
+fn user_layer_skill_ids(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+) -> Result<Option<(PathBuf, HashSet<String>)>, EdenError> {
+    let Some(user_config_path) = user_config_layer_for(config_path, user_config_path) else {
+        return Ok(None);
+    };
+    let loaded = load_layered_from_files(
+        &user_config_path,
+        config_path,
+        LoadOptions { strict: false },
+    )?;
+    let ids = loaded
+        .skill_layers
+        .into_iter()
+        .filter(|(_, layer)| *layer == ConfigLayer::User)
+        .map(|(id, _)| id)
+        .collect();
+    Ok(Some((user_config_path, ids)))
+}
+
+/// Read the lock `config_path` is reconciled against.
+///
+/// For a project layer, skills only the user layer defines are locked in
+/// the user config's `skills.lock`, so their entries come from there and
+/// the project lock supplies the rest.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when either lock or the layered config cannot be
+/// read.
+pub(crate) fn read_lock_for_config(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+) -> Result<Option<LockFile>, EdenError> {
+    let lock = read_lock_file(&lock_path_for_config(config_path))?;
+    let Some((user_config_path, user_ids)) = user_layer_skill_ids(config_path, user_config_path)?
+    else {
+        return Ok(lock);
+    };
+    let user_entries = read_lock_file(&lock_path_for_config(&user_config_path))?
+        .map(|lock| lock.skills)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| user_ids.contains(&entry.id))
+        .collect::<Vec<_>>();
+    if lock.is_none() && user_entries.is_empty() {
+        return Ok(None);
+    }
+    let mut lock = lock.unwrap_or(LockFile {
+        version: LOCK_VERSION,
+        skills: Vec::new(),
+    });
+    lock.skills.retain(|entry| !user_ids.contains(&entry.id));
+    lock.skills.splice(0..0, user_entries);
+    Ok(Some(lock))
+}
+
+/// Map each skill id to the `HEAD` commit of its source checkout, for
+/// git skills whose checkout exists.
+pub(crate) fn collect_resolved_commits(
+    config: &Config,
+    config_dir: &Path,
+) -> HashMap<String, String> {
+    let storage_root = match resolve_path_string(&config.storage_root, config_dir) {
+        Ok(path) => path,
+        Err(_) => return HashMap::new(),
+    };
+    let mut commits = HashMap::new();
+    // Archive sources are pinned by the checksum in their ref instead.
+    for skill in config
+        .skills
+        .iter()
+        .filter(|skill| !skill.source.is_archive())
+    {
+        let repo_dir = resolve_skill_storage_root(&storage_root, skill);
+        if let Some(sha) = git::rev_parse(config.git.backend, &repo_dir, "HEAD") {
+            commits.insert(skill.id.clone(), sha);
+        }
+    }
+    commits
 }
 
+/// [`write_lock_for_config`] with the commits each skill resolved to.
+///
+/// When `config_path` is a project layer over `user_config_path`, the
+/// project lock only holds the skills the project file defines; skills
+/// only the user layer defines go to the user config's lock instead.
 pub(crate) fn write_lock_for_config_with_commits(
     config_path: &Path,
+    user_config_path: Option<&Path>,
     config: &Config,
     config_dir: &Path,
     resolved_commits: &HashMap<String, String>,
+    refresh: DigestRefresh<'_>,
 ) -> Result<(), EdenError> {
-    let lock_path = lock_path_for_config(config_path);
-    let lock = build_lock_from_config(config, config_dir, resolved_commits)?;
-    write_lock_file(&lock_path, &lock)
+    let project_lock_path = lock_path_for_config(config_path);
+    let Some((user_config_path, user_ids)) = user_layer_skill_ids(config_path, user_config_path)?
+    else {
+        return write_layer_lock(
+            &project_lock_path,
+            config,
+            config_dir,
+            resolved_commits,
+            refresh,
+        );
+    };
+    let (user_skills, project_skills): (Vec<_>, Vec<_>) = config
+        .skills
+        .iter()
+        .cloned()
+        .partition(|skill| user_ids.contains(&skill.id));
+    let user_lock_path = lock_path_for_config(&user_config_path);
+    if !user_skills.is_empty() || user_lock_path.exists() {
+        let user_config = Config {
+            skills: user_skills,
+            ..config.clone()
+        };
+        write_layer_lock(
+            &user_lock_path,
+            &user_config,
+            config_dir,
+            resolved_commits,
+            refresh,
+        )?;
+    }
+    let project_config = Config {
+        skills: project_skills,
+        ..config.clone()
+    };
+    write_layer_lock(
+        &project_lock_path,
+        &project_config,
+        config_dir,
+        resolved_commits,
+        refresh,
+    )
 }
 
-pub(crate) fn apply_plan_item(item: &PlanItem) -> Result<(), EdenError> {
-    let source_path = PathBuf::from(&item.source_path);
-    let target_path = PathBuf::from(&item.target_path);
+fn write_layer_lock(
+    lock_path: &Path,
+    config: &Config,
+    config_dir: &Path,
+    resolved_commits: &HashMap<String, String>,
+    refresh: DigestRefresh<'_>,
+) -> Result<(), EdenError> {
+    // Read quietly: callers have already reported a corrupted lock.
+    let previous = fs::read_to_string(lock_path)
+        .ok()
+        .and_then(|raw| toml::from_str::<LockFile>(&raw).ok());
+    let mut lock = build_lock_from_config(config, config_dir, resolved_commits)?;
+    carry_resolved_versions(&mut lock, previous.as_ref());
+    carry_implicit_entries(&mut lock, previous.as_ref());
+    record_content_digests(&mut lock, config, config_dir, previous.as_ref(), refresh)?;
+    write_lock_file(lock_path, &lock)
+}
 
-    if !source_path.exists() {
-        return Err(EdenError::Runtime(format!(
-            "source path missing for skill `{}`: {}",
-            item.skill_id, item.source_path
-        )));
+/// Record the config file and `skills.lock` just written for
+/// `config_path` as a new generation under the storage root.
+///
+/// Does nothing when no lock exists, e.g. after an install that left no
+/// skills configured.
+pub(crate) fn record_generation_for_config(
+    config_path: &Path,
+    config: &Config,
+    config_dir: &Path,
+    command: &str,
+) -> Result<(), EdenError> {
+    let Some(lock) = read_lock_file(&lock_path_for_config(config_path))? else {
+        return Ok(());
+    };
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
+    let config_text = fs::read_to_string(config_path)?;
+    record_generation(
+        &storage_root,
+        config_path,
+        command,
+        &config_text,
+        &lock,
+        config.git.backend,
+    )?;
+    Ok(())
+}
+
+/// A batch of target installs that either all land or are all undone.
+///
+/// Each target is first built at a hidden sibling staging path and then
+/// renamed into place, so an agent never sees a half-populated skill. A
+/// target being replaced is renamed aside as a backup rather than
+/// deleted. [`TargetTransaction::commit`] discards the backups; dropping
+/// the transaction without committing (for example when a later target
+/// fails and the caller returns early) restores every target it already
+/// changed, in reverse order.
+#[derive(Debug, Default)]
+pub(crate) struct TargetTransaction {
+    applied: Vec<AppliedTarget>,
+}
+
+#[derive(Debug)]
+struct AppliedTarget {
+    target_path: PathBuf,
+    backup_path: Option<PathBuf>,
+}
+
+impl TargetTransaction {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage `item` next to its target and swap it into place.
+    pub(crate) fn apply(&mut self, item: &PlanItem) -> Result<(), EdenError> {
+        self.install(
+            &item.skill_id,
+            Path::new(&item.source_path),
+            Path::new(&item.target_path),
+            item.install_mode,
+        )
+    }
+
+    /// Stage `source_path` next to `target_path` and swap it into place.
+    pub(crate) fn install(
+        &mut self,
+        skill_id: &str,
+        source_path: &Path,
+        target_path: &Path,
+        install_mode: InstallMode,
+    ) -> Result<(), EdenError> {
+        let source_path = source_path.to_path_buf();
+        let target_path = target_path.to_path_buf();
+
+        if !source_path.exists() {
+            return Err(EdenError::Runtime(format!(
+                "source path missing for skill `{skill_id}`: {}",
+                source_path.display()
+            )));
+        }
+
+        ensure_parent_dir(&target_path)?;
+        let staging_path = sibling_path(&target_path, "eden-staging");
+        if fs::symlink_metadata(&staging_path).is_ok() {
+            remove_path(&staging_path)?;
+        }
+        let staged = match install_mode {
+            InstallMode::Symlink => apply_symlink(&source_path, &staging_path),
+            InstallMode::Copy => copy_recursively(&source_path, &staging_path),
+        };
+        if let Err(err) = staged {
+            let _ = remove_path(&staging_path);
+            return Err(err);
+        }
+
+        let backup_path = if fs::symlink_metadata(&target_path).is_ok() {
+            let backup_path = sibling_path(&target_path, "eden-backup");
+            if fs::symlink_metadata(&backup_path).is_ok() {
+                remove_path(&backup_path)?;
+            }
+            if let Err(err) = fs::rename(&target_path, &backup_path) {
+                let _ = remove_path(&staging_path);
+                return Err(EdenError::Io(err));
+            }
+            Some(backup_path)
+        } else {
+            None
+        };
+
+        if let Err(err) = fs::rename(&staging_path, &target_path) {
+            let _ = remove_path(&staging_path);
+            if let Some(backup_path) = &backup_path {
+                let _ = fs::rename(backup_path, &target_path);
+            }
+            return Err(EdenError::Io(err));
+        }
+
+        self.applied.push(AppliedTarget {
+            target_path,
+            backup_path,
+        });
+        Ok(())
     }
 
-    match item.install_mode {
-        InstallMode::Symlink => apply_symlink(&source_path, &target_path),
-        InstallMode::Copy => apply_copy(&source_path, &target_path),
+    /// Keep every applied target and discard the backups of replaced ones.
+    pub(crate) fn commit(mut self) {
+        for applied in self.applied.drain(..) {
+            if let Some(backup_path) = applied.backup_path {
+                let _ = remove_path(&backup_path);
+            }
+        }
+    }
+
+    fn rollback(&mut self) {
+        while let Some(applied) = self.applied.pop() {
+            let _ = remove_path(&applied.target_path);
+            if let Some(backup_path) = applied.backup_path {
+                let _ = fs::rename(&backup_path, &applied.target_path);
+            }
+        }
     }
 }
 
+impl Drop for TargetTransaction {
+    fn drop(&mut self) {
+        self.rollback();
+    }
+}
+
+/// Hidden sibling of `target_path` used while swapping it, e.g.
+/// `.demo-skill.eden-staging` next to `demo-skill`.
+fn sibling_path(target_path: &Path, suffix: &str) -> PathBuf {
+    let name = target_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target_path.with_file_name(format!(".{name}.{suffix}"))
+}
+
 pub(crate) fn path_is_symlink_or_junction(path: &Path, metadata: &fs::Metadata) -> bool {
     #[cfg(windows)]
     {
@@ -851,11 +1670,6 @@ pub(crate) fn path_is_symlink_or_junction(path: &Path, metadata: &fs::Metadata)
 }
 
 fn apply_symlink(source_path: &Path, target_path: &Path) -> Result<(), EdenError> {
-    ensure_parent_dir(target_path)?;
-    if fs::symlink_metadata(target_path).is_ok() {
-        remove_path(target_path)?;
-    }
-
     #[cfg(unix)]
     {
         std::os::unix::fs::symlink(source_path, target_path)?;
@@ -919,14 +1733,6 @@ fn map_windows_symlink_error(
     EdenError::Io(err)
 }
 
-fn apply_copy(source_path: &Path, target_path: &Path) -> Result<(), EdenError> {
-    ensure_parent_dir(target_path)?;
-    if fs::symlink_metadata(target_path).is_ok() {
-        remove_path(target_path)?;
-    }
-    copy_recursively(source_path, target_path)
-}
-
 pub(crate) fn ensure_parent_dir(path: &Path) -> Result<(), EdenError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -0,0 +1,725 @@
+//! Format-preserving edits to an existing `skills.toml`.
+//!
+//! Mutating commands (`add`, `set`, `install`, `remove`) sync the parsed
+//! document towards the desired [`Config`] instead of re-rendering it, so
+//! comments, blank lines, key order, and untouched `[[skills]]` entries
+//! survive. A field is written only when its effective value — explicit,
+//! inherited from the user layer, or defaulted — differs from the desired
+//! one. New skills are appended in the normalized layout.
+
+use std::collections::{BTreeMap, HashSet};
+
+use eden_skills_core::config::{
+    decode_registry_mode_repo, default_verify_checks_for_mode, Config, ReactorConfig, SkillConfig,
+    TargetConfig, DEFAULT_STORAGE_ROOT,
+};
+use eden_skills_core::git::GitBackend;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+use super::common::{agent_kind_label, normalized_skill_toml, reactor_toml_fields};
+
+/// Sync `doc` towards `config`.
+///
+/// `user_layer` is the user-global layer when `doc` is a project config:
+/// absent fields inherit from it, and skills it already defines unchanged
+/// are not copied into the document.
+///
+/// Returns `None` when the document has a shape this editor does not
+/// handle (e.g. an inline `skills = [...]` array); callers then fall back
+/// to a normalized rewrite.
+pub(crate) fn edit_config_document(
+    doc: &mut DocumentMut,
+    config: &Config,
+    user_layer: Option<&Config>,
+) -> Option<()> {
+    let root = doc.as_table_mut();
+    sync_field(
+        root,
+        &["version"],
+        &toml::Value::Integer(i64::from(config.version)),
+        None,
+    )?;
+
+    let storage_root = user_layer.map_or(DEFAULT_STORAGE_ROOT, |layer| layer.storage_root.as_str());
+    sync_field(
+        root,
+        &["storage", "root"],
+        &toml::Value::String(config.storage_root.clone()),
+        Some(&toml::Value::String(storage_root.to_string())),
+    )?;
+
+    let reactor = user_layer.map_or(ReactorConfig::default(), |layer| layer.reactor);
+    for ((key, desired), (_, inherited)) in reactor_toml_fields(&config.reactor)
+        .into_iter()
+        .zip(reactor_toml_fields(&reactor))
+    {
+        sync_field(root, &["reactor", key], &desired, Some(&inherited))?;
+    }
+
+    let backend = user_layer.map_or(GitBackend::default(), |layer| layer.git.backend);
+    sync_field(
+        root,
+        &["git", "backend"],
+        &toml::Value::String(config.git.backend.as_str().to_string()),
+        Some(&toml::Value::String(backend.as_str().to_string())),
+    )?;
+    sync_mirrors(root, config, user_layer)?;
+    // Newly created top-level sections go right after the root keys
+    // rather than after the last `[[skills]]` table.
+    for section in ["storage", "reactor", "git", "mirrors"] {
+        if let Some(Item::Table(table)) = root.get_mut(section) {
+            if table.position().is_none() {
+                table.set_position(0);
+            }
+        }
+    }
+
+    sync_skills(root, config, user_layer)
+}
+
+/// Sync `[mirrors]` rule by rule. Rules only the user layer defines
+/// cannot be dropped from a project layer and are left alone.
+fn sync_mirrors(root: &mut Table, config: &Config, user_layer: Option<&Config>) -> Option<()> {
+    let inherited = user_layer.map_or(&[][..], |layer| layer.mirrors.as_slice());
+    if let Some(section) = root.get_mut("mirrors").and_then(Item::as_table_like_mut) {
+        let stale = section
+            .iter()
+            .map(|(prefix, _)| prefix.to_string())
+            .filter(|prefix| !config.mirrors.iter().any(|rule| rule.prefix == *prefix))
+            .collect::<Vec<_>>();
+        for prefix in &stale {
+            section.remove(prefix);
+        }
+        if !stale.is_empty() && section.is_empty() {
+            root.remove("mirrors");
+        }
+    }
+    for rule in &config.mirrors {
+        let base = inherited
+            .iter()
+            .find(|base| base.prefix == rule.prefix)
+            .map(|base| toml::Value::String(base.replacement.clone()));
+        sync_field(
+            root,
+            &["mirrors", &rule.prefix],
+            &toml::Value::String(rule.replacement.clone()),
+            base.as_ref(),
+        )?;
+    }
+    Some(())
+}
+
+fn sync_skills(root: &mut Table, config: &Config, user_layer: Option<&Config>) -> Option<()> {
+    let mut next_position = max_position(root) + 1;
+    let mut entries: Vec<Table> = match root.remove("skills") {
+        Some(Item::ArrayOfTables(entries)) => entries.into_iter().collect(),
+        Some(other) => {
+            root.insert("skills", other);
+            return None;
+        }
+        None => Vec::new(),
+    };
+
+    let desired_ids = config
+        .skills
+        .iter()
+        .map(|skill| skill.id.as_str())
+        .collect::<HashSet<_>>();
+    entries.retain(|entry| entry_key(entry).is_none_or(|key| desired_ids.contains(key)));
+
+    for skill in &config.skills {
+        let base = user_layer.and_then(|layer| {
+            layer
+                .skills
+                .iter()
+                .find(|candidate| candidate.id == skill.id)
+        });
+        let existing = entries
+            .iter()
+            .position(|entry| entry_key(entry) == Some(skill.id.as_str()));
+        match existing {
+            Some(index)
+                if entry_is_registry_mode(&entries[index])
+                    == decode_registry_mode_repo(&skill.source.repo).is_some()
+                    && entry_is_archive_source(&entries[index])
+                        .is_none_or(|archive| archive == skill.source.is_archive()) =>
+            {
+                sync_skill_entry(&mut entries[index], skill, base)?;
+            }
+            Some(index) => {
+                // Switching between Mode A and Mode B, or between git and
+                // archive sources: replace in place.
+                let position = entries[index].position().unwrap_or(next_position);
+                let prefix = entries[index].decor().prefix().cloned();
+                let mut replacement = rendered_skill_entry(skill, position)?;
+                if let Some(prefix) = prefix {
+                    replacement.decor_mut().set_prefix(prefix);
+                }
+                entries[index] = replacement;
+            }
+            None if base == Some(skill) => {}
+            None => {
+                entries.push(rendered_skill_entry(skill, next_position)?);
+                next_position += 1;
+            }
+        }
+    }
+
+    if !entries.is_empty() {
+        root.insert(
+            "skills",
+            Item::ArrayOfTables(entries.into_iter().collect::<ArrayOfTables>()),
+        );
+    }
+    Some(())
+}
+
+fn sync_skill_entry(
+    entry: &mut Table,
+    skill: &SkillConfig,
+    base: Option<&SkillConfig>,
+) -> Option<()> {
+    let string = |value: &str| toml::Value::String(value.to_string());
+
+    if let Some(registry) = decode_registry_mode_repo(&skill.source.repo) {
+        let version = base.map_or("*", |base| base.source.r#ref.as_str());
+        sync_field(
+            entry,
+            &["version"],
+            &string(&skill.source.r#ref),
+            Some(&string(version)),
+        )?;
+        let inherited =
+            base.and_then(|base| decode_registry_mode_repo(&base.source.repo).flatten());
+        let current = entry
+            .get("registry")
+            .and_then(Item::as_str)
+            .map(str::to_string)
+            .or(inherited);
+        match registry {
+            Some(name) if current.as_deref() != Some(name.as_str()) => {
+                set_value(entry, "registry", Value::from(name));
+            }
+            None if current.is_some() => {
+                entry.remove("registry");
+            }
+            _ => {}
+        }
+    } else {
+        // A base source of the other kind is replaced, not inherited from.
+        let source = base
+            .map(|base| &base.source)
+            .filter(|source| source.is_archive() == skill.source.is_archive());
+        if let Some(sha256) = skill.source.archive_sha256() {
+            sync_field(
+                entry,
+                &["source", "archive"],
+                &string(&skill.source.repo),
+                source.map(|source| string(&source.repo)).as_ref(),
+            )?;
+            sync_field(
+                entry,
+                &["source", "sha256"],
+                &string(sha256),
+                source
+                    .and_then(|source| source.archive_sha256())
+                    .map(string)
+                    .as_ref(),
+            )?;
+        } else {
+            sync_field(
+                entry,
+                &["source", "repo"],
+                &string(&skill.source.repo),
+                source.map(|source| string(&source.repo)).as_ref(),
+            )?;
+        }
+        sync_field(
+            entry,
+            &["source", "subpath"],
+            &string(&skill.source.subpath),
+            Some(&string(
+                source.map_or(".", |source| source.subpath.as_str()),
+            )),
+        )?;
+        if !skill.source.is_archive() {
+            // `ref` and `version` are mutually exclusive; drop the other.
+            let (key, other, desired, inherited) = match skill.source.version_constraint() {
+                Some(constraint) => (
+                    "version",
+                    "ref",
+                    constraint,
+                    source.and_then(|source| source.version_constraint()),
+                ),
+                None => (
+                    "ref",
+                    "version",
+                    skill.source.r#ref.as_str(),
+                    match source {
+                        Some(source) if source.version_constraint().is_some() => None,
+                        Some(source) => Some(source.r#ref.as_str()),
+                        None => Some("main"),
+                    },
+                ),
+            };
+            if let Some(section) = entry.get_mut("source").and_then(Item::as_table_like_mut) {
+                section.remove(other);
+            }
+            sync_field(
+                entry,
+                &["source", key],
+                &string(desired),
+                inherited.map(string).as_ref(),
+            )?;
+            sync_field(
+                entry,
+                &["source", "verify_signature"],
+                &toml::Value::Boolean(skill.source.verify_signature),
+                Some(&toml::Value::Boolean(
+                    source.is_some_and(|source| source.verify_signature),
+                )),
+            )?;
+        }
+    }
+
+    sync_requires(entry, &skill.requires, base.map(|base| &base.requires))?;
+
+    let mode = base.map_or("symlink", |base| base.install.mode.as_str());
+    sync_field(
+        entry,
+        &["install", "mode"],
+        &string(skill.install.mode.as_str()),
+        Some(&string(mode)),
+    )?;
+
+    sync_targets(
+        entry,
+        &skill.targets,
+        base.map(|base| base.targets.as_slice()),
+    )?;
+
+    let enabled = base.is_none_or(|base| base.verify.enabled);
+    sync_field(
+        entry,
+        &["verify", "enabled"],
+        &toml::Value::Boolean(skill.verify.enabled),
+        Some(&toml::Value::Boolean(enabled)),
+    )?;
+    let checks = base.map_or_else(
+        || default_verify_checks_for_mode(skill.install.mode),
+        |base| base.verify.checks.clone(),
+    );
+    sync_field(
+        entry,
+        &["verify", "checks"],
+        &string_array(&skill.verify.checks),
+        Some(&string_array(&checks)),
+    )?;
+
+    let no_exec = base.is_some_and(|base| base.safety.no_exec_metadata_only);
+    sync_field(
+        entry,
+        &["safety", "no_exec_metadata_only"],
+        &toml::Value::Boolean(skill.safety.no_exec_metadata_only),
+        Some(&toml::Value::Boolean(no_exec)),
+    )
+}
+
+/// Sync a skill's `requires` name by name; a missing table is added
+/// inline, as the normalized layout writes it.
+fn sync_requires(
+    entry: &mut Table,
+    requires: &BTreeMap<String, String>,
+    inherited: Option<&BTreeMap<String, String>>,
+) -> Option<()> {
+    let inherited_constraint = |name: &str| inherited.and_then(|inherited| inherited.get(name));
+    if !entry.contains_key("requires") {
+        let mut table = InlineTable::new();
+        for (name, constraint) in requires {
+            if inherited_constraint(name) != Some(constraint) {
+                table.insert(name, Value::from(constraint.as_str()));
+            }
+        }
+        if !table.is_empty() {
+            table.fmt();
+            set_value(entry, "requires", Value::InlineTable(table));
+        }
+        return Some(());
+    }
+
+    let section = entry.get_mut("requires")?.as_table_like_mut()?;
+    let stale = section
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| !requires.contains_key(name))
+        .collect::<Vec<_>>();
+    for name in &stale {
+        section.remove(name);
+    }
+    for (name, constraint) in requires {
+        let current = section
+            .get(name)
+            .and_then(Item::as_str)
+            .or_else(|| inherited_constraint(name).map(String::as_str));
+        if current != Some(constraint.as_str()) {
+            set_value(section, name, Value::from(constraint.as_str()));
+        }
+    }
+    if !stale.is_empty() && section.is_empty() {
+        entry.remove("requires");
+    }
+    Some(())
+}
+
+/// Rewrite `targets` only when the effective list changed, keeping each
+/// existing target table that is still wanted at the same index.
+fn sync_targets(
+    entry: &mut Table,
+    targets: &[TargetConfig],
+    inherited: Option<&[TargetConfig]>,
+) -> Option<()> {
+    let desired = targets.iter().map(target_fields).collect::<Vec<_>>();
+    let desired_maps = desired
+        .iter()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    let current = match entry.get("targets") {
+        Some(Item::ArrayOfTables(tables)) => Some(
+            tables
+                .iter()
+                .map(|table| current_target_fields(table))
+                .collect::<Vec<_>>(),
+        ),
+        Some(Item::Value(Value::Array(array))) => Some(
+            array
+                .iter()
+                .map(|value| {
+                    value
+                        .as_inline_table()
+                        .map_or_else(BTreeMap::new, |t| current_target_fields(t))
+                })
+                .collect(),
+        ),
+        Some(_) => return None,
+        None => inherited.map(|targets| {
+            targets
+                .iter()
+                .map(|target| {
+                    target_fields(target)
+                        .into_iter()
+                        .map(|(key, value)| (key.to_string(), value))
+                        .collect()
+                })
+                .collect()
+        }),
+    };
+    if current.as_ref() == Some(&desired_maps) {
+        return Some(());
+    }
+
+    let existing = match entry.remove("targets") {
+        Some(Item::ArrayOfTables(tables)) => tables.into_iter().collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    let mut rebuilt = ArrayOfTables::new();
+    for (index, fields) in desired.iter().enumerate() {
+        let kept = existing
+            .get(index)
+            .filter(|table| current_target_fields(*table) == desired_maps[index]);
+        let table = match kept {
+            Some(table) => table.clone(),
+            None => {
+                let mut table = Table::new();
+                table.decor_mut().set_prefix("\n");
+                for (key, value) in fields {
+                    table.insert(key, toml_edit::value(value.as_str()));
+                }
+                table
+            }
+        };
+        rebuilt.push(table);
+    }
+    entry.insert("targets", Item::ArrayOfTables(rebuilt));
+    Some(())
+}
+
+/// Target keys in normalized order, omitting defaulted fields.
+fn target_fields(target: &TargetConfig) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("agent", agent_kind_label(&target.agent).to_string())];
+    if let Some(expected) = &target.expected_path {
+        fields.push(("expected_path", expected.clone()));
+    }
+    if let Some(path) = &target.path {
+        fields.push(("path", path.clone()));
+    }
+    if target.environment != "local" {
+        fields.push(("environment", target.environment.clone()));
+    }
+    if !target.scope.is_global() {
+        fields.push(("scope", target.scope.as_str().to_string()));
+    }
+    fields
+}
+
+fn current_target_fields(table: &dyn TableLike) -> BTreeMap<String, String> {
+    table
+        .iter()
+        .filter_map(|(key, item)| Some((key.to_string(), item.as_str()?.to_string())))
+        .filter(|(key, value)| {
+            !(key == "environment" && value == "local" || key == "scope" && value == "global")
+        })
+        .collect()
+}
+
+fn rendered_skill_entry(skill: &SkillConfig, position: usize) -> Option<Table> {
+    let rendered = normalized_skill_toml(skill).parse::<DocumentMut>().ok()?;
+    let mut entry = rendered
+        .get("skills")?
+        .as_array_of_tables()?
+        .get(0)?
+        .clone();
+    set_positions(&mut entry, position);
+    entry.decor_mut().set_prefix("\n");
+    Some(entry)
+}
+
+fn entry_key(entry: &Table) -> Option<&str> {
+    entry
+        .get("id")
+        .or_else(|| entry.get("name"))
+        .and_then(Item::as_str)
+}
+
+fn entry_is_registry_mode(entry: &Table) -> bool {
+    entry.contains_key("name")
+}
+
+/// Whether the entry's own `[skills.source]` is an archive source, or
+/// `None` when it sets neither `archive` nor `repo` (and inherits one).
+fn entry_is_archive_source(entry: &Table) -> Option<bool> {
+    let source = entry.get("source")?.as_table_like()?;
+    if source.contains_key("archive") {
+        Some(true)
+    } else {
+        source.contains_key("repo").then_some(false)
+    }
+}
+
+/// Set `path` (one or two keys deep) to `desired` unless the effective
+/// value — explicit, else `inherited` — already matches.
+fn sync_field(
+    table: &mut Table,
+    path: &[&str],
+    desired: &toml::Value,
+    inherited: Option<&toml::Value>,
+) -> Option<()> {
+    let (key, parents) = path.split_last()?;
+    let current = table
+        .get(path[0])
+        .and_then(|first| path[1..].iter().try_fold(first, |item, key| item.get(key)))
+        .and_then(plain_value)
+        .or_else(|| inherited.cloned());
+    if current.as_ref() == Some(desired) {
+        return Some(());
+    }
+
+    let target: &mut dyn TableLike = match parents {
+        [] => table,
+        [parent] => {
+            if !table.contains_key(parent) {
+                let mut section = Table::new();
+                section.decor_mut().set_prefix("\n");
+                table.insert(parent, Item::Table(section));
+            }
+            table.get_mut(parent)?.as_table_like_mut()?
+        }
+        _ => return None,
+    };
+    set_value(target, key, edit_value(desired)?);
+    Some(())
+}
+
+/// Replace a value while keeping its surrounding whitespace and comments.
+fn set_value(table: &mut dyn TableLike, key: &str, new: Value) {
+    match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = new;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(key, Item::Value(new));
+        }
+    }
+}
+
+fn plain_value(item: &Item) -> Option<toml::Value> {
+    match item.as_value()? {
+        Value::String(value) => Some(toml::Value::String(value.value().clone())),
+        Value::Integer(value) => Some(toml::Value::Integer(*value.value())),
+        Value::Float(value) => Some(toml::Value::Float(*value.value())),
+        Value::Boolean(value) => Some(toml::Value::Boolean(*value.value())),
+        Value::Array(array) => array
+            .iter()
+            .map(|value| plain_value(&Item::Value(value.clone())))
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        _ => None,
+    }
+}
+
+fn edit_value(value: &toml::Value) -> Option<Value> {
+    match value {
+        toml::Value::String(value) => Some(Value::from(value.as_str())),
+        toml::Value::Integer(value) => Some(Value::from(*value)),
+        toml::Value::Float(value) => Some(Value::from(*value)),
+        toml::Value::Boolean(value) => Some(Value::from(*value)),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(edit_value)
+            .collect::<Option<Array>>()
+            .map(Value::Array),
+        _ => None,
+    }
+}
+
+fn string_array(values: &[String]) -> toml::Value {
+    toml::Value::Array(values.iter().cloned().map(toml::Value::String).collect())
+}
+
+fn max_position(table: &Table) -> usize {
+    let mut max = table.position().unwrap_or(0);
+    for (_, item) in table.iter() {
+        match item {
+            Item::Table(child) => max = max.max(max_position(child)),
+            Item::ArrayOfTables(children) => {
+                for child in children.iter() {
+                    max = max.max(max_position(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    max
+}
+
+fn set_positions(table: &mut Table, position: usize) {
+    table.set_position(position);
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) => set_positions(child, position),
+            Item::ArrayOfTables(children) => {
+                for child in children.iter_mut() {
+                    set_positions(child, position);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use eden_skills_core::config::{load_from_file, LoadOptions};
+    use eden_skills_core::mirrors::MirrorRule;
+    use toml_edit::DocumentMut;
+
+    use super::edit_config_document;
+
+    const DOCUMENT: &str = r#"version = 1
+
+# Internal mirrors
+[mirrors]
+"https://github.com/" = "https://git.internal/github/" # primary
+"https://gitlab.com/" = "https://git.internal/gitlab/"
+
+[registries]
+official = { url = "https://example.com/official.git", priority = 100 }
+
+# Deploy helpers
+[[skills]]
+id = "deploy"
+requires = { k8s = "^1", helm = "^3" } # keep in step
+
+[skills.source]
+repo = "https://example.com/deploy.git"
+
+[[skills.targets]]
+agent = "claude-code"
+"#;
+
+    fn edited(edit: impl FnOnce(&mut eden_skills_core::config::Config)) -> String {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let path = temp.path().join("skills.toml");
+        fs::write(&path, DOCUMENT).expect("write config");
+        let mut config = load_from_file(&path, LoadOptions { strict: false })
+            .expect("load config")
+            .config;
+        edit(&mut config);
+        let mut doc = DOCUMENT.parse::<DocumentMut>().expect("parse config");
+        edit_config_document(&mut doc, &config, None).expect("edit config");
+        doc.to_string()
+    }
+
+    #[test]
+    fn unchanged_config_leaves_mirrors_and_requires_alone() {
+        assert_eq!(edited(|_| {}), DOCUMENT);
+    }
+
+    #[test]
+    fn mirror_edits_keep_comments() {
+        let written = edited(|config| {
+            config.mirrors = vec![
+                MirrorRule {
+                    prefix: "https://bitbucket.org/".to_string(),
+                    replacement: "https://git.internal/bitbucket/".to_string(),
+                },
+                MirrorRule {
+                    prefix: "https://github.com/".to_string(),
+                    replacement: "https://mirror.internal/github/".to_string(),
+                },
+            ];
+        });
+        assert_eq!(
+            written,
+            DOCUMENT.replace(
+                "\"https://github.com/\" = \"https://git.internal/github/\" # primary\n\"https://gitlab.com/\" = \"https://git.internal/gitlab/\"\n",
+                "\"https://github.com/\" = \"https://mirror.internal/github/\" # primary\n\"https://bitbucket.org/\" = \"https://git.internal/bitbucket/\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn requires_edits_keep_comments() {
+        let written = edited(|config| {
+            let requires = &mut config.skills[0].requires;
+            requires.remove("helm");
+            requires.insert("k8s".to_string(), "^2".to_string());
+            requires.insert("kustomize".to_string(), "^5".to_string());
+        });
+        assert_eq!(
+            written,
+            DOCUMENT.replace(
+                "requires = { k8s = \"^1\", helm = \"^3\" } # keep in step",
+                "requires = { k8s = \"^2\", kustomize = \"^5\" } # keep in step",
+            )
+        );
+
+        let written = edited(|config| config.skills[0].requires.clear());
+        assert_eq!(
+            written,
+            DOCUMENT.replace(
+                "requires = { k8s = \"^1\", helm = \"^3\" } # keep in step\n",
+                ""
+            )
+        );
+    }
+}
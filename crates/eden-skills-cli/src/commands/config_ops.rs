@@ -4,7 +4,7 @@
 //! file. None of them perform source sync, plan execution, or file
 //! installation — they only affect the declarative config layer.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 
 use comfy_table::{ColumnConstraint, Width};
@@ -12,6 +12,7 @@ use eden_skills_core::config::SkillConfig;
 use eden_skills_core::config::{
     config_dir_from_path, default_verify_checks_for_mode, validate_config,
 };
+use eden_skills_core::config_layers::ConfigLayer;
 use eden_skills_core::error::EdenError;
 use eden_skills_core::lock::{lock_path_for_config, write_lock_file, LockFile};
 use eden_skills_core::paths::{resolve_path_string, resolve_target_path};
@@ -19,7 +20,8 @@ use owo_colors::OwoColorize;
 
 use super::common::{
     agent_kind_label, load_config_with_context, normalized_config_toml, parse_target_specs,
-    print_warning, read_existing_registries, resolve_config_path, write_normalized_config,
+    print_warning, push_last_row_cell, read_existing_registries, resolve_config_path,
+    write_normalized_config,
 };
 use super::{AddRequest, CommandOptions, SetRequest};
 use crate::ui::{abbreviate_home_path, abbreviate_repo_url, StatusSymbol, UiContext};
@@ -100,10 +102,14 @@ pub(crate) fn default_config_template() -> String {
 pub fn list(config_path: &str, options: CommandOptions) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
-    for warning in loaded.warnings {
-        print_warning(&ui, &warning);
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
     }
 
     let config_dir = config_dir_from_path(config_path);
@@ -124,7 +130,7 @@ pub fn list(config_path: &str, options: CommandOptions) -> Result<(), EdenError>
                     })
                 }).collect::<Vec<_>>();
 
-                serde_json::json!({
+                let mut entry = serde_json::json!({
                     "id": skill.id,
                     "source": {
                         "repo": skill.source.repo,
@@ -139,7 +145,11 @@ pub fn list(config_path: &str, options: CommandOptions) -> Result<(), EdenError>
                         "checks": skill.verify.checks,
                     },
                     "targets": targets,
-                })
+                });
+                if let Some(layer) = loaded.skill_layer(&skill.id) {
+                    entry["layer"] = serde_json::json!(layer.as_str());
+                }
+                entry
             }).collect::<Vec<_>>(),
         });
 
@@ -159,7 +169,12 @@ pub fn list(config_path: &str, options: CommandOptions) -> Result<(), EdenError>
     }
     println!();
 
-    let mut table = ui.table(&["Skill", "Mode", "Source", "Agents"]);
+    let layered = !loaded.skill_layers.is_empty();
+    let mut table = if layered {
+        ui.table(&["Skill", "Mode", "Source", "Agents", "Layer"])
+    } else {
+        ui.table(&["Skill", "Mode", "Source", "Agents"])
+    };
     if let Some(column) = table.column_mut(1) {
         column.set_constraint(ColumnConstraint::LowerBoundary(Width::Fixed(8)));
     }
@@ -172,6 +187,12 @@ pub fn list(config_path: &str, options: CommandOptions) -> Result<(), EdenError>
             ui.styled_cyan(&source),
             render_skill_agents(&ui, skill, &config_dir),
         ]);
+        if layered {
+            let layer = loaded
+                .skill_layer(&skill.id)
+                .map_or("-", ConfigLayer::as_str);
+            push_last_row_cell(&mut table, ui.styled_secondary(layer));
+        }
     }
     println!("{table}");
 
@@ -237,7 +258,11 @@ pub fn add(req: AddRequest) -> Result<(), EdenError> {
     let ui = UiContext::from_env(req.options.json);
     let config_path_buf = resolve_config_path(&req.config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        req.options.strict,
+        req.options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
     }
@@ -265,6 +290,7 @@ pub fn add(req: AddRequest) -> Result<(), EdenError> {
             repo: req.repo.clone(),
             subpath: req.subpath.clone(),
             r#ref: req.r#ref.clone(),
+            verify_signature: false,
         },
         install: eden_skills_core::config::InstallConfig { mode: req.mode },
         targets,
@@ -272,12 +298,18 @@ pub fn add(req: AddRequest) -> Result<(), EdenError> {
         safety: eden_skills_core::config::SafetyConfig {
             no_exec_metadata_only: req.no_exec_metadata_only.unwrap_or(false),
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     };
 
     config.skills.push(skill);
 
     validate_config(&config, &config_dir)?;
-    write_normalized_config(config_path, &config)?;
+    write_normalized_config(
+        config_path,
+        &config,
+        req.options.user_config_path.as_deref(),
+    )?;
 
     if req.options.json {
         let payload = serde_json::json!({
@@ -328,7 +360,11 @@ pub fn set(req: SetRequest) -> Result<(), EdenError> {
     let ui = UiContext::from_env(req.options.json);
     let config_path_buf = resolve_config_path(&req.config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        req.options.strict,
+        req.options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
     }
@@ -369,7 +405,11 @@ pub fn set(req: SetRequest) -> Result<(), EdenError> {
     }
 
     validate_config(&config, &config_dir)?;
-    write_normalized_config(config_path, &config)?;
+    write_normalized_config(
+        config_path,
+        &config,
+        req.options.user_config_path.as_deref(),
+    )?;
 
     if req.options.json {
         let payload = serde_json::json!({
@@ -401,7 +441,11 @@ pub fn config_export(config_path: &str, options: CommandOptions) -> Result<(), E
     let ui = UiContext::from_env(options.json);
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
     }
@@ -443,7 +487,11 @@ pub fn config_import(
     let ui = UiContext::from_env(options.json);
     let cwd = std::env::current_dir().map_err(EdenError::Io)?;
     let from_path = resolve_path_string(from_path, &cwd)?;
-    let loaded = load_config_with_context(&from_path, options.strict)?;
+    let loaded = load_config_with_context(
+        &from_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
     }
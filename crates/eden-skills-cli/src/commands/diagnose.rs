@@ -1,7 +1,10 @@
 //! Health diagnostics via the `doctor` command.
 //!
 //! Collects findings from plan conflicts, verification issues, safety
-//! reports, adapter health checks, and stale registry markers. Renders
+//! reports, content digest drift against `skills.lock`, adapter health
+//! checks, sparse repo caches missing a skill's subpath, unsigned
+//! sources under `verify_signature`, stale registry markers, and registry
+//! indexes in a format this client cannot read. Renders
 //! results as severity-tagged cards in human mode or as a JSON array.
 
 use std::fs;
@@ -10,20 +13,32 @@ use std::process::Command;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use comfy_table::{ColumnConstraint, Width};
-use eden_skills_core::config::{config_dir_from_path, Config};
+use eden_skills_core::config::{config_dir_from_path, Config, InstallMode};
+use eden_skills_core::digest::tree_digest;
 use eden_skills_core::error::EdenError;
+use eden_skills_core::git::verify_head_signature;
+use eden_skills_core::lock::LockFile;
 use eden_skills_core::managed::{ManagedManifest, ManagedSource, MANAGED_MANIFEST_FILE};
 use eden_skills_core::paths::{default_agent_path, normalize_lexical, resolve_path_string};
 use eden_skills_core::plan::{build_plan, Action, PlanItem};
-use eden_skills_core::registry::{parse_registry_specs_from_toml, sort_registry_specs_by_priority};
+use eden_skills_core::registry::{
+    parse_registry_specs_from_toml, read_index_format_version, sort_registry_specs_by_priority,
+    SUPPORTED_INDEX_FORMAT_VERSIONS,
+};
 use eden_skills_core::safety::{analyze_skills, LicenseStatus, SkillSafetyReport};
-use eden_skills_core::verify::{verify_config_state, VerifyIssue};
+use eden_skills_core::source::{
+    is_local_source_repo, repo_cache_contains_subpath, resolve_skill_source_path,
+    resolve_skill_storage_root,
+};
+use eden_skills_core::verify::{verify_config_state_with_lock, VerifyIssue};
 use owo_colors::OwoColorize;
 use serde::Deserialize;
 
 use super::clean::{collect_orphan_repo_cache_entries, orphan_cache_target_path};
 use super::common::{
-    doctor_docker_bin, load_config_with_context, resolve_config_path, REGISTRY_SYNC_MARKER_FILE,
+    configured_registry_sources, doctor_docker_bin, load_config_with_context, print_note,
+    read_effective_config_toml, read_lock_for_config, resolve_config_path,
+    supported_index_format_versions, REGISTRY_SYNC_MARKER_FILE,
 };
 use super::CommandOptions;
 use crate::ui::{StatusSymbol, UiContext};
@@ -56,7 +71,9 @@ pub(crate) struct DoctorFinding {
 ///
 /// Collects findings from plan conflicts, verification issues, safety
 /// reports, Phase 2 adapter health, and stale registry markers. Outputs
-/// severity-tagged cards (human) or a JSON array (`--json`).
+/// severity-tagged cards (human) or a JSON array (`--json`). In offline
+/// mode registry staleness is not checked, since `update` cannot run;
+/// a note says so instead.
 ///
 /// # Errors
 ///
@@ -68,26 +85,45 @@ pub fn doctor(
 ) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     let config_dir = config_dir_from_path(config_path);
     let plan = build_plan(&loaded.config, &config_dir)?;
-    let verify_issues = verify_config_state(&loaded.config, &config_dir)?;
+    let lock = read_lock_for_config(config_path, options.user_config_path.as_deref())?;
+    let verify_issues = verify_config_state_with_lock(&loaded.config, &config_dir, lock.as_ref())?;
     let safety_reports = analyze_skills(&loaded.config, &config_dir)?;
     let mut findings = collect_doctor_findings(&plan, &verify_issues, &safety_reports);
+    if let Some(lock) = &lock {
+        findings.extend(collect_content_digest_findings(
+            &loaded.config,
+            &config_dir,
+            lock,
+        )?);
+    }
+    let mut notes = Vec::new();
     findings.extend(collect_phase2_doctor_findings(
         config_path,
+        options.user_config_path.as_deref(),
         &loaded.config,
         &config_dir,
+        options.offline,
+        &mut notes,
     )?);
     if no_warning {
         findings.retain(|finding| finding.severity != "warning");
     }
 
     if options.json {
-        print_doctor_json(&findings)?;
+        print_doctor_json(&findings, &notes)?;
     } else {
         print_doctor_text(&ui, &findings);
+        for note in &notes {
+            print_note(&ui, note);
+        }
     }
 
     if options.strict && !findings.is_empty() {
@@ -124,21 +160,113 @@ fn collect_doctor_findings(
 
 fn collect_phase2_doctor_findings(
     config_path: &std::path::Path,
+    user_config_path: Option<&std::path::Path>,
     config: &Config,
     config_dir: &std::path::Path,
+    offline: bool,
+    notes: &mut Vec<String>,
 ) -> Result<Vec<DoctorFinding>, EdenError> {
     let mut findings = Vec::new();
     findings.extend(collect_registry_stale_findings(
         config_path,
+        user_config_path,
+        config,
+        config_dir,
+        offline,
+        notes,
+    )?);
+    findings.extend(collect_registry_format_findings(
+        config_path,
+        user_config_path,
         config,
         config_dir,
     )?);
     findings.extend(collect_orphan_cache_findings(config, config_dir)?);
+    findings.extend(collect_sparse_checkout_findings(config, config_dir)?);
+    findings.extend(collect_signature_findings(config, config_dir)?);
     findings.extend(collect_docker_managed_findings(config, config_dir));
     findings.extend(collect_adapter_health_findings(config, config_dir));
     Ok(findings)
 }
 
+/// Compare the digests pinned in `skills.lock` with the content on disk.
+///
+/// Symlink targets share the source digest, so only the cache checkout is
+/// reported for them. Targets of skills that run the `content-hash` verify
+/// check are already covered by the verify findings.
+fn collect_content_digest_findings(
+    config: &Config,
+    config_dir: &Path,
+    lock: &LockFile,
+) -> Result<Vec<DoctorFinding>, EdenError> {
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
+    let mut findings = Vec::new();
+
+    for skill in &config.skills {
+        if skill.safety.no_exec_metadata_only {
+            continue;
+        }
+        let Some(entry) = lock.skills.iter().find(|entry| entry.id == skill.id) else {
+            continue;
+        };
+
+        let source_path = resolve_skill_source_path(&storage_root, skill);
+        if let Some(expected) = entry.content_digest.as_deref() {
+            if digest_drifted(&source_path, expected) {
+                findings.push(DoctorFinding {
+                    code: "SOURCE_DIGEST_MISMATCH".to_string(),
+                    severity: "error".to_string(),
+                    skill_id: skill.id.clone(),
+                    target_path: source_path.display().to_string(),
+                    message: "Cached source content no longer matches the digest in skills.lock"
+                        .to_string(),
+                    remediation:
+                        "Remove the cached checkout and run `eden-skills apply` to fetch it again."
+                            .to_string(),
+                });
+            }
+        }
+
+        let verified_by_check = skill.verify.enabled
+            && skill
+                .verify
+                .checks
+                .iter()
+                .any(|check| check == "content-hash");
+        if matches!(skill.install.mode, InstallMode::Symlink) || verified_by_check {
+            continue;
+        }
+        for target in &entry.targets {
+            let Some(expected) = target.content_digest.as_deref() else {
+                continue;
+            };
+            if target.environment != "local" {
+                continue;
+            }
+            let target_path = target.resolved_path(config_dir);
+            if digest_drifted(&target_path, expected) {
+                findings.push(DoctorFinding {
+                    code: "CONTENT_DIGEST_MISMATCH".to_string(),
+                    severity: "error".to_string(),
+                    skill_id: skill.id.clone(),
+                    target_path: target_path.display().to_string(),
+                    message: "Installed content no longer matches the digest in skills.lock"
+                        .to_string(),
+                    remediation: "Run `eden-skills repair` to restore the installed content."
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Missing paths are reported by the plan and verify findings instead.
+fn digest_drifted(path: &Path, expected: &str) -> bool {
+    path.exists() && tree_digest(path).map_or(true, |actual| actual != expected)
+}
+
 fn collect_orphan_cache_findings(
     config: &Config,
     config_dir: &Path,
@@ -161,18 +289,100 @@ fn collect_orphan_cache_findings(
         .collect())
 }
 
+/// Report skills whose subpath is not part of the sparse checkout of
+/// their repo cache entry, e.g. after the cache was narrowed by hand.
+fn collect_sparse_checkout_findings(
+    config: &Config,
+    config_dir: &Path,
+) -> Result<Vec<DoctorFinding>, EdenError> {
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
+    let mut findings = Vec::new();
+    for skill in &config.skills {
+        if is_local_source_repo(&skill.source.repo) {
+            continue;
+        }
+        let repo_dir = resolve_skill_storage_root(&storage_root, skill);
+        if !repo_dir.join(".git").exists()
+            || repo_cache_contains_subpath(config.git.backend, &repo_dir, &skill.source.subpath)
+        {
+            continue;
+        }
+        findings.push(DoctorFinding {
+            code: "SPARSE_PATH_MISSING".to_string(),
+            severity: "warning".to_string(),
+            skill_id: skill.id.clone(),
+            target_path: repo_dir.display().to_string(),
+            message: format!(
+                "Repo cache does not check out subpath `{}`",
+                skill.source.subpath
+            ),
+            remediation: "Run `eden-skills apply` to widen the sparse checkout.".to_string(),
+        });
+    }
+    Ok(findings)
+}
+
+/// Report `verify_signature` skills whose cached checkout is not signed
+/// by a trusted key, e.g. because it was synced before the policy was
+/// enabled or the trusted keys changed since.
+fn collect_signature_findings(
+    config: &Config,
+    config_dir: &Path,
+) -> Result<Vec<DoctorFinding>, EdenError> {
+    if !config
+        .skills
+        .iter()
+        .any(|skill| skill.source.verify_signature)
+    {
+        return Ok(Vec::new());
+    }
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
+    let trust = config.git.signature_trust(config_dir)?;
+    let mut findings = Vec::new();
+    for skill in &config.skills {
+        if !skill.source.verify_signature {
+            continue;
+        }
+        let repo_dir = resolve_skill_storage_root(&storage_root, skill);
+        if !repo_dir.join(".git").exists() {
+            continue;
+        }
+        let Err(detail) = verify_head_signature(&repo_dir, &trust) else {
+            continue;
+        };
+        findings.push(DoctorFinding {
+            code: "UNSIGNED_SOURCE".to_string(),
+            severity: "error".to_string(),
+            skill_id: skill.id.clone(),
+            target_path: repo_dir.display().to_string(),
+            message: format!("Installed source is not signed by a trusted key: {detail}"),
+            remediation:
+                "Check `git.allowed_signers` / `git.gpg_home`, then run `eden-skills apply`."
+                    .to_string(),
+        });
+    }
+    Ok(findings)
+}
+
 fn collect_registry_stale_findings(
     config_path: &std::path::Path,
+    user_config_path: Option<&std::path::Path>,
     config: &Config,
     config_dir: &std::path::Path,
+    offline: bool,
+    notes: &mut Vec<String>,
 ) -> Result<Vec<DoctorFinding>, EdenError> {
-    let raw_toml = fs::read_to_string(config_path)?;
+    let raw_toml = read_effective_config_toml(config_path, user_config_path)?;
     let registry_specs = sort_registry_specs_by_priority(
         &parse_registry_specs_from_toml(&raw_toml).map_err(EdenError::from)?,
     );
     if registry_specs.is_empty() {
         return Ok(Vec::new());
     }
+    if offline {
+        notes.push("registry staleness checks skipped in offline mode".to_string());
+        return Ok(Vec::new());
+    }
 
     let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
     let registries_root = storage_root.join("registries");
@@ -221,6 +431,38 @@ fn collect_registry_stale_findings(
     Ok(findings)
 }
 
+/// Flag synced registries whose manifest declares an index
+/// `format_version` this client cannot read.
+fn collect_registry_format_findings(
+    config_path: &std::path::Path,
+    user_config_path: Option<&std::path::Path>,
+    config: &Config,
+    config_dir: &std::path::Path,
+) -> Result<Vec<DoctorFinding>, EdenError> {
+    let mut findings = Vec::new();
+    for source in configured_registry_sources(config_path, user_config_path, config, config_dir)? {
+        let problem = match read_index_format_version(&source.root) {
+            Ok(Some(version)) if !SUPPORTED_INDEX_FORMAT_VERSIONS.contains(&version) => format!(
+                "registry `{}` declares index format_version {version}, which this client does not understand (supported: {})",
+                source.name,
+                supported_index_format_versions()
+            ),
+            Ok(_) => continue,
+            Err(err) => format!("registry `{}` has an unreadable manifest: {err}", source.name),
+        };
+        findings.push(DoctorFinding {
+            code: "REGISTRY_FORMAT_UNSUPPORTED".to_string(),
+            severity: "warning".to_string(),
+            skill_id: format!("registry:{}", source.name),
+            target_path: source.root.join("manifest.toml").display().to_string(),
+            message: problem,
+            remediation: "Upgrade eden-skills, or pin the registry to a commit that uses a supported index format."
+                .to_string(),
+        });
+    }
+    Ok(findings)
+}
+
 fn collect_adapter_health_findings(config: &Config, config_dir: &Path) -> Vec<DoctorFinding> {
     let mut findings = Vec::new();
     let docker_bin = doctor_docker_bin();
@@ -769,6 +1011,11 @@ fn map_verify_issue(issue: &VerifyIssue) -> (&'static str, &'static str, &'stati
                 )
             }
         }
+        "content-hash" => (
+            "CONTENT_DIGEST_MISMATCH",
+            "error",
+            "Run `eden-skills repair` to restore the installed content.",
+        ),
         _ => (
             "VERIFY_CHECK_FAILED",
             "error",
@@ -888,7 +1135,7 @@ fn doctor_display_message(ui: &UiContext, finding: &DoctorFinding) -> String {
         .replace(&abbreviated_target, &styled_path)
 }
 
-fn print_doctor_json(findings: &[DoctorFinding]) -> Result<(), EdenError> {
+fn print_doctor_json(findings: &[DoctorFinding], notes: &[String]) -> Result<(), EdenError> {
     let error_count = findings.iter().filter(|f| f.severity == "error").count();
     let warning_count = findings.iter().filter(|f| f.severity == "warning").count();
 
@@ -911,6 +1158,7 @@ fn print_doctor_json(findings: &[DoctorFinding]) -> Result<(), EdenError> {
                 })
             })
             .collect::<Vec<_>>(),
+        "notes": notes,
     });
 
     let encoded = serde_json::to_string_pretty(&payload)
@@ -0,0 +1,235 @@
+//! File-level drift report via the `diff` command and `plan --diff`.
+//!
+//! Compares a skill's cached source with each of its copy-mode targets
+//! and lists added, removed, and modified files, with a unified diff for
+//! modified text files. Docker targets are copied out of the container
+//! through the adapter before comparison.
+
+use std::path::{Path, PathBuf};
+
+use eden_skills_core::adapter::{DockerAdapter, TargetAdapter};
+use eden_skills_core::config::{config_dir_from_path, InstallMode, TargetConfig};
+use eden_skills_core::error::EdenError;
+use eden_skills_core::paths::{normalize_lexical, resolve_path_string, resolve_target_path};
+use eden_skills_core::source::resolve_skill_source_path;
+use eden_skills_core::tree_diff::{diff_trees, FileChange, FileChangeKind};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use super::common::{load_config_with_context, print_warning, resolve_config_path};
+use super::install::resolve_docker_target_root;
+use super::CommandOptions;
+use crate::ui::{StatusSymbol, UiContext};
+
+#[derive(Debug, Serialize)]
+struct TargetDiff {
+    skill_id: String,
+    environment: String,
+    target_path: String,
+    status: &'static str,
+    changes: Vec<FileChange>,
+}
+
+/// Show how each installed copy of a skill differs from its cached source.
+///
+/// Symlink-mode local targets always mirror the source and are reported
+/// as skipped; targets that are not installed yet are reported as
+/// missing.
+///
+/// # Errors
+///
+/// Returns [`EdenError::InvalidArguments`] for an unknown skill ID, and
+/// [`EdenError`] on config load, adapter, or file read failures.
+pub async fn diff_async(
+    config_path: &str,
+    skill_id: &str,
+    options: CommandOptions,
+) -> Result<(), EdenError> {
+    let ui = UiContext::from_env(options.json);
+    let config_path_buf = resolve_config_path(config_path)?;
+    let config_path = config_path_buf.as_path();
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
+    }
+
+    let config_dir = config_dir_from_path(config_path);
+    let Some(skill) = loaded
+        .config
+        .skills
+        .iter()
+        .find(|skill| skill.id == skill_id)
+    else {
+        return Err(EdenError::InvalidArguments(format!(
+            "unknown skill id: `{skill_id}`"
+        )));
+    };
+    let storage_root = resolve_path_string(&loaded.config.storage_root, &config_dir)?;
+    let source_path = resolve_skill_source_path(&storage_root, skill);
+
+    let mut reports = Vec::with_capacity(skill.targets.len());
+    for target in &skill.targets {
+        reports.push(
+            diff_target(
+                &skill.id,
+                skill.install.mode,
+                target,
+                &source_path,
+                &config_dir,
+            )
+            .await?,
+        );
+    }
+
+    if options.json {
+        let payload = serde_json::to_string_pretty(&reports).map_err(|err| {
+            EdenError::Runtime(format!("failed to serialize diff as json: {err}"))
+        })?;
+        println!("{payload}");
+        return Ok(());
+    }
+
+    for report in &reports {
+        print_diff_header(&ui, &report.skill_id, &report.target_path);
+        match report.status {
+            "missing" => println!("           target is not installed"),
+            "symlink" => {
+                println!("           symlink target mirrors the source; nothing to compare")
+            }
+            _ => print_file_changes(&ui, &report.changes),
+        }
+    }
+    Ok(())
+}
+
+async fn diff_target(
+    skill_id: &str,
+    install_mode: InstallMode,
+    target: &TargetConfig,
+    source_path: &Path,
+    config_dir: &Path,
+) -> Result<TargetDiff, EdenError> {
+    let mut report = TargetDiff {
+        skill_id: skill_id.to_string(),
+        environment: target.environment.clone(),
+        target_path: String::new(),
+        status: "identical",
+        changes: Vec::new(),
+    };
+
+    if let Some(container_name) = target.environment.strip_prefix("docker:") {
+        let docker = DockerAdapter::new(container_name).map_err(EdenError::from)?;
+        let target_root = resolve_docker_target_root(target, &docker).await?;
+        let target_path = normalize_lexical(&target_root.join(skill_id));
+        report.target_path = target_path.display().to_string();
+        if !docker
+            .path_exists(&target_path)
+            .await
+            .map_err(EdenError::from)?
+        {
+            report.status = "missing";
+            return Ok(report);
+        }
+
+        let snapshot = snapshot_dir(skill_id);
+        let copied = docker
+            .copy_out(&target_path, &snapshot)
+            .await
+            .map_err(EdenError::from);
+        let changes =
+            copied.and_then(|()| diff_trees(source_path, &snapshot).map_err(EdenError::Io));
+        let _ = std::fs::remove_dir_all(&snapshot);
+        report.changes = changes?;
+    } else {
+        let target_root = resolve_target_path(target, config_dir)?;
+        let target_path = normalize_lexical(&target_root.join(skill_id));
+        report.target_path = target_path.display().to_string();
+        if std::fs::symlink_metadata(&target_path).is_err() {
+            report.status = "missing";
+            return Ok(report);
+        }
+        if install_mode == InstallMode::Symlink {
+            report.status = "symlink";
+            return Ok(report);
+        }
+        report.changes = diff_trees(source_path, &target_path)?;
+    }
+
+    if !report.changes.is_empty() {
+        report.status = "differs";
+    }
+    Ok(report)
+}
+
+/// Scratch directory on the host that receives a container target's files.
+fn snapshot_dir(skill_id: &str) -> PathBuf {
+    let unique = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!(
+        "eden-skills-diff-{skill_id}-{}-{unique}",
+        std::process::id()
+    ))
+}
+
+pub(crate) fn print_diff_header(ui: &UiContext, skill_id: &str, target_path: &str) {
+    println!(
+        "{}  {} {} {}",
+        ui.action_prefix("Diff"),
+        ui.styled_skill_id(skill_id),
+        ui.hint_prefix(),
+        ui.styled_path(target_path),
+    );
+}
+
+/// Render a file change list: one `kind  path` line per file, followed by
+/// the unified diff of each modified text file.
+pub(crate) fn print_file_changes(ui: &UiContext, changes: &[FileChange]) {
+    if changes.is_empty() {
+        println!(
+            "           {} target content matches source",
+            ui.status_symbol(StatusSymbol::Success)
+        );
+        return;
+    }
+    for change in changes {
+        println!(
+            "           {:<8}  {}",
+            change.kind.as_str(),
+            ui.styled_path(&change.path)
+        );
+        match &change.unified_diff {
+            Some(diff) => {
+                for line in diff.lines() {
+                    println!("             {}", style_diff_line(ui, line));
+                }
+            }
+            None if change.kind == FileChangeKind::Modified => {
+                println!("             (binary files differ)");
+            }
+            None => {}
+        }
+    }
+}
+
+fn style_diff_line(ui: &UiContext, line: &str) -> String {
+    if !ui.colors_enabled() {
+        return line.to_string();
+    }
+    if line.starts_with("+++") || line.starts_with("---") {
+        line.bold().to_string()
+    } else if line.starts_with('+') {
+        line.green().to_string()
+    } else if line.starts_with('-') {
+        line.red().to_string()
+    } else if line.starts_with("@@") {
+        line.cyan().to_string()
+    } else {
+        line.to_string()
+    }
+}
@@ -29,7 +29,7 @@ pub async fn docker_mount_hint_async(
 ) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, false)?;
+    let loaded = load_config_with_context(config_path, false, None)?;
     let config_dir = config_dir_from_path(config_path);
     let adapter = DockerAdapter::new(container_name).map_err(EdenError::from)?;
     let recommendations =
@@ -0,0 +1,255 @@
+//! Generation history via the `history` and `rollback` commands.
+//!
+//! Every successful `apply`, `install`, and `update --apply` records a
+//! generation (config, `skills.lock`, timestamp, command) under the
+//! storage root. `history` lists the generations of one config and
+//! `rollback` restores the config and lock of an earlier one, then
+//! reinstalls its targets at the locked commits.
+
+use std::path::Path;
+
+use eden_skills_core::config::config_dir_from_path;
+use eden_skills_core::error::EdenError;
+use eden_skills_core::generations::{
+    clear_current_generation, current_generation, list_generations, read_generation,
+    set_current_generation,
+};
+use eden_skills_core::lock::{lock_path_for_config, read_lock_file, write_lock_file};
+use eden_skills_core::paths::resolve_path_string;
+
+use super::common::{
+    ensure_docker_available_for_targets, load_config_with_context, print_warning,
+    resolve_config_path, with_hint,
+};
+use super::reconcile::{apply_with_lock_mode_async, uninstall_orphaned_lock_entries};
+use super::{CommandOptions, LockMode};
+use crate::ui::{StatusSymbol, UiContext};
+
+/// List the recorded generations of a config, oldest first.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] on config load failure or when a generation
+/// cannot be read.
+pub fn history(config_path: &str, options: CommandOptions) -> Result<(), EdenError> {
+    let config_path_buf = resolve_config_path(config_path)?;
+    let config_path = config_path_buf.as_path();
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    let ui = UiContext::from_env(options.json);
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
+    }
+
+    let config_dir = config_dir_from_path(config_path);
+    let storage_root = resolve_path_string(&loaded.config.storage_root, &config_dir)?;
+    let current = current_generation(&storage_root, config_path)?;
+    let mut rows = Vec::new();
+    for meta in list_generations(&storage_root, config_path)? {
+        let skills = read_generation(&storage_root, meta.number)?
+            .lock
+            .skills
+            .len();
+        rows.push((meta, skills));
+    }
+
+    if options.json {
+        let payload = serde_json::json!({
+            "current": current,
+            "generations": rows.iter().map(|(meta, skills)| serde_json::json!({
+                "number": meta.number,
+                "created_at": meta.created_at,
+                "command": meta.command,
+                "skills": skills,
+                "current": current == Some(meta.number),
+            })).collect::<Vec<_>>(),
+        });
+        let encoded = serde_json::to_string_pretty(&payload).map_err(|err| {
+            EdenError::Runtime(format!("failed to serialize history json: {err}"))
+        })?;
+        println!("{encoded}");
+        return Ok(());
+    }
+
+    println!("{}  {} recorded", ui.action_prefix("History"), rows.len());
+    if rows.is_empty() {
+        return Ok(());
+    }
+    println!();
+
+    let mut table = ui.table(&["Generation", "Created", "Command", "Skills"]);
+    for (meta, skills) in &rows {
+        let number = if current == Some(meta.number) {
+            format!("{} {}", meta.number, ui.styled_secondary("(current)"))
+        } else {
+            meta.number.to_string()
+        };
+        table.add_row(vec![
+            number,
+            meta.created_at.clone(),
+            meta.command.clone(),
+            skills.to_string(),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+/// Restore the config, lock, and targets of an earlier generation.
+///
+/// Without `to`, rolls back to the generation recorded before the
+/// current one. Skills absent from that generation are uninstalled, its
+/// config and lock are written back, and the remaining skills are
+/// installed exactly at their locked commits. The restored generation
+/// becomes current; no new generation is recorded. When the reinstall
+/// fails, the previous config, lock, and current generation are put
+/// back.
+///
+/// # Errors
+///
+/// Returns [`EdenError::InvalidArguments`] when there is no generation
+/// to roll back to, plus every error of `apply --locked`.
+pub async fn rollback_async(
+    config_path: &str,
+    to: Option<u64>,
+    options: CommandOptions,
+) -> Result<(), EdenError> {
+    let config_path_buf = resolve_config_path(config_path)?;
+    let config_path = config_path_buf.as_path();
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    let ui = UiContext::from_env(options.json);
+
+    let config_dir = config_dir_from_path(config_path);
+    let storage_root = resolve_path_string(&loaded.config.storage_root, &config_dir)?;
+    let generations = list_generations(&storage_root, config_path)?;
+    let current = current_generation(&storage_root, config_path)?;
+    let number = match to {
+        Some(number) => {
+            if !generations.iter().any(|meta| meta.number == number) {
+                return Err(EdenError::InvalidArguments(with_hint(
+                    format!("generation {number} is not recorded for this config"),
+                    "Run `eden-skills history` to list available generations.",
+                )));
+            }
+            number
+        }
+        None => generations
+            .iter()
+            .rev()
+            .map(|meta| meta.number)
+            .find(|number| current.is_some_and(|current| *number < current))
+            .ok_or_else(|| {
+                EdenError::InvalidArguments(with_hint(
+                    "no earlier generation to roll back to",
+                    "Run `eden-skills history` to list available generations.",
+                ))
+            })?,
+    };
+    let generation = read_generation(&storage_root, number)?;
+
+    let lock_path = lock_path_for_config(config_path);
+    let previous = RollbackRestore {
+        config_text: std::fs::read_to_string(config_path)?,
+        lock_text: match std::fs::read_to_string(&lock_path) {
+            Ok(text) => Some(text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        },
+        current,
+    };
+    let orphans = read_lock_file(&lock_path)?
+        .map(|lock| {
+            lock.skills
+                .into_iter()
+                .filter(|entry| {
+                    !generation
+                        .lock
+                        .skills
+                        .iter()
+                        .any(|kept| kept.id == entry.id)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    ensure_docker_available_for_targets(orphans.iter().flat_map(|entry| {
+        entry
+            .targets
+            .iter()
+            .map(|target| target.environment.as_str())
+    }))?;
+
+    println!(
+        "{}  {} generation {number} ({}, {})",
+        ui.action_prefix("Rollback"),
+        ui.status_symbol(StatusSymbol::Success),
+        generation.meta.command,
+        generation.meta.created_at
+    );
+    let result = async {
+        uninstall_orphaned_lock_entries(&orphans, &config_dir, &loaded.config.storage_root).await?;
+        std::fs::write(config_path, &generation.config_text)?;
+        write_lock_file(&lock_path, &generation.lock)?;
+        // Current before applying, so the apply sees an unchanged state and
+        // does not record the restored generation a second time.
+        set_current_generation(&storage_root, config_path, number)?;
+
+        apply_with_lock_mode_async(
+            &config_path.display().to_string(),
+            options,
+            None,
+            false,
+            LockMode::Locked,
+        )
+        .await
+    }
+    .await;
+    let Err(err) = result else {
+        return Ok(());
+    };
+    previous.restore(&storage_root, config_path, &lock_path)?;
+    print_warning(
+        &ui,
+        &format!(
+            "rollback to generation {number} failed; restored the previous config, lock, and current generation (run `eden-skills apply` to reinstall their targets)"
+        ),
+    );
+    Err(err)
+}
+
+/// The config, lock, and current generation as they were before a
+/// rollback started.
+struct RollbackRestore {
+    config_text: String,
+    lock_text: Option<String>,
+    current: Option<u64>,
+}
+
+impl RollbackRestore {
+    fn restore(
+        &self,
+        storage_root: &Path,
+        config_path: &Path,
+        lock_path: &Path,
+    ) -> Result<(), EdenError> {
+        std::fs::write(config_path, &self.config_text)?;
+        match &self.lock_text {
+            Some(text) => std::fs::write(lock_path, text)?,
+            None => match std::fs::remove_file(lock_path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            },
+        }
+        match self.current {
+            Some(number) => set_current_generation(storage_root, config_path, number),
+            None => clear_current_generation(storage_root, config_path),
+        }
+    }
+}
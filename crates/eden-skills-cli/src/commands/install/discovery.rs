@@ -1,14 +1,18 @@
-//! Remote and local skill discovery via temporary git checkouts.
+//! Remote and local skill discovery via temporary git checkouts and
+//! cached release archives.
 
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use eden_skills_core::discovery::{discover_skills, DiscoveredSkill};
 use eden_skills_core::error::EdenError;
+use eden_skills_core::git::{self, CloneOptions, GitBackend};
+use eden_skills_core::mirrors::MirrorRule;
 use eden_skills_core::paths::normalize_lexical;
-use eden_skills_core::source::resolve_repo_cache_root;
+use eden_skills_core::retry::{is_transient_failure, RetryPolicy};
+use eden_skills_core::source::{fetch_archive_source, resolve_repo_cache_root};
 use owo_colors::OwoColorize;
 
 use crate::ui::{
@@ -16,63 +20,138 @@ use crate::ui::{
 };
 
 use crate::commands::clean::DISCOVERY_TEMP_DIR_PREFIX;
-use crate::commands::common::{
-    extract_git_clone_failure_reason, git_clone_failure_hint, run_git_command,
-};
+use crate::commands::common::{extract_git_clone_failure_reason, git_clone_failure_hint};
 
 #[derive(Debug)]
 pub(super) struct RemoteDiscoveryResult {
     pub(super) discovered: Vec<DiscoveredSkill>,
-    pub(super) temp_checkout: Option<TempDiscoveryCheckout>,
 }
 
+/// A temporary discovery checkout, deleted on drop. It is never moved
+/// into the repo cache: cache entries are sparse worktrees of the shared
+/// object store, created by the source sync.
 #[derive(Debug)]
-pub(super) struct TempDiscoveryCheckout {
-    path: Option<PathBuf>,
+struct TempDiscoveryCheckout {
+    path: PathBuf,
 }
 
 impl TempDiscoveryCheckout {
-    pub(super) fn path(&self) -> &Path {
-        self.path
-            .as_deref()
-            .expect("temp discovery checkout path should be present")
-    }
-
-    pub(super) fn disarm(&mut self) {
-        self.path = None;
+    fn path(&self) -> &Path {
+        &self.path
     }
 }
 
 impl Drop for TempDiscoveryCheckout {
     fn drop(&mut self) {
-        if let Some(path) = &self.path {
-            let _ = fs::remove_dir_all(path);
-        }
+        let _ = fs::remove_dir_all(&self.path);
     }
 }
 
 pub(super) async fn discover_remote_skills_via_temp_clone(
+    backend: GitBackend,
     repo_url: &str,
     reference: &str,
     scoped_subpath: &str,
+    retry: RetryPolicy,
 ) -> Result<RemoteDiscoveryResult, EdenError> {
     let repo_url = repo_url.to_string();
     let reference = reference.to_string();
     let scoped_subpath = scoped_subpath.to_string();
     tokio::task::spawn_blocking(move || {
-        discover_remote_skills_via_temp_clone_blocking(&repo_url, &reference, &scoped_subpath)
+        discover_remote_skills_via_temp_clone_blocking(
+            backend,
+            &repo_url,
+            &reference,
+            &scoped_subpath,
+            retry,
+        )
     })
     .await
     .map_err(|err| EdenError::Runtime(format!("remote discovery worker failed: {err}")))?
 }
 
+/// Fetch a release archive into the repo cache and discover the skills
+/// it contains. The cache entry doubles as the install source.
+pub(super) async fn discover_archive_skills(
+    storage_root: &Path,
+    archive_url: &str,
+    sha256: &str,
+    scoped_subpath: &str,
+    mirrors: &[MirrorRule],
+    allow_network: bool,
+    retry: RetryPolicy,
+) -> Result<RemoteDiscoveryResult, EdenError> {
+    let storage_root = storage_root.to_path_buf();
+    let archive_url = archive_url.to_string();
+    let sha256 = sha256.to_string();
+    let scoped_subpath = scoped_subpath.to_string();
+    let mirrors = mirrors.to_vec();
+    tokio::task::spawn_blocking(move || {
+        let archive_root = fetch_archive_source(
+            &storage_root,
+            &archive_url,
+            &sha256,
+            &mirrors,
+            allow_network,
+            retry,
+        )?;
+        discover_in_repo_cache(&archive_root, &scoped_subpath)
+    })
+    .await
+    .map_err(|err| EdenError::Runtime(format!("archive discovery worker failed: {err}")))?
+}
+
+/// Offline counterpart of [`discover_remote_skills_via_temp_clone`]:
+/// discover from the repo cache entry of `(repo_url, reference)`, which
+/// must already exist.
+pub(super) fn discover_cached_remote_skills(
+    storage_root: &Path,
+    repo_url: &str,
+    reference: &str,
+    scoped_subpath: &str,
+) -> Result<RemoteDiscoveryResult, EdenError> {
+    let cache_root = resolve_repo_cache_root(storage_root, repo_url, reference);
+    if !cache_root.join(".git").exists() {
+        return Err(EdenError::Runtime(format!(
+            "`{repo_url}` is not cached at `{}` and network access is disabled\nhint: Run the install once without --offline to populate the cache.",
+            cache_root.display()
+        )));
+    }
+    discover_in_repo_cache(&cache_root, scoped_subpath)
+}
+
+fn discover_in_repo_cache(
+    cache_root: &Path,
+    scoped_subpath: &str,
+) -> Result<RemoteDiscoveryResult, EdenError> {
+    let discovery_root = normalize_lexical(&cache_root.join(scoped_subpath));
+    if !discovery_root.exists() {
+        return Err(EdenError::Runtime(format!(
+            "discovery path does not exist: {}",
+            discovery_root.display()
+        )));
+    }
+    Ok(RemoteDiscoveryResult {
+        discovered: discover_skills(&discovery_root)?,
+    })
+}
+
 fn discover_remote_skills_via_temp_clone_blocking(
+    backend: GitBackend,
     repo_url: &str,
     reference: &str,
     scoped_subpath: &str,
+    retry: RetryPolicy,
 ) -> Result<RemoteDiscoveryResult, EdenError> {
     let temp_checkout = create_discovery_temp_checkout()?;
-    clone_repo_for_discovery(repo_url, reference, temp_checkout.path())?;
+    clone_repo_for_discovery(
+        backend,
+        repo_url,
+        reference,
+        scoped_subpath,
+        temp_checkout.path(),
+        retry,
+    )?;
     let discovery_root = normalize_lexical(&temp_checkout.path().join(scoped_subpath));
     if !discovery_root.exists() {
         return Err(EdenError::Runtime(format!(
@@ -82,7 +161,6 @@ fn discover_remote_skills_via_temp_clone_blocking(
     }
     Ok(RemoteDiscoveryResult {
         discovered: discover_skills(&discovery_root)?,
-        temp_checkout: Some(temp_checkout),
     })
 }
 
@@ -97,11 +175,7 @@ fn create_discovery_temp_checkout() -> Result<TempDiscoveryCheckout, EdenError>
             std::process::id()
         ));
         match fs::create_dir(&candidate) {
-            Ok(()) => {
-                return Ok(TempDiscoveryCheckout {
-                    path: Some(candidate),
-                })
-            }
+            Ok(()) => return Ok(TempDiscoveryCheckout { path: candidate }),
             Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
             Err(err) => return Err(EdenError::Io(err)),
         }
@@ -111,97 +185,71 @@ fn create_discovery_temp_checkout() -> Result<TempDiscoveryCheckout, EdenError>
     ))
 }
 
-pub(super) fn seed_repo_cache_from_discovery_checkout(
-    temp_checkout: Option<TempDiscoveryCheckout>,
-    storage_root: &Path,
-    repo_url: &str,
-    reference: &str,
-) -> Result<(), EdenError> {
-    let Some(mut temp_checkout) = temp_checkout else {
-        return Ok(());
-    };
-
-    let cache_root = resolve_repo_cache_root(storage_root, repo_url, reference);
-    if cache_root.exists() {
-        return Ok(());
-    }
-    if let Some(parent) = cache_root.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    if let Ok(()) = rename_discovery_checkout_for_cache(temp_checkout.path(), &cache_root) {
-        temp_checkout.disarm()
-    }
-    Ok(())
-}
-
-fn rename_discovery_checkout_for_cache(from: &Path, to: &Path) -> std::io::Result<()> {
-    if std::env::var("EDEN_SKILLS_TEST_FORCE_DISCOVERY_RENAME_FAIL")
-        .ok()
-        .as_deref()
-        == Some("1")
-    {
-        return Err(std::io::Error::other(
-            "forced discovery checkout rename failure",
-        ));
-    }
-    fs::rename(from, to)
-}
-
+/// Clone `repo_url` at `reference` for discovery. With the CLI backend
+/// and a `scoped_subpath` below the repository root, the clone is
+/// blobless and only checks out that subpath.
 fn clone_repo_for_discovery(
+    backend: GitBackend,
     repo_url: &str,
     reference: &str,
+    scoped_subpath: &str,
     repo_dir: &Path,
+    retry: RetryPolicy,
 ) -> Result<(), EdenError> {
     if let Some(parent) = repo_dir.parent() {
         fs::create_dir_all(parent)?;
     }
-
-    record_test_git_clone_if_configured();
-    let branch_clone_result = run_git_command(
-        Command::new("git")
-            .arg("clone")
-            .arg("--depth")
-            .arg("1")
-            .arg("--branch")
-            .arg(reference)
-            .arg(repo_url)
-            .arg(repo_dir),
-        &format!(
-            "clone `{repo_url}` into `{}` with ref `{reference}`",
-            repo_dir.display()
-        ),
-    );
+    let sparse_path = discovery_sparse_path(scoped_subpath);
+
+    let branch_clone_result = retry
+        .run(|| {
+            record_test_git_clone_if_configured();
+            git::clone(
+                backend,
+                repo_url,
+                repo_dir,
+                CloneOptions {
+                    reference: Some(reference),
+                    shallow: true,
+                    sparse: sparse_path.is_some(),
+                    timeout: retry.timeout,
+                    ..CloneOptions::default()
+                },
+            )
+        })
+        .into_result();
 
     if let Err(branch_error) = branch_clone_result {
-        record_test_git_clone_if_configured();
-        let fallback_clone = run_git_command(
-            Command::new("git").arg("clone").arg(repo_url).arg(repo_dir),
-            &format!(
-                "clone `{repo_url}` into `{}` without branch hint",
-                repo_dir.display()
-            ),
-        );
-        if let Err(fallback_error) = fallback_clone {
-            let combined_stderr = format!("{branch_error}\n{fallback_error}");
+        // A network failure would only repeat without the branch hint.
+        let clone_failure = if is_transient_failure(&branch_error) {
+            Some(branch_error)
+        } else {
+            retry
+                .run(|| {
+                    record_test_git_clone_if_configured();
+                    git::clone(
+                        backend,
+                        repo_url,
+                        repo_dir,
+                        CloneOptions {
+                            sparse: sparse_path.is_some(),
+                            timeout: retry.timeout,
+                            ..CloneOptions::default()
+                        },
+                    )
+                })
+                .into_result()
+                .err()
+                .map(|fallback_error| format!("{branch_error}\n{fallback_error}"))
+        };
+        if let Some(combined_stderr) = clone_failure {
             let reason = extract_git_clone_failure_reason(&combined_stderr);
             let hint = git_clone_failure_hint(reason, repo_url);
             return Err(EdenError::Runtime(format!(
                 "failed to clone `{repo_url}` — {reason}\nhint: {hint}"
             )));
         }
-        run_git_command(
-            Command::new("git")
-                .arg("-C")
-                .arg(repo_dir)
-                .arg("checkout")
-                .arg(reference),
-            &format!(
-                "checkout ref `{reference}` in temporary discovery repo `{}`",
-                repo_dir.display()
-            ),
-        )
-        .map_err(|_| {
+        git::checkout(backend, repo_dir, reference).map_err(|_| {
             let reason = "remote branch not found";
             let hint = git_clone_failure_hint(reason, repo_url);
             EdenError::Runtime(format!(
@@ -210,9 +258,28 @@ fn clone_repo_for_discovery(
         })?;
     }
 
+    if let Some(sparse_path) = sparse_path {
+        git::sparse_checkout_set(backend, repo_dir, Some(&BTreeSet::from([sparse_path])))
+            .map_err(EdenError::Runtime)?;
+    }
     Ok(())
 }
 
+/// The cone-mode sparse path discovery checks out, or `None` for the full
+/// tree: the repository root, or a subpath that escapes it.
+fn discovery_sparse_path(scoped_subpath: &str) -> Option<String> {
+    let parts = scoped_subpath
+        .replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if parts.is_empty() || parts.iter().any(|part| part == "..") {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
 fn record_test_git_clone_if_configured() {
     let Some(log_path) = std::env::var_os("EDEN_SKILLS_TEST_GIT_CLONE_LOG") else {
         return;
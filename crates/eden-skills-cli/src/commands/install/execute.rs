@@ -1,17 +1,20 @@
 //! Install plan execution and adapter dispatch.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use eden_skills_core::adapter::{
-    read_managed_manifest, write_managed_manifest, DockerAdapter, LocalAdapter, TargetAdapter,
+    read_managed_manifest, write_managed_manifest, DockerAdapter, TargetAdapter,
 };
 use eden_skills_core::config::{
     default_verify_checks_for_mode, encode_registry_mode_repo, AgentKind, Config, InstallMode,
-    SkillConfig, SourceConfig, TargetConfig,
+    SkillConfig, SourceConfig, TargetConfig, TargetScope,
 };
 use eden_skills_core::error::EdenError;
-use eden_skills_core::managed::{external_install_origin, local_install_origin, ManagedSource};
+use eden_skills_core::managed::{
+    external_install_origin, local_install_origin, project_install_origin, ManagedSource,
+};
 use eden_skills_core::paths::{normalize_lexical, resolve_path_string};
 use eden_skills_core::plan::{build_plan, Action};
 use eden_skills_core::source::resolve_skill_source_path;
@@ -20,8 +23,8 @@ use crate::ui::UiContext;
 
 use super::platform::default_install_mode;
 use crate::commands::common::{
-    apply_plan_item, copy_recursively, ensure_parent_dir, path_is_symlink_or_junction,
-    print_warning, remove_path,
+    copy_recursively, ensure_parent_dir, path_is_symlink_or_junction, print_warning, remove_path,
+    TargetTransaction,
 };
 
 #[derive(Debug, Default)]
@@ -85,13 +88,30 @@ pub(super) async fn execute_install_plan_async(
         return execute_install_plan(single_skill_config, config_dir, strict, force, ui).await;
     }
 
-    let skill = single_skill_config
-        .skills
-        .first()
-        .ok_or_else(|| EdenError::Runtime("install skill is missing".to_string()))?;
+    if single_skill_config.skills.is_empty() {
+        return Err(EdenError::Runtime("install skill is missing".to_string()));
+    }
     let storage_root = resolve_path_string(&single_skill_config.storage_root, config_dir)?;
-    let source_path = resolve_skill_source_path(&storage_root, skill);
-    execute_single_skill_targets_async(skill, &source_path, config_dir, strict, force, ui).await
+    // Implicit dependencies resolved for the skill follow it.
+    let mut summary = InstallExecutionSummary::default();
+    let mut transaction = TargetTransaction::new();
+    for skill in &single_skill_config.skills {
+        let source_path = resolve_skill_source_path(&storage_root, skill);
+        summary.merge(
+            execute_single_skill_targets_async(
+                skill,
+                &source_path,
+                config_dir,
+                force,
+                &mut transaction,
+                ui,
+            )
+            .await?,
+        );
+    }
+    transaction.commit();
+    ensure_no_strict_conflicts(&summary, strict)?;
+    Ok(summary)
 }
 
 async fn execute_install_plan(
@@ -102,14 +122,20 @@ async fn execute_install_plan(
     ui: &UiContext,
 ) -> Result<InstallExecutionSummary, EdenError> {
     let plan = build_plan(single_skill_config, config_dir)?;
+    let target_scopes = single_skill_config
+        .skills
+        .iter()
+        .flat_map(|skill| skill.targets.iter().map(|target| target.scope));
     let mut summary = InstallExecutionSummary::default();
-    for item in &plan {
+    let mut transaction = TargetTransaction::new();
+    for (item, scope) in plan.iter().zip(target_scopes) {
         match item.action {
             Action::Create | Action::Update => {
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 update_managed_manifest_after_install(
                     &item.skill_id,
                     "local",
+                    scope,
                     Path::new(&item.target_path).parent().ok_or_else(|| {
                         EdenError::Runtime(format!(
                             "installed target path missing parent directory: {}",
@@ -129,12 +155,8 @@ async fn execute_install_plan(
             Action::Noop | Action::Remove => {}
         }
     }
-    if strict && summary.conflicts > 0 {
-        return Err(EdenError::Conflict(format!(
-            "strict mode blocked install: {} conflict entries",
-            summary.conflicts
-        )));
-    }
+    transaction.commit();
+    ensure_no_strict_conflicts(&summary, strict)?;
     Ok(summary)
 }
 
@@ -163,15 +185,30 @@ pub(super) async fn install_local_source_skill_async(
         )));
     }
 
-    execute_single_skill_targets_async(skill, &source_path, config_dir, strict, force, ui).await
+    let mut transaction = TargetTransaction::new();
+    let summary = execute_single_skill_targets_async(
+        skill,
+        &source_path,
+        config_dir,
+        force,
+        &mut transaction,
+        ui,
+    )
+    .await?;
+    transaction.commit();
+    ensure_no_strict_conflicts(&summary, strict)?;
+    Ok(summary)
 }
 
+/// Install `skill` into each of its targets. Local targets are swapped in
+/// through `transaction`, so a failure on a later target, local or Docker,
+/// restores the ones already replaced once the caller drops it.
 async fn execute_single_skill_targets_async(
     skill: &SkillConfig,
     source_path: &Path,
     config_dir: &Path,
-    strict: bool,
     force: bool,
+    transaction: &mut TargetTransaction,
     ui: &UiContext,
 ) -> Result<InstallExecutionSummary, EdenError> {
     let mut summary = InstallExecutionSummary::default();
@@ -185,8 +222,14 @@ async fn execute_single_skill_targets_async(
                 .install(source_path, &target_path, skill.install.mode)
                 .await
                 .map_err(EdenError::from)?;
-            update_managed_manifest_after_install(&skill.id, &target.environment, &target_root, ui)
-                .await?;
+            update_managed_manifest_after_install(
+                &skill.id,
+                &target.environment,
+                target.scope,
+                &target_root,
+                ui,
+            )
+            .await?;
             summary.installed_targets.push(InstallTargetLine {
                 skill_id: skill.id.clone(),
                 target_path: target_path.display().to_string(),
@@ -203,6 +246,7 @@ async fn execute_single_skill_targets_async(
         if maybe_adopt_external_local_target(
             skill,
             &target.environment,
+            target.scope,
             &target_root,
             &target_path,
             force,
@@ -237,32 +281,41 @@ async fn execute_single_skill_targets_async(
             Err(err) => return Err(EdenError::Io(err)),
         }
 
-        LocalAdapter::new()
-            .install(source_path, &target_path, skill.install.mode)
-            .await
-            .map_err(EdenError::from)?;
-        update_managed_manifest_after_install(&skill.id, &target.environment, &target_root, ui)
-            .await?;
+        transaction.install(&skill.id, source_path, &target_path, skill.install.mode)?;
+        update_managed_manifest_after_install(
+            &skill.id,
+            &target.environment,
+            target.scope,
+            &target_root,
+            ui,
+        )
+        .await?;
         summary.installed_targets.push(InstallTargetLine {
             skill_id: skill.id.clone(),
             target_path: target_path.display().to_string(),
             mode: skill.install.mode.as_str().to_string(),
         });
     }
+    Ok(summary)
+}
 
+fn ensure_no_strict_conflicts(
+    summary: &InstallExecutionSummary,
+    strict: bool,
+) -> Result<(), EdenError> {
     if strict && summary.conflicts > 0 {
         return Err(EdenError::Conflict(format!(
             "strict mode blocked install: {} conflict entries",
             summary.conflicts
         )));
     }
-
-    Ok(summary)
+    Ok(())
 }
 
 pub(super) async fn update_managed_manifest_after_install(
     skill_id: &str,
     environment: &str,
+    scope: TargetScope,
     agent_dir: &Path,
     ui: &UiContext,
 ) -> Result<(), EdenError> {
@@ -280,7 +333,7 @@ pub(super) async fn update_managed_manifest_after_install(
         manifest.record_install(
             skill_id,
             ManagedSource::Local,
-            local_install_origin(environment),
+            managed_local_origin(environment, scope),
         );
     }
 
@@ -289,9 +342,17 @@ pub(super) async fn update_managed_manifest_after_install(
         .map_err(EdenError::from)
 }
 
+fn managed_local_origin(environment: &str, scope: TargetScope) -> String {
+    match scope {
+        TargetScope::Global => local_install_origin(environment),
+        TargetScope::Project => project_install_origin(),
+    }
+}
+
 async fn maybe_adopt_external_local_target(
     skill: &SkillConfig,
     environment: &str,
+    scope: TargetScope,
     agent_dir: &Path,
     target_path: &Path,
     force: bool,
@@ -328,7 +389,7 @@ async fn maybe_adopt_external_local_target(
     manifest.record_install(
         &skill.id,
         ManagedSource::Local,
-        local_install_origin(environment),
+        managed_local_origin(environment, scope),
     );
     write_managed_manifest(environment, agent_dir, &manifest)
         .await
@@ -340,7 +401,7 @@ pub(super) fn describe_external_origin(origin: &str) -> &str {
     origin.strip_prefix("host:").unwrap_or(origin)
 }
 
-pub(super) async fn resolve_docker_target_root(
+pub(crate) async fn resolve_docker_target_root(
     target: &TargetConfig,
     docker: &DockerAdapter,
 ) -> Result<PathBuf, EdenError> {
@@ -408,6 +469,7 @@ pub(super) fn upsert_mode_b_skill(
             repo: encode_registry_mode_repo(registry),
             subpath: ".".to_string(),
             r#ref: version_constraint.to_string(),
+            verify_signature: false,
         };
         skill.install.mode = install_mode;
         skill.verify.enabled = true;
@@ -426,6 +488,7 @@ pub(super) fn upsert_mode_b_skill(
             repo: encode_registry_mode_repo(registry),
             subpath: ".".to_string(),
             r#ref: version_constraint.to_string(),
+            verify_signature: false,
         },
         install: eden_skills_core::config::InstallConfig { mode: install_mode },
         targets,
@@ -436,6 +499,8 @@ pub(super) fn upsert_mode_b_skill(
         safety: eden_skills_core::config::SafetyConfig {
             no_exec_metadata_only: false,
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     });
     Ok(())
 }
@@ -455,6 +520,7 @@ pub(super) fn upsert_mode_a_skill(
             repo: repo.to_string(),
             subpath: subpath.to_string(),
             r#ref: reference.to_string(),
+            verify_signature: skill.source.verify_signature,
         };
         skill.install.mode = install_mode;
         skill.verify.enabled = true;
@@ -473,6 +539,7 @@ pub(super) fn upsert_mode_a_skill(
             repo: repo.to_string(),
             subpath: subpath.to_string(),
             r#ref: reference.to_string(),
+            verify_signature: false,
         },
         install: eden_skills_core::config::InstallConfig { mode: install_mode },
         targets: effective_targets,
@@ -483,6 +550,8 @@ pub(super) fn upsert_mode_a_skill(
         safety: eden_skills_core::config::SafetyConfig {
             no_exec_metadata_only: false,
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     });
     Ok(())
 }
@@ -493,6 +562,14 @@ pub(super) fn default_install_target() -> TargetConfig {
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
+    }
+}
+
+pub(super) fn default_project_install_target() -> TargetConfig {
+    TargetConfig {
+        scope: TargetScope::Project,
+        ..default_install_target()
     }
 }
 
@@ -502,6 +579,7 @@ pub(super) fn default_docker_install_target(container_name: &str) -> TargetConfi
         expected_path: None,
         path: None,
         environment: format!("docker:{container_name}"),
+        scope: TargetScope::Global,
     }
 }
 
@@ -15,16 +15,24 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
-use eden_skills_core::agents::detect_installed_agent_targets;
-use eden_skills_core::config::{config_dir_from_path, validate_config, Config};
-use eden_skills_core::config::{SkillConfig, TargetConfig};
+use eden_skills_core::agents::{detect_installed_agent_targets, detect_project_agent_targets};
+use eden_skills_core::config::{
+    config_dir_from_path, encode_archive_ref, normalize_sha256, validate_config, Config,
+    DEFAULT_STORAGE_ROOT,
+};
+use eden_skills_core::config::{SkillConfig, TargetConfig, TargetScope};
 use eden_skills_core::discovery::{discover_skills, DiscoveredSkill};
 use eden_skills_core::error::EdenError;
+use eden_skills_core::git::GitBackend;
+use eden_skills_core::lock::DigestRefresh;
+use eden_skills_core::mirrors::{rewrite_url, MirrorRule};
 use eden_skills_core::paths::{normalize_lexical, resolve_path_string};
-use eden_skills_core::source::sync_sources_async;
+use eden_skills_core::reactor::SkillReactor;
+use eden_skills_core::retry::RetryPolicy;
+use eden_skills_core::source::{sync_sources_async, sync_sources_offline_async, SyncSummary};
 use eden_skills_core::source_format::{
     derive_skill_id_from_source_repo, detect_install_source, DetectedInstallSource,
-    UrlInstallSource,
+    UrlInstallSource, UrlSourceType,
 };
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 
@@ -32,25 +40,27 @@ use crate::ui::{StatusSymbol, UiContext};
 use crate::DEFAULT_CONFIG_PATH;
 
 use super::common::{
-    agent_kind_label, ensure_git_available, load_config_with_context, parse_target_specs,
-    print_source_sync_step_summary_human, print_warning, resolve_config_path,
-    resolve_registry_mode_skills_for_execution, source_sync_failure_error, write_lock_for_config,
-    write_normalized_config,
+    agent_kind_label, check_source_sync, ensure_git_available, load_config_with_context,
+    parse_target_specs, print_source_sync_step_summary_human, print_warning,
+    record_generation_for_config, resolve_config_path, resolve_skill_subset_for_execution,
+    user_config_layer_for, with_hint, write_lock_for_config, write_normalized_config,
 };
 use super::config_ops::default_config_template;
 use super::InstallRequest;
 
 use self::adapter::DockerAdapter;
 use self::discovery::{
-    discover_remote_skills_via_temp_clone, join_scoped_subpath, print_discovery_json,
-    print_discovery_preview, resolve_local_install_selection,
-    seed_repo_cache_from_discovery_checkout,
+    discover_archive_skills, discover_cached_remote_skills, discover_remote_skills_via_temp_clone,
+    join_scoped_subpath, print_discovery_json, print_discovery_preview,
+    resolve_local_install_selection, RemoteDiscoveryResult,
 };
 use self::dry_run::print_install_dry_run;
+pub(super) use self::execute::resolve_docker_target_root;
 use self::execute::{
-    default_docker_install_target, default_install_target, execute_install_plan_async,
-    install_local_source_skill_async, print_install_success_json, should_preserve_existing_targets,
-    upsert_mode_a_skill, upsert_mode_b_skill, InstallExecutionSummary,
+    default_docker_install_target, default_install_target, default_project_install_target,
+    execute_install_plan_async, install_local_source_skill_async, print_install_success_json,
+    should_preserve_existing_targets, upsert_mode_a_skill, upsert_mode_b_skill,
+    InstallExecutionSummary,
 };
 use self::output::{
     print_docker_cp_hints, print_install_result_lines, print_install_result_summary,
@@ -170,12 +180,22 @@ pub async fn install_async(req: InstallRequest) -> Result<(), EdenError> {
     }
     match detected_source {
         DetectedInstallSource::RegistryName(skill_name) => {
-            ensure_install_config_exists(config_path, &ui, auto_create_missing_parent)?;
+            ensure_install_config_exists(
+                config_path,
+                req.options.user_config_path.as_deref(),
+                &ui,
+                auto_create_missing_parent,
+            )?;
             install_registry_mode_async(&req, config_path, &skill_name, &ui).await
         }
         DetectedInstallSource::Url(url_source) => {
             if !req.list || req.dry_run {
-                ensure_install_config_exists(config_path, &ui, auto_create_missing_parent)?;
+                ensure_install_config_exists(
+                    config_path,
+                    req.options.user_config_path.as_deref(),
+                    &ui,
+                    auto_create_missing_parent,
+                )?;
             }
             install_url_mode_async(&req, config_path, &url_source, &ui).await
         }
@@ -184,6 +204,7 @@ pub async fn install_async(req: InstallRequest) -> Result<(), EdenError> {
 
 fn ensure_install_config_exists(
     config_path: &Path,
+    user_config_path: Option<&Path>,
     ui: &UiContext,
     auto_create_missing_parent: bool,
 ) -> Result<(), EdenError> {
@@ -206,7 +227,13 @@ fn ensure_install_config_exists(
         }
     }
 
-    fs::write(config_path, default_config_template())?;
+    // A project layer inherits `[storage]` from the user config.
+    let template = if user_config_layer_for(config_path, user_config_path).is_some() {
+        "version = 1\n".to_string()
+    } else {
+        default_config_template()
+    };
+    fs::write(config_path, template)?;
     if !ui.json_mode() {
         let display_path = ui.styled_path(&config_path.display().to_string());
         if ui.symbols_enabled() {
@@ -234,7 +261,11 @@ async fn install_registry_mode_async(
         ));
     }
 
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        req.options.strict,
+        req.options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(ui, &warning);
     }
@@ -242,7 +273,9 @@ async fn install_registry_mode_async(
 
     let mut config = loaded.config;
     let requested_constraint = req.version.clone().unwrap_or_else(|| "*".to_string());
-    let target_override = resolve_registry_mode_install_targets(&req.target, ui).await?;
+    let project_root = req.project.then_some(config_dir.as_path());
+    let target_override =
+        resolve_registry_mode_install_targets(&req.target, project_root, ui).await?;
     upsert_mode_b_skill(
         &mut config,
         skill_name,
@@ -255,15 +288,22 @@ async fn install_registry_mode_async(
 
     let mut single_skill_config = select_single_skill_config(&config, skill_name)?;
 
-    single_skill_config = resolve_registry_mode_skills_for_execution(
+    single_skill_config = resolve_skill_subset_for_execution(
         config_path,
+        req.options.user_config_path.as_deref(),
         &single_skill_config,
+        &config,
         &config_dir,
         ui,
     )?;
+    let installed_skill_ids = single_skill_config
+        .skills
+        .iter()
+        .map(|skill| skill.id.clone())
+        .collect::<Vec<_>>();
 
     if req.dry_run {
-        let preview_skill_ids = vec![skill_name.to_string()];
+        let preview_skill_ids = installed_skill_ids;
         print_install_dry_run(
             ui,
             req.options.json,
@@ -275,19 +315,22 @@ async fn install_registry_mode_async(
         return Ok(());
     }
 
-    write_normalized_config(config_path, &config)?;
+    write_normalized_config(
+        config_path,
+        &config,
+        req.options.user_config_path.as_deref(),
+    )?;
 
-    ensure_git_available()?;
+    ensure_git_available(config.git.backend)?;
     let mut sync_progress = StepProgress::new(ui, "Syncing", 1);
     sync_progress.start_step(skill_name);
-    let sync_summary = sync_sources_async(&single_skill_config, &config_dir).await?;
+    let sync_summary =
+        sync_install_sources(&single_skill_config, &config_dir, req.options.offline).await?;
     sync_progress.record_step(ui, 0, skill_name, sync_summary.failed > 0);
     if !req.options.json {
         sync_progress.finish_with_sync_summary(ui);
     }
-    if let Some(err) = source_sync_failure_error(&sync_summary) {
-        return Err(err);
-    }
+    check_source_sync(&sync_summary, "install", req.options.json)?;
 
     let mut execution_summary = execute_install_plan_async(
         &single_skill_config,
@@ -298,8 +341,28 @@ async fn install_registry_mode_async(
     )
     .await?;
 
-    let full_loaded = load_config_with_context(config_path, false)?;
-    write_lock_for_config(config_path, &full_loaded.config, &config_dir)?;
+    let full_loaded =
+        load_config_with_context(config_path, false, req.options.user_config_path.as_deref())?;
+    let mut lock_config = full_loaded.config.clone();
+    for skill in &single_skill_config.skills {
+        if skill.implicit {
+            lock_config.skills.push(skill.clone());
+        } else if let Some(configured) = lock_config
+            .skills
+            .iter_mut()
+            .find(|configured| configured.id == skill.id)
+        {
+            configured.requires = skill.requires.clone();
+        }
+    }
+    write_lock_for_config(
+        config_path,
+        req.options.user_config_path.as_deref(),
+        &lock_config,
+        &config_dir,
+        DigestRefresh::Skills(&installed_skill_ids),
+    )?;
+    record_generation_for_config(config_path, &full_loaded.config, &config_dir, "install")?;
 
     if execution_summary.installed_targets.is_empty() && execution_summary.conflicts == 0 {
         execution_summary.skipped_skills += 1;
@@ -338,44 +401,81 @@ async fn install_remote_url_mode_async(
     url_source: &UrlInstallSource,
     ui: &UiContext,
 ) -> Result<(), EdenError> {
-    ensure_git_available()?;
-    let source_ref = req
-        .r#ref
-        .clone()
-        .or_else(|| url_source.reference.clone())
-        .unwrap_or_else(|| "main".to_string());
+    // `--list` may run before any config exists; fall back to the default backend.
+    let loaded = if config_path.exists() {
+        Some(load_config_with_context(
+            config_path,
+            req.options.strict,
+            req.options.user_config_path.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let git_backend = loaded
+        .as_ref()
+        .map_or_else(GitBackend::default, |loaded| loaded.config.git.backend);
+    let mirrors = loaded
+        .as_ref()
+        .map_or(&[][..], |loaded| loaded.config.mirrors.as_slice());
+    let retry = loaded.as_ref().map_or_else(RetryPolicy::default, |loaded| {
+        loaded.config.reactor.retry_policy()
+    });
+    let archive_sha256 = archive_install_sha256(req, url_source)?;
+    if archive_sha256.is_none() {
+        ensure_git_available(git_backend)?;
+    }
+    let source_ref = match &archive_sha256 {
+        Some(sha256) => encode_archive_ref(sha256),
+        None => req
+            .r#ref
+            .clone()
+            .or_else(|| url_source.reference.clone())
+            .unwrap_or_else(|| "main".to_string()),
+    };
     let scope_subpath = url_source
         .subpath
         .clone()
         .unwrap_or_else(|| ".".to_string());
-    let clone_spinner = ui.spinner(
-        "Cloning",
-        format!("{}@{} ({})", url_source.repo, source_ref, scope_subpath),
-    );
-    let mut remote_discovery =
-        match discover_remote_skills_via_temp_clone(&url_source.repo, &source_ref, &scope_subpath)
-            .await
+    let storage_root = loaded.as_ref().map_or(DEFAULT_STORAGE_ROOT, |loaded| {
+        loaded.config.storage_root.as_str()
+    });
+    let storage_root = resolve_path_string(storage_root, &config_dir_from_path(config_path))?;
+    let mut remote_discovery = if let Some(sha256) = &archive_sha256 {
+        let download_spinner = ui.spinner("Downloading", url_source.repo.clone());
+        match discover_archive_skills(
+            &storage_root,
+            &url_source.repo,
+            sha256,
+            &scope_subpath,
+            mirrors,
+            !req.options.offline,
+            retry,
+        )
+        .await
         {
             Ok(discovery) => {
-                clone_spinner.finish_success(ui);
+                download_spinner.finish_success(ui);
                 discovery
             }
             Err(err) => {
-                let spinner_summary = err
-                    .to_string()
-                    .split('\n')
-                    .next()
-                    .unwrap_or("clone failed")
-                    .strip_prefix("failed to clone ")
-                    .and_then(|rest| rest.split_once(" — "))
-                    .map_or_else(
-                        || "clone failed".to_string(),
-                        |(_, reason)| reason.to_string(),
-                    );
-                clone_spinner.finish_failure(ui, &spinner_summary);
+                download_spinner.finish_failure(ui, "download failed");
                 return Err(err);
             }
-        };
+        }
+    } else if req.options.offline {
+        discover_cached_remote_skills(&storage_root, &url_source.repo, &source_ref, &scope_subpath)?
+    } else {
+        discover_remote_skills_with_spinner(
+            git_backend,
+            &url_source.repo,
+            &source_ref,
+            &scope_subpath,
+            mirrors,
+            retry,
+            ui,
+        )
+        .await?
+    };
     if remote_discovery.discovered.is_empty() {
         print_warning(ui, "No SKILL.md found; installing directory as-is.");
     }
@@ -418,7 +518,14 @@ async fn install_remote_url_mode_async(
     if selected.is_empty() {
         return Ok(());
     }
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = match loaded {
+        Some(loaded) => loaded,
+        None => load_config_with_context(
+            config_path,
+            req.options.strict,
+            req.options.user_config_path.as_deref(),
+        )?,
+    };
     for warning in loaded.warnings {
         print_warning(ui, &warning);
     }
@@ -438,8 +545,13 @@ async fn install_remote_url_mode_async(
             .iter()
             .find(|existing| existing.id == skill_id)
             .cloned();
-        let target_override =
-            resolve_url_mode_install_targets(&req.target, existing_skill.as_ref(), ui).await?;
+        let target_override = resolve_url_mode_install_targets(
+            &req.target,
+            existing_skill.as_ref(),
+            req.project.then_some(config_dir.as_path()),
+            ui,
+        )
+        .await?;
         upsert_mode_a_skill(
             &mut config,
             &skill_id,
@@ -467,17 +579,14 @@ async fn install_remote_url_mode_async(
         return Ok(());
     }
 
-    write_normalized_config(config_path, &config)?;
-    let storage_root = resolve_path_string(&selected_config.storage_root, &config_dir)?;
-    seed_repo_cache_from_discovery_checkout(
-        remote_discovery.temp_checkout.take(),
-        &storage_root,
-        &url_source.repo,
-        &source_ref,
+    write_normalized_config(
+        config_path,
+        &config,
+        req.options.user_config_path.as_deref(),
     )?;
-
     let sync_spinner = ui.spinner("Syncing", format!("{} skill sources…", selected_ids.len()));
-    let sync_summary = sync_sources_async(&selected_config, &config_dir).await?;
+    let sync_summary =
+        sync_install_sources(&selected_config, &config_dir, req.options.offline).await?;
     if sync_summary.failed > 0 {
         sync_spinner.finish_failure(ui, &format!("{} source(s) failed", sync_summary.failed));
     } else {
@@ -490,9 +599,7 @@ async fn install_remote_url_mode_async(
             sync_summary.failed,
         );
     }
-    if let Some(err) = source_sync_failure_error(&sync_summary) {
-        return Err(err);
-    }
+    check_source_sync(&sync_summary, "install", req.options.json)?;
 
     let mut execution_summary = InstallExecutionSummary::default();
     let mut install_progress = StepProgress::new(ui, "Installing", selected_ids.len());
@@ -519,8 +626,16 @@ async fn install_remote_url_mode_async(
         install_progress.finish_quiet();
     }
 
-    let full_loaded = load_config_with_context(config_path, false)?;
-    write_lock_for_config(config_path, &full_loaded.config, &config_dir)?;
+    let full_loaded =
+        load_config_with_context(config_path, false, req.options.user_config_path.as_deref())?;
+    write_lock_for_config(
+        config_path,
+        req.options.user_config_path.as_deref(),
+        &full_loaded.config,
+        &config_dir,
+        DigestRefresh::Skills(&selected_ids),
+    )?;
+    record_generation_for_config(config_path, &full_loaded.config, &config_dir, "install")?;
 
     if req.options.json {
         let payload = serde_json::json!({
@@ -539,6 +654,98 @@ async fn install_remote_url_mode_async(
     Ok(())
 }
 
+/// Sync the sources of skills being installed, from the cache alone when
+/// offline.
+async fn sync_install_sources(
+    config: &Config,
+    config_dir: &Path,
+    offline: bool,
+) -> Result<SyncSummary, EdenError> {
+    let summary = if offline {
+        sync_sources_offline_async(config, config_dir, SkillReactor::default()).await?
+    } else {
+        sync_sources_async(config, config_dir).await?
+    };
+    Ok(summary)
+}
+
+async fn discover_remote_skills_with_spinner(
+    backend: GitBackend,
+    repo_url: &str,
+    source_ref: &str,
+    scope_subpath: &str,
+    mirrors: &[MirrorRule],
+    retry: RetryPolicy,
+    ui: &UiContext,
+) -> Result<RemoteDiscoveryResult, EdenError> {
+    let clone_spinner = ui.spinner(
+        "Cloning",
+        format!("{repo_url}@{source_ref} ({scope_subpath})"),
+    );
+    let fetch_url = rewrite_url(mirrors, repo_url);
+    match discover_remote_skills_via_temp_clone(
+        backend,
+        &fetch_url,
+        source_ref,
+        scope_subpath,
+        retry,
+    )
+    .await
+    {
+        Ok(discovery) => {
+            clone_spinner.finish_success(ui);
+            Ok(discovery)
+        }
+        Err(err) => {
+            let spinner_summary = err
+                .to_string()
+                .split('\n')
+                .next()
+                .unwrap_or("clone failed")
+                .strip_prefix("failed to clone ")
+                .and_then(|rest| rest.split_once(" — "))
+                .map_or_else(
+                    || "clone failed".to_string(),
+                    |(_, reason)| reason.to_string(),
+                );
+            clone_spinner.finish_failure(ui, &spinner_summary);
+            Err(err)
+        }
+    }
+}
+
+/// The checksum an archive install is pinned to. Archive URLs require
+/// `--sha256`, which in turn only applies to archive URLs.
+fn archive_install_sha256(
+    req: &InstallRequest,
+    url_source: &UrlInstallSource,
+) -> Result<Option<String>, EdenError> {
+    if url_source.source_type != UrlSourceType::Archive {
+        if req.sha256.is_some() {
+            return Err(EdenError::InvalidArguments(
+                "`--sha256` only applies to archive URLs (.tar.gz, .tgz, .zip)".to_string(),
+            ));
+        }
+        return Ok(None);
+    }
+    if req.r#ref.is_some() {
+        return Err(EdenError::InvalidArguments(
+            "`--ref` is not supported for archive URLs".to_string(),
+        ));
+    }
+    let Some(sha256) = req.sha256.as_deref() else {
+        return Err(EdenError::InvalidArguments(with_hint(
+            format!("archive `{}` requires a checksum", url_source.repo),
+            "Pass `--sha256 <hex>` with the archive's published SHA-256.",
+        )));
+    };
+    normalize_sha256(sha256).map(Some).ok_or_else(|| {
+        EdenError::InvalidArguments(format!(
+            "invalid --sha256 `{sha256}`: expected 64 hexadecimal characters"
+        ))
+    })
+}
+
 async fn install_local_url_mode_async(
     req: &InstallRequest,
     config_path: &Path,
@@ -596,7 +803,11 @@ async fn install_local_url_mode_async(
     if selected.is_empty() {
         return Ok(());
     }
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        req.options.strict,
+        req.options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         print_warning(ui, &warning);
     }
@@ -620,8 +831,13 @@ async fn install_local_url_mode_async(
             .iter()
             .find(|existing| existing.id == skill_id)
             .cloned();
-        let target_override =
-            resolve_url_mode_install_targets(&req.target, existing_skill.as_ref(), ui).await?;
+        let target_override = resolve_url_mode_install_targets(
+            &req.target,
+            existing_skill.as_ref(),
+            req.project.then_some(config_dir.as_path()),
+            ui,
+        )
+        .await?;
         upsert_mode_a_skill(
             &mut config,
             &skill_id,
@@ -649,7 +865,11 @@ async fn install_local_url_mode_async(
         return Ok(());
     }
 
-    write_normalized_config(config_path, &config)?;
+    write_normalized_config(
+        config_path,
+        &config,
+        req.options.user_config_path.as_deref(),
+    )?;
 
     let mut execution_summary = InstallExecutionSummary::default();
     for skill_id in &selected_ids {
@@ -670,8 +890,16 @@ async fn install_local_url_mode_async(
         execution_summary.merge(skill_summary);
     }
 
-    let full_loaded = load_config_with_context(config_path, false)?;
-    write_lock_for_config(config_path, &full_loaded.config, &config_dir)?;
+    let full_loaded =
+        load_config_with_context(config_path, false, req.options.user_config_path.as_deref())?;
+    write_lock_for_config(
+        config_path,
+        req.options.user_config_path.as_deref(),
+        &full_loaded.config,
+        &config_dir,
+        DigestRefresh::Skills(&selected_ids),
+    )?;
+    record_generation_for_config(config_path, &full_loaded.config, &config_dir, "install")?;
 
     if req.options.json {
         let payload = serde_json::json!({
@@ -695,6 +923,8 @@ fn select_config_skills(config: &Config, skill_ids: &[String]) -> Config {
         version: config.version,
         storage_root: config.storage_root.clone(),
         reactor: config.reactor,
+        git: config.git.clone(),
+        mirrors: config.mirrors.clone(),
         skills: config
             .skills
             .iter()
@@ -709,6 +939,8 @@ fn select_single_skill_config(config: &Config, skill_id: &str) -> Result<Config,
         version: config.version,
         storage_root: config.storage_root.clone(),
         reactor: config.reactor,
+        git: config.git.clone(),
+        mirrors: config.mirrors.clone(),
         skills: config
             .skills
             .iter()
@@ -737,11 +969,18 @@ fn unique_agent_count(config: &Config) -> usize {
 
 async fn resolve_registry_mode_install_targets(
     target_specs: &[String],
+    project_root: Option<&Path>,
     ui: &UiContext,
 ) -> Result<Option<Vec<TargetConfig>>, EdenError> {
     match target_specs {
-        [] => Ok(None),
-        [spec] => Ok(Some(resolve_install_special_target_spec(spec, ui).await?)),
+        [] => Ok(project_root.map(|root| detect_project_install_targets(root, ui))),
+        [spec] => {
+            let targets = resolve_install_special_target_spec(spec, ui).await?;
+            if project_root.is_some() {
+                return scope_install_targets_to_project(targets).map(Some);
+            }
+            Ok(Some(targets))
+        }
         _ => Err(EdenError::InvalidArguments(
             "registry-mode install accepts at most one --target (`local` or `docker:<container>`)"
                 .to_string(),
@@ -752,16 +991,25 @@ async fn resolve_registry_mode_install_targets(
 async fn resolve_url_mode_install_targets(
     target_specs: &[String],
     existing_skill: Option<&SkillConfig>,
+    project_root: Option<&Path>,
     ui: &UiContext,
 ) -> Result<Option<Vec<TargetConfig>>, EdenError> {
     if !target_specs.is_empty() {
-        return resolve_explicit_install_targets(target_specs, ui).await;
+        let targets = resolve_explicit_install_targets(target_specs, ui).await?;
+        if project_root.is_some() {
+            return targets.map(scope_install_targets_to_project).transpose();
+        }
+        return Ok(targets);
     }
 
     if existing_skill.is_some_and(should_preserve_existing_targets) {
         return Ok(None);
     }
 
+    if let Some(project_root) = project_root {
+        return Ok(Some(detect_project_install_targets(project_root, ui)));
+    }
+
     let detected = detect_installed_agent_targets()?;
     if !detected.is_empty() {
         return Ok(Some(detected));
@@ -774,6 +1022,37 @@ async fn resolve_url_mode_install_targets(
     Ok(Some(vec![default_install_target()]))
 }
 
+fn detect_project_install_targets(project_root: &Path, ui: &UiContext) -> Vec<TargetConfig> {
+    let detected = detect_project_agent_targets(project_root);
+    if !detected.is_empty() {
+        return detected;
+    }
+
+    print_warning(
+        ui,
+        "No project agent directories detected; defaulting to claude-code (.claude/skills/)",
+    );
+    vec![default_project_install_target()]
+}
+
+fn scope_install_targets_to_project(
+    targets: Vec<TargetConfig>,
+) -> Result<Vec<TargetConfig>, EdenError> {
+    targets
+        .into_iter()
+        .map(|mut target| {
+            if target.environment != "local" {
+                return Err(EdenError::InvalidArguments(format!(
+                    "`--project` only supports local targets (got `{}`)",
+                    target.environment
+                )));
+            }
+            target.scope = TargetScope::Project;
+            Ok(target)
+        })
+        .collect()
+}
+
 async fn resolve_explicit_install_targets(
     target_specs: &[String],
     ui: &UiContext,
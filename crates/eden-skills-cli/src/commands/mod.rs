@@ -6,32 +6,92 @@
 
 mod clean;
 pub(crate) mod common;
+mod config_edit;
 mod config_ops;
 mod diagnose;
+mod diff_cmd;
 mod docker_cmd;
+mod history_cmd;
 mod install;
 mod plan_cmd;
 mod reconcile;
+mod registry_cmd;
 mod remove;
 mod update;
 
 pub use clean::*;
 pub use config_ops::*;
 pub use diagnose::*;
+pub use diff_cmd::*;
 pub use docker_cmd::*;
+pub use history_cmd::*;
 pub use install::*;
 pub use plan_cmd::*;
 pub use reconcile::*;
+pub use registry_cmd::*;
 pub use remove::*;
 pub use update::*;
 
+use std::path::PathBuf;
+
 use eden_skills_core::config::InstallMode;
 
-/// Flags shared by every CLI command: strict mode and JSON output.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Flags shared by every CLI command: strict mode, JSON output, offline
+/// mode, and the user-global config layer.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct CommandOptions {
     pub strict: bool,
     pub json: bool,
+    /// Never touch the network; sync only from existing cache entries.
+    pub offline: bool,
+    /// User-global config merged beneath the command's config
+    /// (`--project`); `None` loads the config on its own.
+    pub user_config_path: Option<PathBuf>,
+}
+
+impl CommandOptions {
+    /// These options with a command's own `--strict` and `--json` flags.
+    pub fn for_command(&self, strict: bool, json: bool) -> Self {
+        Self {
+            strict,
+            json,
+            ..self.clone()
+        }
+    }
+}
+
+/// Parameters for the `plan` command.
+#[derive(Debug, Clone)]
+pub struct PlanRequest {
+    pub config_path: String,
+    /// Save the plan and its fingerprints to this file for `apply <planfile>`.
+    pub out: Option<String>,
+    /// List file-level differences under each copy-mode update.
+    pub diff: bool,
+    pub options: CommandOptions,
+}
+
+/// Overall result of a computed plan, as reported by `--detailed-exitcode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStatus {
+    /// Every target is a noop.
+    UpToDate,
+    /// At least one create, update, or remove is pending.
+    ChangesPending,
+    /// At least one target is in conflict (takes precedence over changes).
+    Conflicts(usize),
+}
+
+/// How `apply` treats the commits pinned in `skills.lock`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockMode {
+    /// Follow configured refs and rewrite the lock afterwards.
+    #[default]
+    Sync,
+    /// Check out exactly the locked commits; fail if the lock is missing or stale.
+    Locked,
+    /// Like [`LockMode::Locked`], but never touch the network.
+    Frozen,
 }
 
 /// Parameters for the `update` command.
@@ -40,6 +100,7 @@ pub struct UpdateRequest {
     pub config_path: String,
     pub concurrency: Option<usize>,
     pub apply: bool,
+    pub dry_run: bool,
     pub options: CommandOptions,
 }
 
@@ -47,9 +108,11 @@ pub struct UpdateRequest {
 #[derive(Debug, Clone)]
 pub struct InstallRequest {
     pub config_path: String,
+    pub project: bool,
     pub source: String,
     pub id: Option<String>,
     pub r#ref: Option<String>,
+    pub sha256: Option<String>,
     pub skill: Vec<String>,
     pub all: bool,
     pub yes: bool,
@@ -2,19 +2,31 @@
 //!
 //! Computes the lock diff and builds an action plan without performing
 //! any side effects. Renders as colored text for small plans or as a
-//! table when the action count exceeds a threshold.
+//! table when the action count exceeds a threshold. `--out` additionally
+//! saves the plan with input fingerprints for `apply <planfile>`, and
+//! `--diff` lists the files behind each copy-mode update. Skills with
+//! `requires` get a dependency tree after the plan.
+
+use std::collections::BTreeMap;
+use std::path::Path;
 
 use comfy_table::{ColumnConstraint, Width};
-use eden_skills_core::config::{config_dir_from_path, InstallMode};
+use eden_skills_core::config::{config_dir_from_path, Config, InstallMode, SkillConfig};
+use eden_skills_core::config_layers::ConfigLayer;
 use eden_skills_core::error::EdenError;
-use eden_skills_core::lock::{
-    compute_lock_diff, lock_path_for_config, read_lock_file, LockSkillEntry,
-};
+use eden_skills_core::lock::{compute_lock_diff, lock_path_for_config, LockFile, LockSkillEntry};
 use eden_skills_core::plan::{build_plan, Action, PlanItem};
+use eden_skills_core::plan_file::{fingerprint_plan, write_plan_file, PlanFile, PLAN_FILE_VERSION};
+use eden_skills_core::tree_diff::{diff_trees, FileChange};
 use owo_colors::OwoColorize;
 
-use super::common::{load_config_with_context, print_warning, resolve_config_path};
-use super::CommandOptions;
+use super::common::{
+    load_config_with_context, print_warning, push_last_row_cell, read_effective_config_toml,
+    read_lock_for_config, resolve_config_path, resolve_registry_mode_skills_for_execution,
+    user_config_layer_for,
+};
+use super::diff_cmd::{print_diff_header, print_file_changes};
+use super::{CommandOptions, PlanRequest, PlanStatus};
 use crate::ui::{StatusSymbol, UiContext};
 
 /// Preview planned reconciliation actions without side effects.
@@ -27,9 +39,31 @@ use crate::ui::{StatusSymbol, UiContext};
 ///
 /// Returns [`EdenError`] on config load, lock read, or plan build failures.
 pub fn plan(config_path: &str, options: CommandOptions) -> Result<(), EdenError> {
-    let config_path_buf = resolve_config_path(config_path)?;
+    plan_with_request(PlanRequest {
+        config_path: config_path.to_string(),
+        out: None,
+        diff: false,
+        options,
+    })
+    .map(|_| ())
+}
+
+/// [`plan`] with the full set of `plan` options, reporting whether the
+/// plan is up to date, has pending changes, or has conflicts.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] on config load, lock read, plan build, or plan
+/// file write failures.
+pub fn plan_with_request(request: PlanRequest) -> Result<PlanStatus, EdenError> {
+    let options = request.options;
+    let config_path_buf = resolve_config_path(&request.config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     for warning in &loaded.warnings {
         print_warning(&ui, warning);
@@ -37,20 +71,248 @@ pub fn plan(config_path: &str, options: CommandOptions) -> Result<(), EdenError>
 
     let config_dir = config_dir_from_path(config_path);
     let lock_path = lock_path_for_config(config_path);
-    let lock = read_lock_file(&lock_path)?;
-    let diff = compute_lock_diff(&loaded.config, &lock, &config_dir)?;
+    let lock = read_lock_for_config(config_path, options.user_config_path.as_deref())?;
+    let plan_config = with_resolved_dependencies(
+        config_path,
+        options.user_config_path.as_deref(),
+        &loaded.config,
+        lock.as_ref(),
+        &config_dir,
+        &ui,
+    );
+    let diff = compute_lock_diff(&plan_config, &lock, &config_dir)?;
 
-    let mut plan = build_plan(&loaded.config, &config_dir)?;
-    plan.extend(build_remove_plan_items(&diff.removed));
+    let mut plan = build_plan(&plan_config, &config_dir)?;
+    for item in &mut plan {
+        item.layer = loaded.skill_layer(&item.skill_id);
+    }
+    plan.extend(build_remove_plan_items(&diff.removed, &config_dir));
 
+    let file_changes = if request.diff {
+        collect_update_file_changes(&plan)?
+    } else {
+        BTreeMap::new()
+    };
     if options.json {
-        print_plan_json(&plan)?;
+        if request.diff {
+            print_plan_json_with_changes(&plan, &file_changes)?;
+        } else {
+            print_plan_json(&plan)?;
+        }
     } else {
         print_plan_text(&ui, &plan);
+        print_requires_tree(&ui, &plan_config);
+        for (index, changes) in &file_changes {
+            let item = &plan[*index];
+            println!();
+            print_diff_header(&ui, &item.skill_id, &item.target_path);
+            print_file_changes(&ui, changes);
+        }
     }
+
+    let status = plan_status(&plan);
+    if let Some(out) = &request.out {
+        let out_path = resolve_config_path(out)?;
+        let saved = PlanFile {
+            version: PLAN_FILE_VERSION,
+            config_path: config_path.display().to_string(),
+            user_config_path: user_config_layer_for(
+                config_path,
+                options.user_config_path.as_deref(),
+            )
+            .map(|path| path.display().to_string()),
+            fingerprints: fingerprint_plan(
+                &read_effective_config_toml(config_path, options.user_config_path.as_deref())?,
+                &lock_path,
+                &plan,
+            )?,
+            items: plan,
+        };
+        write_plan_file(&out_path, &saved)?;
+        if !options.json {
+            println!();
+            println!(
+                "  {} Saved plan to {}",
+                ui.status_symbol(StatusSymbol::Success),
+                ui.styled_path(&out_path.display().to_string())
+            );
+            println!(
+                "  Run `eden-skills apply {}` to execute exactly these actions.",
+                out_path.display()
+            );
+        }
+    }
+    Ok(status)
+}
+
+/// `config` plus the implicit skills its `requires` pull in, so they are
+/// planned rather than reported as removed. Resolution only runs when the
+/// config or lock records dependencies; a failure is reported as a warning
+/// and leaves `config` as is.
+fn with_resolved_dependencies(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+    config: &Config,
+    lock: Option<&LockFile>,
+    config_dir: &Path,
+    ui: &UiContext,
+) -> Config {
+    let declares_requires = config.skills.iter().any(|skill| !skill.requires.is_empty());
+    let locks_dependencies = lock.is_some_and(|lock| {
+        lock.skills
+            .iter()
+            .any(|entry| entry.implicit || !entry.requires.is_empty())
+    });
+    if !declares_requires && !locks_dependencies {
+        return config.clone();
+    }
+    match resolve_registry_mode_skills_for_execution(
+        config_path,
+        user_config_path,
+        config,
+        config_dir,
+        ui,
+    ) {
+        Ok(resolved) => {
+            let mut planned = config.clone();
+            for (skill, resolved_skill) in planned.skills.iter_mut().zip(&resolved.skills) {
+                skill.requires = resolved_skill.requires.clone();
+            }
+            planned
+                .skills
+                .extend(resolved.skills.into_iter().filter(|skill| skill.implicit));
+            planned
+        }
+        Err(err) => {
+            print_warning(ui, &format!("could not resolve skill dependencies: {err}"));
+            config.clone()
+        }
+    }
+}
+
+/// The `requires` of every configured skill that has any, as a tree.
+fn print_requires_tree(ui: &UiContext, config: &Config) {
+    let roots = config
+        .skills
+        .iter()
+        .filter(|skill| !skill.implicit && !skill.requires.is_empty())
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("  Requires:");
+    for root in roots {
+        println!("    {}", ui.styled_skill_id(&root.id));
+        let mut path = vec![root.id.as_str()];
+        print_requires_children(ui, config, root, "    ", &mut path);
+    }
+}
+
+fn print_requires_children<'a>(
+    ui: &UiContext,
+    config: &'a Config,
+    skill: &'a SkillConfig,
+    prefix: &str,
+    path: &mut Vec<&'a str>,
+) {
+    let count = skill.requires.len();
+    for (index, (name, constraint)) in skill.requires.iter().enumerate() {
+        let last = index + 1 == count;
+        let required = config.skills.iter().find(|candidate| &candidate.id == name);
+        let implicit_label = if required.is_some_and(|required| required.implicit) {
+            format!(" {}", ui.styled_secondary("(implicit)"))
+        } else {
+            String::new()
+        };
+        println!(
+            "{prefix}{} {} {}{}",
+            style_tree_connector(ui, if last { "└─" } else { "├─" }),
+            ui.styled_skill_id(name),
+            ui.styled_secondary(constraint),
+            implicit_label
+        );
+        let Some(required) = required else {
+            continue;
+        };
+        if path.contains(&name.as_str()) {
+            continue;
+        }
+        path.push(name.as_str());
+        let child_prefix = format!("{prefix}{}", if last { "   " } else { "│  " });
+        print_requires_children(ui, config, required, &child_prefix, path);
+        path.pop();
+    }
+}
+
+fn style_tree_connector(ui: &UiContext, connector: &str) -> String {
+    if ui.colors_enabled() {
+        connector.dimmed().to_string()
+    } else {
+        connector.to_string()
+    }
+}
+
+/// File-level changes behind every copy-mode update, keyed by plan index.
+fn collect_update_file_changes(
+    items: &[PlanItem],
+) -> Result<BTreeMap<usize, Vec<FileChange>>, EdenError> {
+    let mut changes = BTreeMap::new();
+    for (index, item) in items.iter().enumerate() {
+        if item.action != Action::Update || item.install_mode != InstallMode::Copy {
+            continue;
+        }
+        changes.insert(
+            index,
+            diff_trees(Path::new(&item.source_path), Path::new(&item.target_path))?,
+        );
+    }
+    Ok(changes)
+}
+
+fn print_plan_json_with_changes(
+    items: &[PlanItem],
+    file_changes: &BTreeMap<usize, Vec<FileChange>>,
+) -> Result<(), EdenError> {
+    let to_json_err = |err: serde_json::Error| {
+        EdenError::Runtime(format!("failed to serialize plan as json: {err}"))
+    };
+    let mut payload = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let mut value = serde_json::to_value(item).map_err(to_json_err)?;
+        if let (Some(changes), Some(object)) = (file_changes.get(&index), value.as_object_mut()) {
+            object.insert(
+                "changes".to_string(),
+                serde_json::to_value(changes).map_err(to_json_err)?,
+            );
+        }
+        payload.push(value);
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&payload).map_err(to_json_err)?
+    );
     Ok(())
 }
 
+fn plan_status(items: &[PlanItem]) -> PlanStatus {
+    let conflicts = items
+        .iter()
+        .filter(|item| matches!(item.action, Action::Conflict))
+        .count();
+    if conflicts > 0 {
+        PlanStatus::Conflicts(conflicts)
+    } else if items
+        .iter()
+        .any(|item| !matches!(item.action, Action::Noop))
+    {
+        PlanStatus::ChangesPending
+    } else {
+        PlanStatus::UpToDate
+    }
+}
+
 pub(crate) fn print_plan_text(ui: &UiContext, items: &[PlanItem]) {
     const TABLE_THRESHOLD: usize = 5;
     let has_pending_action = items
@@ -74,13 +336,18 @@ pub(crate) fn print_plan_text(ui: &UiContext, items: &[PlanItem]) {
 
     for item in items {
         let mode_label = style_mode_label(ui, item.install_mode.as_str());
+        let layer_label = item
+            .layer
+            .map(|layer| format!(" {}", style_layer_label(ui, layer.as_str())))
+            .unwrap_or_default();
         println!(
-            "  {}  {} {} {} {}",
+            "  {}  {} {} {} {}{}",
             style_plan_action_label(ui, item.action),
             ui.styled_skill_id(&item.skill_id),
             style_arrow(ui),
             ui.styled_path(&item.target_path),
-            mode_label
+            mode_label,
+            layer_label
         );
         for reason in &item.reasons {
             println!("           reason: {reason}");
@@ -89,7 +356,12 @@ pub(crate) fn print_plan_text(ui: &UiContext, items: &[PlanItem]) {
 }
 
 fn print_plan_table(ui: &UiContext, items: &[PlanItem]) {
-    let mut table = ui.table(&["Action", "Skill", "Target", "Mode"]);
+    let layered = items.iter().any(|item| item.layer.is_some());
+    let mut table = if layered {
+        ui.table(&["Action", "Skill", "Target", "Mode", "Layer"])
+    } else {
+        ui.table(&["Action", "Skill", "Target", "Mode"])
+    };
     if let Some(column) = table.column_mut(0) {
         column.set_constraint(ColumnConstraint::LowerBoundary(Width::Fixed(10)));
     }
@@ -103,6 +375,10 @@ fn print_plan_table(ui: &UiContext, items: &[PlanItem]) {
             ui.styled_path(&item.target_path),
             ui.styled_secondary(item.install_mode.as_str()),
         ]);
+        if layered {
+            let layer = item.layer.map_or("-", ConfigLayer::as_str);
+            push_last_row_cell(&mut table, ui.styled_secondary(layer));
+        }
     }
     println!("{table}");
 
@@ -165,6 +441,11 @@ fn style_mode_label(ui: &UiContext, mode: &str) -> String {
     ui.styled_secondary(&raw)
 }
 
+fn style_layer_label(ui: &UiContext, layer: &str) -> String {
+    let raw = format!("[{layer}]");
+    ui.styled_secondary(&raw)
+}
+
 fn style_arrow(ui: &UiContext) -> String {
     ui.hint_prefix()
 }
@@ -173,14 +454,17 @@ fn plan_action_cell(action: Action) -> String {
     action_label(action).to_string()
 }
 
-pub(crate) fn build_remove_plan_items(removed: &[LockSkillEntry]) -> Vec<PlanItem> {
+pub(crate) fn build_remove_plan_items(
+    removed: &[LockSkillEntry],
+    config_dir: &Path,
+) -> Vec<PlanItem> {
     let mut items = Vec::new();
     for entry in removed {
         for target in &entry.targets {
             items.push(PlanItem {
                 skill_id: entry.id.clone(),
                 source_path: String::new(),
-                target_path: target.path.clone(),
+                target_path: target.resolved_path(config_dir).display().to_string(),
                 install_mode: if entry.install_mode == "copy" {
                     InstallMode::Copy
                 } else {
@@ -188,6 +472,7 @@ pub(crate) fn build_remove_plan_items(removed: &[LockSkillEntry]) -> Vec<PlanIte
                 },
                 action: Action::Remove,
                 reasons: vec!["skill removed from configuration".to_string()],
+                layer: None,
             });
         }
     }
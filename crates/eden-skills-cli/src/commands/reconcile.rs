@@ -3,7 +3,11 @@
 //! Both commands follow the same lifecycle: source sync → safety analysis →
 //! orphan removal → plan execution → verification → lock file write.
 //! `repair` additionally force-reinstalls every target regardless of drift
-//! status, ensuring convergence from any starting state.
+//! status, ensuring convergence from any starting state.  `apply --locked`
+//! and `--frozen` replace the source sync with a checkout of the commits
+//! pinned in `skills.lock` and leave the lock untouched. `apply <planfile>`
+//! skips planning altogether and executes a saved plan after checking
+//! that none of its inputs changed.
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -13,30 +17,34 @@ use eden_skills_core::adapter::create_adapter;
 use eden_skills_core::config::{config_dir_from_path, Config};
 use eden_skills_core::error::EdenError;
 use eden_skills_core::lock::{
-    compute_lock_diff, lock_path_for_config, read_lock_file, LockSkillEntry, SkillDiffStatus,
+    compute_lock_diff, lock_path_for_config, DigestRefresh, LockDiffResult, LockFile,
+    LockSkillEntry, SkillDiffStatus,
 };
 use eden_skills_core::managed::{external_install_origin, ManagedSource};
 use eden_skills_core::paths::{known_default_agent_paths, resolve_path_string};
 use eden_skills_core::plan::{build_plan, Action};
+use eden_skills_core::plan_file::{fingerprint_plan, read_plan_file};
 use eden_skills_core::reactor::SkillReactor;
 use eden_skills_core::safety::{analyze_skills, persist_reports, SkillSafetyReport};
 use eden_skills_core::source::{
-    repo_cache_key, resolve_skill_storage_root, sync_sources_async_with_reactor,
-    sync_sources_async_with_reactor_skipping_repos,
+    is_local_source_repo, repo_cache_key, sync_sources_async_with_reactor,
+    sync_sources_async_with_reactor_skipping_repos, sync_sources_locked_async,
+    sync_sources_offline_async, LockedSync,
 };
 use eden_skills_core::verify::verify_config_state;
 use owo_colors::OwoColorize;
 
 use super::common::{
-    apply_plan_item, block_on_command_future, ensure_docker_available_for_targets,
-    ensure_git_available, load_config_with_context, print_safety_summary_human,
-    print_source_sync_summary_human, print_warning, read_head_sha, remove_path,
+    block_on_command_future, check_source_sync, collect_resolved_commits,
+    ensure_docker_available_for_targets, ensure_git_available, load_config_with_context,
+    print_safety_summary_human, print_source_sync_summary_human, print_warning,
+    read_effective_config_toml, read_lock_for_config, record_generation_for_config, remove_path,
     resolve_config_path, resolve_effective_reactor_concurrency,
-    resolve_registry_mode_skills_for_execution, source_sync_failure_error, style_count_for_action,
-    write_lock_for_config, write_lock_for_config_with_commits,
+    resolve_registry_mode_skills_for_execution, style_count_for_action, with_hint,
+    write_lock_for_config, write_lock_for_config_with_commits, TargetTransaction,
 };
 
-use super::CommandOptions;
+use super::{CommandOptions, LockMode};
 use crate::ui::{StatusSymbol, UiContext};
 use eden_skills_core::adapter::{read_managed_manifest, write_managed_manifest};
 
@@ -71,10 +79,41 @@ pub async fn apply_async(
     options: CommandOptions,
     concurrency_override: Option<usize>,
     force: bool,
+) -> Result<(), EdenError> {
+    apply_with_lock_mode_async(
+        config_path,
+        options,
+        concurrency_override,
+        force,
+        LockMode::Sync,
+    )
+    .await
+}
+
+/// [`apply_async`] with an explicit [`LockMode`].
+///
+/// In [`LockMode::Locked`] and [`LockMode::Frozen`] the lock must exist and
+/// match the config exactly; sources are checked out at their locked
+/// commits and the lock file is not rewritten.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Conflict`] when the lock is missing or stale in a
+/// locked mode, plus every error of [`apply_async`].
+pub async fn apply_with_lock_mode_async(
+    config_path: &str,
+    options: CommandOptions,
+    concurrency_override: Option<usize>,
+    force: bool,
+    lock_mode: LockMode,
 ) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     for warning in &loaded.warnings {
         print_warning(&ui, warning);
@@ -86,14 +125,22 @@ pub async fn apply_async(
     )?;
     let reactor = SkillReactor::new(concurrency).map_err(EdenError::from)?;
     let config_dir = config_dir_from_path(config_path);
-    let execution_config =
-        resolve_registry_mode_skills_for_execution(config_path, &loaded.config, &config_dir, &ui)?;
-    if !execution_config.skills.is_empty() {
-        ensure_git_available()?;
+    let execution_config = resolve_registry_mode_skills_for_execution(
+        config_path,
+        options.user_config_path.as_deref(),
+        &loaded.config,
+        &config_dir,
+        &ui,
+    )?;
+    if execution_config
+        .skills
+        .iter()
+        .any(|skill| !skill.source.is_archive())
+    {
+        ensure_git_available(execution_config.git.backend)?;
     }
 
-    let lock_path = lock_path_for_config(config_path);
-    let lock = read_lock_file(&lock_path)?;
+    let lock = read_lock_for_config(config_path, options.user_config_path.as_deref())?;
     let diff = compute_lock_diff(&execution_config, &lock, &config_dir)?;
     ensure_docker_available_for_targets(diff.removed.iter().flat_map(|entry| {
         entry
@@ -102,21 +149,37 @@ pub async fn apply_async(
             .map(|target| target.environment.as_str())
     }))?;
 
-    let skip_repos = skip_repo_cache_keys_for_apply(&execution_config, &diff);
-    let sync_summary = sync_sources_async_with_reactor_skipping_repos(
-        &execution_config,
-        &config_dir,
-        reactor,
-        &skip_repos,
-    )
-    .await?;
-    print_source_sync_summary_human(&ui, &sync_summary);
+    let sync_summary = if lock_mode == LockMode::Sync && options.offline {
+        sync_sources_offline_async(&execution_config, &config_dir, reactor).await?
+    } else if lock_mode == LockMode::Sync {
+        let skip_repos = skip_repo_cache_keys_for_apply(&execution_config, &diff);
+        sync_sources_async_with_reactor_skipping_repos(
+            &execution_config,
+            &config_dir,
+            reactor,
+            &skip_repos,
+        )
+        .await?
+    } else {
+        let pins = locked_source_pins(&execution_config, lock.as_ref(), &diff, lock_mode)?;
+        sync_sources_locked_async(
+            &execution_config,
+            &config_dir,
+            reactor,
+            LockedSync {
+                pins: &pins,
+                allow_network: lock_mode != LockMode::Frozen && !options.offline,
+            },
+        )
+        .await?
+    };
     let safety_reports = analyze_skills(&execution_config, &config_dir)?;
     persist_reports(&safety_reports)?;
-    print_safety_summary_human(&ui, &safety_reports);
-    if let Some(err) = source_sync_failure_error(&sync_summary) {
-        return Err(err);
+    if !options.json {
+        print_source_sync_summary_human(&ui, &sync_summary);
+        print_safety_summary_human(&ui, &safety_reports);
     }
+    check_source_sync(&sync_summary, "apply", options.json)?;
 
     let removed_skill_ids =
         uninstall_orphaned_lock_entries(&diff.removed, &config_dir, &execution_config.storage_root)
@@ -136,6 +199,7 @@ pub async fn apply_async(
     let mut noops = 0usize;
     let mut conflicts = 0usize;
 
+    let mut transaction = TargetTransaction::new();
     for item in &plan {
         match item.action {
             Action::Create => {
@@ -145,7 +209,7 @@ pub async fn apply_async(
                     push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
                     continue;
                 }
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 created += 1;
                 applied_targets.push(AppliedInstallTargetLine {
                     skill_id: item.skill_id.clone(),
@@ -160,7 +224,7 @@ pub async fn apply_async(
                     push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
                     continue;
                 }
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 updated += 1;
                 applied_targets.push(AppliedInstallTargetLine {
                     skill_id: item.skill_id.clone(),
@@ -183,6 +247,7 @@ pub async fn apply_async(
             Action::Remove => {}
         }
     }
+    transaction.commit();
     if force {
         reclaim_docker_ownership(&execution_config, &config_dir).await?;
     }
@@ -216,13 +281,188 @@ pub async fn apply_async(
         )));
     }
 
+    if lock_mode == LockMode::Sync {
+        let resolved_commits = collect_resolved_commits(&execution_config, &config_dir);
+        write_lock_for_config_with_commits(
+            config_path,
+            options.user_config_path.as_deref(),
+            &execution_config,
+            &config_dir,
+            &resolved_commits,
+            DigestRefresh::All,
+        )?;
+    }
+    record_generation_for_config(config_path, &execution_config, &config_dir, "apply")?;
+
+    println!(
+        "  {} Verification passed",
+        ui.status_symbol(StatusSymbol::Success)
+    );
+    Ok(())
+}
+
+/// Execute a plan saved by `plan --out`.
+///
+/// Recomputes the fingerprints of the config, lock, source trees, and
+/// target paths the plan was made from and refuses to run if any of them
+/// changed. Sources are not synced; only the saved create, update, and
+/// remove actions are performed, followed by verification and a lock
+/// write.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Conflict`] when the saved plan is stale, plus
+/// every error of [`apply_async`].
+pub async fn apply_plan_file_async(
+    plan_path: &str,
+    options: CommandOptions,
+) -> Result<(), EdenError> {
+    let plan_path = resolve_config_path(plan_path)?;
+    let saved = read_plan_file(&plan_path)?;
+    let options = CommandOptions {
+        user_config_path: saved.user_config_path.as_ref().map(PathBuf::from),
+        ..options
+    };
+    let config_path = PathBuf::from(&saved.config_path);
+    let loaded = load_config_with_context(
+        &config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    let ui = UiContext::from_env(options.json);
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
+    }
+
+    let lock_path = lock_path_for_config(&config_path);
+    let current = fingerprint_plan(
+        &read_effective_config_toml(&config_path, options.user_config_path.as_deref())?,
+        &lock_path,
+        &saved.items,
+    )?;
+    let changes = current.changes_since(&saved.fingerprints);
+    if !changes.is_empty() {
+        return Err(EdenError::Conflict(with_hint(
+            format!(
+                "saved plan `{}` is stale: {}",
+                plan_path.display(),
+                changes.join(", ")
+            ),
+            "Run `eden-skills plan --out <file>` again and review the new plan.",
+        )));
+    }
+
+    let config_dir = config_dir_from_path(&config_path);
+    let execution_config = resolve_registry_mode_skills_for_execution(
+        &config_path,
+        options.user_config_path.as_deref(),
+        &loaded.config,
+        &config_dir,
+        &ui,
+    )?;
+    let safety_reports = analyze_skills(&execution_config, &config_dir)?;
+    let no_exec_skill_ids = no_exec_skill_ids(&safety_reports);
+
+    let lock = read_lock_for_config(&config_path, options.user_config_path.as_deref())?;
+    let diff = compute_lock_diff(&execution_config, &lock, &config_dir)?;
+    let planned_removals = saved
+        .items
+        .iter()
+        .filter(|item| matches!(item.action, Action::Remove))
+        .map(|item| item.skill_id.as_str())
+        .collect::<HashSet<_>>();
+    let removed_entries = diff
+        .removed
+        .into_iter()
+        .filter(|entry| planned_removals.contains(entry.id.as_str()))
+        .collect::<Vec<_>>();
+    ensure_docker_available_for_targets(removed_entries.iter().flat_map(|entry| {
+        entry
+            .targets
+            .iter()
+            .map(|target| target.environment.as_str())
+    }))?;
+    let removed_skill_ids = uninstall_orphaned_lock_entries(
+        &removed_entries,
+        &config_dir,
+        &execution_config.storage_root,
+    )
+    .await?;
+    print_remove_lines(&ui, &removed_skill_ids);
+
+    let mut applied_targets: Vec<AppliedInstallTargetLine> = Vec::new();
+    let mut skipped_skill_ids: Vec<String> = Vec::new();
+    let mut created = 0usize;
+    let mut updated = 0usize;
+    let mut noops = 0usize;
+    let mut conflicts = 0usize;
+
+    let mut transaction = TargetTransaction::new();
+    for item in &saved.items {
+        match item.action {
+            Action::Create | Action::Update => {
+                if no_exec_skill_ids.contains(item.skill_id.as_str()) {
+                    push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
+                    continue;
+                }
+                transaction.apply(item)?;
+                if matches!(item.action, Action::Create) {
+                    created += 1;
+                } else {
+                    updated += 1;
+                }
+                applied_targets.push(AppliedInstallTargetLine {
+                    skill_id: item.skill_id.clone(),
+                    target_path: item.target_path.clone(),
+                    mode: item.install_mode.as_str().to_string(),
+                });
+            }
+            Action::Noop => noops += 1,
+            Action::Conflict => conflicts += 1,
+            Action::Remove => {}
+        }
+    }
+    transaction.commit();
+    print_install_result_lines(&ui, &applied_targets, &skipped_skill_ids);
+
+    println!(
+        "{}  {} {} created, {} updated, {} noop, {} conflicts, {} removed",
+        ui.action_prefix("Summary"),
+        ui.status_symbol(StatusSymbol::Success),
+        style_count_for_action(&ui, "create", created),
+        style_count_for_action(&ui, "update", updated),
+        style_count_for_action(&ui, "noop", noops),
+        style_count_for_action(&ui, "conflict", conflicts),
+        style_count_for_action(&ui, "remove", removed_skill_ids.len()),
+    );
+
+    if options.strict && conflicts > 0 {
+        return Err(EdenError::Conflict(format!(
+            "strict mode blocked apply: {conflicts} conflict entries"
+        )));
+    }
+
+    let verify_issues = verify_config_state(&execution_config, &config_dir)?;
+    if !verify_issues.is_empty() {
+        return Err(EdenError::Runtime(format!(
+            "post-apply verification failed with {} issue(s); first: [{}] {} {}",
+            verify_issues.len(),
+            verify_issues[0].check,
+            verify_issues[0].skill_id,
+            verify_issues[0].message
+        )));
+    }
+
     let resolved_commits = collect_resolved_commits(&execution_config, &config_dir);
     write_lock_for_config_with_commits(
-        config_path,
+        &config_path,
+        options.user_config_path.as_deref(),
         &execution_config,
         &config_dir,
         &resolved_commits,
+        DigestRefresh::All,
     )?;
+    record_generation_for_config(&config_path, &execution_config, &config_dir, "apply")?;
 
     println!(
         "  {} Verification passed",
@@ -231,6 +471,85 @@ pub async fn apply_async(
     Ok(())
 }
 
+/// Map each repo cache entry to the commit recorded in `lock`, refusing
+/// to proceed when the lock is missing or no longer matches the config.
+fn locked_source_pins(
+    config: &Config,
+    lock: Option<&LockFile>,
+    diff: &LockDiffResult,
+    lock_mode: LockMode,
+) -> Result<HashMap<String, String>, EdenError> {
+    let flag = if lock_mode == LockMode::Frozen {
+        "--frozen"
+    } else {
+        "--locked"
+    };
+    let Some(lock) = lock else {
+        return Err(EdenError::Conflict(with_hint(
+            format!("{flag} requires skills.lock, but it is missing or unreadable"),
+            format!("Run `eden-skills apply` without {flag} to create it."),
+        )));
+    };
+
+    let mut stale = Vec::new();
+    let mut pins = HashMap::new();
+    for skill in &config.skills {
+        match diff.statuses.get(&skill.id) {
+            Some(SkillDiffStatus::Unchanged) => {}
+            Some(SkillDiffStatus::Changed) => {
+                stale.push(format!("`{}` changed", skill.id));
+                continue;
+            }
+            _ => {
+                stale.push(format!("`{}` is not locked", skill.id));
+                continue;
+            }
+        }
+        if is_local_source_repo(&skill.source.repo) || skill.source.is_archive() {
+            continue;
+        }
+        let commit = lock
+            .skills
+            .iter()
+            .find(|entry| entry.id == skill.id)
+            .map(|entry| entry.resolved_commit.as_str())
+            .unwrap_or_default();
+        if commit.is_empty() {
+            stale.push(format!("`{}` has no resolved commit", skill.id));
+            continue;
+        }
+        let cache_key = repo_cache_key(&skill.source.repo, &skill.source.r#ref);
+        match pins.get(&cache_key) {
+            Some(pinned) if pinned != commit => {
+                stale.push(format!(
+                    "`{}` pins `{commit}` but its repo cache is pinned to `{pinned}`",
+                    skill.id
+                ));
+            }
+            Some(_) => {}
+            None => {
+                pins.insert(cache_key, commit.to_string());
+            }
+        }
+    }
+    stale.extend(
+        diff.removed
+            .iter()
+            .map(|entry| format!("`{}` is no longer configured", entry.id)),
+    );
+
+    if !stale.is_empty() {
+        return Err(EdenError::Conflict(with_hint(
+            format!(
+                "skills.lock is out of date with the config: {}",
+                stale.join(", ")
+            ),
+            format!("Run `eden-skills apply` without {flag} to update skills.lock."),
+        )));
+    }
+    Ok(pins)
+}
+
 /// Synchronous wrapper around [`repair_async`] using a single-threaded runtime.
 ///
 /// # Errors
@@ -257,7 +576,11 @@ pub async fn repair_async(
 ) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     for warning in &loaded.warnings {
         print_warning(&ui, warning);
@@ -269,20 +592,32 @@ pub async fn repair_async(
     )?;
     let reactor = SkillReactor::new(concurrency).map_err(EdenError::from)?;
     let config_dir = config_dir_from_path(config_path);
-    let execution_config =
-        resolve_registry_mode_skills_for_execution(config_path, &loaded.config, &config_dir, &ui)?;
-    if !execution_config.skills.is_empty() {
-        ensure_git_available()?;
-    }
-    let sync_summary =
-        sync_sources_async_with_reactor(&execution_config, &config_dir, reactor, true).await?;
-    print_source_sync_summary_human(&ui, &sync_summary);
+    let execution_config = resolve_registry_mode_skills_for_execution(
+        config_path,
+        options.user_config_path.as_deref(),
+        &loaded.config,
+        &config_dir,
+        &ui,
+    )?;
+    if execution_config
+        .skills
+        .iter()
+        .any(|skill| !skill.source.is_archive())
+    {
+        ensure_git_available(execution_config.git.backend)?;
+    }
+    let sync_summary = if options.offline {
+        sync_sources_offline_async(&execution_config, &config_dir, reactor).await?
+    } else {
+        sync_sources_async_with_reactor(&execution_config, &config_dir, reactor, true).await?
+    };
     let safety_reports = analyze_skills(&execution_config, &config_dir)?;
     persist_reports(&safety_reports)?;
-    print_safety_summary_human(&ui, &safety_reports);
-    if let Some(err) = source_sync_failure_error(&sync_summary) {
-        return Err(err);
+    if !options.json {
+        print_source_sync_summary_human(&ui, &sync_summary);
+        print_safety_summary_human(&ui, &safety_reports);
     }
+    check_source_sync(&sync_summary, "repair", options.json)?;
 
     let no_exec_skill_ids = no_exec_skill_ids(&safety_reports);
     let ownership_blocked_skill_ids =
@@ -296,6 +631,7 @@ pub async fn repair_async(
     let mut noops = 0usize;
     let mut conflicts = 0usize;
 
+    let mut transaction = TargetTransaction::new();
     for item in &plan {
         match item.action {
             Action::Create => {
@@ -305,7 +641,7 @@ pub async fn repair_async(
                     push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
                     continue;
                 }
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 created += 1;
                 applied_targets.push(AppliedInstallTargetLine {
                     skill_id: item.skill_id.clone(),
@@ -320,7 +656,7 @@ pub async fn repair_async(
                     push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
                     continue;
                 }
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 updated += 1;
                 applied_targets.push(AppliedInstallTargetLine {
                     skill_id: item.skill_id.clone(),
@@ -343,6 +679,7 @@ pub async fn repair_async(
             Action::Remove => {}
         }
     }
+    transaction.commit();
     if force {
         reclaim_docker_ownership(&execution_config, &config_dir).await?;
     }
@@ -376,7 +713,13 @@ pub async fn repair_async(
         )));
     }
 
-    write_lock_for_config(config_path, &execution_config, &config_dir)?;
+    write_lock_for_config(
+        config_path,
+        options.user_config_path.as_deref(),
+        &execution_config,
+        &config_dir,
+        DigestRefresh::All,
+    )?;
 
     println!(
         "  {} Verification passed",
@@ -420,22 +763,7 @@ fn skip_repo_cache_keys_for_apply(
     unchanged
 }
 
-fn collect_resolved_commits(config: &Config, config_dir: &Path) -> HashMap<String, String> {
-    let storage_root = match resolve_path_string(&config.storage_root, config_dir) {
-        Ok(p) => p,
-        Err(_) => return HashMap::new(),
-    };
-    let mut commits = HashMap::new();
-    for skill in &config.skills {
-        let repo_dir = resolve_skill_storage_root(&storage_root, skill);
-        if let Some(sha) = read_head_sha(&repo_dir) {
-            commits.insert(skill.id.clone(), sha);
-        }
-    }
-    commits
-}
-
-async fn uninstall_orphaned_lock_entries(
+pub(super) async fn uninstall_orphaned_lock_entries(
     removed: &[LockSkillEntry],
     config_dir: &Path,
     storage_root: &str,
@@ -443,7 +771,7 @@ async fn uninstall_orphaned_lock_entries(
     let mut removed_ids = Vec::with_capacity(removed.len());
     for entry in removed {
         for target in &entry.targets {
-            let target_path = PathBuf::from(&target.path);
+            let target_path = target.resolved_path(config_dir);
             let adapter = create_adapter(&target.environment).map_err(EdenError::from)?;
             adapter
                 .uninstall(&target_path)
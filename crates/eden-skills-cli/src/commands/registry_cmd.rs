@@ -0,0 +1,528 @@
+//! Registry index queries via the `search` and `info` commands.
+//!
+//! Both read only the registry caches synced by `eden-skills update`;
+//! they never touch the network. `search` scans every configured registry
+//! and ranks the matches across all of them, so a skill published to
+//! several registries appears once per registry. `info` shows one skill's
+//! entry in each registry and, when the skill is installed, its lock
+//! entry, targets, safety report, and `SKILL.md` frontmatter.
+
+use std::path::Path;
+
+use eden_skills_core::config::{
+    config_dir_from_path, is_registry_mode_repo, Config, LoadedConfig, SkillConfig,
+};
+use eden_skills_core::discovery::read_skill_frontmatter;
+use eden_skills_core::error::EdenError;
+use eden_skills_core::lock::LockSkillEntry;
+use eden_skills_core::paths::{resolve_path_string, resolve_target_path};
+use eden_skills_core::registry::{
+    lookup_skill_in_registry_sources, search_registry_sources, RegistrySkillInfo, RegistrySource,
+    SkillSearchHit,
+};
+use eden_skills_core::safety::{analyze_skills, SkillSafetyReport};
+use eden_skills_core::source::resolve_skill_source_path;
+
+use super::common::{
+    agent_kind_label, configured_registry_sources, load_config_with_context, print_warning,
+    read_lock_for_config, resolve_config_path, validate_registry_manifest_for_resolution,
+    with_hint,
+};
+use super::CommandOptions;
+use crate::ui::UiContext;
+
+/// Search the cached indexes of every configured registry for `query`.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Runtime`] when no registry is configured or none
+/// has been synced yet, plus config load and index read failures.
+pub fn search(config_path: &str, query: &str, options: CommandOptions) -> Result<(), EdenError> {
+    let config_path_buf = resolve_config_path(config_path)?;
+    let config_path = config_path_buf.as_path();
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    let ui = UiContext::from_env(options.json);
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
+    }
+
+    let config_dir = config_dir_from_path(config_path);
+    let sources = configured_registry_sources(
+        config_path,
+        options.user_config_path.as_deref(),
+        &loaded.config,
+        &config_dir,
+    )?;
+    if sources.is_empty() {
+        return Err(EdenError::Runtime(with_hint(
+            "no registries are configured",
+            "Add a `[registries]` table to skills.toml, then run `eden-skills update`.",
+        )));
+    }
+    let synced = synced_registry_sources(sources, &ui)?;
+    if synced.is_empty() {
+        return Err(EdenError::Runtime(
+            "Registry index not found. Run `eden-skills update` first.".to_string(),
+        ));
+    }
+
+    let search = search_registry_sources(&synced, query).map_err(EdenError::from)?;
+    for skipped in &search.skipped {
+        print_warning(&ui, &format!("skipped registry entry: {skipped}"));
+    }
+
+    if options.json {
+        let payload = serde_json::json!({
+            "query": query,
+            "results": search.hits.iter().map(|hit| serde_json::json!({
+                "name": hit.name,
+                "registry": hit.registry_name,
+                "version": hit.latest_version,
+                "yanked": hit.latest_version.is_none(),
+                "deprecated": hit.deprecated,
+                "description": hit.description,
+                "tags": hit.tags,
+                "score": hit.score,
+            })).collect::<Vec<_>>(),
+        });
+        let encoded = serde_json::to_string_pretty(&payload)
+            .map_err(|err| EdenError::Runtime(format!("failed to serialize search json: {err}")))?;
+        println!("{encoded}");
+        return Ok(());
+    }
+
+    println!(
+        "{}  {} match{} for \"{query}\"",
+        ui.action_prefix("Search"),
+        search.hits.len(),
+        if search.hits.len() == 1 { "" } else { "es" }
+    );
+    if search.hits.is_empty() {
+        return Ok(());
+    }
+    println!();
+
+    let mut table = ui.table(&["Skill", "Registry", "Version", "Status", "Description"]);
+    for hit in &search.hits {
+        table.add_row(vec![
+            ui.styled_skill_id(&hit.name),
+            hit.registry_name.clone(),
+            hit.latest_version
+                .as_deref()
+                .map_or_else(|| "-".to_string(), |version| ui.styled_version(version)),
+            search_status(&ui, hit),
+            hit.description.clone().unwrap_or_default(),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+/// Show a skill's entry in every registry that carries it and, when it
+/// is installed, its lock entry, targets, safety report, and `SKILL.md`
+/// frontmatter. `version_constraint` picks the version reported as
+/// selected, as `install --version` would.
+///
+/// # Errors
+///
+/// Returns [`EdenError::InvalidArguments`] when the skill is neither in
+/// a synced registry nor installed, plus config, lock, and index read
+/// failures.
+pub fn info(
+    config_path: &str,
+    name: &str,
+    version_constraint: Option<&str>,
+    options: CommandOptions,
+) -> Result<(), EdenError> {
+    let config_path_buf = resolve_config_path(config_path)?;
+    let config_path = config_path_buf.as_path();
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
+    let ui = UiContext::from_env(options.json);
+    for warning in &loaded.warnings {
+        print_warning(&ui, warning);
+    }
+
+    let config_dir = config_dir_from_path(config_path);
+    let sources = synced_registry_sources(
+        configured_registry_sources(
+            config_path,
+            options.user_config_path.as_deref(),
+            &loaded.config,
+            &config_dir,
+        )?,
+        &ui,
+    )?;
+    let registries = lookup_skill_in_registry_sources(&sources, name, version_constraint)
+        .map_err(EdenError::from)?;
+    let installed = installed_skill_info(
+        config_path,
+        options.user_config_path.as_deref(),
+        &config_dir,
+        &loaded,
+        name,
+    )?;
+    if registries.is_empty() && installed.is_none() {
+        return Err(EdenError::InvalidArguments(with_hint(
+            format!("skill `{name}` is not in any synced registry and is not installed"),
+            "Run `eden-skills search <query>` to find skills by name, tag, or description.",
+        )));
+    }
+
+    if options.json {
+        let payload = serde_json::json!({
+            "name": name,
+            "version_constraint": version_constraint,
+            "registries": registries.iter().map(registry_info_json).collect::<Vec<_>>(),
+            "installed": installed.as_ref().map(installed_info_json),
+        });
+        let encoded = serde_json::to_string_pretty(&payload)
+            .map_err(|err| EdenError::Runtime(format!("failed to serialize info json: {err}")))?;
+        println!("{encoded}");
+        return Ok(());
+    }
+
+    let description = registries
+        .iter()
+        .find_map(|entry| entry.description.clone())
+        .or_else(|| {
+            installed
+                .as_ref()
+                .and_then(|installed| frontmatter_value(installed, "description"))
+        });
+    println!(
+        "{}  {}{}",
+        ui.action_prefix("Info"),
+        ui.styled_skill_id(name),
+        description.map_or_else(String::new, |description| format!(" — {description}"))
+    );
+    for entry in &registries {
+        print_registry_info(&ui, entry, version_constraint);
+    }
+    if registries.is_empty() {
+        println!();
+        println!(
+            "  {}",
+            ui.styled_secondary("not listed in any synced registry")
+        );
+    }
+    match &installed {
+        Some(installed) => print_installed_info(&ui, installed),
+        None => {
+            println!();
+            println!("  {}", ui.styled_secondary("not installed"));
+        }
+    }
+    Ok(())
+}
+
+/// The sources whose cache exists, warning about each one that has not
+/// been synced yet.
+fn synced_registry_sources(
+    sources: Vec<RegistrySource>,
+    ui: &UiContext,
+) -> Result<Vec<RegistrySource>, EdenError> {
+    let mut synced = Vec::with_capacity(sources.len());
+    for source in sources {
+        if !source.root.exists() {
+            print_warning(
+                ui,
+                &format!(
+                    "registry `{}` has not been synced; run `eden-skills update`",
+                    source.name
+                ),
+            );
+            continue;
+        }
+        validate_registry_manifest_for_resolution(&source, ui)?;
+        synced.push(source);
+    }
+    Ok(synced)
+}
+
+struct InstalledSkillInfo {
+    lock_entry: Option<LockSkillEntry>,
+    /// `(agent, environment, path)` of each target.
+    targets: Vec<(String, String, String)>,
+    safety: Option<SkillSafetyReport>,
+    frontmatter: Option<Vec<(String, String)>>,
+}
+
+/// What is known about `name` as an installed skill: configured, locked,
+/// or both. The safety report and frontmatter need a synced source.
+fn installed_skill_info(
+    config_path: &Path,
+    user_config_path: Option<&Path>,
+    config_dir: &Path,
+    loaded: &LoadedConfig,
+    name: &str,
+) -> Result<Option<InstalledSkillInfo>, EdenError> {
+    let lock_entry = read_lock_for_config(config_path, user_config_path)?
+        .and_then(|lock| lock.skills.into_iter().find(|entry| entry.id == name));
+    let skill = loaded
+        .config
+        .skills
+        .iter()
+        .find(|skill| skill.id == name)
+        .map(|skill| locked_source_skill(skill, lock_entry.as_ref()));
+    if skill.is_none() && lock_entry.is_none() {
+        return Ok(None);
+    }
+
+    let targets = match (&lock_entry, &skill) {
+        (Some(entry), _) => entry
+            .targets
+            .iter()
+            .map(|target| {
+                (
+                    target.agent.clone(),
+                    target.environment.clone(),
+                    target.resolved_path(config_dir).display().to_string(),
+                )
+            })
+            .collect(),
+        (None, Some(skill)) => skill
+            .targets
+            .iter()
+            .map(|target| {
+                let path = resolve_target_path(target, config_dir)
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|err| format!("ERROR: {err}"));
+                (
+                    agent_kind_label(&target.agent).to_string(),
+                    target.environment.clone(),
+                    path,
+                )
+            })
+            .collect(),
+        (None, None) => Vec::new(),
+    };
+
+    let mut safety = None;
+    let mut frontmatter = None;
+    if let Some(skill) = skill {
+        let storage_root = resolve_path_string(&loaded.config.storage_root, config_dir)?;
+        let source_path = resolve_skill_source_path(&storage_root, &skill);
+        if source_path.exists() {
+            frontmatter = read_skill_frontmatter(&source_path)?;
+            let single = Config {
+                skills: vec![skill],
+                ..loaded.config.clone()
+            };
+            safety = analyze_skills(&single, config_dir)?.into_iter().next();
+        }
+    }
+
+    Ok(Some(InstalledSkillInfo {
+        lock_entry,
+        targets,
+        safety,
+        frontmatter,
+    }))
+}
+
+/// A registry-mode skill's cache lives under the repo it resolved to,
+/// which only the lock records.
+fn locked_source_skill(skill: &SkillConfig, lock_entry: Option<&LockSkillEntry>) -> SkillConfig {
+    let mut skill = skill.clone();
+    if let Some(entry) = lock_entry.filter(|_| is_registry_mode_repo(&skill.source.repo)) {
+        skill.source.repo = entry.source_repo.clone();
+        skill.source.subpath = entry.source_subpath.clone();
+        skill.source.r#ref = entry.source_ref.clone();
+    }
+    skill
+}
+
+fn frontmatter_value(installed: &InstalledSkillInfo, key: &str) -> Option<String> {
+    installed
+        .frontmatter
+        .as_ref()?
+        .iter()
+        .find(|(field, value)| field == key && !value.is_empty())
+        .map(|(_, value)| value.clone())
+}
+
+fn registry_info_json(entry: &RegistrySkillInfo) -> serde_json::Value {
+    serde_json::json!({
+        "registry": entry.registry_name,
+        "priority": entry.registry_priority,
+        "description": entry.description,
+        "license": entry.license,
+        "tags": entry.tags,
+        "deprecated": entry.deprecated,
+        "repo": entry.repo,
+        "subpath": entry.subpath,
+        "versions": entry.versions.iter().map(|version| serde_json::json!({
+            "version": version.version,
+            "ref": version.git_ref,
+            "commit": version.commit,
+            "yanked": version.yanked,
+        })).collect::<Vec<_>>(),
+        "selected_version": entry.selected_version,
+        "selection_error": entry.selection_error,
+    })
+}
+
+fn installed_info_json(installed: &InstalledSkillInfo) -> serde_json::Value {
+    serde_json::json!({
+        "lock": installed.lock_entry,
+        "targets": installed.targets.iter().map(|(agent, environment, path)| serde_json::json!({
+            "agent": agent,
+            "environment": environment,
+            "path": path,
+        })).collect::<Vec<_>>(),
+        "safety": installed.safety.as_ref().map(|report| serde_json::json!({
+            "license_status": report.license_status.as_str(),
+            "license_hint": report.license_hint,
+            "risk_labels": report.risk_labels,
+            "no_exec_metadata_only": report.no_exec_metadata_only,
+        })),
+        "frontmatter": installed.frontmatter.as_ref().map(|fields| fields
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::from(value.clone())))
+            .collect::<serde_json::Map<_, _>>()),
+    })
+}
+
+fn print_registry_info(ui: &UiContext, entry: &RegistrySkillInfo, constraint: Option<&str>) {
+    println!();
+    println!(
+        "  Registry {} {}",
+        entry.registry_name,
+        ui.styled_secondary(&format!("(priority {})", entry.registry_priority))
+    );
+    println!("    repo      {}", entry.repo);
+    println!("    subpath   {}", entry.subpath);
+    if let Some(license) = &entry.license {
+        println!("    license   {license}");
+    }
+    if !entry.tags.is_empty() {
+        println!("    tags      {}", entry.tags.join(", "));
+    }
+    if entry.deprecated {
+        println!("    {}", ui.styled_warning_text("deprecated"));
+    }
+    let requested = constraint.map_or_else(|| "latest".to_string(), |c| format!("`{c}`"));
+    match (&entry.selected_version, &entry.selection_error) {
+        (Some(version), _) => println!(
+            "    selects   {} for {requested}",
+            ui.styled_version(version)
+        ),
+        (None, Some(error)) => println!(
+            "    selects   {} ({error})",
+            ui.styled_warning_text(&format!("nothing for {requested}"))
+        ),
+        (None, None) => {}
+    }
+    println!();
+
+    let mut table = ui.table(&["Version", "Ref", "Commit", "Status"]);
+    for version in &entry.versions {
+        let status = if version.yanked {
+            ui.styled_warning_text("yanked")
+        } else if entry.selected_version.as_deref() == Some(version.version.as_str()) {
+            ui.styled_cyan("selected")
+        } else {
+            ui.styled_secondary("-")
+        };
+        table.add_row(vec![
+            ui.styled_version(&version.version),
+            version.git_ref.clone(),
+            short_commit(&version.commit).to_string(),
+            status,
+        ]);
+    }
+    println!("{table}");
+}
+
+fn print_installed_info(ui: &UiContext, installed: &InstalledSkillInfo) {
+    println!();
+    println!("  Installed");
+    match &installed.lock_entry {
+        Some(entry) => {
+            println!("    ref       {}", entry.source_ref);
+            if let Some(version) = &entry.resolved_version {
+                println!("    version   {}", ui.styled_version(version));
+            }
+            if !entry.resolved_commit.is_empty() {
+                println!("    commit    {}", short_commit(&entry.resolved_commit));
+            }
+            println!("    mode      {}", entry.install_mode);
+            println!("    installed {}", entry.installed_at);
+        }
+        None => println!(
+            "    {}",
+            ui.styled_secondary("not locked yet; run `eden-skills apply`")
+        ),
+    }
+    match &installed.safety {
+        Some(report) => {
+            let license = report.license_hint.as_deref().map_or_else(
+                || report.license_status.as_str().to_string(),
+                |hint| format!("{} ({hint})", report.license_status.as_str()),
+            );
+            println!("    license   {license}");
+            let risks = if report.risk_labels.is_empty() {
+                ui.styled_secondary("none")
+            } else {
+                ui.styled_warning_text(&report.risk_labels.join(", "))
+            };
+            println!("    risks     {risks}");
+        }
+        None => println!(
+            "    {}",
+            ui.styled_secondary("source not synced; no safety report")
+        ),
+    }
+
+    if !installed.targets.is_empty() {
+        println!();
+        let mut table = ui.table(&["Agent", "Environment", "Path"]);
+        for (agent, environment, path) in &installed.targets {
+            table.add_row(vec![
+                agent.clone(),
+                environment.clone(),
+                ui.styled_path(path),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    if let Some(fields) = installed
+        .frontmatter
+        .as_ref()
+        .filter(|fields| !fields.is_empty())
+    {
+        println!();
+        println!("  SKILL.md");
+        for (key, value) in fields {
+            println!("    {key}: {value}");
+        }
+    }
+}
+
+fn short_commit(commit: &str) -> &str {
+    commit.get(..12).unwrap_or(commit)
+}
+
+fn search_status(ui: &UiContext, hit: &SkillSearchHit) -> String {
+    let mut labels = Vec::new();
+    if hit.latest_version.is_none() {
+        labels.push("yanked");
+    }
+    if hit.deprecated {
+        labels.push("deprecated");
+    }
+    if labels.is_empty() {
+        ui.styled_secondary("-")
+    } else {
+        ui.styled_warning_text(&labels.join(", "))
+    }
+}
@@ -4,7 +4,7 @@
 //! confirmation (when applicable), uninstalls targets via the adapter,
 //! removes config entries, and updates the lock file atomically.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -14,7 +14,9 @@ use crate::ui::{
 use dialoguer::Confirm;
 use eden_skills_core::adapter::{create_adapter, read_managed_manifest, write_managed_manifest};
 use eden_skills_core::config::{config_dir_from_path, validate_config, Config, SkillConfig};
+use eden_skills_core::config_layers::ConfigLayer;
 use eden_skills_core::error::EdenError;
+use eden_skills_core::lock::{lock_dependents, DigestRefresh, LockFile};
 use eden_skills_core::managed::ManagedSource;
 use eden_skills_core::paths::{
     known_default_agent_paths, normalize_lexical, resolve_path_string, resolve_target_path,
@@ -23,9 +25,10 @@ use eden_skills_core::paths::{
 use super::clean::{clean_with_loaded_config, print_clean_summary};
 use super::common::{
     block_on_command_future, ensure_docker_available_for_targets, format_quoted_ids,
-    load_config_with_context, print_warning, remove_path, resolve_config_path, unique_ids,
-    with_hint, write_lock_for_config, write_normalized_config,
+    load_config_with_context, print_warning, read_lock_for_config, remove_path,
+    resolve_config_path, unique_ids, with_hint, write_lock_for_config, write_normalized_config,
 };
+use super::reconcile::uninstall_orphaned_lock_entries;
 use super::CommandOptions;
 
 /// Remove a single skill, skipping interactive confirmation.
@@ -80,7 +83,11 @@ pub async fn remove_many_async(
 ) -> Result<(), EdenError> {
     let config_path_buf = resolve_config_path(config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        options.strict,
+        options.user_config_path.as_deref(),
+    )?;
     let ui = UiContext::from_env(options.json);
     for warning in loaded.warnings {
         print_warning(&ui, &warning);
@@ -103,6 +110,7 @@ pub async fn remove_many_async(
         return Ok(());
     }
     validate_remove_ids(&config, &removal_ids, skill_ids.len() == 1)?;
+    reject_user_layer_removals(&loaded.skill_layers, &removal_ids)?;
 
     match confirm_remove_execution(&removal_ids, prompted, skip_confirmation, &ui)? {
         PromptOutcome::Interrupted => {
@@ -116,6 +124,9 @@ pub async fn remove_many_async(
         PromptOutcome::Value(true) => {}
     }
 
+    let previous_lock = read_lock_for_config(config_path, options.user_config_path.as_deref())?;
+    warn_about_dependents(&config, previous_lock.as_ref(), &removal_ids, &ui);
+
     let mut removed = Vec::with_capacity(removal_ids.len());
     for skill_id in &removal_ids {
         let idx = config
@@ -147,8 +158,24 @@ pub async fn remove_many_async(
     }
 
     validate_config(&config, &config_dir)?;
-    write_normalized_config(config_path, &config)?;
-    write_lock_for_config(config_path, &config, &config_dir)?;
+    write_normalized_config(config_path, &config, options.user_config_path.as_deref())?;
+    write_lock_for_config(
+        config_path,
+        options.user_config_path.as_deref(),
+        &config,
+        &config_dir,
+        DigestRefresh::Keep,
+    )?;
+    let current_lock = read_lock_for_config(config_path, options.user_config_path.as_deref())?;
+    removed.extend(
+        remove_unrequired_implicit_skills(
+            current_lock,
+            previous_lock.as_ref(),
+            &config,
+            &config_dir,
+        )
+        .await?,
+    );
     let clean_report = if auto_clean {
         Some(clean_with_loaded_config(&config, &config_dir, false)?)
     } else {
@@ -181,6 +208,72 @@ pub async fn remove_many_async(
     Ok(())
 }
 
+/// Warn when a skill being removed is still required by a remaining one;
+/// the next `apply` brings it back as an implicit dependency.
+fn warn_about_dependents(
+    config: &Config,
+    lock: Option<&LockFile>,
+    removal_ids: &[String],
+    ui: &UiContext,
+) {
+    for skill_id in removal_ids {
+        let mut dependents = config
+            .skills
+            .iter()
+            .filter(|skill| skill.requires.contains_key(skill_id))
+            .map(|skill| skill.id.clone())
+            .chain(lock.map_or_else(Vec::new, |lock| lock_dependents(lock, skill_id)))
+            .filter(|id| !removal_ids.contains(id))
+            .collect::<Vec<_>>();
+        dependents.sort();
+        dependents.dedup();
+        if dependents.is_empty() {
+            continue;
+        }
+        print_warning(
+            ui,
+            &format!(
+                "'{skill_id}' is required by {}; `eden-skills apply` will reinstall it as an implicit dependency",
+                format_quoted_ids(&dependents)
+            ),
+        );
+    }
+}
+
+/// Uninstall implicit skills that the lock just written no longer keeps
+/// because nothing requires them anymore. Returns their ids.
+async fn remove_unrequired_implicit_skills(
+    current_lock: Option<LockFile>,
+    previous_lock: Option<&LockFile>,
+    config: &Config,
+    config_dir: &Path,
+) -> Result<Vec<String>, EdenError> {
+    let Some(previous_lock) = previous_lock else {
+        return Ok(Vec::new());
+    };
+    let current_ids = current_lock
+        .map(|lock| {
+            lock.skills
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<HashSet<_>>()
+        })
+        .unwrap_or_default();
+    let unrequired = previous_lock
+        .skills
+        .iter()
+        .filter(|entry| entry.implicit && !current_ids.contains(&entry.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    ensure_docker_available_for_targets(unrequired.iter().flat_map(|entry| {
+        entry
+            .targets
+            .iter()
+            .map(|target| target.environment.as_str())
+    }))?;
+    uninstall_orphaned_lock_entries(&unrequired, config_dir, &config.storage_root).await
+}
+
 enum RemoveSelection {
     SkillIds { ids: Vec<String>, prompted: bool },
     Cancelled,
@@ -243,6 +336,29 @@ fn resolve_remove_ids(
     })
 }
 
+/// A skill defined only in the user-global layer cannot be removed through
+/// a project config: dropping it from the project file would not stick.
+fn reject_user_layer_removals(
+    skill_layers: &BTreeMap<String, ConfigLayer>,
+    removal_ids: &[String],
+) -> Result<(), EdenError> {
+    let user_ids = removal_ids
+        .iter()
+        .filter(|id| skill_layers.get(id.as_str()) == Some(&ConfigLayer::User))
+        .cloned()
+        .collect::<Vec<_>>();
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    Err(EdenError::InvalidArguments(with_hint(
+        format!(
+            "skill(s) {} are defined in the user config, not the project config",
+            format_quoted_ids(&user_ids)
+        ),
+        "Run `eden-skills remove` without --project to remove them from the user config.",
+    )))
+}
+
 fn validate_remove_ids(
     config: &Config,
     removal_ids: &[String],
@@ -2,43 +2,63 @@
 //!
 //! Resolves configured registries, clones or fetches each in parallel
 //! using the reactor, and records sync markers. Results are rendered as
-//! a table with per-registry status and a timing footer.
+//! a table with per-registry status and a timing footer. Every fetch is
+//! retried and capped per host according to `[reactor]`.
+//!
+//! Mode A skills with a new upstream commit also get a changelog: the
+//! commits between the locked `resolved_commit` and the fetched one that
+//! touch the skill's subpath, plus counts of files added, removed, and
+//! changed. `--dry-run` reports only that, leaving registries alone.
+//!
+//! Offline, nothing is fetched: registries keep their cached index and
+//! skills are compared against the commit their cache last fetched, so
+//! the report reflects the last online refresh. Sources that are not
+//! cached are reported as `uncached`.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use comfy_table::{ColumnConstraint, Width};
 use eden_skills_core::config::{config_dir_from_path, is_registry_mode_repo, Config};
 use eden_skills_core::error::EdenError;
+use eden_skills_core::git::{self, CloneOptions, CommitSummary, DiffStat, GitBackend};
+use eden_skills_core::lock::{DigestRefresh, LockFile};
+use eden_skills_core::mirrors::rewrite_url;
 use eden_skills_core::paths::resolve_path_string;
 use eden_skills_core::plan::{build_plan, Action};
-use eden_skills_core::reactor::SkillReactor;
+use eden_skills_core::reactor::{HostLimiter, SkillReactor};
 use eden_skills_core::registry::{parse_registry_specs_from_toml, sort_registry_specs_by_priority};
+use eden_skills_core::retry::RetryPolicy;
 use eden_skills_core::safety::{analyze_skills, persist_reports, SkillSafetyReport};
 use eden_skills_core::source::{
-    repo_cache_key, resolve_skill_storage_root, sync_sources_async_with_reactor,
+    lock_object_store, repo_cache_key, resolve_skill_storage_root, resolve_version_tag,
+    sync_sources_async_with_reactor, sync_sources_offline_async, uncached_detail,
 };
 use eden_skills_core::verify::verify_config_state;
 use owo_colors::OwoColorize;
 
 use super::common::{
-    apply_plan_item, ensure_git_available, load_config_with_context, print_safety_summary_human,
-    print_source_sync_summary_human, print_warning, read_head_sha, resolve_config_path,
-    resolve_effective_reactor_concurrency, run_git_command, source_sync_failure_error,
-    write_lock_for_config_with_commits, REGISTRY_SYNC_MARKER_FILE,
+    check_source_sync, collect_resolved_commits, ensure_git_available, load_config_with_context,
+    print_safety_summary_human, print_source_sync_summary_human, print_warning,
+    read_effective_config_toml, read_lock_for_config, record_generation_for_config,
+    resolve_config_path, resolve_effective_reactor_concurrency, write_lock_for_config_with_commits,
+    TargetTransaction, REGISTRY_SYNC_MARKER_FILE,
 };
-use super::UpdateRequest;
+use super::{CommandOptions, UpdateRequest};
 use crate::ui::{StatusSymbol, UiContext};
 
+/// Commits listed per skill in human output; JSON carries all of them.
+const CHANGELOG_COMMIT_LIMIT: usize = 10;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum RegistrySyncStatus {
     Cloned,
     Updated,
     Skipped,
     Failed,
+    Uncached,
 }
 
 impl RegistrySyncStatus {
@@ -48,8 +68,13 @@ impl RegistrySyncStatus {
             Self::Updated => "updated",
             Self::Skipped => "skipped",
             Self::Failed => "failed",
+            Self::Uncached => "uncached",
         }
     }
+
+    fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed | Self::Uncached)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,6 +83,7 @@ enum SkillRefreshStatus {
     UpToDate,
     Missing,
     Failed,
+    Uncached,
 }
 
 impl SkillRefreshStatus {
@@ -67,6 +93,7 @@ impl SkillRefreshStatus {
             Self::UpToDate => "up-to-date",
             Self::Missing => "missing",
             Self::Failed => "failed",
+            Self::Uncached => "uncached",
         }
     }
 
@@ -76,9 +103,14 @@ impl SkillRefreshStatus {
             Self::UpToDate => "up-to-date",
             Self::Missing => "missing",
             Self::Failed => "failed",
+            Self::Uncached => "uncached",
         }
     }
 
+    fn is_failure(self) -> bool {
+        matches!(self, Self::Failed | Self::Uncached)
+    }
+
     fn requires_apply(self) -> bool {
         matches!(self, Self::NewCommit | Self::Missing)
     }
@@ -88,7 +120,11 @@ impl SkillRefreshStatus {
 struct RegistrySyncTask {
     name: String,
     url: String,
+    /// `url` after `[mirrors]` rewriting.
+    fetch_url: String,
     local_dir: PathBuf,
+    backend: GitBackend,
+    retry: RetryPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -97,13 +133,48 @@ struct RegistrySyncResult {
     status: RegistrySyncStatus,
     url: String,
     detail: Option<String>,
+    /// Clone or fetch attempts made; 0 when the remote was never reached.
+    attempts: u32,
+}
+
+/// What a skill's changelog covers.
+#[derive(Debug, Clone)]
+struct ChangelogScope {
+    /// `None` when the skill is the whole repository.
+    subpath: Option<String>,
+    /// `resolved_commit` from the lock file; the local `HEAD` is used
+    /// when the skill is not locked.
+    locked_commit: Option<String>,
+}
+
+/// Upstream commits for one skill between its locked commit and the
+/// fetched one.
+#[derive(Debug, Clone)]
+struct SkillChangelog {
+    from: String,
+    to: String,
+    /// Newest first, limited to the skill's subpath.
+    commits: Vec<CommitSummary>,
+    files: DiffStat,
 }
 
 #[derive(Debug, Clone)]
 struct SkillRefreshTask {
     skill_ids: Vec<String>,
+    /// Semver constraint when the source follows tags.
+    version: Option<String>,
+    /// Changelog scope per skill id.
+    scopes: BTreeMap<String, ChangelogScope>,
     reference: String,
     local_dir: PathBuf,
+    /// Mirror-rewritten repo URL; `None` for local sources.
+    fetch_url: Option<String>,
+    /// Canonical repo URL, for messages.
+    repo_url: String,
+    backend: GitBackend,
+    retry: RetryPolicy,
+    /// Compare against the last fetched commit instead of fetching.
+    offline: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -114,6 +185,12 @@ struct SkillRefreshResult {
     remote_sha: Option<String>,
     detail: Option<String>,
     applied: bool,
+    /// Network attempts made; 0 when the remote was never reached.
+    attempts: u32,
+    /// Newest tag matching the skill's `version` constraint.
+    remote_version: Option<String>,
+    /// Set for new commits whose locked commit is in the local clone.
+    changelog: Option<SkillChangelog>,
 }
 
 #[derive(Debug, Clone)]
@@ -132,28 +209,40 @@ struct ApplyOutcome {
 ///
 /// Resolves registry specs from the config, clones new registries or
 /// fetches updates for existing ones using reactor-based concurrency,
-/// and writes sync marker timestamps. Results are displayed as a table.
+/// and writes sync marker timestamps. Results are displayed as a table,
+/// followed by the upstream changelog of each skill with a new commit.
+/// With `dry_run`, registries are not synced and only the skill refresh
+/// and changelogs are reported. Offline, the report comes from the cache
+/// (see the module docs).
 ///
 /// # Errors
 ///
 /// Returns [`EdenError`] on config load failure, git unavailability,
 /// or reactor initialization errors.
 pub async fn update_async(req: UpdateRequest) -> Result<(), EdenError> {
+    let offline = req.options.offline;
     let ui = UiContext::from_env(req.options.json);
     let config_path_buf = resolve_config_path(&req.config_path)?;
     let config_path = config_path_buf.as_path();
-    let loaded = load_config_with_context(config_path, req.options.strict)?;
+    let loaded = load_config_with_context(
+        config_path,
+        req.options.strict,
+        req.options.user_config_path.as_deref(),
+    )?;
     for warning in loaded.warnings {
         super::common::print_warning(&ui, &warning);
     }
 
-    let raw_toml = fs::read_to_string(config_path)?;
+    let raw_toml =
+        read_effective_config_toml(config_path, req.options.user_config_path.as_deref())?;
     let registry_specs = sort_registry_specs_by_priority(
         &parse_registry_specs_from_toml(&raw_toml).map_err(EdenError::from)?,
     );
     let config_dir = config_dir_from_path(config_path);
     let storage_root = resolve_path_string(&loaded.config.storage_root, &config_dir)?;
-    let mode_a_tasks = build_mode_a_refresh_tasks(&loaded.config, &storage_root);
+    let lock = read_lock_for_config(config_path, req.options.user_config_path.as_deref())?;
+    let mode_a_tasks =
+        build_mode_a_refresh_tasks(&loaded.config, &storage_root, lock.as_ref(), offline);
     let has_registries = !registry_specs.is_empty();
     let has_mode_a_skills = !mode_a_tasks.is_empty();
 
@@ -180,12 +269,38 @@ pub async fn update_async(req: UpdateRequest) -> Result<(), EdenError> {
         loaded.config.reactor.concurrency,
         "update.concurrency",
     )?;
-    ensure_git_available()?;
+    ensure_git_available(loaded.config.git.backend)?;
 
     let registries_root = storage_root.join("registries");
     let started = Instant::now();
     let mut registry_results = Vec::new();
-    if has_registries {
+    if has_registries && !req.dry_run && offline {
+        registry_results = registry_specs
+            .into_iter()
+            .map(|spec| {
+                let local_dir = registries_root.join(&spec.name);
+                let (status, detail) = if local_dir.join(".git").exists() {
+                    (
+                        RegistrySyncStatus::Skipped,
+                        "offline; using the cached index".to_string(),
+                    )
+                } else {
+                    (
+                        RegistrySyncStatus::Uncached,
+                        uncached_detail(&spec.url, &local_dir),
+                    )
+                };
+                RegistrySyncResult {
+                    name: spec.name,
+                    status,
+                    url: spec.url,
+                    detail: Some(detail),
+                    attempts: 0,
+                }
+            })
+            .collect();
+        registry_results.sort_by(|left, right| left.name.cmp(&right.name));
+    } else if has_registries && !req.dry_run {
         tokio::fs::create_dir_all(&registries_root).await?;
         let tasks = registry_specs
             .into_iter()
@@ -193,17 +308,22 @@ pub async fn update_async(req: UpdateRequest) -> Result<(), EdenError> {
                 let name = spec.name;
                 RegistrySyncTask {
                     name: name.clone(),
+                    fetch_url: rewrite_url(&loaded.config.mirrors, &spec.url),
                     url: spec.url,
                     local_dir: registries_root.join(name),
+                    backend: loaded.config.git.backend,
+                    retry: loaded.config.reactor.retry_policy(),
                 }
             })
             .collect::<Vec<_>>();
 
         let reactor = SkillReactor::new(concurrency).map_err(EdenError::from)?;
+        let hosts = HostLimiter::new(loaded.config.reactor.host_concurrency);
         let outcomes = reactor
             .run_phase_a(tasks, move |task| {
                 let reactor = reactor;
-                async move { sync_registry_task(task, reactor).await }
+                let hosts = hosts.clone();
+                async move { sync_registry_task(task, reactor, hosts).await }
             })
             .await
             .map_err(EdenError::from)?;
@@ -216,7 +336,12 @@ pub async fn update_async(req: UpdateRequest) -> Result<(), EdenError> {
     }
 
     let mut skill_results = if has_mode_a_skills {
-        refresh_mode_a_skills(mode_a_tasks, concurrency).await?
+        refresh_mode_a_skills(
+            mode_a_tasks,
+            concurrency,
+            HostLimiter::new(loaded.config.reactor.host_concurrency),
+        )
+        .await?
     } else {
         Vec::new()
     };
@@ -244,8 +369,8 @@ pub async fn update_async(req: UpdateRequest) -> Result<(), EdenError> {
             &config_dir,
             &pending_skill_ids,
             concurrency,
+            &req.options,
             &ui,
-            !req.options.json,
         )
         .await?;
     }
@@ -334,12 +459,71 @@ fn print_update_refresh_sections(
             ]);
         }
         println!("{table}");
+        print_skill_changelogs(ui, skill_results);
         printed = true;
     }
 
     printed
 }
 
+/// List each skill's upstream commits below the refresh table, newest
+/// first, capped at [`CHANGELOG_COMMIT_LIMIT`] lines per skill.
+fn print_skill_changelogs(ui: &UiContext, skill_results: &[SkillRefreshResult]) {
+    let mut prefix_emitted = false;
+    for result in skill_results {
+        let Some(changelog) = &result.changelog else {
+            continue;
+        };
+        let prefix = if prefix_emitted {
+            "          ".to_string()
+        } else {
+            println!();
+            prefix_emitted = true;
+            format!("{}  ", ui.action_prefix("Changes"))
+        };
+        let summary = format!(
+            "({} commits, {} added, {} removed, {} changed)",
+            changelog.commits.len(),
+            changelog.files.added,
+            changelog.files.removed,
+            changelog.files.changed
+        );
+        println!(
+            "{prefix}{} {}..{} {}",
+            ui.styled_skill_id(&result.id),
+            short_sha(&changelog.from),
+            short_sha(&changelog.to),
+            ui.styled_secondary(&summary)
+        );
+        let shown = changelog.commits.len().min(CHANGELOG_COMMIT_LIMIT);
+        let hidden = changelog.commits.len() - shown;
+        for (index, commit) in changelog.commits.iter().take(shown).enumerate() {
+            let connector = if index + 1 == shown && hidden == 0 {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!(
+                "            {} {} {}",
+                style_tree_connector(ui, connector),
+                ui.styled_secondary(short_sha(&commit.id)),
+                commit.subject
+            );
+        }
+        if hidden > 0 {
+            println!(
+                "            {} {}",
+                style_tree_connector(ui, "└─"),
+                ui.styled_secondary(&format!("... and {hidden} more"))
+            );
+        }
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
 fn print_update_summary(
     ui: &UiContext,
     registry_results: &[RegistrySyncResult],
@@ -349,11 +533,11 @@ fn print_update_summary(
 ) {
     let registry_failed = registry_results
         .iter()
-        .filter(|result| matches!(result.status, RegistrySyncStatus::Failed))
+        .filter(|result| result.status.is_failure())
         .count();
     let skill_failed = skill_results
         .iter()
-        .filter(|result| matches!(result.status, SkillRefreshStatus::Failed))
+        .filter(|result| result.status.is_failure())
         .count();
     let updates_available = skill_results
         .iter()
@@ -393,21 +577,29 @@ fn print_update_summary(
 
     for result in registry_results
         .iter()
-        .filter(|result| matches!(result.status, RegistrySyncStatus::Failed))
+        .filter(|result| result.status.is_failure())
     {
         if let Some(detail) = &result.detail {
-            print_warning(ui, &format!("registry `{}` failed: {detail}", result.name));
+            print_warning(
+                ui,
+                &format!(
+                    "registry `{}` {}: {detail}",
+                    result.name,
+                    result.status.as_str()
+                ),
+            );
         }
     }
     for result in skill_results
         .iter()
-        .filter(|result| matches!(result.status, SkillRefreshStatus::Failed))
+        .filter(|result| result.status.is_failure())
     {
         if let Some(detail) = &result.detail {
-            print_warning(
-                ui,
-                &format!("skill `{}` refresh failed: {detail}", result.id),
-            );
+            let outcome = match result.status {
+                SkillRefreshStatus::Uncached => "is uncached",
+                _ => "refresh failed",
+            };
+            print_warning(ui, &format!("skill `{}` {outcome}: {detail}", result.id));
         }
     }
     if !apply_requested && updates_available > 0 {
@@ -426,7 +618,7 @@ fn print_update_json(
 ) -> Result<(), EdenError> {
     let registry_failed = registry_results
         .iter()
-        .filter(|result| matches!(result.status, RegistrySyncStatus::Failed))
+        .filter(|result| result.status.is_failure())
         .count();
     let payload = serde_json::json!({
         "registries": registry_results.iter().map(|result| {
@@ -435,6 +627,7 @@ fn print_update_json(
                 "status": result.status.as_str(),
                 "url": result.url,
                 "detail": result.detail,
+                "attempts": result.attempts,
             })
         }).collect::<Vec<_>>(),
         "skills": skill_results.iter().map(|result| {
@@ -444,7 +637,10 @@ fn print_update_json(
                     "status": result.status.json_label(),
                     "local_sha": result.local_sha,
                     "remote_sha": result.remote_sha,
+                    "remote_version": result.remote_version,
                     "applied": result.applied,
+                    "attempts": result.attempts,
+                    "changelog": changelog_json(result.changelog.as_ref()),
                 })
             } else {
                 serde_json::json!({
@@ -452,6 +648,9 @@ fn print_update_json(
                     "status": result.status.json_label(),
                     "local_sha": result.local_sha,
                     "remote_sha": result.remote_sha,
+                    "remote_version": result.remote_version,
+                    "attempts": result.attempts,
+                    "changelog": changelog_json(result.changelog.as_ref()),
                 })
             }
         }).collect::<Vec<_>>(),
@@ -464,33 +663,74 @@ fn print_update_json(
     Ok(())
 }
 
-fn build_mode_a_refresh_tasks(config: &Config, storage_root: &Path) -> Vec<SkillRefreshTask> {
+fn changelog_json(changelog: Option<&SkillChangelog>) -> serde_json::Value {
+    let Some(changelog) = changelog else {
+        return serde_json::Value::Null;
+    };
+    serde_json::json!({
+        "from": changelog.from,
+        "to": changelog.to,
+        "commits": changelog.commits,
+        "files": changelog.files,
+    })
+}
+
+fn build_mode_a_refresh_tasks(
+    config: &Config,
+    storage_root: &Path,
+    lock: Option<&LockFile>,
+    offline: bool,
+) -> Vec<SkillRefreshTask> {
     let mut remote_tasks = BTreeMap::new();
     let mut local_tasks = Vec::new();
 
     for skill in config
         .skills
         .iter()
-        .filter(|skill| !is_registry_mode_repo(&skill.source.repo))
+        .filter(|skill| !is_registry_mode_repo(&skill.source.repo) && !skill.source.is_archive())
     {
+        let scope = ChangelogScope {
+            subpath: (skill.source.subpath != ".").then(|| skill.source.subpath.clone()),
+            locked_commit: lock
+                .and_then(|lock| lock.skills.iter().find(|entry| entry.id == skill.id))
+                .filter(|entry| {
+                    entry.source_repo == skill.source.repo && !entry.resolved_commit.is_empty()
+                })
+                .map(|entry| entry.resolved_commit.clone()),
+        };
         if Path::new(&skill.source.repo).is_absolute() {
             local_tasks.push(SkillRefreshTask {
                 skill_ids: vec![skill.id.clone()],
+                version: skill.source.version_constraint().map(str::to_string),
+                scopes: BTreeMap::from([(skill.id.clone(), scope)]),
                 reference: skill.source.r#ref.clone(),
                 local_dir: resolve_skill_storage_root(storage_root, skill),
+                fetch_url: None,
+                repo_url: skill.source.repo.clone(),
+                backend: config.git.backend,
+                retry: config.reactor.retry_policy(),
+                offline,
             });
             continue;
         }
 
         let cache_key = repo_cache_key(&skill.source.repo, &skill.source.r#ref);
-        remote_tasks
+        let task = remote_tasks
             .entry(cache_key)
-            .and_modify(|task: &mut SkillRefreshTask| task.skill_ids.push(skill.id.clone()))
             .or_insert_with(|| SkillRefreshTask {
-                skill_ids: vec![skill.id.clone()],
+                skill_ids: Vec::new(),
+                version: skill.source.version_constraint().map(str::to_string),
+                scopes: BTreeMap::new(),
                 reference: skill.source.r#ref.clone(),
                 local_dir: resolve_skill_storage_root(storage_root, skill),
+                fetch_url: Some(rewrite_url(&config.mirrors, &skill.source.repo)),
+                repo_url: skill.source.repo.clone(),
+                backend: config.git.backend,
+                retry: config.reactor.retry_policy(),
+                offline,
             });
+        task.skill_ids.push(skill.id.clone());
+        task.scopes.insert(skill.id.clone(), scope);
     }
 
     let mut tasks = remote_tasks
@@ -508,6 +748,7 @@ fn build_mode_a_refresh_tasks(config: &Config, storage_root: &Path) -> Vec<Skill
 async fn refresh_mode_a_skills(
     tasks: Vec<SkillRefreshTask>,
     concurrency: usize,
+    hosts: HostLimiter,
 ) -> Result<Vec<SkillRefreshResult>, EdenError> {
     if tasks.is_empty() {
         return Ok(Vec::new());
@@ -516,7 +757,8 @@ async fn refresh_mode_a_skills(
     let outcomes = reactor
         .run_phase_a(tasks, move |task| {
             let reactor = reactor;
-            async move { refresh_mode_a_task(task, reactor).await }
+            let hosts = hosts.clone();
+            async move { refresh_mode_a_task(task, reactor, hosts).await }
         })
         .await
         .map_err(EdenError::from)?;
@@ -533,8 +775,14 @@ async fn refresh_mode_a_skills(
 async fn refresh_mode_a_task(
     task: SkillRefreshTask,
     reactor: SkillReactor,
+    hosts: HostLimiter,
 ) -> Result<Vec<SkillRefreshResult>, Vec<SkillRefreshResult>> {
     let failed_skill_ids = task.skill_ids.clone();
+    let _store = lock_object_store(&task.local_dir).await;
+    let _host_permit = match &task.fetch_url {
+        Some(fetch_url) => hosts.acquire(fetch_url).await,
+        None => None,
+    };
     let task_label = format!(
         "refresh source `{}`",
         describe_refresh_task(&task.skill_ids)
@@ -551,6 +799,7 @@ async fn refresh_mode_a_task(
             None,
             None,
             Some(err.to_string()),
+            0,
         )),
     }
 }
@@ -559,6 +808,16 @@ fn refresh_mode_a_task_blocking(
     task: SkillRefreshTask,
 ) -> Result<Result<Vec<SkillRefreshResult>, Vec<SkillRefreshResult>>, EdenError> {
     let git_dir = task.local_dir.join(".git");
+    if !git_dir.exists() && task.offline {
+        return Ok(Err(build_skill_refresh_results(
+            task.skill_ids,
+            SkillRefreshStatus::Uncached,
+            None,
+            None,
+            Some(uncached_detail(&task.repo_url, &task.local_dir)),
+            0,
+        )));
+    }
     if !git_dir.exists() {
         return Ok(Ok(build_skill_refresh_results(
             task.skill_ids,
@@ -566,73 +825,176 @@ fn refresh_mode_a_task_blocking(
             None,
             None,
             None,
+            0,
         )));
     }
 
     cleanup_stale_git_locks(&task.local_dir);
-    let local_sha = read_head_sha(&task.local_dir);
-    record_test_git_fetch_if_configured();
-    let fetch_result = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(&task.local_dir)
-            .arg("fetch")
-            .arg("--depth")
-            .arg("1")
-            .arg("origin")
-            .arg(&task.reference),
-        &format!("fetch source `{}`", describe_refresh_task(&task.skill_ids)),
-    );
-    if let Err(detail) = fetch_result {
-        return Ok(Err(build_skill_refresh_results(
-            task.skill_ids,
-            SkillRefreshStatus::Failed,
-            local_sha,
-            None,
-            Some(detail),
+    let local_sha = git::rev_parse(task.backend, &task.local_dir, "HEAD");
+    if task.offline {
+        let cached_sha = cached_remote_sha(&task);
+        return Ok(Ok(skill_refresh_results_against(
+            &task, local_sha, cached_sha, None, 0,
         )));
     }
+    if let Some(fetch_url) = &task.fetch_url {
+        if let Err(detail) = git::set_origin_url(task.backend, &task.local_dir, fetch_url) {
+            return Ok(Err(build_skill_refresh_results(
+                task.skill_ids,
+                SkillRefreshStatus::Failed,
+                local_sha,
+                None,
+                Some(detail),
+                0,
+            )));
+        }
+    }
+    // A `version` source moves to the newest matching tag, not a ref.
+    let mut attempts = 0;
+    let mut remote_tag = None;
+    let reference = match &task.version {
+        Some(constraint) => {
+            let resolved =
+                resolve_version_tag(task.backend, &task.local_dir, constraint, task.retry);
+            attempts += resolved.attempts;
+            match resolved.result {
+                Ok(tag) => {
+                    let reference = format!("refs/tags/{}", tag.name);
+                    remote_tag = Some(tag);
+                    reference
+                }
+                Err(detail) => {
+                    return Ok(Err(build_skill_refresh_results(
+                        task.skill_ids,
+                        SkillRefreshStatus::Failed,
+                        local_sha,
+                        None,
+                        Some(detail),
+                        attempts,
+                    )));
+                }
+            }
+        }
+        None => task.reference.clone(),
+    };
+    let fetched = task.retry.run(|| {
+        record_test_git_fetch_if_configured();
+        git::fetch_head(
+            task.backend,
+            &task.local_dir,
+            Some(&reference),
+            false,
+            task.retry.timeout,
+        )
+    });
+    attempts += fetched.attempts;
+    let remote_sha = match fetched.result {
+        // FETCH_HEAD of an annotated tag names the tag object.
+        Ok(remote_sha) => remote_tag
+            .as_ref()
+            .map_or(remote_sha, |tag| tag.commit.clone()),
+        Err(detail) => {
+            return Ok(Err(build_skill_refresh_results(
+                task.skill_ids,
+                SkillRefreshStatus::Failed,
+                local_sha,
+                None,
+                Some(detail),
+                attempts,
+            )));
+        }
+    };
+    Ok(Ok(skill_refresh_results_against(
+        &task,
+        local_sha,
+        remote_sha,
+        remote_tag.map(|tag| tag.name),
+        attempts,
+    )))
+}
 
-    let Some(remote_sha) = read_fetch_head_sha(&task.local_dir) else {
-        return Ok(Err(build_skill_refresh_results(
-            task.skill_ids,
-            SkillRefreshStatus::Failed,
-            local_sha,
-            None,
-            Some("failed to read FETCH_HEAD after fetch".to_string()),
-        )));
+/// The commit the cache entry last fetched for the task's ref: its
+/// `FETCH_HEAD`, else its `origin` branch, else its own `HEAD`.
+fn cached_remote_sha(task: &SkillRefreshTask) -> String {
+    let peeled = |sha: String| {
+        git::rev_parse(task.backend, &task.local_dir, &format!("{sha}^{{commit}}")).unwrap_or(sha)
     };
+    git::read_fetch_head(&task.local_dir)
+        .map(peeled)
+        .or_else(|| {
+            task.version.is_none().then(|| {
+                git::rev_parse(
+                    task.backend,
+                    &task.local_dir,
+                    &format!("refs/remotes/origin/{}", task.reference),
+                )
+            })?
+        })
+        .or_else(|| git::rev_parse(task.backend, &task.local_dir, "HEAD"))
+        .unwrap_or_default()
+}
+
+/// Results for every skill of `task` comparing its checked-out commit
+/// with `remote_sha`, with changelogs for new commits.
+fn skill_refresh_results_against(
+    task: &SkillRefreshTask,
+    local_sha: Option<String>,
+    remote_sha: String,
+    remote_version: Option<String>,
+    attempts: u32,
+) -> Vec<SkillRefreshResult> {
     let status = if local_sha.as_deref() == Some(remote_sha.as_str()) {
         SkillRefreshStatus::UpToDate
     } else {
         SkillRefreshStatus::NewCommit
     };
 
-    Ok(Ok(build_skill_refresh_results(
-        task.skill_ids,
+    let mut results = build_skill_refresh_results(
+        task.skill_ids.clone(),
         status,
-        local_sha,
-        Some(remote_sha),
+        local_sha.clone(),
+        Some(remote_sha.clone()),
         None,
-    )))
+        attempts,
+    );
+    for result in &mut results {
+        result.remote_version = remote_version.clone();
+    }
+    if status == SkillRefreshStatus::NewCommit {
+        for result in &mut results {
+            let Some(scope) = task.scopes.get(&result.id) else {
+                continue;
+            };
+            let Some(from) = scope.locked_commit.as_ref().or(local_sha.as_ref()) else {
+                continue;
+            };
+            result.changelog = build_skill_changelog(task, scope, from, &remote_sha);
+        }
+    }
+    results
 }
 
-fn read_fetch_head_sha(repo_dir: &Path) -> Option<String> {
-    let stdout = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("rev-parse")
-            .arg("FETCH_HEAD"),
-        &format!("read FETCH_HEAD for `{}`", repo_dir.display()),
-    )
-    .ok()?;
-    let sha = stdout.lines().next()?.trim();
-    if sha.is_empty() {
-        None
-    } else {
-        Some(sha.to_string())
+/// Commits and file counts between `from` and `to` within the skill's
+/// subpath, or `None` when `from` is not in the local clone (e.g. a lock
+/// file written on another machine).
+fn build_skill_changelog(
+    task: &SkillRefreshTask,
+    scope: &ChangelogScope,
+    from: &str,
+    to: &str,
+) -> Option<SkillChangelog> {
+    if from == to || !git::commit_exists(task.backend, &task.local_dir, from) {
+        return None;
     }
+    let path = scope.subpath.as_deref();
+    let commits = git::log(task.backend, &task.local_dir, from, to, path).ok()?;
+    let files = git::diff_stat(task.backend, &task.local_dir, from, to, path).ok()?;
+    Some(SkillChangelog {
+        from: from.to_string(),
+        to: to.to_string(),
+        commits,
+        files,
+    })
 }
 
 fn describe_refresh_task(skill_ids: &[String]) -> String {
@@ -649,6 +1011,7 @@ fn build_skill_refresh_results(
     local_sha: Option<String>,
     remote_sha: Option<String>,
     detail: Option<String>,
+    attempts: u32,
 ) -> Vec<SkillRefreshResult> {
     skill_ids
         .into_iter()
@@ -659,15 +1022,20 @@ fn build_skill_refresh_results(
             remote_sha: remote_sha.clone(),
             detail: detail.clone(),
             applied: false,
+            attempts,
+            remote_version: None,
+            changelog: None,
         })
         .collect()
 }
 
 fn cleanup_stale_git_locks(repo_dir: &Path) {
-    let git_dir = repo_dir.join(".git");
     let ui = UiContext::from_env(false);
-    for file_name in ["shallow.lock", "index.lock"] {
-        let lock_path = git_dir.join(file_name);
+    // A worktree keeps its index apart from the store's `shallow` file.
+    for lock_path in [
+        git::common_dir(repo_dir).join("shallow.lock"),
+        git::git_dir(repo_dir).join("index.lock"),
+    ] {
         let Ok(metadata) = fs::metadata(&lock_path) else {
             continue;
         };
@@ -723,9 +1091,10 @@ async fn apply_refreshed_skills(
     config_dir: &Path,
     pending_skill_ids: &[String],
     concurrency: usize,
+    options: &CommandOptions,
     ui: &UiContext,
-    emit_human_output: bool,
 ) -> Result<ApplyOutcome, EdenError> {
+    let emit_human_output = !ui.json_mode();
     let selected_ids = pending_skill_ids
         .iter()
         .map(String::as_str)
@@ -744,21 +1113,24 @@ async fn apply_refreshed_skills(
         version: full_config.version,
         storage_root: full_config.storage_root.clone(),
         reactor: full_config.reactor,
+        git: full_config.git.clone(),
+        mirrors: full_config.mirrors.clone(),
         skills: selected_skills,
     };
 
     materialize_fetch_heads(&selected_config, config_dir)?;
 
     let reactor = SkillReactor::new(concurrency).map_err(EdenError::from)?;
-    let sync_summary = sync_sources_async_with_reactor(&selected_config, config_dir, reactor, true)
-        .await
-        .map_err(EdenError::from)?;
+    let sync_summary = if options.offline {
+        sync_sources_offline_async(&selected_config, config_dir, reactor).await
+    } else {
+        sync_sources_async_with_reactor(&selected_config, config_dir, reactor, true).await
+    }
+    .map_err(EdenError::from)?;
     if emit_human_output {
         print_source_sync_summary_human(ui, &sync_summary);
     }
-    if let Some(err) = source_sync_failure_error(&sync_summary) {
-        return Err(err);
-    }
+    check_source_sync(&sync_summary, "update", ui.json_mode())?;
 
     let safety_reports = analyze_skills(&selected_config, config_dir)?;
     persist_reports(&safety_reports)?;
@@ -770,6 +1142,7 @@ async fn apply_refreshed_skills(
     let plan = build_plan(&selected_config, config_dir)?;
     let mut applied_targets = Vec::new();
     let mut skipped_skill_ids = Vec::new();
+    let mut transaction = TargetTransaction::new();
     for item in &plan {
         match item.action {
             Action::Create | Action::Update => {
@@ -777,7 +1150,7 @@ async fn apply_refreshed_skills(
                     push_skipped_skill(&mut skipped_skill_ids, &item.skill_id);
                     continue;
                 }
-                apply_plan_item(item)?;
+                transaction.apply(item)?;
                 applied_targets.push(AppliedInstallTargetLine {
                     skill_id: item.skill_id.clone(),
                     target_path: item.target_path.clone(),
@@ -792,6 +1165,7 @@ async fn apply_refreshed_skills(
             Action::Noop | Action::Remove => {}
         }
     }
+    transaction.commit();
     if emit_human_output {
         print_install_result_lines(ui, &applied_targets, &skipped_skill_ids);
     }
@@ -808,12 +1182,21 @@ async fn apply_refreshed_skills(
     }
 
     let resolved_commits = collect_resolved_commits(full_config, config_dir);
-    write_lock_for_config_with_commits(config_path, full_config, config_dir, &resolved_commits)?;
-    let applied_skill_ids = selected_config
+    let selected_ids = selected_config
         .skills
         .iter()
         .map(|skill| skill.id.clone())
-        .collect::<HashSet<_>>();
+        .collect::<Vec<_>>();
+    write_lock_for_config_with_commits(
+        config_path,
+        options.user_config_path.as_deref(),
+        full_config,
+        config_dir,
+        &resolved_commits,
+        DigestRefresh::Skills(&selected_ids),
+    )?;
+    record_generation_for_config(config_path, full_config, config_dir, "update")?;
+    let applied_skill_ids = selected_ids.into_iter().collect::<HashSet<_>>();
     Ok(ApplyOutcome { applied_skill_ids })
 }
 
@@ -825,44 +1208,30 @@ fn no_exec_skill_ids(reports: &[SkillSafetyReport]) -> HashSet<&str> {
         .collect()
 }
 
-fn collect_resolved_commits(config: &Config, config_dir: &Path) -> HashMap<String, String> {
-    let storage_root = match resolve_path_string(&config.storage_root, config_dir) {
-        Ok(path) => path,
-        Err(_) => return HashMap::new(),
-    };
-    let mut commits = HashMap::new();
-    for skill in &config.skills {
-        let repo_dir = resolve_skill_storage_root(&storage_root, skill);
-        if let Some(sha) = read_head_sha(&repo_dir) {
-            commits.insert(skill.id.clone(), sha);
-        }
-    }
-    commits
-}
-
 fn materialize_fetch_heads(config: &Config, config_dir: &Path) -> Result<(), EdenError> {
     let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
     for skill in &config.skills {
+        // Left to the sync, which rolls back commits it refuses.
+        if skill.source.verify_signature {
+            continue;
+        }
         let repo_dir = resolve_skill_storage_root(&storage_root, skill);
         if !repo_dir.join(".git").exists() {
             continue;
         }
-        let Some(fetch_head) = read_fetch_head_sha(&repo_dir) else {
+        let Some(fetch_head) = git::read_fetch_head(&repo_dir) else {
             continue;
         };
-        if read_head_sha(&repo_dir).as_deref() == Some(fetch_head.as_str()) {
+        let backend = config.git.backend;
+        if git::rev_parse(backend, &repo_dir, "HEAD").as_deref() == Some(fetch_head.as_str()) {
             continue;
         }
-        run_git_command(
-            Command::new("git")
-                .arg("-C")
-                .arg(&repo_dir)
-                .arg("reset")
-                .arg("--hard")
-                .arg("FETCH_HEAD"),
-            &format!("apply fetched commit for `{}`", skill.id),
-        )
-        .map_err(EdenError::Runtime)?;
+        git::reset_hard(backend, &repo_dir, &fetch_head).map_err(|detail| {
+            EdenError::Runtime(format!(
+                "failed to apply fetched commit for `{}`: {detail}",
+                skill.id
+            ))
+        })?;
     }
     Ok(())
 }
@@ -974,9 +1343,11 @@ fn style_detail_cell(ui: &UiContext, detail: Option<&str>) -> String {
 async fn sync_registry_task(
     task: RegistrySyncTask,
     reactor: SkillReactor,
+    hosts: HostLimiter,
 ) -> Result<RegistrySyncResult, RegistrySyncResult> {
     let failed_name = task.name.clone();
     let failed_url = task.url.clone();
+    let _host_permit = hosts.acquire(&task.fetch_url).await;
 
     let task_label = format!("sync registry `{}`", task.name);
     match reactor
@@ -990,6 +1361,7 @@ async fn sync_registry_task(
             status: RegistrySyncStatus::Failed,
             url: failed_url,
             detail: Some(err.to_string()),
+            attempts: 0,
         }),
     }
 }
@@ -997,11 +1369,12 @@ async fn sync_registry_task(
 fn sync_registry_task_blocking(
     task: RegistrySyncTask,
 ) -> Result<Result<RegistrySyncResult, RegistrySyncResult>, EdenError> {
-    let failed = |detail: String| RegistrySyncResult {
+    let failed = |detail: String, attempts: u32| RegistrySyncResult {
         name: task.name.clone(),
         status: RegistrySyncStatus::Failed,
         url: task.url.clone(),
         detail: Some(detail),
+        attempts,
     };
 
     if let Some(parent) = task.local_dir.parent() {
@@ -1010,16 +1383,19 @@ fn sync_registry_task_blocking(
 
     let git_dir = task.local_dir.join(".git");
     if !git_dir.exists() {
-        let clone_result = run_git_command(
-            Command::new("git")
-                .arg("clone")
-                .arg("--depth")
-                .arg("1")
-                .arg(&task.url)
-                .arg(&task.local_dir),
-            &format!("clone registry `{}`", task.name),
-        );
-        return Ok(match clone_result {
+        let cloned = task.retry.run(|| {
+            git::clone(
+                task.backend,
+                &task.fetch_url,
+                &task.local_dir,
+                CloneOptions {
+                    shallow: true,
+                    timeout: task.retry.timeout,
+                    ..CloneOptions::default()
+                },
+            )
+        });
+        return Ok(match cloned.result {
             Ok(_) => {
                 write_registry_sync_marker(&task.local_dir)?;
                 Ok(RegistrySyncResult {
@@ -1027,41 +1403,37 @@ fn sync_registry_task_blocking(
                     status: RegistrySyncStatus::Cloned,
                     url: task.url,
                     detail: None,
+                    attempts: cloned.attempts,
                 })
             }
-            Err(detail) => Err(failed(detail)),
+            Err(detail) => Err(failed(detail, cloned.attempts)),
         });
     }
 
-    let head_before = read_head_sha(&task.local_dir);
-    let fetch_result = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(&task.local_dir)
-            .arg("fetch")
-            .arg("--depth")
-            .arg("1")
-            .arg("origin"),
-        &format!("fetch registry `{}`", task.name),
-    );
-    if let Err(detail) = fetch_result {
-        return Ok(Err(failed(detail)));
+    let head_before = git::rev_parse(task.backend, &task.local_dir, "HEAD");
+    if let Err(detail) = git::set_origin_url(task.backend, &task.local_dir, &task.fetch_url) {
+        return Ok(Err(failed(detail, 0)));
     }
+    let fetched = task.retry.run(|| {
+        git::fetch_head(
+            task.backend,
+            &task.local_dir,
+            None,
+            true,
+            task.retry.timeout,
+        )
+    });
+    let attempts = fetched.attempts;
+    let fetched = match fetched.result {
+        Ok(fetched) => fetched,
+        Err(detail) => return Ok(Err(failed(detail, attempts))),
+    };
 
-    let reset_result = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(&task.local_dir)
-            .arg("reset")
-            .arg("--hard")
-            .arg("FETCH_HEAD"),
-        &format!("reset registry `{}`", task.name),
-    );
-    if let Err(detail) = reset_result {
-        return Ok(Err(failed(detail)));
+    if let Err(detail) = git::reset_hard(task.backend, &task.local_dir, &fetched) {
+        return Ok(Err(failed(detail, attempts)));
     }
 
-    let head_after = read_head_sha(&task.local_dir);
+    let head_after = git::rev_parse(task.backend, &task.local_dir, "HEAD");
     let status = if head_before.is_some() && head_before == head_after {
         RegistrySyncStatus::Skipped
     } else {
@@ -1073,6 +1445,7 @@ fn sync_registry_task_blocking(
         status,
         url: task.url,
         detail: None,
+        attempts,
     }))
 }
 
@@ -10,6 +10,7 @@ pub mod signal;
 pub mod ui;
 
 use std::io::IsTerminal;
+use std::path::Path;
 
 use clap::builder::styling::{AnsiColor, Style, Styles};
 use clap::builder::StyledStr;
@@ -20,6 +21,11 @@ use eden_skills_core::error::EdenError;
 use ui::{configure_color_output, ColorWhen};
 
 pub const DEFAULT_CONFIG_PATH: &str = "~/.eden-skills/skills.toml";
+/// Config file name looked up inside a `--project` directory.
+pub const PROJECT_CONFIG_FILE: &str = "skills.toml";
+
+/// Hidden alternative project config name, used when `skills.toml` is absent.
+pub const PROJECT_CONFIG_FILE_HIDDEN: &str = ".eden-skills.toml";
 
 /// Top-level CLI error preserving either domain failures or clap parse errors.
 #[derive(Debug)]
@@ -28,8 +34,13 @@ pub enum CliError {
     Domain(EdenError),
     /// A clap parsing / help-rendering error emitted before command dispatch.
     Clap(clap::Error),
+    /// `plan --detailed-exitcode` found pending changes; not a failure.
+    ChangesPending,
 }
 
+/// Exit code of `plan --detailed-exitcode` when changes are pending.
+pub const EXIT_CODE_CHANGES_PENDING: u8 = 4;
+
 impl From<EdenError> for CliError {
     fn from(value: EdenError) -> Self {
         Self::Domain(value)
@@ -71,16 +82,28 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
             _ => return Err(CliError::from(err)),
         },
     };
-    let cli = Cli::from_arg_matches(&matches).map_err(CliError::from)?;
+    let mut cli = Cli::from_arg_matches(&matches).map_err(CliError::from)?;
     configure_color_output(cli.color, cli.command.json_mode());
+    if let Some((config, project)) = cli.command.project_args() {
+        if project.is_none() && config == DEFAULT_CONFIG_PATH {
+            *project = detect_project_dir();
+        }
+    }
+    let global_options = CommandOptions {
+        offline: cli.offline || env_flag_enabled("EDEN_SKILLS_OFFLINE"),
+        ..CommandOptions::default()
+    };
 
     let result = match cli.command {
         Commands::Install(args) => {
+            let project = args.project.is_some();
             commands::install_async(commands::InstallRequest {
-                config_path: args.config,
+                config_path: scoped_config_path(args.config, args.project.as_deref()),
+                project,
                 source: args.source,
                 id: args.id,
                 r#ref: args.r#ref,
+                sha256: args.sha256,
                 skill: args.skill,
                 all: args.all,
                 yes: args.yes,
@@ -91,10 +114,10 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
                 dry_run: args.dry_run,
                 copy: args.copy,
                 force: args.force,
-                options: CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
+                options: scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
             })
             .await
         }
@@ -103,60 +126,121 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
                 config_path: args.config,
                 concurrency: args.concurrency,
                 apply: args.apply,
-                options: CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
+                dry_run: args.dry_run,
+                options: global_options.for_command(args.strict, args.json),
             })
             .await
         }
         Commands::Remove(args) => {
             commands::remove_many_async(
-                &args.config,
+                &scoped_config_path(args.config, args.project.as_deref()),
                 &args.skill_ids,
                 args.yes,
                 args.force,
                 args.auto_clean,
-                CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
+                scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
             )
             .await
         }
         Commands::Clean(args) => commands::clean(
             &args.config,
             args.dry_run,
-            CommandOptions {
-                strict: false,
-                json: args.json,
-            },
-        ),
-        Commands::Plan(args) => commands::plan(
-            &args.config,
-            CommandOptions {
-                strict: args.strict,
-                json: args.json,
-            },
+            global_options.for_command(false, args.json),
         ),
+        Commands::Plan(args) => {
+            let detailed_exitcode = args.detailed_exitcode;
+            let result = commands::plan_with_request(commands::PlanRequest {
+                config_path: scoped_config_path(args.config, args.project.as_deref()),
+                out: args.out,
+                diff: args.diff,
+                options: scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
+            });
+            match result {
+                Ok(status) if detailed_exitcode => return plan_detailed_exit(status),
+                result => result.map(|_| ()),
+            }
+        }
+        Commands::Diff(args) => {
+            commands::diff_async(
+                &scoped_config_path(args.config, args.project.as_deref()),
+                &args.skill_id,
+                scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
+            )
+            .await
+        }
         Commands::Apply(args) => {
-            commands::apply_async(
-                &args.config,
-                CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
-                args.concurrency,
-                args.force,
+            let options = scoped_options(
+                global_options.for_command(args.strict, args.json),
+                args.project.as_deref(),
+            );
+            let lock_mode = if args.frozen {
+                commands::LockMode::Frozen
+            } else if args.locked {
+                commands::LockMode::Locked
+            } else {
+                commands::LockMode::Sync
+            };
+            match args.plan_file {
+                Some(plan_file) => commands::apply_plan_file_async(&plan_file, options).await,
+                None => {
+                    commands::apply_with_lock_mode_async(
+                        &scoped_config_path(args.config, args.project.as_deref()),
+                        options,
+                        args.concurrency,
+                        args.force,
+                        lock_mode,
+                    )
+                    .await
+                }
+            }
+        }
+        Commands::Search(args) => commands::search(
+            &scoped_config_path(args.config, args.project.as_deref()),
+            &args.query.join(" "),
+            scoped_options(
+                global_options.for_command(args.strict, args.json),
+                args.project.as_deref(),
+            ),
+        ),
+        Commands::Info(args) => commands::info(
+            &scoped_config_path(args.config, args.project.as_deref()),
+            &args.name,
+            args.version.as_deref(),
+            scoped_options(
+                global_options.for_command(args.strict, args.json),
+                args.project.as_deref(),
+            ),
+        ),
+        Commands::History(args) => commands::history(
+            &scoped_config_path(args.config, args.project.as_deref()),
+            scoped_options(
+                global_options.for_command(args.strict, args.json),
+                args.project.as_deref(),
+            ),
+        ),
+        Commands::Rollback(args) => {
+            commands::rollback_async(
+                &scoped_config_path(args.config, args.project.as_deref()),
+                args.to,
+                scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
             )
             .await
         }
         Commands::Doctor(args) => commands::doctor(
             &args.config,
-            CommandOptions {
-                strict: args.strict,
-                json: args.json,
-            },
+            global_options.for_command(args.strict, args.json),
             args.no_warning,
         ),
         Commands::Docker(args) => match args.command {
@@ -166,11 +250,11 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
         },
         Commands::Repair(args) => {
             commands::repair_async(
-                &args.config,
-                CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
+                &scoped_config_path(args.config, args.project.as_deref()),
+                scoped_options(
+                    global_options.for_command(args.strict, args.json),
+                    args.project.as_deref(),
+                ),
                 args.concurrency,
                 args.force,
             )
@@ -178,11 +262,11 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
         }
         Commands::Init(args) => commands::init(&args.config, args.force),
         Commands::List(args) => commands::list(
-            &args.config,
-            CommandOptions {
-                strict: args.strict,
-                json: args.json,
-            },
+            &scoped_config_path(args.config, args.project.as_deref()),
+            scoped_options(
+                global_options.for_command(args.strict, args.json),
+                args.project.as_deref(),
+            ),
         ),
         Commands::Add(args) => commands::add(commands::AddRequest {
             config_path: args.config,
@@ -195,10 +279,7 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
             verify_enabled: args.verify_enabled,
             verify_checks: args.verify_check,
             no_exec_metadata_only: args.no_exec_metadata_only,
-            options: CommandOptions {
-                strict: args.strict,
-                json: args.json,
-            },
+            options: global_options.for_command(args.strict, args.json),
         }),
         Commands::Set(args) => commands::set(commands::SetRequest {
             config_path: args.config,
@@ -211,35 +292,90 @@ pub async fn run_with_args(args: Vec<String>) -> Result<(), CliError> {
             verify_checks: args.verify_check,
             target_specs: args.target,
             no_exec_metadata_only: args.no_exec_metadata_only,
-            options: CommandOptions {
-                strict: args.strict,
-                json: args.json,
-            },
+            options: global_options.for_command(args.strict, args.json),
         }),
         Commands::Config(cmd) => match cmd.command {
             ConfigSubcommand::Export(args) => commands::config_export(
                 &args.config,
-                CommandOptions {
-                    strict: args.strict,
-                    json: args.json,
-                },
+                global_options.for_command(args.strict, args.json),
             ),
             ConfigSubcommand::Import(args) => commands::config_import(
                 &args.from,
                 &args.config,
                 args.dry_run,
-                CommandOptions {
-                    strict: args.strict,
-                    json: false,
-                },
+                global_options.for_command(args.strict, false),
             ),
         },
     };
     result.map_err(CliError::from)
 }
 
+/// `--project [DIR]` replaces `--config` with the project's own config file.
+fn scoped_config_path(config: String, project: Option<&str>) -> String {
+    let Some(dir) = project else {
+        return config;
+    };
+    let dir = Path::new(&dir);
+    let hidden = dir.join(PROJECT_CONFIG_FILE_HIDDEN);
+    let config_path = if !dir.join(PROJECT_CONFIG_FILE).exists() && hidden.exists() {
+        hidden
+    } else {
+        dir.join(PROJECT_CONFIG_FILE)
+    };
+    config_path.display().to_string()
+}
+
+/// The nearest directory with its own project config, searched from the
+/// working directory up to the enclosing repository root (the first
+/// ancestor containing `.git`). Outside a repository only the working
+/// directory itself is checked.
+fn detect_project_dir() -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+    let user_config = commands::common::resolve_config_path(DEFAULT_CONFIG_PATH).ok();
+    let in_repo = cwd.ancestors().any(|dir| dir.join(".git").exists());
+    for dir in cwd.ancestors() {
+        let has_config = [PROJECT_CONFIG_FILE, PROJECT_CONFIG_FILE_HIDDEN]
+            .iter()
+            .map(|name| dir.join(name))
+            .any(|path| path.is_file() && user_config.as_deref() != Some(path.as_path()));
+        if has_config {
+            return Some(dir.display().to_string());
+        }
+        if !in_repo || dir.join(".git").exists() {
+            return None;
+        }
+    }
+    None
+}
+
+/// With `--project [DIR]`, the project config is layered over the
+/// user-global config.
+fn scoped_options(options: CommandOptions, project: Option<&str>) -> CommandOptions {
+    if project.is_none() {
+        return options;
+    }
+    CommandOptions {
+        user_config_path: commands::common::resolve_config_path(DEFAULT_CONFIG_PATH).ok(),
+        ..options
+    }
+}
+
+/// Map a plan outcome onto the exit-code matrix: `0` when up to date,
+/// [`EXIT_CODE_CHANGES_PENDING`] when actions are pending, and the
+/// conflict code `3` when any target is in conflict.
+fn plan_detailed_exit(status: commands::PlanStatus) -> Result<(), CliError> {
+    match status {
+        commands::PlanStatus::UpToDate => Ok(()),
+        commands::PlanStatus::ChangesPending => Err(CliError::ChangesPending),
+        commands::PlanStatus::Conflicts(count) => Err(CliError::Domain(EdenError::Conflict(
+            format!("plan has {count} conflict(s)"),
+        ))),
+    }
+}
+
 pub fn exit_code_for_error(err: &CliError) -> u8 {
     match err {
+        CliError::ChangesPending => EXIT_CODE_CHANGES_PENDING,
         CliError::Domain(EdenError::InvalidArguments(_))
         | CliError::Domain(EdenError::Validation(_)) => 2,
         CliError::Domain(EdenError::Conflict(_)) => 3,
@@ -394,15 +530,19 @@ fn help_colors_enabled(clap_color_choice: ColorChoice) -> bool {
             if env_var_present("CI") {
                 return false;
             }
-            std::env::var("EDEN_SKILLS_FORCE_TTY")
-                .ok()
-                .as_deref()
-                .is_some_and(|value| value != "0" && !value.is_empty())
-                || std::io::stdout().is_terminal()
+            env_flag_enabled("EDEN_SKILLS_FORCE_TTY") || std::io::stdout().is_terminal()
         }
     }
 }
 
+/// Whether a boolean environment switch is set to anything but `0`.
+fn env_flag_enabled(name: &str) -> bool {
+    std::env::var(name)
+        .ok()
+        .as_deref()
+        .is_some_and(|value| value != "0" && !value.is_empty())
+}
+
 fn env_var_present(name: &str) -> bool {
     std::env::var_os(name).is_some_and(|value| !value.is_empty())
 }
@@ -461,6 +601,12 @@ struct Cli {
         help = "Control color output"
     )]
     color: ColorWhen,
+    #[arg(
+        long,
+        global = true,
+        help = "Never access the network; use only cached sources (or set EDEN_SKILLS_OFFLINE=1)"
+    )]
+    offline: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -482,6 +628,16 @@ enum Commands {
         next_help_heading = "Quick Management"
     )]
     Remove(RemoveArgs),
+    #[command(
+        about = "Search cached registry indexes for skills",
+        next_help_heading = "Quick Management"
+    )]
+    Search(SearchArgs),
+    #[command(
+        about = "Show registry versions and installed state of a skill",
+        next_help_heading = "Quick Management"
+    )]
+    Info(InfoArgs),
     #[command(
         about = "Remove orphaned cache entries and stale discovery directories",
         next_help_heading = "State Reconciliation"
@@ -491,12 +647,27 @@ enum Commands {
         about = "Preview planned actions without making changes",
         next_help_heading = "State Reconciliation"
     )]
-    Plan(CommonArgs),
+    Plan(PlanArgs),
+    #[command(
+        about = "Show how installed copies of a skill differ from its source",
+        next_help_heading = "State Reconciliation"
+    )]
+    Diff(DiffArgs),
     #[command(
         about = "Reconcile installed state with configuration",
         next_help_heading = "State Reconciliation"
     )]
-    Apply(ApplyRepairArgs),
+    Apply(ApplyArgs),
+    #[command(
+        about = "List recorded generations of installed state",
+        next_help_heading = "State Reconciliation"
+    )]
+    History(ProjectScopedArgs),
+    #[command(
+        about = "Restore the config, lock, and targets of an earlier generation",
+        next_help_heading = "State Reconciliation"
+    )]
+    Rollback(RollbackArgs),
     #[command(
         about = "Diagnose configuration and installation health",
         next_help_heading = "State Reconciliation"
@@ -521,7 +692,7 @@ enum Commands {
         about = "List configured skills and their targets",
         next_help_heading = "Configuration"
     )]
-    List(CommonArgs),
+    List(ProjectScopedArgs),
     #[command(
         about = "Add a skill entry to skills.toml",
         next_help_heading = "Configuration"
@@ -540,14 +711,38 @@ enum Commands {
 }
 
 impl Commands {
+    /// `--config` and `--project` of the commands that accept a project
+    /// scope.
+    fn project_args(&mut self) -> Option<(&str, &mut Option<String>)> {
+        match self {
+            Self::Install(args) => Some((&args.config, &mut args.project)),
+            Self::Remove(args) => Some((&args.config, &mut args.project)),
+            Self::Search(args) => Some((&args.config, &mut args.project)),
+            Self::Info(args) => Some((&args.config, &mut args.project)),
+            Self::Plan(args) => Some((&args.config, &mut args.project)),
+            Self::Diff(args) => Some((&args.config, &mut args.project)),
+            Self::Apply(args) => Some((&args.config, &mut args.project)),
+            Self::History(args) => Some((&args.config, &mut args.project)),
+            Self::Rollback(args) => Some((&args.config, &mut args.project)),
+            Self::Repair(args) => Some((&args.config, &mut args.project)),
+            Self::List(args) => Some((&args.config, &mut args.project)),
+            _ => None,
+        }
+    }
+
     fn json_mode(&self) -> bool {
         match self {
             Self::Install(args) => args.json,
             Self::Update(args) => args.json,
             Self::Remove(args) => args.json,
+            Self::Search(args) => args.json,
+            Self::Info(args) => args.json,
             Self::Clean(args) => args.json,
             Self::Plan(args) => args.json,
+            Self::Diff(args) => args.json,
             Self::Apply(args) => args.json,
+            Self::History(args) => args.json,
+            Self::Rollback(args) => args.json,
             Self::Doctor(args) => args.json,
             Self::Docker(_) => false,
             Self::Repair(args) => args.json,
@@ -617,6 +812,30 @@ struct CommonArgs {
     json: bool,
 }
 
+#[derive(Debug, Clone, Args)]
+struct ProjectScopedArgs {
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+}
+
 #[derive(Debug, Clone, Args)]
 struct DoctorArgs {
     #[arg(
@@ -634,6 +853,201 @@ struct DoctorArgs {
     no_warning: bool,
 }
 
+#[derive(Debug, Clone, Args)]
+struct PlanArgs {
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Save the plan to FILE for a later `apply FILE`"
+    )]
+    out: Option<String>,
+    #[arg(
+        long,
+        help = "Exit 0 when up to date, 4 when changes are pending, 3 on conflicts"
+    )]
+    detailed_exitcode: bool,
+    #[arg(long, help = "List file-level differences for each copy-mode update")]
+    diff: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DiffArgs {
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+    #[arg(help = "Skill identifier to compare")]
+    skill_id: String,
+}
+
+#[derive(Debug, Clone, Args)]
+struct ApplyArgs {
+    #[arg(
+        value_name = "PLANFILE",
+        conflicts_with_all = ["config", "project", "locked", "frozen"],
+        help = "Execute a plan saved by `plan --out` instead of planning anew"
+    )]
+    plan_file: Option<String>,
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(long, help = "Maximum number of concurrent operations")]
+    concurrency: Option<usize>,
+    #[arg(long, help = "Force ownership reclaim for docker-managed targets")]
+    force: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+    #[arg(
+        long,
+        help = "Install exactly the commits in skills.lock; fail if the lock is missing or stale"
+    )]
+    locked: bool,
+    #[arg(long, help = "Like --locked, and never fetch from the network")]
+    frozen: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SearchArgs {
+    #[arg(
+        required = true,
+        value_name = "QUERY",
+        help = "Terms matched against skill names, tags, and descriptions"
+    )]
+    query: Vec<String>,
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+struct InfoArgs {
+    #[arg(value_name = "NAME", help = "Skill name or installed skill ID")]
+    name: String,
+    #[arg(
+        long,
+        help = "Version constraint to evaluate (e.g. ^2.0) [default: latest]"
+    )]
+    version: Option<String>,
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+struct RollbackArgs {
+    #[arg(
+        long,
+        default_value = DEFAULT_CONFIG_PATH,
+        hide_default_value = true,
+        help = "Path to skills.toml config file [default: ~/.eden-skills/skills.toml]"
+    )]
+    config: String,
+    #[arg(long, help = "Exit with error on drift or warnings")]
+    strict: bool,
+    #[arg(long, help = "Output machine-readable JSON")]
+    json: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Generation to restore [default: the one before the current generation]"
+    )]
+    to: Option<u64>,
+}
+
 #[derive(Debug, Clone, Args)]
 struct ApplyRepairArgs {
     #[arg(
@@ -651,6 +1065,15 @@ struct ApplyRepairArgs {
     concurrency: Option<usize>,
     #[arg(long, help = "Force ownership reclaim for docker-managed targets")]
     force: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -673,6 +1096,12 @@ struct UpdateArgs {
         help = "After refresh, reconcile skills with detected source updates"
     )]
     apply: bool,
+    #[arg(
+        long,
+        conflicts_with = "apply",
+        help = "Show upstream changes for each skill without syncing registries"
+    )]
+    dry_run: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -697,6 +1126,8 @@ struct InstallArgs {
     id: Option<String>,
     #[arg(long, help = "Git reference (branch, tag, or commit)")]
     r#ref: Option<String>,
+    #[arg(long, value_name = "HEX", help = "Expected SHA-256 of an archive URL")]
+    sha256: Option<String>,
     #[arg(
         short = 's',
         long,
@@ -724,6 +1155,15 @@ struct InstallArgs {
     copy: bool,
     #[arg(long, help = "Overwrite externally-managed targets and take ownership")]
     force: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Install into the project's agent directories using <DIR>/skills.toml [default: .]"
+    )]
+    project: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -859,6 +1299,15 @@ struct RemoveArgs {
     force: bool,
     #[arg(long, help = "Run cache cleanup after removing the selected skills")]
     auto_clean: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "config",
+        help = "Use the project config <DIR>/skills.toml, layered over the user config [default: .]"
+    )]
+    project: Option<String>,
 
     #[arg(
         value_name = "SKILL_ID",
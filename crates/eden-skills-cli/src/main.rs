@@ -37,6 +37,7 @@ fn print_error(err: &CliError) {
     match err {
         CliError::Domain(err) => print_domain_error(err),
         CliError::Clap(err) => print_clap_error(err),
+        CliError::ChangesPending => {}
     }
 }
 
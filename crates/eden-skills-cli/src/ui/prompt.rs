@@ -261,10 +261,8 @@ pub fn prompt_skill_multi_select(
             Key::ArrowUp | Key::Char('k') => {
                 cursor = cursor.saturating_sub(1);
             }
-            Key::ArrowDown | Key::Char('j') => {
-                if cursor + 1 < items.len() {
-                    cursor += 1;
-                }
+            Key::ArrowDown | Key::Char('j') if cursor + 1 < items.len() => {
+                cursor += 1;
             }
             Key::Char(' ') => {
                 if let Some(current) = selected.get_mut(cursor) {
@@ -0,0 +1,158 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use eden_skills_core::digest::sha256_hex;
+use tempfile::tempdir;
+
+use common::{assert_success, eden_command, path_to_file_url, toml_escape_path};
+
+const SKILL_MD: &str = "---\nname: packed-skill\ndescription: from an archive\n---\n";
+
+/// Pack `files` under a top-level `packed-skill/` directory into a
+/// `.tar.gz` and return its path.
+fn write_tar_gz(base: &Path, files: &[(&str, &str)]) -> PathBuf {
+    let staging = base.join("archive-src");
+    for (rel, content) in files {
+        let path = staging.join("packed-skill").join(rel);
+        fs::create_dir_all(path.parent().expect("parent")).expect("create parent dirs");
+        fs::write(&path, content).expect("write file");
+    }
+    let archive = base.join("packed-skill-1.0.tar.gz");
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&staging)
+        .arg("packed-skill")
+        .status()
+        .expect("spawn tar");
+    assert!(status.success(), "tar should succeed");
+    archive
+}
+
+fn write_storage_config(config_path: &Path, storage_root: &Path) {
+    fs::write(
+        config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n",
+            toml_escape_path(storage_root)
+        ),
+    )
+    .expect("write config");
+}
+
+#[test]
+fn install_archive_url_records_checksum_in_config_and_lock() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let archive = write_tar_gz(temp.path(), &[("SKILL.md", SKILL_MD)]);
+    let sha256 = sha256_hex(&fs::read(&archive).expect("read archive"));
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    write_storage_config(&config_path, &storage_root);
+
+    let output = eden_command(&home_dir)
+        .args(["install", &path_to_file_url(&archive), "--copy", "--all"])
+        .args(["--sha256", &sha256, "--target"])
+        .arg(format!("custom:{}", target_root.display()))
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_success(&output);
+    assert_eq!(
+        fs::read_to_string(target_root.join("packed-skill/SKILL.md")).expect("read installed"),
+        SKILL_MD
+    );
+
+    let config = fs::read_to_string(&config_path).expect("read config");
+    assert!(config.contains(&format!("archive = \"{}\"", path_to_file_url(&archive))));
+    assert!(config.contains(&format!("sha256 = \"{sha256}\"")));
+    assert!(config.contains("subpath = \"packed-skill\""));
+    assert!(!config.contains("ref = "), "archive sources have no ref");
+
+    let lock = fs::read_to_string(temp.path().join("skills.lock")).expect("read lock");
+    assert!(lock.contains(&format!("source_ref = \"sha256:{sha256}\"")));
+    assert!(lock.contains("resolved_commit = \"\""));
+
+    // A second apply reuses the cached archive even though it is gone.
+    fs::remove_file(&archive).expect("remove archive");
+    let output = eden_command(&home_dir)
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_success(&output);
+}
+
+#[test]
+fn install_archive_url_requires_sha256() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let archive = write_tar_gz(temp.path(), &[("SKILL.md", SKILL_MD)]);
+    let config_path = temp.path().join("skills.toml");
+    write_storage_config(&config_path, &temp.path().join("storage"));
+
+    let output = eden_command(&home_dir)
+        .args(["install", &path_to_file_url(&archive), "--all", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("requires a checksum"), "stderr={stderr}");
+    assert!(stderr.contains("--sha256"), "stderr={stderr}");
+}
+
+#[test]
+fn apply_fails_on_archive_checksum_mismatch() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let archive = write_tar_gz(temp.path(), &[("SKILL.md", SKILL_MD)]);
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version = 1
+
+[storage]
+root = "{}"
+
+[[skills]]
+id = "packed-skill"
+
+[skills.source]
+archive = "{}"
+sha256 = "{}"
+subpath = "packed-skill"
+
+[[skills.targets]]
+agent = "custom"
+path = "{}"
+"#,
+            toml_escape_path(&storage_root),
+            path_to_file_url(&archive),
+            "0".repeat(64),
+            toml_escape_path(&target_root),
+        ),
+    )
+    .expect("write config");
+
+    let output = eden_command(&home_dir)
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_ne!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("checksum mismatch"), "stderr={stderr}");
+    assert!(!target_root.join("packed-skill").exists());
+}
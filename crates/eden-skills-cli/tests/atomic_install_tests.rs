@@ -0,0 +1,213 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use eden_skills_cli::commands::apply;
+use tempfile::tempdir;
+
+use common::{
+    default_options, init_git_repo, path_to_file_url, toml_escape_path, toml_escape_string,
+};
+
+fn write_two_skill_config(base: &Path, good_repo: &Path, broken_repo: &Path) -> String {
+    let target_root = base.join("agent-skills");
+    let config = format!(
+        r#"version = 1
+
+[storage]
+root = "{storage}"
+
+[[skills]]
+id = "good-skill"
+
+[skills.source]
+repo = "{good}"
+ref = "main"
+
+[skills.install]
+mode = "copy"
+
+[[skills.targets]]
+agent = "custom"
+path = "{target}"
+
+[[skills]]
+id = "broken-skill"
+
+[skills.source]
+repo = "{broken}"
+ref = "main"
+
+[skills.install]
+mode = "copy"
+
+[[skills.targets]]
+agent = "custom"
+path = "{target}"
+"#,
+        storage = toml_escape_path(&base.join("storage")),
+        good = toml_escape_string(&path_to_file_url(good_repo)),
+        broken = toml_escape_string(&path_to_file_url(broken_repo)),
+        target = toml_escape_path(&target_root),
+    );
+    let config_path = base.join("skills.toml");
+    fs::write(&config_path, config).expect("write config");
+    config_path.display().to_string()
+}
+
+/// Find the repo cache checkout whose `SKILL.md` has `content`.
+#[cfg(unix)]
+fn cached_source_with(storage_root: &Path, content: &str) -> PathBuf {
+    fs::read_dir(storage_root.join(".repos"))
+        .expect("read repo cache")
+        .map(|entry| entry.expect("entry").path())
+        .find(|path| fs::read_to_string(path.join("SKILL.md")).is_ok_and(|raw| raw == content))
+        .expect("cached source")
+}
+
+#[test]
+fn copy_install_leaves_no_staging_or_backup_entries() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "good-repo", &[("SKILL.md", "v1\n")]);
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[[skills]]\nid = \"good-skill\"\n\n[skills.source]\nrepo = \"{}\"\nref = \"main\"\n\n[skills.install]\nmode = \"copy\"\n\n[[skills.targets]]\nagent = \"custom\"\npath = \"{}\"\n",
+            toml_escape_path(&temp.path().join("storage")),
+            toml_escape_string(&path_to_file_url(&repo)),
+            toml_escape_path(&temp.path().join("agent-skills")),
+        ),
+    )
+    .expect("write config");
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("first apply");
+    let target_root = temp.path().join("agent-skills");
+    fs::write(target_root.join("good-skill").join("SKILL.md"), "edited\n").expect("edit target");
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("second apply");
+
+    let entries = fs::read_dir(&target_root)
+        .expect("read target root")
+        .map(|entry| {
+            entry
+                .expect("entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(entries, vec!["good-skill".to_string()]);
+    assert_eq!(
+        fs::read_to_string(target_root.join("good-skill").join("SKILL.md")).expect("read"),
+        "v1\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_apply_rolls_back_targets_it_already_replaced() {
+    let temp = tempdir().expect("tempdir");
+    let good_repo = init_git_repo(temp.path(), "good-repo", &[("SKILL.md", "v1\n")]);
+    let broken_repo = init_git_repo(temp.path(), "broken-repo", &[("SKILL.md", "ok\n")]);
+    let config_path = write_two_skill_config(temp.path(), &good_repo, &broken_repo);
+    apply(&config_path, default_options()).expect("initial apply");
+
+    // Drift the good skill's installed copy so apply updates it first,
+    // then plant a dangling symlink in the broken skill's cached source,
+    // which cannot be copied, and drop its installed copy so that it is
+    // re-created after the good skill is replaced.
+    let target_root = temp.path().join("agent-skills");
+    fs::write(target_root.join("good-skill").join("SKILL.md"), "edited\n").expect("edit target");
+    std::os::unix::fs::symlink(
+        "missing-file",
+        cached_source_with(&temp.path().join("storage"), "ok\n").join("dangling"),
+    )
+    .expect("create dangling symlink");
+    fs::remove_dir_all(target_root.join("broken-skill")).expect("remove broken target");
+
+    apply(&config_path, default_options()).expect_err("copying a dangling symlink should fail");
+
+    assert_eq!(
+        fs::read_to_string(target_root.join("good-skill").join("SKILL.md")).expect("read"),
+        "edited\n",
+        "good-skill should be rolled back to its previous content"
+    );
+    let entries = fs::read_dir(&target_root)
+        .expect("read target root")
+        .map(|entry| {
+            entry
+                .expect("entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(entries, vec!["good-skill".to_string()]);
+}
+
+#[test]
+fn failed_local_source_install_restores_targets_it_already_replaced() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let source_dir = temp.path().join("local-skill");
+    fs::create_dir_all(&source_dir).expect("create source dir");
+    fs::write(source_dir.join("SKILL.md"), "v1\n").expect("write source");
+    let first_root = temp.path().join("first-agent");
+    let second_root = temp.path().join("second-agent");
+    let config_path = temp.path().join("skills.toml");
+    let install = || {
+        common::eden_command(&home_dir)
+            .args(["install"])
+            .arg(&source_dir)
+            .args(["--copy", "--target"])
+            .arg(format!("custom:{}", first_root.display()))
+            .arg("--target")
+            .arg(format!("custom:{}", second_root.display()))
+            .arg("--config")
+            .arg(&config_path)
+            .output()
+            .expect("run install")
+    };
+    common::assert_success(&install());
+
+    // Replace the second agent dir with a file so its target cannot be
+    // staged after the first target has already been swapped.
+    fs::write(source_dir.join("SKILL.md"), "v2\n").expect("update source");
+    fs::remove_dir_all(&second_root).expect("remove second agent dir");
+    fs::write(&second_root, "not a directory").expect("block second agent dir");
+
+    let output = install();
+    assert_ne!(output.status.code(), Some(0), "install should fail");
+    assert_eq!(
+        fs::read_to_string(first_root.join("local-skill").join("SKILL.md")).expect("read"),
+        "v1\n",
+        "the first target should be rolled back to its previous content"
+    );
+    let entries = fs::read_dir(&first_root)
+        .expect("read first agent dir")
+        .map(|entry| {
+            entry
+                .expect("entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        entries
+            .iter()
+            .all(|entry| !entry.ends_with(".eden-staging") && !entry.ends_with(".eden-backup")),
+        "no staging or backup entries should be left behind, entries={entries:?}"
+    );
+}
@@ -5,12 +5,14 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use eden_skills_core::source::repo_cache_key;
+use eden_skills_core::git;
+use eden_skills_core::source::{normalize_repo_url, repo_cache_key};
 use serde_json::Value;
 use tempfile::tempdir;
 
 use common::{
-    as_file_url, assert_success, init_origin_repo, toml_escape_path, write_config, SKILL_ID,
+    as_file_url, assert_success, init_origin_repo, run_git_cmd, toml_escape_path, write_config,
+    SKILL_ID,
 };
 
 #[test]
@@ -300,6 +302,139 @@ fn tm_p297_036_clean_reports_freed_disk_space_in_human_mode() {
     );
 }
 
+#[test]
+fn clean_collects_object_store_until_its_last_worktree_is_removed() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let origin_repo = init_origin_repo(temp.path());
+    run_git_cmd(&origin_repo, &["tag", "v2"]);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+    let config_path = write_config(
+        temp.path(),
+        &repo_url,
+        "symlink",
+        &["path-exists"],
+        &storage_root,
+        &temp.path().join("agent-skills"),
+    );
+    let store = storage_root
+        .join(".objects")
+        .join(format!("{}.git", normalize_repo_url(&repo_url)));
+    let main_entry = storage_root
+        .join(".repos")
+        .join(repo_cache_key(&repo_url, "main"));
+    let tag_entry = storage_root
+        .join(".repos")
+        .join(repo_cache_key(&repo_url, "v2"));
+
+    assert_success(&run_eden(&home_dir, &["apply"], &config_path));
+    let config = fs::read_to_string(&config_path).expect("read config");
+    fs::write(
+        &config_path,
+        config.replace("ref = \"main\"", "ref = \"v2\""),
+    )
+    .expect("switch ref");
+    assert_success(&run_eden(&home_dir, &["apply"], &config_path));
+    assert_eq!(git::store_worktrees(&store).len(), 2);
+    // Without generations pinning them, unreferenced entries are orphans.
+    fs::remove_dir_all(storage_root.join(".generations")).expect("drop generations");
+
+    // The `main` entry is orphaned; the store still backs `v2`.
+    let output = run_eden(&home_dir, &["clean", "--json"], &config_path);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("clean json");
+    assert_eq!(
+        payload["removed_cache_entries"],
+        serde_json::json!([main_entry.display().to_string()])
+    );
+    assert!(store.exists(), "store still has a worktree");
+    assert_eq!(git::store_worktrees(&store), vec![tag_entry.clone()]);
+
+    write_empty_config(&config_path, &storage_root);
+    let output = run_eden(&home_dir, &["clean", "--json"], &config_path);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("clean json");
+    assert_eq!(
+        payload["removed_cache_entries"],
+        serde_json::json!([tag_entry.display().to_string(), store.display().to_string()])
+    );
+    assert!(!store.exists(), "store without worktrees should be removed");
+}
+
+#[test]
+fn second_config_keeps_shared_cache_subpaths_until_clean_narrows_them() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let origin_repo = init_origin_repo(temp.path());
+    fs::create_dir_all(origin_repo.join("packages/other")).expect("create other dir");
+    fs::write(origin_repo.join("packages/other/README.txt"), "other\n").expect("write other");
+    run_git_cmd(&origin_repo, &["add", "."]);
+    run_git_cmd(&origin_repo, &["commit", "-m", "add other"]);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+    let entry = storage_root
+        .join(".repos")
+        .join(repo_cache_key(&repo_url, "main"));
+
+    let alpha_config = write_config(
+        temp.path(),
+        &repo_url,
+        "symlink",
+        &["path-exists"],
+        &storage_root,
+        &temp.path().join("alpha-skills"),
+    );
+    let beta_dir = temp.path().join("beta");
+    fs::create_dir_all(&beta_dir).expect("create beta dir");
+    let beta_config = write_config(
+        &beta_dir,
+        &repo_url,
+        "symlink",
+        &["path-exists"],
+        &storage_root,
+        &temp.path().join("beta-skills"),
+    );
+    let beta = fs::read_to_string(&beta_config).expect("read beta config");
+    fs::write(
+        &beta_config,
+        beta.replace("packages/browser", "packages/other")
+            .replace(&format!("id = \"{SKILL_ID}\""), "id = \"beta-skill\""),
+    )
+    .expect("write beta config");
+
+    assert_success(&run_eden(&home_dir, &["apply"], &alpha_config));
+    assert_success(&run_eden(&home_dir, &["apply"], &beta_config));
+    assert!(entry.join("packages/browser/README.txt").exists());
+    assert!(entry.join("packages/other/README.txt").exists());
+    let output = run_eden(&home_dir, &["doctor", "--json"], &alpha_config);
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("SOURCE_MISSING"),
+        "the second config must not narrow the first one's checkout, stdout={}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // `clean` takes the config as the owner of the storage root.
+    let output = run_eden(&home_dir, &["clean", "--json"], &alpha_config);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("clean json");
+    assert_eq!(
+        payload["narrowed_cache_entries"],
+        serde_json::json!([entry.display().to_string()])
+    );
+    assert!(entry.join("packages/browser/README.txt").exists());
+    assert!(!entry.join("packages/other").exists());
+}
+
+fn run_eden(home_dir: &Path, args: &[&str], config_path: &Path) -> std::process::Output {
+    eden_command(home_dir)
+        .args(args)
+        .arg("--config")
+        .arg(config_path)
+        .output()
+        .expect("run eden-skills")
+}
+
 fn eden_command(home_dir: &Path) -> Command {
     let mut command = Command::new(env!("CARGO_BIN_EXE_eden-skills"));
     let temp_root = temp_root_for_home(home_dir);
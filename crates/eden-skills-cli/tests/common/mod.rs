@@ -19,6 +19,8 @@ pub fn default_options() -> CommandOptions {
     CommandOptions {
         strict: false,
         json: false,
+        offline: false,
+        user_config_path: None,
     }
 }
 
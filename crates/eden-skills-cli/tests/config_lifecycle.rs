@@ -484,3 +484,90 @@ fn add_accepts_supported_agent_aliases_from_skills_ecosystem() {
         "config should persist windsurf target alias, config=\n{written}"
     );
 }
+
+const COMMENTED_CONFIG: &str = r#"# Team skills
+version = 1
+
+[storage]
+root = "./store" # shared cache
+
+# Primary skill
+[[skills]]
+id = "alpha"
+
+[skills.source]
+repo = "https://example.com/alpha.git"
+ref = "main" # track main
+
+[[skills.targets]]
+agent = "claude-code"
+
+# Keep this one pinned
+[[skills]]
+id = "beta"
+
+[skills.source]
+repo = "https://example.com/beta.git"
+
+[[skills.targets]]
+agent = "cursor"
+"#;
+
+#[test]
+fn set_edits_only_the_changed_field_and_keeps_comments() {
+    let temp = tempdir().expect("tempdir");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(&config_path, COMMENTED_CONFIG).expect("write config");
+
+    let set = eden_command(temp.path())
+        .args(["set", "alpha", "--ref", "v2", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run set");
+    common::assert_success(&set);
+
+    let written = fs::read_to_string(&config_path).expect("read config");
+    assert_eq!(
+        written,
+        COMMENTED_CONFIG.replace("ref = \"main\" # track main", "ref = \"v2\" # track main"),
+        "set should only touch the edited value"
+    );
+}
+
+#[test]
+fn add_and_remove_leave_other_entries_untouched() {
+    let temp = tempdir().expect("tempdir");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(&config_path, COMMENTED_CONFIG).expect("write config");
+
+    let add = eden_command(temp.path())
+        .args([
+            "add",
+            "--id",
+            "gamma",
+            "--repo",
+            "https://example.com/gamma.git",
+            "--target",
+            "claude-code",
+            "--config",
+        ])
+        .arg(&config_path)
+        .output()
+        .expect("run add");
+    common::assert_success(&add);
+    let written = fs::read_to_string(&config_path).expect("read config");
+    assert!(
+        written.starts_with(COMMENTED_CONFIG),
+        "add should append without rewriting existing entries, config={written}"
+    );
+    assert!(written.contains("id = \"gamma\""), "config={written}");
+
+    let remove = eden_command(temp.path())
+        .args(["remove", "gamma", "--yes", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run remove");
+    common::assert_success(&remove);
+    let written = fs::read_to_string(&config_path).expect("read config");
+    assert_eq!(written, COMMENTED_CONFIG);
+}
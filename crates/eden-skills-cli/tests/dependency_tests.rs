@@ -0,0 +1,222 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::{tempdir, TempDir};
+
+/// `deploy` requires `k8s-conventions ^1`, which requires `shell-utils`.
+struct DependencyFixture {
+    _temp: TempDir,
+    home_dir: PathBuf,
+    config_path: PathBuf,
+}
+
+impl DependencyFixture {
+    fn new() -> Self {
+        let temp = tempdir().expect("tempdir");
+        let home_dir = temp.path().join("home");
+        let storage_root = temp.path().join("storage");
+        let registry_root = storage_root.join("registries").join("official");
+        fs::create_dir_all(&registry_root).expect("create registry");
+        fs::write(
+            registry_root.join("manifest.toml"),
+            "format_version = 1\nname = \"official\"\n",
+        )
+        .expect("write manifest");
+        for (name, requires) in [
+            ("deploy", "k8s-conventions = \"^1\""),
+            ("k8s-conventions", "shell-utils = \"*\""),
+            ("shell-utils", ""),
+        ] {
+            let repo = common::init_git_repo(
+                temp.path(),
+                &format!("{name}-origin"),
+                &[("SKILL.md", &format!("---\nname: {name}\n---\n"))],
+            );
+            write_index_entry(&registry_root, name, &repo, requires);
+        }
+
+        let config_path = temp.path().join("skills.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "version = 1\n\n[storage]\nroot = \"{}\"\n\n[registries]\nofficial = {{ url = \"https://example.com/official.git\", priority = 100 }}\n",
+                common::toml_escape_path(&storage_root)
+            ),
+        )
+        .expect("write config");
+        Self {
+            _temp: temp,
+            home_dir,
+            config_path,
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        common::eden_command(&self.home_dir)
+            .args(args)
+            .arg("--config")
+            .arg(&self.config_path)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("run eden-skills")
+    }
+
+    fn skill_target(&self, name: &str) -> PathBuf {
+        self.home_dir.join(".claude").join("skills").join(name)
+    }
+
+    fn lock(&self) -> toml::Value {
+        let raw = fs::read_to_string(self.config_path.with_extension("lock")).expect("read lock");
+        toml::from_str(&raw).expect("parse lock")
+    }
+}
+
+#[test]
+fn install_adds_transitive_requirements_as_implicit_lock_entries() {
+    let fixture = DependencyFixture::new();
+    let output = fixture.run(&["install", "deploy"]);
+    common::assert_success(&output);
+
+    let config = fs::read_to_string(&fixture.config_path).expect("read config");
+    assert!(config.contains("name = \"deploy\""), "config=\n{config}");
+    assert!(
+        !config.contains("k8s-conventions"),
+        "implicit skills stay out of the config, config=\n{config}"
+    );
+
+    let lock = fixture.lock();
+    let entries = lock_entries(&lock);
+    assert_eq!(
+        entries
+            .iter()
+            .map(|(id, implicit, requires)| (id.as_str(), *implicit, requires.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("deploy", false, vec!["k8s-conventions".to_string()]),
+            ("k8s-conventions", true, vec!["shell-utils".to_string()]),
+            ("shell-utils", true, Vec::new()),
+        ]
+    );
+    for name in ["deploy", "k8s-conventions", "shell-utils"] {
+        assert!(
+            fixture.skill_target(name).join("SKILL.md").exists(),
+            "`{name}` should be installed"
+        );
+    }
+
+    let output = fixture.run(&["plan"]);
+    common::assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "  Requires:\n    deploy\n    └─ k8s-conventions ^1 (implicit)\n       └─ shell-utils * (implicit)\n"
+        ),
+        "stdout={stdout}"
+    );
+    assert!(
+        !stdout.contains("remove"),
+        "implicit lock entries are not orphans, stdout={stdout}"
+    );
+
+    let output = fixture.run(&["remove", "deploy"]);
+    common::assert_success(&output);
+    assert!(
+        lock_entries(&fixture.lock()).is_empty(),
+        "implicit entries go with the last skill requiring them"
+    );
+    assert!(!fixture.skill_target("shell-utils").exists());
+}
+
+#[test]
+fn remove_warns_about_dependents_and_apply_restores_them_as_implicit() {
+    let fixture = DependencyFixture::new();
+    common::assert_success(&fixture.run(&["install", "deploy"]));
+    common::assert_success(&fixture.run(&["install", "k8s-conventions"]));
+    let entries = lock_entries(&fixture.lock());
+    assert!(
+        entries.contains(&(
+            "k8s-conventions".to_string(),
+            false,
+            vec!["shell-utils".to_string()]
+        )),
+        "configuring a skill makes it explicit, entries={entries:?}"
+    );
+
+    let output = fixture.run(&["remove", "k8s-conventions"]);
+    common::assert_success(&output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "'k8s-conventions' is required by 'deploy'; `eden-skills apply` will reinstall it as an implicit dependency"
+        ),
+        "stderr={stderr}"
+    );
+
+    common::assert_success(&fixture.run(&["apply"]));
+    let entries = lock_entries(&fixture.lock());
+    assert!(
+        entries.contains(&(
+            "k8s-conventions".to_string(),
+            true,
+            vec!["shell-utils".to_string()]
+        )),
+        "entries={entries:?}"
+    );
+}
+
+/// `(id, implicit, requires)` for every lock entry.
+fn lock_entries(lock: &toml::Value) -> Vec<(String, bool, Vec<String>)> {
+    lock.get("skills")
+        .and_then(toml::Value::as_array)
+        .map(|skills| {
+            skills
+                .iter()
+                .map(|skill| {
+                    (
+                        skill["id"].as_str().expect("id").to_string(),
+                        skill
+                            .get("implicit")
+                            .and_then(toml::Value::as_bool)
+                            .unwrap_or(false),
+                        skill
+                            .get("requires")
+                            .and_then(toml::Value::as_array)
+                            .map(|names| {
+                                names
+                                    .iter()
+                                    .map(|name| name.as_str().expect("name").to_string())
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_index_entry(registry_root: &Path, name: &str, repo: &Path, requires: &str) {
+    let index_dir = registry_root.join("index").join(&name[..1]);
+    fs::create_dir_all(&index_dir).expect("create index dir");
+    fs::write(
+        index_dir.join(format!("{name}.toml")),
+        format!(
+            "[skill]\nname = \"{name}\"\nrepo = \"{}\"\n\n[[versions]]\nversion = \"1.0.0\"\nref = \"main\"\ncommit = \"{}\"\nrequires = {{ {requires} }}\n",
+            common::path_to_file_url(repo),
+            git_head(repo)
+        ),
+    )
+    .expect("write index entry");
+}
+
+fn git_head(repo: &Path) -> String {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo)
+        .output()
+        .expect("git rev-parse");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eden_skills_cli::commands::apply;
+use tempfile::tempdir;
+
+use common::{
+    as_file_url, assert_success, default_options, eden_command, expected_target_path,
+    init_origin_repo, write_config, SKILL_ID,
+};
+
+/// Apply a copy-mode config, then hand-edit the installed copy: change
+/// `README.txt` and add `notes.md`.
+fn install_and_edit_copy(base: &Path) -> (PathBuf, PathBuf) {
+    let origin_repo = init_origin_repo(base);
+    let storage_root = base.join("storage");
+    let target_root = base.join("agent-skills");
+    let config_path = write_config(
+        base,
+        &as_file_url(&origin_repo),
+        "copy",
+        &["path-exists"],
+        &storage_root,
+        &target_root,
+    );
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("apply");
+
+    let target = expected_target_path(&target_root);
+    fs::write(target.join("README.txt"), "v1 edited by hand\n").expect("edit target");
+    fs::write(target.join("notes.md"), "local notes\n").expect("add target file");
+    (config_path, target)
+}
+
+#[test]
+fn diff_lists_hand_edits_with_unified_diff() {
+    let temp = tempdir().expect("tempdir");
+    let (config_path, _) = install_and_edit_copy(temp.path());
+
+    let output = eden_command(temp.path())
+        .args(["diff", SKILL_ID, "--color", "never", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run diff");
+    assert_success(&output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("modified  README.txt"), "stdout={stdout}");
+    assert!(stdout.contains("added     notes.md"), "stdout={stdout}");
+    assert!(stdout.contains("--- a/README.txt"), "stdout={stdout}");
+    assert!(stdout.contains("-v1"), "stdout={stdout}");
+    assert!(stdout.contains("+v1 edited by hand"), "stdout={stdout}");
+}
+
+#[test]
+fn diff_rejects_unknown_skill() {
+    let temp = tempdir().expect("tempdir");
+    let (config_path, _) = install_and_edit_copy(temp.path());
+
+    let output = eden_command(temp.path())
+        .args(["diff", "no-such-skill", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run diff");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown skill id: `no-such-skill`"),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn plan_diff_json_attaches_changes_to_copy_updates() {
+    let temp = tempdir().expect("tempdir");
+    let (config_path, _) = install_and_edit_copy(temp.path());
+
+    let output = eden_command(temp.path())
+        .args(["plan", "--diff", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run plan --diff");
+    assert_success(&output);
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("plan json should parse");
+    let item = &payload.as_array().expect("plan array")[0];
+    assert_eq!(item["action"], "update");
+    let changes = item["changes"].as_array().expect("changes array");
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0]["path"], "README.txt");
+    assert_eq!(changes[0]["kind"], "modified");
+    assert!(changes[0]["unified_diff"]
+        .as_str()
+        .expect("unified diff")
+        .contains("+v1 edited by hand"));
+    assert_eq!(changes[1]["path"], "notes.md");
+    assert_eq!(changes[1]["kind"], "added");
+}
+
+#[cfg(unix)]
+#[test]
+fn diff_reads_docker_targets_through_the_adapter() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().expect("tempdir");
+    let (config_path, target) = install_and_edit_copy(temp.path());
+
+    // Stand in for the container filesystem with a host directory, and
+    // move the edited copy to where the container target would live.
+    let container_root = temp.path().join("container-fs");
+    let container_target = container_root.join("opt/skills").join(SKILL_ID);
+    fs::create_dir_all(container_target.parent().expect("parent")).expect("create container dir");
+    fs::rename(&target, &container_target).expect("move target into container");
+
+    let config = fs::read_to_string(&config_path).expect("read config");
+    let target_block_start = config.find("\npath = ").expect("target path line") + 1;
+    let target_block_end = target_block_start
+        + config[target_block_start..]
+            .find('\n')
+            .expect("end of target path line");
+    let config = format!(
+        "{}path = \"/opt/skills\"\nenvironment = \"docker:skill-box\"{}",
+        &config[..target_block_start],
+        &config[target_block_end..]
+    );
+    fs::write(&config_path, config).expect("write docker config");
+
+    let bin_dir = temp.path().join("bin");
+    fs::create_dir_all(&bin_dir).expect("create bin dir");
+    let docker_bin = bin_dir.join("docker");
+    let script = format!(
+        r#"#!/bin/sh
+set -eu
+root="{root}"
+cmd="$1"
+shift
+case "$cmd" in
+  --version) echo "Docker version 27.0.0" ;;
+  inspect)
+    if [ "$2" = "{{{{.State.Running}}}}" ]; then echo "true"; else echo "[]"; fi ;;
+  exec)
+    path="${{4#test -e }}"
+    path="${{path#\"}}"
+    path="${{path%\"}}"
+    test -e "$root$path" ;;
+  cp)
+    cp -R "$root${{1#skill-box:}}" "$2" ;;
+  *) echo "unsupported docker call: $cmd" >&2; exit 1 ;;
+esac
+"#,
+        root = container_root.display()
+    );
+    fs::write(&docker_bin, script).expect("write docker stub");
+    let mut perms = fs::metadata(&docker_bin)
+        .expect("stub metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&docker_bin, perms).expect("make stub executable");
+
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let output = eden_command(temp.path())
+        .env("PATH", path)
+        .args(["diff", SKILL_ID, "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run diff");
+    assert_success(&output);
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("diff json should parse");
+    let report = &payload.as_array().expect("report array")[0];
+    assert_eq!(report["environment"], "docker:skill-box");
+    assert_eq!(report["target_path"], format!("/opt/skills/{SKILL_ID}"));
+    assert_eq!(report["status"], "differs");
+    let paths = report["changes"]
+        .as_array()
+        .expect("changes array")
+        .iter()
+        .map(|change| change["path"].as_str().unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["README.txt", "notes.md"]);
+}
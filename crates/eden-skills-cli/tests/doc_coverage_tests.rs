@@ -24,6 +24,7 @@ fn tm_p28_003_commands_modules_have_module_docs() {
         "diagnose.rs",
         "plan_cmd.rs",
         "config_ops.rs",
+        "config_edit.rs",
         "remove.rs",
         "update.rs",
         "common.rs",
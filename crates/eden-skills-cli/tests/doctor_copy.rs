@@ -1,6 +1,8 @@
 mod common;
 
 use std::fs;
+use std::path::Path;
+use std::process::Command;
 
 use eden_skills_cli::commands::{apply, doctor, CommandOptions};
 use eden_skills_core::config::{config_dir_from_path, load_from_file, LoadOptions};
@@ -9,10 +11,26 @@ use eden_skills_core::plan::{build_plan, Action};
 use tempfile::tempdir;
 
 use common::{
-    as_file_url, default_options, expected_source_path, init_origin_repo, run_git_cmd,
-    write_config, SKILL_ID,
+    as_file_url, default_options, expected_source_path, expected_target_path, init_origin_repo,
+    run_git_cmd, write_config, SKILL_ID,
 };
 
+fn doctor_finding_codes(config_path: &Path) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_eden-skills"))
+        .args(["doctor", "--json", "--config"])
+        .arg(config_path)
+        .output()
+        .expect("run doctor --json");
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("doctor json should parse");
+    payload["findings"]
+        .as_array()
+        .expect("findings array")
+        .iter()
+        .map(|finding| finding["code"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
 #[test]
 fn doctor_strict_detects_missing_source() {
     let temp = tempdir().expect("tempdir");
@@ -41,6 +59,8 @@ fn doctor_strict_detects_missing_source() {
         CommandOptions {
             strict: true,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
         false,
     )
@@ -131,8 +151,124 @@ fn tm_p295_036_doctor_resolves_remote_sources_from_repo_cache() {
         CommandOptions {
             strict: true,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
         false,
     )
     .expect("doctor should accept repo-cache-backed source paths");
 }
+
+#[test]
+fn doctor_detects_hand_edited_copy_target_against_lock_digest() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "copy",
+        &["path-exists", "content-present"],
+        &storage_root,
+        &target_root,
+    );
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("apply");
+    let lock_text = fs::read_to_string(temp.path().join("skills.lock")).expect("read skills.lock");
+    assert_eq!(
+        lock_text.matches("content_digest = \"sha256:").count(),
+        2,
+        "lock should pin source and target digests, lock={lock_text}"
+    );
+    assert!(!doctor_finding_codes(&config_path).contains(&"CONTENT_DIGEST_MISMATCH".to_string()));
+
+    let target_file = expected_target_path(&target_root).join("README.txt");
+    fs::write(&target_file, "edited by hand\n").expect("edit target");
+    assert!(doctor_finding_codes(&config_path).contains(&"CONTENT_DIGEST_MISMATCH".to_string()));
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("re-apply restores content");
+    assert!(!doctor_finding_codes(&config_path).contains(&"CONTENT_DIGEST_MISMATCH".to_string()));
+}
+
+#[test]
+fn content_hash_verify_check_reports_tampered_target() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "copy",
+        &["path-exists", "content-hash"],
+        &storage_root,
+        &target_root,
+    );
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("apply");
+    fs::write(
+        expected_target_path(&target_root).join("extra.txt"),
+        "injected\n",
+    )
+    .expect("add file to target");
+
+    let codes = doctor_finding_codes(&config_path);
+    assert_eq!(
+        codes
+            .iter()
+            .filter(|code| code.as_str() == "CONTENT_DIGEST_MISMATCH")
+            .count(),
+        1,
+        "tampering should be reported once, codes={codes:?}"
+    );
+}
+
+#[test]
+fn doctor_detects_altered_cache_checkout() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "symlink",
+        &["path-exists", "target-resolves", "is-symlink"],
+        &storage_root,
+        &target_root,
+    );
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("apply");
+    fs::write(
+        expected_source_path(&storage_root).join("README.txt"),
+        "altered cache\n",
+    )
+    .expect("alter cache checkout");
+
+    let codes = doctor_finding_codes(&config_path);
+    assert!(
+        codes.contains(&"SOURCE_DIGEST_MISMATCH".to_string()),
+        "codes={codes:?}"
+    );
+    assert!(!codes.contains(&"CONTENT_DIGEST_MISMATCH".to_string()));
+}
@@ -544,6 +544,54 @@ fn apply_strict_returns_exit_code_3_on_target_conflict() {
     );
 }
 
+#[test]
+fn plan_detailed_exitcode_distinguishes_changes_and_conflicts() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "symlink",
+        &["path-exists", "target-resolves", "is-symlink"],
+        &storage_root,
+        &target_root,
+    );
+    let plan_exit_code = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_eden-skills"))
+            .args(["plan", "--detailed-exitcode", "--config"])
+            .arg(&config_path)
+            .output()
+            .expect("run plan");
+        output.status.code()
+    };
+
+    assert_eq!(plan_exit_code(), Some(3), "missing source is a conflict");
+
+    let apply = Command::new(env!("CARGO_BIN_EXE_eden-skills"))
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_eq!(apply.status.code(), Some(0));
+    assert_eq!(plan_exit_code(), Some(0), "applied config is up to date");
+
+    remove_symlink(&target_root.join("demo-skill")).expect("remove installed symlink");
+    assert_eq!(
+        plan_exit_code(),
+        Some(4),
+        "missing target is a pending create"
+    );
+
+    let plain = Command::new(env!("CARGO_BIN_EXE_eden-skills"))
+        .args(["plan", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run plan without flag");
+    assert_eq!(plain.status.code(), Some(0));
+}
+
 fn write_multiskill_config(
     base: &Path,
     storage_root: &Path,
@@ -0,0 +1,266 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use eden_skills_cli::commands::apply;
+use eden_skills_core::generations::{current_generation, list_generations, read_generation};
+use tempfile::tempdir;
+
+use common::{
+    assert_success, default_options, eden_command, init_git_repo, path_to_file_url, run_git_cmd,
+    toml_escape_path, toml_escape_string,
+};
+
+fn skill_entry(id: &str, repo: &Path, target_root: &Path) -> String {
+    format!(
+        "\n[[skills]]\nid = \"{id}\"\n\n[skills.source]\nrepo = \"{}\"\nref = \"main\"\n\n[skills.install]\nmode = \"copy\"\n\n[[skills.targets]]\nagent = \"custom\"\npath = \"{}\"\n",
+        toml_escape_string(&path_to_file_url(repo)),
+        toml_escape_path(target_root),
+    )
+}
+
+fn write_config(config_path: &Path, storage_root: &Path, skills: &[String]) {
+    let mut config = format!(
+        "version = 1\n\n[storage]\nroot = \"{}\"\n",
+        toml_escape_path(storage_root)
+    );
+    for skill in skills {
+        config.push_str(skill);
+    }
+    fs::write(config_path, config).expect("write config");
+}
+
+fn commit_file(repo: &Path, name: &str, content: &str) {
+    fs::write(repo.join(name), content).expect("write repo file");
+    run_git_cmd(repo, &["add", "."]);
+    run_git_cmd(repo, &["commit", "-m", "update"]);
+}
+
+#[test]
+fn apply_records_a_generation_only_when_state_changes() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "repo", &[("SKILL.md", "v1\n")]);
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    let target_root = temp.path().join("agent-skills");
+    write_config(
+        &config_path,
+        &storage_root,
+        &[skill_entry("demo", &repo, &target_root)],
+    );
+
+    let config = config_path.to_str().expect("config path");
+    apply(config, default_options()).expect("first apply");
+    apply(config, default_options()).expect("second apply");
+
+    let generations = list_generations(&storage_root, &config_path).expect("list");
+    assert_eq!(generations.len(), 1, "unchanged apply should not record");
+    assert_eq!(generations[0].command, "apply");
+    assert_eq!(
+        current_generation(&storage_root, &config_path).expect("current"),
+        Some(generations[0].number)
+    );
+    let generation = read_generation(&storage_root, generations[0].number).expect("read");
+    assert_eq!(generation.lock.skills.len(), 1);
+    assert_eq!(
+        generation.config_text,
+        fs::read_to_string(&config_path).expect("read config")
+    );
+}
+
+#[test]
+fn generation_pins_locked_commit_in_repo_cache() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "repo", &[("SKILL.md", "v1\n")]);
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    write_config(
+        &config_path,
+        &storage_root,
+        &[skill_entry(
+            "demo",
+            &repo,
+            &temp.path().join("agent-skills"),
+        )],
+    );
+
+    apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("apply");
+
+    let generation = read_generation(&storage_root, 1).expect("generation 1");
+    let cache_dir = fs::read_dir(storage_root.join(".repos"))
+        .expect("read repo cache")
+        .map(|entry| entry.expect("entry").path())
+        .next()
+        .expect("cache dir");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&cache_dir)
+        .args(["rev-parse", "refs/eden-skills/generations/1/0"])
+        .output()
+        .expect("run git");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        generation.lock.skills[0].resolved_commit
+    );
+}
+
+#[test]
+fn rollback_restores_previous_commit_and_removes_added_skills() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "repo", &[("SKILL.md", "v1\n")]);
+    let extra_repo = init_git_repo(temp.path(), "extra-repo", &[("SKILL.md", "extra\n")]);
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    let target_root = temp.path().join("agent-skills");
+    let demo = skill_entry("demo", &repo, &target_root);
+    write_config(&config_path, &storage_root, std::slice::from_ref(&demo));
+    let config = config_path.to_str().expect("config path");
+    apply(config, default_options()).expect("first apply");
+    let first_config = fs::read_to_string(&config_path).expect("read config");
+
+    commit_file(&repo, "SKILL.md", "v2\n");
+    write_config(
+        &config_path,
+        &storage_root,
+        &[demo, skill_entry("extra", &extra_repo, &target_root)],
+    );
+    apply(config, default_options()).expect("second apply");
+    let output = eden_command(temp.path())
+        .args(["update", "--apply", "--config", config])
+        .output()
+        .expect("run update");
+    assert_success(&output);
+    assert_eq!(
+        fs::read_to_string(target_root.join("demo").join("SKILL.md")).expect("read"),
+        "v2\n"
+    );
+    assert!(target_root.join("extra").exists());
+
+    let output = eden_command(temp.path())
+        .args(["rollback", "--config", config, "--to", "1"])
+        .output()
+        .expect("run rollback");
+    assert_success(&output);
+
+    assert_eq!(
+        fs::read_to_string(target_root.join("demo").join("SKILL.md")).expect("read"),
+        "v1\n"
+    );
+    assert!(!target_root.join("extra").exists(), "extra skill removed");
+    assert_eq!(
+        fs::read_to_string(&config_path).expect("read config"),
+        first_config
+    );
+    assert_eq!(
+        current_generation(&storage_root, &config_path).expect("current"),
+        Some(1)
+    );
+    assert_eq!(
+        list_generations(&storage_root, &config_path)
+            .expect("list")
+            .len(),
+        3,
+        "rollback should not record a new generation"
+    );
+
+    let output = eden_command(temp.path())
+        .args(["history", "--config", config, "--json"])
+        .output()
+        .expect("run history");
+    assert_success(&output);
+    let payload: serde_json::Value = serde_json::from_slice(&output.stdout).expect("history json");
+    assert_eq!(payload["current"], 1);
+    assert_eq!(payload["generations"].as_array().expect("array").len(), 3);
+    assert_eq!(payload["generations"][1]["skills"], 2);
+    assert_eq!(payload["generations"][2]["command"], "update");
+}
+
+#[test]
+fn failed_rollback_restores_previous_config_lock_and_current_generation() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "repo", &[("SKILL.md", "v1\n")]);
+    let extra_repo = init_git_repo(temp.path(), "extra-repo", &[("SKILL.md", "extra\n")]);
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    let target_root = temp.path().join("agent-skills");
+    let demo = skill_entry("demo", &repo, &target_root);
+    write_config(&config_path, &storage_root, std::slice::from_ref(&demo));
+    let config = config_path.to_str().expect("config path");
+    apply(config, default_options()).expect("first apply");
+    write_config(
+        &config_path,
+        &storage_root,
+        &[demo, skill_entry("extra", &extra_repo, &target_root)],
+    );
+    apply(config, default_options()).expect("second apply");
+    let second_config = fs::read_to_string(&config_path).expect("read config");
+    let lock_path = temp.path().join("skills.lock");
+    let second_lock = fs::read_to_string(&lock_path).expect("read lock");
+
+    // Without the cache or the origin, the locked commit cannot be fetched.
+    for cache in [".repos", ".objects"] {
+        let _ = fs::remove_dir_all(storage_root.join(cache));
+    }
+    fs::rename(&repo, temp.path().join("repo-moved")).expect("move origin away");
+
+    let output = eden_command(temp.path())
+        .args(["rollback", "--config", config, "--to", "1"])
+        .output()
+        .expect("run rollback");
+    assert_ne!(output.status.code(), Some(0));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("rollback to generation 1 failed"),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(&config_path).expect("read config"),
+        second_config
+    );
+    assert_eq!(
+        fs::read_to_string(&lock_path).expect("read lock"),
+        second_lock
+    );
+    assert_eq!(
+        current_generation(&storage_root, &config_path).expect("current"),
+        Some(2)
+    );
+}
+
+#[test]
+fn rollback_without_earlier_generation_fails() {
+    let temp = tempdir().expect("tempdir");
+    let repo = init_git_repo(temp.path(), "repo", &[("SKILL.md", "v1\n")]);
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    write_config(
+        &config_path,
+        &storage_root,
+        &[skill_entry(
+            "demo",
+            &repo,
+            &temp.path().join("agent-skills"),
+        )],
+    );
+    let config = config_path.to_str().expect("config path");
+    apply(config, default_options()).expect("apply");
+
+    let output = eden_command(temp.path())
+        .args(["rollback", "--config", config])
+        .output()
+        .expect("run rollback");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no earlier generation"));
+
+    let output = eden_command(temp.path())
+        .args(["rollback", "--config", config, "--to", "7"])
+        .output()
+        .expect("run rollback");
+    assert_eq!(output.status.code(), Some(2));
+}
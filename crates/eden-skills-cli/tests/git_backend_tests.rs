@@ -0,0 +1,221 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tempfile::tempdir;
+
+use common::{
+    assert_success, eden_command, init_git_repo, path_to_file_url, run_git_cmd, toml_escape_path,
+};
+
+/// A `PATH` directory without `git`. `file://` remotes are still served
+/// by `git-upload-pack`, so only that helper is linked in.
+fn path_without_git(base: &Path) -> PathBuf {
+    let upload_pack = std::env::split_paths(&std::env::var_os("PATH").expect("PATH"))
+        .map(|dir| dir.join("git-upload-pack"))
+        .find(|candidate| candidate.is_file())
+        .expect("git-upload-pack on PATH");
+    let bin = base.join("no-git-bin");
+    fs::create_dir_all(&bin).expect("create bin dir");
+    std::os::unix::fs::symlink(upload_pack, bin.join("git-upload-pack")).expect("link upload-pack");
+    bin
+}
+
+const SKILL_V1: &str = "---\nname: demo-skill\ndescription: v1\n---\n";
+const SKILL_V2: &str = "---\nname: demo-skill\ndescription: v2\n---\n";
+
+#[test]
+fn embedded_backend_installs_and_updates_without_git_binary() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let repo = init_git_repo(temp.path(), "demo-skill", &[("SKILL.md", SKILL_V1)]);
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[git]\nbackend = \"embedded\"\n",
+            toml_escape_path(&storage_root)
+        ),
+    )
+    .expect("write config");
+    let no_git_path = path_without_git(temp.path());
+    let target = format!("custom:{}", target_root.display());
+
+    let output = eden_command(&home_dir)
+        .env("PATH", &no_git_path)
+        .args(["install", &path_to_file_url(&repo), "--copy", "--target"])
+        .arg(&target)
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_success(&output);
+    let installed = target_root.join("demo-skill").join("SKILL.md");
+    assert_eq!(fs::read_to_string(&installed).expect("read"), SKILL_V1);
+    assert!(
+        fs::read_to_string(&config_path)
+            .expect("read config")
+            .contains("backend = \"embedded\""),
+        "install should keep the backend setting"
+    );
+
+    fs::write(repo.join("SKILL.md"), SKILL_V2).expect("write update");
+    run_git_cmd(&repo, &["commit", "-am", "update"]);
+    let output = eden_command(&home_dir)
+        .env("PATH", &no_git_path)
+        .args(["update", "--apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run update");
+    assert_success(&output);
+    assert_eq!(fs::read_to_string(&installed).expect("read"), SKILL_V2);
+}
+
+#[test]
+fn cli_backend_still_requires_git_binary() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let repo = init_git_repo(temp.path(), "demo-skill", &[("SKILL.md", SKILL_V1)]);
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[git]\nbackend = \"cli\"\n",
+            toml_escape_path(&temp.path().join("storage"))
+        ),
+    )
+    .expect("write config");
+
+    let output = eden_command(&home_dir)
+        .env("PATH", path_without_git(temp.path()))
+        .args(["install", &path_to_file_url(&repo), "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("git executable not found"),
+        "stderr={stderr}"
+    );
+    assert!(stderr.contains("backend = \"embedded\""), "stderr={stderr}");
+}
+
+#[test]
+fn embedded_backend_reports_update_changelog() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let repo = init_git_repo(temp.path(), "demo-skill", &[("SKILL.md", SKILL_V1)]);
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[git]\nbackend = \"embedded\"\n",
+            toml_escape_path(&temp.path().join("storage"))
+        ),
+    )
+    .expect("write config");
+    let no_git_path = path_without_git(temp.path());
+    let target = format!("custom:{}", temp.path().join("agent-skills").display());
+
+    let output = eden_command(&home_dir)
+        .env("PATH", &no_git_path)
+        .args(["install", &path_to_file_url(&repo), "--copy", "--target"])
+        .arg(&target)
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_success(&output);
+
+    fs::write(repo.join("SKILL.md"), SKILL_V2).expect("write update");
+    fs::write(repo.join("notes.md"), "notes\n").expect("write notes");
+    run_git_cmd(&repo, &["add", "."]);
+    run_git_cmd(&repo, &["commit", "-m", "describe v2"]);
+    let output = eden_command(&home_dir)
+        .env("PATH", &no_git_path)
+        .args(["update", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run update");
+    assert_success(&output);
+    let payload: serde_json::Value = serde_json::from_slice(&output.stdout).expect("update json");
+    let changelog = &payload["skills"][0]["changelog"];
+    assert_eq!(
+        changelog["commits"][0]["subject"], "describe v2",
+        "json={payload}"
+    );
+    assert_eq!(
+        changelog["files"],
+        serde_json::json!({ "added": 1, "removed": 0, "changed": 1 }),
+        "json={payload}"
+    );
+}
+
+#[test]
+fn embedded_backend_follows_version_tags() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let repo = init_git_repo(temp.path(), "demo-skill", &[("SKILL.md", SKILL_V1)]);
+    run_git_cmd(&repo, &["tag", "-a", "v1.0.0", "-m", "v1.0.0"]);
+    fs::write(repo.join("SKILL.md"), SKILL_V2).expect("write v2");
+    run_git_cmd(&repo, &["commit", "-am", "v2"]);
+    run_git_cmd(&repo, &["tag", "v2.0.0"]);
+
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version = 1
+
+[storage]
+root = "{storage}"
+
+[git]
+backend = "embedded"
+
+[[skills]]
+id = "demo-skill"
+
+[skills.source]
+repo = "{repo}"
+version = "~1"
+
+[skills.install]
+mode = "copy"
+
+[[skills.targets]]
+agent = "custom"
+path = "{target}"
+"#,
+            storage = toml_escape_path(&storage_root),
+            repo = path_to_file_url(&repo),
+            target = toml_escape_path(&target_root),
+        ),
+    )
+    .expect("write config");
+
+    let output = eden_command(&home_dir)
+        .env("PATH", path_without_git(temp.path()))
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_success(&output);
+    assert_eq!(
+        fs::read_to_string(target_root.join("demo-skill/SKILL.md")).expect("read skill"),
+        SKILL_V1
+    );
+    let lock = fs::read_to_string(temp.path().join("skills.lock")).expect("read lock");
+    assert!(
+        lock.contains("resolved_version = \"v1.0.0\""),
+        "lock={lock}"
+    );
+}
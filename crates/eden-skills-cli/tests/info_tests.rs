@@ -0,0 +1,194 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::{tempdir, TempDir};
+
+use common::{
+    assert_success, eden_command, init_git_repo, path_to_file_url, toml_escape_path,
+    toml_escape_string,
+};
+
+const SKILL_MD: &str =
+    "---\nname: browser-tool\ndescription: \"Drive a headless browser\"\n---\n# Browser\n";
+
+struct InfoFixture {
+    temp: TempDir,
+    config_path: PathBuf,
+    storage_root: PathBuf,
+}
+
+#[test]
+fn info_json_lists_registry_versions_and_constraint_selection() {
+    let fixture = setup_info_fixture(false);
+    let official = registry_root(&fixture, "official");
+    write_index_entry(
+        &official,
+        "browser-tool",
+        &[("1.0.0", false), ("1.4.0", false), ("2.0.0", false)],
+    );
+    let forge = registry_root(&fixture, "forge");
+    write_index_entry(&forge, "browser-tool", &[("1.2.0", false), ("1.5.0", true)]);
+
+    let output = run_command(
+        &fixture,
+        &["info", "browser-tool", "--version", "^1", "--json"],
+    );
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("info json");
+    assert_eq!(payload["installed"], Value::Null, "json={payload}");
+    let registries = payload["registries"].as_array().expect("registries array");
+    assert_eq!(registries.len(), 2, "json={payload}");
+
+    assert_eq!(registries[0]["registry"], "official");
+    assert_eq!(registries[0]["license"], "MIT");
+    assert_eq!(registries[0]["selected_version"], "1.4.0");
+    let versions = registries[0]["versions"].as_array().expect("versions");
+    assert_eq!(versions[0]["version"], "2.0.0");
+    assert_eq!(versions[0]["ref"], "v2.0.0");
+
+    assert_eq!(registries[1]["registry"], "forge");
+    assert_eq!(registries[1]["selected_version"], "1.2.0");
+    assert_eq!(registries[1]["versions"][0]["yanked"], true);
+
+    let output = run_command(
+        &fixture,
+        &["info", "browser-tool", "--version", "^3", "--json"],
+    );
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("info json");
+    assert_eq!(payload["registries"][0]["selected_version"], Value::Null);
+    assert!(payload["registries"][0]["selection_error"]
+        .as_str()
+        .is_some_and(|error| error.contains("no version matched constraint `^3`")));
+}
+
+#[test]
+fn info_shows_lock_targets_safety_and_frontmatter_of_installed_skill() {
+    let fixture = setup_info_fixture(true);
+    assert_success(&run_command(&fixture, &["apply"]));
+
+    let output = run_command(&fixture, &["info", "browser-tool", "--json"]);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("info json");
+    let installed = &payload["installed"];
+    assert_eq!(installed["lock"]["id"], "browser-tool", "json={payload}");
+    assert_eq!(installed["lock"]["source_ref"], "main");
+    assert_eq!(installed["targets"][0]["agent"], "custom");
+    assert_eq!(installed["safety"]["license_status"], "permissive");
+    assert_eq!(installed["safety"]["license_hint"], "MIT");
+    assert!(
+        installed["safety"]["risk_labels"]
+            .as_array()
+            .expect("risk labels")
+            .iter()
+            .any(|label| label == "contains-shell-script"),
+        "json={payload}"
+    );
+    assert_eq!(
+        installed["frontmatter"]["description"],
+        "Drive a headless browser"
+    );
+
+    let output = run_command(&fixture, &["info", "browser-tool"]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("browser-tool — Drive a headless browser"),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains("permissive (MIT)"), "stdout={stdout}");
+    assert!(stdout.contains("SKILL.md"), "stdout={stdout}");
+}
+
+#[test]
+fn info_for_unknown_skill_fails_with_search_hint() {
+    let fixture = setup_info_fixture(false);
+    write_index_entry(
+        &registry_root(&fixture, "official"),
+        "browser-tool",
+        &[("1.0.0", false)],
+    );
+
+    let output = run_command(&fixture, &["info", "missing-skill"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("eden-skills search"), "stderr={stderr}");
+}
+
+/// A config with `official` and `forge` registries (caches not written
+/// yet) and, with `installed`, a local `browser-tool` skill.
+fn setup_info_fixture(installed: bool) -> InfoFixture {
+    let temp = tempdir().expect("tempdir");
+    let storage_root = temp.path().join("storage");
+    let mut config = format!(
+        "version = 1\n\n[storage]\nroot = \"{}\"\n\n[registries]\nofficial = {{ url = \"https://example.com/official.git\", priority = 100 }}\nforge = {{ url = \"https://example.com/forge.git\", priority = 10 }}\n",
+        toml_escape_path(&storage_root),
+    );
+    if installed {
+        let repo = init_git_repo(
+            temp.path(),
+            "browser-origin",
+            &[
+                ("SKILL.md", SKILL_MD),
+                ("LICENSE", "MIT License\n"),
+                ("scripts/run.sh", "echo run\n"),
+            ],
+        );
+        config.push_str(&format!(
+            "\n[[skills]]\nid = \"browser-tool\"\n\n[skills.source]\nrepo = \"{}\"\nref = \"main\"\n\n[[skills.targets]]\nagent = \"custom\"\npath = \"{}\"\n",
+            toml_escape_string(&path_to_file_url(&repo)),
+            toml_escape_path(&temp.path().join("agent-skills")),
+        ));
+    }
+    let config_path = temp.path().join("skills.toml");
+    fs::write(&config_path, config).expect("write config");
+
+    InfoFixture {
+        temp,
+        config_path,
+        storage_root,
+    }
+}
+
+fn registry_root(fixture: &InfoFixture, name: &str) -> PathBuf {
+    let root = fixture.storage_root.join("registries").join(name);
+    fs::create_dir_all(&root).expect("create registry root");
+    fs::write(
+        root.join("manifest.toml"),
+        format!("format_version = 1\nname = \"{name}\"\n"),
+    )
+    .expect("write manifest");
+    root
+}
+
+/// Each version is `(version, yanked)`.
+fn write_index_entry(registry_root: &Path, skill_name: &str, versions: &[(&str, bool)]) {
+    let first = skill_name.chars().next().expect("skill name");
+    let index_dir = registry_root.join("index").join(first.to_string());
+    fs::create_dir_all(&index_dir).expect("create index dir");
+
+    let mut body = format!(
+        "[skill]\nname = \"{skill_name}\"\nrepo = \"https://example.com/{skill_name}.git\"\nlicense = \"MIT\"\n\n"
+    );
+    for (version, yanked) in versions {
+        body.push_str(&format!(
+            "[[versions]]\nversion = \"{version}\"\nref = \"v{version}\"\ncommit = \"{}\"\nyanked = {yanked}\n\n",
+            "b".repeat(40)
+        ));
+    }
+    fs::write(index_dir.join(format!("{skill_name}.toml")), body).expect("write index entry");
+}
+
+fn run_command(fixture: &InfoFixture, args: &[&str]) -> Output {
+    eden_command(&fixture.temp.path().join("home"))
+        .current_dir(fixture.temp.path())
+        .args(args)
+        .arg("--config")
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run eden-skills")
+}
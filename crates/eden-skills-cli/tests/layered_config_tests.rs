@@ -0,0 +1,269 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eden_skills_cli::commands::{remove, CommandOptions};
+use tempfile::tempdir;
+
+/// Write a user-global config under `home_dir` defining `user-skill`.
+fn write_user_config(home_dir: &Path) -> PathBuf {
+    let user_dir = home_dir.join(".eden-skills");
+    fs::create_dir_all(&user_dir).expect("create user config dir");
+    let config_path = user_dir.join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"version = 1
+
+[storage]
+root = "skills"
+
+[[skills]]
+id = "user-skill"
+
+[skills.source]
+repo = "https://github.com/example/user-skill.git"
+
+[[skills.targets]]
+agent = "claude-code"
+"#,
+    )
+    .expect("write user config");
+    config_path
+}
+
+fn write_local_skill_source(base: &Path) {
+    let source_dir = base.join("test-skills");
+    fs::create_dir_all(&source_dir).expect("create source dir");
+    fs::write(source_dir.join("README.md"), "demo skill").expect("write source file");
+}
+
+#[test]
+fn list_project_merges_user_config_and_reports_layers() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    write_user_config(&home_dir);
+    fs::create_dir_all(&project_dir).expect("create project dir");
+    fs::write(
+        project_dir.join(".eden-skills.toml"),
+        r#"version = 1
+
+[[skills]]
+id = "project-skill"
+
+[skills.source]
+repo = "https://github.com/example/project-skill.git"
+
+[[skills.targets]]
+agent = "cursor"
+scope = "project"
+"#,
+    )
+    .expect("write hidden project config");
+
+    let output = common::eden_command(&home_dir)
+        .args(["list", "--json", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run list --project");
+    common::assert_success(&output);
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("list json should parse");
+    let layers = payload["skills"]
+        .as_array()
+        .expect("skills array")
+        .iter()
+        .map(|skill| {
+            (
+                skill["id"].as_str().unwrap_or_default().to_string(),
+                skill["layer"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        layers,
+        vec![
+            ("user-skill".to_string(), "user".to_string()),
+            ("project-skill".to_string(), "project".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn project_config_in_the_enclosing_repository_is_layered_without_project_flag() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    let nested_dir = project_dir.join("docs").join("guides");
+    write_user_config(&home_dir);
+    fs::create_dir_all(project_dir.join(".git")).expect("create .git");
+    fs::create_dir_all(&nested_dir).expect("create nested dir");
+    fs::write(
+        project_dir.join("skills.toml"),
+        r#"version = 1
+
+[reactor]
+concurrency = 2
+
+[[skills]]
+id = "project-skill"
+
+[skills.source]
+repo = "https://github.com/example/project-skill.git"
+
+[[skills.targets]]
+agent = "cursor"
+scope = "project"
+"#,
+    )
+    .expect("write project config");
+
+    let output = common::eden_command(&home_dir)
+        .current_dir(&nested_dir)
+        .args(["list", "--json"])
+        .output()
+        .expect("run list");
+    common::assert_success(&output);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("list json should parse");
+    let ids = payload["skills"]
+        .as_array()
+        .expect("skills array")
+        .iter()
+        .filter_map(|skill| skill["id"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["user-skill", "project-skill"]);
+}
+
+#[test]
+fn install_project_does_not_copy_user_skills_into_project_config() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    write_user_config(&home_dir);
+    fs::create_dir_all(project_dir.join(".claude")).expect("create project .claude");
+    write_local_skill_source(temp.path());
+
+    let output = common::eden_command(&home_dir)
+        .current_dir(temp.path())
+        .args(["install", "./test-skills", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run install --project");
+    common::assert_success(&output);
+
+    let config_text =
+        fs::read_to_string(project_dir.join("skills.toml")).expect("read project config");
+    assert!(
+        config_text.contains("id = \"test-skills\""),
+        "installed skill should be written to the project config, config={config_text}"
+    );
+    assert!(
+        !config_text.contains("user-skill") && !config_text.contains("[storage]"),
+        "user-layer settings should stay out of the project config, config={config_text}"
+    );
+}
+
+fn locked_skill_ids(lock_path: &Path) -> Vec<String> {
+    let lock_text = fs::read_to_string(lock_path).expect("read lock");
+    let lock: toml::Value = toml::from_str(&lock_text).expect("valid lock toml");
+    lock["skills"]
+        .as_array()
+        .map(|skills| {
+            skills
+                .iter()
+                .filter_map(|skill| skill["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn install_project_keeps_user_layer_skills_out_of_the_project_lock() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    let user_config = write_user_config(&home_dir);
+    fs::create_dir_all(project_dir.join(".claude")).expect("create project .claude");
+    write_local_skill_source(temp.path());
+
+    let output = common::eden_command(&home_dir)
+        .current_dir(temp.path())
+        .args(["install", "./test-skills", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run install --project");
+    common::assert_success(&output);
+
+    assert_eq!(
+        locked_skill_ids(&project_dir.join("skills.lock")),
+        vec!["test-skills".to_string()],
+        "the project lock must only hold project-layer skills"
+    );
+    assert_eq!(
+        locked_skill_ids(&user_config.with_file_name("skills.lock")),
+        vec!["user-skill".to_string()],
+        "user-layer skills are locked in the user lock"
+    );
+}
+
+#[test]
+fn remove_project_rejects_user_layer_skill() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    let user_config = write_user_config(&home_dir);
+    fs::create_dir_all(&project_dir).expect("create project dir");
+    fs::write(project_dir.join("skills.toml"), "version = 1\n").expect("write project config");
+
+    let output = common::eden_command(&home_dir)
+        .args(["remove", "user-skill", "--yes", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run remove --project");
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "removing a user-layer skill via --project should fail, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("defined in the user config"),
+        "stderr={stderr}"
+    );
+    assert!(
+        fs::read_to_string(user_config)
+            .expect("read user config")
+            .contains("user-skill"),
+        "user config must be left untouched"
+    );
+}
+
+#[test]
+fn user_config_layer_applies_only_to_the_call_that_passes_it() {
+    let temp = tempdir().expect("tempdir");
+    let user_config = write_user_config(&temp.path().join("home"));
+    let project_config = temp.path().join("skills.toml");
+    fs::write(&project_config, "version = 1\n").expect("write project config");
+    let project_config = project_config.to_str().expect("config path");
+
+    let layered = CommandOptions {
+        user_config_path: Some(user_config),
+        ..common::default_options()
+    };
+    let err = remove(project_config, "user-skill", layered).expect_err("user-layer skill");
+    assert!(
+        err.to_string().contains("defined in the user config"),
+        "err={err}"
+    );
+
+    let err = remove(project_config, "user-skill", common::default_options())
+        .expect_err("unknown skill without the user layer");
+    assert!(
+        !err.to_string().contains("defined in the user config"),
+        "the user layer must not outlive the call that passed it, err={err}"
+    );
+}
@@ -3,6 +3,7 @@ mod common;
 use std::fs;
 
 use eden_skills_cli::commands::CommandOptions;
+use eden_skills_core::config::TargetScope;
 use eden_skills_core::lock::{
     lock_path_for_config, read_lock_file, write_lock_file, LockFile, LockSkillEntry, LockTarget,
     LOCK_VERSION,
@@ -168,11 +169,16 @@ root = "{}"
             resolved_version: None,
             install_mode: "symlink".to_string(),
             installed_at: "2026-02-21T10:00:00Z".to_string(),
+            content_digest: None,
             targets: vec![LockTarget {
                 agent: "claude-code".to_string(),
                 path: target.join("orphan-skill").display().to_string(),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
+                content_digest: None,
             }],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
     let lock_path = lock_path_for_config(&config_path);
@@ -209,11 +215,16 @@ root = "{}"
             resolved_version: None,
             install_mode: "symlink".to_string(),
             installed_at: "2026-02-21T10:00:00Z".to_string(),
+            content_digest: None,
             targets: vec![LockTarget {
                 agent: "cursor".to_string(),
                 path: "/tmp/orphan".to_string(),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
+                content_digest: None,
             }],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
     let lock_path = lock_path_for_config(&config_path);
@@ -224,6 +235,8 @@ root = "{}"
         CommandOptions {
             strict: false,
             json: true,
+            offline: false,
+            user_config_path: None,
         },
     );
     assert!(result.is_ok());
@@ -392,6 +405,8 @@ root = "{}"
         CommandOptions {
             strict: true,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
         None,
         false,
@@ -511,11 +526,16 @@ exit 1
         resolved_version: None,
         install_mode: "copy".to_string(),
         installed_at: "2026-02-21T10:00:00Z".to_string(),
+        content_digest: None,
         targets: vec![LockTarget {
             agent: "custom".to_string(),
             path: "/tmp/docker-orphan".to_string(),
             environment: "docker:test-container".to_string(),
+            scope: TargetScope::Global,
+            content_digest: None,
         }],
+        implicit: false,
+        requires: Vec::new(),
     });
     write_lock_file(&lock_path, &lock).unwrap();
 
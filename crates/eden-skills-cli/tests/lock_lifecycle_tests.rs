@@ -10,6 +10,8 @@ fn default_options() -> CommandOptions {
     CommandOptions {
         strict: false,
         json: false,
+        offline: false,
+        user_config_path: None,
     }
 }
 
@@ -223,9 +225,11 @@ async fn install_creates_lock_file() {
     let result =
         eden_skills_cli::commands::install_async(eden_skills_cli::commands::InstallRequest {
             config_path: config_path.to_str().unwrap().to_string(),
+            project: false,
             source: local_path,
             id: Some("installed-skill".to_string()),
             r#ref: None,
+            sha256: None,
             skill: vec![],
             all: true,
             yes: false,
@@ -457,3 +461,128 @@ checks = ["path-exists", "target-resolves", "is-symlink"]
         "lock entries should be sorted alphabetically"
     );
 }
+
+// ---------------------------------------------------------------------------
+// apply --locked / --frozen
+// ---------------------------------------------------------------------------
+
+async fn apply_with_mode(
+    config_path: &Path,
+    lock_mode: eden_skills_cli::commands::LockMode,
+) -> Result<(), eden_skills_core::error::EdenError> {
+    eden_skills_cli::commands::apply_with_lock_mode_async(
+        config_path.to_str().unwrap(),
+        default_options(),
+        None,
+        false,
+        lock_mode,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn locked_apply_reinstalls_locked_commit_on_fresh_machine() {
+    use eden_skills_cli::commands::LockMode;
+
+    let dir = tempfile::tempdir().unwrap();
+    let storage = dir.path().join("storage");
+    let target = dir.path().join("target");
+    let origin = common::init_origin_repo(dir.path());
+    let config_path = common::write_config(
+        dir.path(),
+        &common::as_file_url(&origin),
+        "copy",
+        &["path-exists", "content-present"],
+        &storage,
+        &target,
+    );
+    apply_with_mode(&config_path, LockMode::Sync).await.unwrap();
+    let lock_path = lock_path_for_config(&config_path);
+    let locked_text = fs::read_to_string(&lock_path).unwrap();
+
+    fs::write(origin.join("packages/browser/README.txt"), "v2\n").unwrap();
+    common::run_git_cmd(&origin, &["commit", "-am", "v2"]);
+    fs::remove_dir_all(&storage).unwrap();
+    fs::remove_dir_all(&target).unwrap();
+
+    apply_with_mode(&config_path, LockMode::Locked)
+        .await
+        .unwrap();
+    let readme = common::expected_target_path(&target).join("README.txt");
+    assert_eq!(fs::read_to_string(&readme).unwrap(), "v1\n");
+    assert_eq!(
+        fs::read_to_string(&lock_path).unwrap(),
+        locked_text,
+        "--locked must not rewrite skills.lock"
+    );
+
+    fs::remove_dir_all(&target).unwrap();
+    apply_with_mode(&config_path, LockMode::Frozen)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&readme).unwrap(), "v1\n");
+}
+
+#[tokio::test]
+async fn frozen_apply_fails_when_source_is_not_cached() {
+    use eden_skills_cli::commands::LockMode;
+
+    let dir = tempfile::tempdir().unwrap();
+    let storage = dir.path().join("storage");
+    let origin = common::init_origin_repo(dir.path());
+    let config_path = common::write_config(
+        dir.path(),
+        &common::as_file_url(&origin),
+        "symlink",
+        &["path-exists"],
+        &storage,
+        &dir.path().join("target"),
+    );
+    apply_with_mode(&config_path, LockMode::Sync).await.unwrap();
+    fs::remove_dir_all(&storage).unwrap();
+
+    let err = apply_with_mode(&config_path, LockMode::Frozen)
+        .await
+        .expect_err("frozen apply must not clone");
+    assert!(
+        err.to_string().contains("network access is disabled"),
+        "err={err}"
+    );
+    assert!(!common::expected_source_path(&storage).exists());
+}
+
+#[tokio::test]
+async fn locked_apply_rejects_missing_or_stale_lock() {
+    use eden_skills_cli::commands::LockMode;
+    use eden_skills_core::error::EdenError;
+
+    let dir = tempfile::tempdir().unwrap();
+    let origin = common::init_origin_repo(dir.path());
+    let config_path = common::write_config(
+        dir.path(),
+        &common::as_file_url(&origin),
+        "symlink",
+        &["path-exists"],
+        &dir.path().join("storage"),
+        &dir.path().join("target"),
+    );
+
+    let err = apply_with_mode(&config_path, LockMode::Locked)
+        .await
+        .expect_err("missing lock should fail");
+    assert!(matches!(err, EdenError::Conflict(_)), "err={err}");
+
+    apply_with_mode(&config_path, LockMode::Sync).await.unwrap();
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(
+        &config_path,
+        config.replace("mode = \"symlink\"", "mode = \"copy\""),
+    )
+    .unwrap();
+
+    let err = apply_with_mode(&config_path, LockMode::Locked)
+        .await
+        .expect_err("stale lock should fail");
+    assert!(matches!(err, EdenError::Conflict(_)), "err={err}");
+    assert!(err.to_string().contains("out of date"), "err={err}");
+}
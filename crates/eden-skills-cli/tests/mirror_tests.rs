@@ -0,0 +1,89 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+use common::{
+    assert_success, eden_command, init_git_repo, path_to_file_url, run_git_cmd, toml_escape_path,
+    toml_escape_string,
+};
+
+const UPSTREAM_URL: &str = "https://upstream.invalid/org/demo-skill";
+const SKILL_V1: &str = "---\nname: demo-skill\ndescription: v1\n---\n";
+const SKILL_V2: &str = "---\nname: demo-skill\ndescription: v2\n---\n";
+
+fn write_mirror_config(config_path: &Path, storage_root: &Path, backend: &str, mirror_dir: &Path) {
+    fs::write(
+        config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[git]\nbackend = \"{backend}\"\n\n[mirrors]\n\"https://upstream.invalid/org/\" = \"{}/\"\n",
+            toml_escape_path(storage_root),
+            toml_escape_string(&path_to_file_url(mirror_dir)),
+        ),
+    )
+    .expect("write config");
+}
+
+fn install_and_update_through_moved_mirror(backend: &str) {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let first_mirror = temp.path().join("mirror-a");
+    let repo = init_git_repo(&first_mirror, "demo-skill", &[("SKILL.md", SKILL_V1)]);
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    write_mirror_config(&config_path, &storage_root, backend, &first_mirror);
+
+    let output = eden_command(&home_dir)
+        .args(["install", UPSTREAM_URL, "--copy", "--target"])
+        .arg(format!("custom:{}", target_root.display()))
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .expect("run install");
+    assert_success(&output);
+    let installed = target_root.join("demo-skill").join("SKILL.md");
+    assert_eq!(fs::read_to_string(&installed).expect("read"), SKILL_V1);
+
+    let config = fs::read_to_string(&config_path).expect("read config");
+    assert!(config.contains(UPSTREAM_URL), "config={config}");
+    assert!(config.contains("[mirrors]"), "config={config}");
+    let lock = fs::read_to_string(temp.path().join("skills.lock")).expect("read lock");
+    assert!(lock.contains(UPSTREAM_URL), "lock={lock}");
+    assert!(!lock.contains("mirror-a"), "lock={lock}");
+
+    // Move the mirror; only the new location has the next commit.
+    let second_mirror = temp.path().join("mirror-b");
+    fs::create_dir_all(&second_mirror).expect("create mirror dir");
+    fs::rename(&repo, second_mirror.join("demo-skill")).expect("move repo");
+    let repo = second_mirror.join("demo-skill");
+    fs::write(repo.join("SKILL.md"), SKILL_V2).expect("write update");
+    run_git_cmd(&repo, &["commit", "-am", "update"]);
+    let config = config.replace(
+        &path_to_file_url(&first_mirror),
+        &path_to_file_url(&second_mirror),
+    );
+    fs::write(&config_path, config).expect("write config");
+
+    let output = eden_command(&home_dir)
+        .args(["update", "--apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run update");
+    assert_success(&output);
+    assert_eq!(fs::read_to_string(&installed).expect("read"), SKILL_V2);
+}
+
+#[test]
+fn mirror_rules_rewrite_fetches_but_not_recorded_urls() {
+    install_and_update_through_moved_mirror("cli");
+}
+
+#[test]
+fn embedded_backend_follows_mirror_changes() {
+    install_and_update_through_moved_mirror("embedded");
+}
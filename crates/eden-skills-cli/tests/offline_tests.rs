@@ -0,0 +1,167 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use tempfile::tempdir;
+
+use common::{
+    as_file_url, assert_success, eden_command, expected_target_path, init_origin_repo,
+    path_to_file_url, run_git_cmd, toml_escape_string, write_config,
+};
+
+const CHECKS: &[&str] = &["path-exists", "target-resolves", "is-symlink"];
+
+struct Fixture {
+    _temp: tempfile::TempDir,
+    home_dir: PathBuf,
+    origin_repo: PathBuf,
+    target_root: PathBuf,
+    config_path: PathBuf,
+}
+
+fn fixture() -> Fixture {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "symlink",
+        CHECKS,
+        &storage_root,
+        &target_root,
+    );
+    Fixture {
+        home_dir: temp.path().join("home"),
+        _temp: temp,
+        origin_repo,
+        target_root,
+        config_path,
+    }
+}
+
+fn run(fixture: &Fixture, args: &[&str]) -> std::process::Output {
+    eden_command(&fixture.home_dir)
+        .args(args)
+        .arg("--config")
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run eden-skills")
+}
+
+#[test]
+fn offline_apply_reports_missing_cache_entries_as_uncached() {
+    let fixture = fixture();
+
+    let output = run(&fixture, &["apply", "--offline"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("uncached"), "stderr={stderr}");
+    assert!(
+        stderr.contains("network access is disabled"),
+        "stderr={stderr}"
+    );
+    assert!(!fixture.target_root.exists());
+}
+
+#[test]
+fn offline_apply_uses_cached_sources_without_the_remote() {
+    let fixture = fixture();
+    assert_success(&run(&fixture, &["apply"]));
+    fs::remove_dir_all(&fixture.origin_repo).expect("remove origin");
+    fs::remove_dir_all(&fixture.target_root).expect("remove targets");
+
+    let output = eden_command(&fixture.home_dir)
+        .env("EDEN_SKILLS_OFFLINE", "1")
+        .args(["apply", "--config"])
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run apply");
+    assert_success(&output);
+    assert!(expected_target_path(&fixture.target_root).exists());
+
+    assert_success(&run(&fixture, &["repair", "--offline"]));
+}
+
+#[test]
+fn offline_update_reports_uncached_sources() {
+    let fixture = fixture();
+
+    let output = run(&fixture, &["update", "--offline", "--json"]);
+    assert_success(&output);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("update json payload");
+    assert_eq!(payload["skills"][0]["status"], "uncached");
+    assert_eq!(payload["skills"][0]["attempts"], 0);
+}
+
+#[test]
+fn offline_update_reports_changes_from_the_last_fetch() {
+    let fixture = fixture();
+    assert_success(&run(&fixture, &["apply"]));
+    let readme = fixture
+        .origin_repo
+        .join("packages")
+        .join("browser")
+        .join("README.txt");
+    fs::write(&readme, "v2\n").expect("write update");
+    run_git_cmd(&fixture.origin_repo, &["commit", "-am", "update"]);
+    assert_success(&run(&fixture, &["update"]));
+    fs::remove_dir_all(&fixture.origin_repo).expect("remove origin");
+
+    let output = run(&fixture, &["update", "--offline", "--json"]);
+    assert_success(&output);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("update json payload");
+    let skill = &payload["skills"][0];
+    assert_eq!(skill["status"], "new-commit");
+    assert_eq!(skill["attempts"], 0);
+    assert_eq!(skill["changelog"]["commits"][0]["subject"], "update");
+
+    let output = run(&fixture, &["update", "--offline", "--apply"]);
+    assert_success(&output);
+    assert_eq!(
+        fs::read_to_string(expected_target_path(&fixture.target_root).join("README.txt"))
+            .expect("read installed skill"),
+        "v2\n"
+    );
+}
+
+#[test]
+fn offline_doctor_skips_registry_staleness_with_a_note() {
+    let fixture = fixture();
+    let config = fs::read_to_string(&fixture.config_path).expect("read config");
+    let registry_dir = fixture.home_dir.join("registry");
+    fs::write(
+        &fixture.config_path,
+        config.replacen(
+            "\n",
+            &format!(
+                "\n\n[registries.official]\nurl = \"{}\"\n",
+                toml_escape_string(&path_to_file_url(&registry_dir))
+            ),
+            1,
+        ),
+    )
+    .expect("write config");
+
+    let online = run(&fixture, &["doctor", "--json"]);
+    assert!(
+        String::from_utf8_lossy(&online.stdout).contains("REGISTRY_STALE"),
+        "registry should be stale without --offline"
+    );
+
+    let output = run(&fixture, &["doctor", "--json", "--offline"]);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("doctor json payload");
+    let findings = payload["findings"].as_array().expect("findings");
+    assert!(findings
+        .iter()
+        .all(|finding| finding["code"] != "REGISTRY_STALE"));
+    assert_eq!(
+        payload["notes"],
+        serde_json::json!(["registry staleness checks skipped in offline mode"])
+    );
+}
@@ -8,6 +8,7 @@ use common::{
     assert_success, create_symlink, eden_command, init_git_repo, path_to_file_url, remove_symlink,
     toml_escape_path, toml_escape_string,
 };
+use eden_skills_core::config::TargetScope;
 use eden_skills_core::lock::{
     lock_path_for_config, read_lock_file, write_lock_file, LockSkillEntry, LockTarget,
 };
@@ -199,6 +200,7 @@ fn tm_p28_021_plan_header_and_colored_actions() {
         resolved_version: None,
         install_mode: "symlink".to_string(),
         installed_at: "2026-03-05T00:00:00Z".to_string(),
+        content_digest: None,
         targets: vec![LockTarget {
             agent: "custom".to_string(),
             path: fixture.target_roots[0]
@@ -206,7 +208,11 @@ fn tm_p28_021_plan_header_and_colored_actions() {
                 .display()
                 .to_string(),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
+            content_digest: None,
         }],
+        implicit: false,
+        requires: Vec::new(),
     });
     write_lock_file(&lock_path, &lock).expect("write lock with orphan entry");
 
@@ -3,11 +3,11 @@ mod common;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use eden_skills_core::source::repo_cache_key;
+use eden_skills_core::source::{object_store_dir, repo_cache_key};
 use tempfile::tempdir;
 
 #[test]
-fn remote_install_reuses_discovery_clone_when_repo_cache_is_empty() {
+fn remote_install_checks_out_repo_cache_from_the_object_store() {
     let temp = tempdir().expect("tempdir");
     let home_dir = temp.path().join("home");
     let repo_dir = init_remote_skill_repo(temp.path(), "remote-skill-repo", "remote-skill");
@@ -34,33 +34,36 @@ fn remote_install_reuses_discovery_clone_when_repo_cache_is_empty() {
     let cache_dir = storage_root
         .join(".repos")
         .join(repo_cache_key(&repo_url, "main"));
+    let store_dir = object_store_dir(&cache_dir).expect("object store dir");
 
     assert!(
-        cache_dir.join(".git").exists(),
-        "expected repo cache checkout"
+        cache_dir.join(".git").is_file(),
+        "repo cache entry should be a worktree, not the discovery clone"
     );
+    assert!(store_dir.exists(), "expected the shared object store");
     assert_eq!(
         clone_count(&git_log),
-        1,
-        "expected discovery clone reuse, stderr={}",
+        2,
+        "expected one discovery clone and one repo-cache clone, stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
 }
 
 #[test]
-fn remote_install_falls_back_to_fresh_clone_when_cache_move_fails() {
+fn remote_install_caches_only_the_selected_skill_subpaths() {
     let temp = tempdir().expect("tempdir");
     let home_dir = temp.path().join("home");
-    let repo_dir = init_remote_skill_repo(temp.path(), "rename-fallback-repo", "fallback-skill");
+    let repo_dir = init_remote_multi_skill_repo(
+        temp.path(),
+        "sparse-remote-repo",
+        &["alpha-skill", "beta-skill"],
+    );
     let config_path = temp.path().join("skills.toml");
-    let git_log = temp.path().join("git-clones.log");
     let repo_url = common::path_to_file_url(&repo_dir);
 
     let output = common::eden_command(&home_dir)
         .current_dir(temp.path())
-        .env("EDEN_SKILLS_TEST_GIT_CLONE_LOG", &git_log)
-        .env("EDEN_SKILLS_TEST_FORCE_DISCOVERY_RENAME_FAIL", "1")
-        .args(["install", &repo_url, "--config"])
+        .args(["install", &repo_url, "--skill", "alpha-skill", "--config"])
         .arg(&config_path)
         .output()
         .expect("run remote install");
@@ -68,24 +71,19 @@ fn remote_install_falls_back_to_fresh_clone_when_cache_move_fails() {
     assert_eq!(
         output.status.code(),
         Some(0),
-        "remote install should succeed when rename fallback is forced, stderr={}",
+        "remote install should succeed, stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
 
-    let storage_root = home_dir.join(".eden-skills").join("skills");
-    let cache_dir = storage_root
+    let cache_dir = home_dir
+        .join(".eden-skills")
+        .join("skills")
         .join(".repos")
         .join(repo_cache_key(&repo_url, "main"));
-
+    assert!(cache_dir.join("alpha-skill").join("SKILL.md").exists());
     assert!(
-        cache_dir.join(".git").exists(),
-        "expected repo cache checkout"
-    );
-    assert_eq!(
-        clone_count(&git_log),
-        2,
-        "forced rename failure should fall back to a fresh cache clone, stderr={}",
-        String::from_utf8_lossy(&output.stderr)
+        !cache_dir.join("beta-skill").exists(),
+        "unselected skills should stay outside the sparse checkout"
     );
 }
 
@@ -132,7 +130,7 @@ description: Local skill
 }
 
 #[test]
-fn tm_p295_031_install_batches_remote_sync_into_one_repo_fetch() {
+fn tm_p295_031_install_batches_remote_sync_into_one_repo_clone() {
     let temp = tempdir().expect("tempdir");
     let home_dir = temp.path().join("home");
     let repo_dir = init_remote_multi_skill_repo(
@@ -162,14 +160,14 @@ fn tm_p295_031_install_batches_remote_sync_into_one_repo_fetch() {
     );
     assert_eq!(
         clone_count(&clone_log),
-        1,
-        "remote install should still perform exactly one discovery clone, stderr={}",
+        2,
+        "remote install should batch selected skills into one repo-cache clone after discovery, stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
     assert_eq!(
         fetch_count(&fetch_log),
-        1,
-        "remote install should batch selected skills into one repo-cache fetch, stderr={}",
+        0,
+        "a freshly cloned repo cache entry needs no fetch, stderr={}",
         String::from_utf8_lossy(&output.stderr)
     );
 }
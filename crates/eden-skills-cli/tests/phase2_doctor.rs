@@ -82,6 +82,57 @@ path = "{target_root}"
     );
 }
 
+#[test]
+fn doctor_warns_when_registry_declares_unknown_index_format() {
+    let temp = tempdir().expect("tempdir");
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+
+    let registry_root = storage_root.join("registries").join("official");
+    fs::create_dir_all(&registry_root).expect("create registry cache dir");
+    fs::write(
+        registry_root.join("manifest.toml"),
+        "format_version = 3\nname = \"official\"\n",
+    )
+    .expect("write manifest");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[registries]\nofficial = {{ url = \"https://example.com/official.git\", priority = 100 }}\n",
+            toml_escape_path(&storage_root),
+        ),
+    )
+    .expect("write config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_eden-skills"))
+        .args(["doctor", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run doctor");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "doctor should succeed without --strict, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("doctor json payload");
+    let finding = payload["findings"]
+        .as_array()
+        .expect("findings array")
+        .iter()
+        .find(|finding| finding["code"] == "REGISTRY_FORMAT_UNSUPPORTED")
+        .unwrap_or_else(|| panic!("expected REGISTRY_FORMAT_UNSUPPORTED, json={payload}"));
+    assert_eq!(finding["skill_id"], "registry:official");
+    assert!(
+        finding["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("format_version 3")),
+        "finding={finding}"
+    );
+}
+
 #[test]
 fn doctor_emits_docker_not_found_for_docker_targets() {
     let temp = tempdir().expect("tempdir");
@@ -0,0 +1,142 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use tempfile::tempdir;
+
+use common::{as_file_url, default_options, expected_target_path, init_origin_repo, write_config};
+
+struct Fixture {
+    _temp: tempfile::TempDir,
+    home: PathBuf,
+    config_path: PathBuf,
+    target_root: PathBuf,
+    plan_path: PathBuf,
+}
+
+/// Apply once so the source cache exists, then remove the installed
+/// target so the next plan has a pending create.
+fn fixture_with_pending_create() -> Fixture {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    let target_root = temp.path().join("agent-skills");
+    let config_path = write_config(
+        temp.path(),
+        &as_file_url(&origin_repo),
+        "copy",
+        &["path-exists", "content-present"],
+        &temp.path().join("storage"),
+        &target_root,
+    );
+    eden_skills_cli::commands::apply(
+        config_path.to_str().expect("config path"),
+        default_options(),
+    )
+    .expect("initial apply");
+    fs::remove_dir_all(expected_target_path(&target_root)).expect("remove target");
+
+    Fixture {
+        home: temp.path().join("home"),
+        plan_path: temp.path().join("plan.json"),
+        _temp: temp,
+        config_path,
+        target_root,
+    }
+}
+
+fn run(fixture: &Fixture, args: &[&str]) -> Output {
+    common::eden_command(&fixture.home)
+        .args(args)
+        .output()
+        .expect("run eden-skills")
+}
+
+fn save_plan(fixture: &Fixture) {
+    let output = run(
+        fixture,
+        &[
+            "plan",
+            "--config",
+            path_str(&fixture.config_path),
+            "--out",
+            path_str(&fixture.plan_path),
+        ],
+    );
+    common::assert_success(&output);
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().expect("utf-8 path")
+}
+
+#[test]
+fn apply_planfile_executes_saved_actions() {
+    let fixture = fixture_with_pending_create();
+    save_plan(&fixture);
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&fixture.plan_path).expect("read plan"))
+            .expect("plan file is json");
+    assert_eq!(saved["version"], 1);
+    assert_eq!(saved["items"][0]["action"], "create");
+    assert!(saved["fingerprints"]["config"]
+        .as_str()
+        .is_some_and(|digest| digest.starts_with("sha256:")));
+    assert_eq!(
+        saved["fingerprints"]["targets"]
+            .as_object()
+            .and_then(|targets| targets.values().next())
+            .and_then(|state| state.as_str()),
+        Some("absent")
+    );
+
+    let output = run(&fixture, &["apply", path_str(&fixture.plan_path)]);
+    common::assert_success(&output);
+    assert!(expected_target_path(&fixture.target_root)
+        .join("README.txt")
+        .exists());
+}
+
+#[test]
+fn apply_planfile_refuses_when_config_changed() {
+    let fixture = fixture_with_pending_create();
+    save_plan(&fixture);
+    let config = fs::read_to_string(&fixture.config_path).expect("read config");
+    fs::write(&fixture.config_path, format!("# reviewed\n{config}")).expect("edit config");
+
+    let output = run(&fixture, &["apply", path_str(&fixture.plan_path)]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("is stale: config changed"),
+        "stderr={stderr}"
+    );
+    assert!(!expected_target_path(&fixture.target_root).exists());
+}
+
+#[test]
+fn apply_planfile_refuses_when_target_changed() {
+    let fixture = fixture_with_pending_create();
+    save_plan(&fixture);
+    let target = expected_target_path(&fixture.target_root);
+    fs::create_dir_all(&target).expect("create target out of band");
+
+    let output = run(&fixture, &["apply", path_str(&fixture.plan_path)]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("target `"), "stderr={stderr}");
+}
+
+#[test]
+fn apply_planfile_conflicts_with_locked() {
+    let fixture = fixture_with_pending_create();
+    save_plan(&fixture);
+
+    let output = run(
+        &fixture,
+        &["apply", path_str(&fixture.plan_path), "--locked"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+fn write_local_skill_source(base: &Path) {
+    let source_dir = base.join("test-skills");
+    fs::create_dir_all(&source_dir).expect("create source dir");
+    fs::write(source_dir.join("README.md"), "demo skill").expect("write source file");
+}
+
+fn read_lock_value(project_dir: &Path) -> toml::Value {
+    let lock_text = fs::read_to_string(project_dir.join("skills.lock")).expect("read lock");
+    toml::from_str(&lock_text).expect("valid lock toml")
+}
+
+#[test]
+fn install_project_writes_project_config_and_installs_into_project_agent_dir() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    fs::create_dir_all(project_dir.join(".claude")).expect("create project .claude");
+    write_local_skill_source(temp.path());
+
+    let output = common::eden_command(&home_dir)
+        .current_dir(temp.path())
+        .args(["install", "./test-skills", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run install --project");
+    common::assert_success(&output);
+
+    let config_text =
+        fs::read_to_string(project_dir.join("skills.toml")).expect("read project config");
+    assert!(
+        config_text.contains("scope = \"project\""),
+        "project install should persist target scope, config={config_text}"
+    );
+
+    let installed = project_dir
+        .join(".claude")
+        .join("skills")
+        .join("test-skills");
+    assert!(
+        fs::symlink_metadata(&installed).is_ok(),
+        "skill should be installed into the project agent dir: {}",
+        installed.display()
+    );
+    assert!(
+        fs::symlink_metadata(home_dir.join(".claude").join("skills").join("test-skills")).is_err(),
+        "project install must not touch the global agent dir"
+    );
+
+    let lock = read_lock_value(&project_dir);
+    let target = &lock["skills"][0]["targets"][0];
+    assert_eq!(target["path"].as_str(), Some(".claude/skills/test-skills"));
+    assert_eq!(target["scope"].as_str(), Some("project"));
+
+    let manifest_text = fs::read_to_string(
+        project_dir
+            .join(".claude")
+            .join("skills")
+            .join(".eden-managed"),
+    )
+    .expect("read managed manifest");
+    let manifest: serde_json::Value =
+        serde_json::from_str(&manifest_text).expect("valid manifest json");
+    assert_eq!(
+        manifest["skills"]["test-skills"]["origin"].as_str(),
+        Some("project")
+    );
+}
+
+#[test]
+fn project_lock_reconciles_after_checkout_moves() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    let project_dir = temp.path().join("project");
+    fs::create_dir_all(project_dir.join(".claude")).expect("create project .claude");
+    write_local_skill_source(temp.path());
+
+    let install = common::eden_command(&home_dir)
+        .current_dir(temp.path())
+        .args(["install", "./test-skills", "--copy", "--project"])
+        .arg(&project_dir)
+        .output()
+        .expect("run install --project");
+    common::assert_success(&install);
+
+    let moved_dir = temp.path().join("teammate-checkout");
+    fs::rename(&project_dir, &moved_dir).expect("move project checkout");
+
+    let plan = common::eden_command(&home_dir)
+        .args(["plan", "--json", "--project"])
+        .arg(&moved_dir)
+        .output()
+        .expect("run plan --project");
+    common::assert_success(&plan);
+    let items: serde_json::Value =
+        serde_json::from_slice(&plan.stdout).expect("plan json should parse");
+    let items = items.as_array().expect("plan json array");
+    assert!(
+        items
+            .iter()
+            .all(|item| item["action"].as_str() != Some("remove")),
+        "moved checkout should not plan removals, plan={items:?}"
+    );
+    let expected_target = moved_dir.join(".claude").join("skills").join("test-skills");
+    assert!(
+        items.iter().any(|item| item["target_path"].as_str()
+            == Some(expected_target.display().to_string().as_str())),
+        "plan should resolve project targets against the moved checkout, plan={items:?}"
+    );
+
+    let apply = common::eden_command(&home_dir)
+        .args(["apply", "--project"])
+        .arg(&moved_dir)
+        .output()
+        .expect("run apply --project");
+    common::assert_success(&apply);
+    let lock = read_lock_value(&moved_dir);
+    assert_eq!(
+        lock["skills"][0]["targets"][0]["path"].as_str(),
+        Some(".claude/skills/test-skills")
+    );
+}
+
+#[test]
+fn project_flag_conflicts_with_config() {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+
+    let output = common::eden_command(&home_dir)
+        .current_dir(temp.path())
+        .args(["plan", "--project", ".", "--config", "./skills.toml"])
+        .output()
+        .expect("run plan");
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "--project with --config should be rejected, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,182 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tempfile::tempdir;
+
+use common::{
+    as_file_url, assert_success, eden_command, expected_target_path, init_git_repo,
+    init_origin_repo, path_to_file_url, toml_escape_string, write_config,
+};
+
+const CHECKS: &[&str] = &["path-exists", "target-resolves", "is-symlink"];
+
+/// A `git` wrapper whose first `failures` clones fail with a DNS error;
+/// `hang` makes every clone stall instead. Cache entries are cloned by
+/// fetching into their object store, so `git -C <store> fetch` counts as
+/// a clone too.
+fn write_flaky_git(base: &Path, failures: u32, hang: bool) -> PathBuf {
+    let script = base.join("flaky-git");
+    let count_file = base.join("clone-count");
+    let clone_body = if hang {
+        "exec sleep 30".to_string()
+    } else {
+        format!(
+            "if [ \"$count\" -le {failures} ]; then\n    echo \"fatal: unable to access 'https://example.invalid/': Could not resolve host: example.invalid\" >&2\n    exit 128\n  fi"
+        )
+    };
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = \"clone\" ] || {{ [ \"$1\" = \"-C\" ] && [ \"$3\" = \"fetch\" ]; }}; then\n  count=$(( $(cat '{count}' 2>/dev/null || echo 0) + 1 ))\n  echo \"$count\" > '{count}'\n  {clone_body}\nfi\nexec git \"$@\"\n",
+            count = count_file.display(),
+        ),
+    )
+    .expect("write git wrapper");
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).expect("chmod");
+    script
+}
+
+fn clone_count(base: &Path) -> u32 {
+    fs::read_to_string(base.join("clone-count"))
+        .map_or(0, |count| count.trim().parse().expect("clone count"))
+}
+
+fn write_reactor_config(base: &Path, reactor: &str) -> PathBuf {
+    let origin_repo = init_origin_repo(base);
+    let config_path = write_config(
+        base,
+        &as_file_url(&origin_repo),
+        "symlink",
+        CHECKS,
+        &base.join("storage"),
+        &base.join("agent-skills"),
+    );
+    let config = fs::read_to_string(&config_path).expect("read config");
+    fs::write(
+        &config_path,
+        config.replacen("\n", &format!("\n\n[reactor]\n{reactor}\n"), 1),
+    )
+    .expect("write config");
+    config_path
+}
+
+#[test]
+fn transient_clone_failures_are_retried() {
+    let temp = tempdir().expect("tempdir");
+    let git = write_flaky_git(temp.path(), 2, false);
+    let config_path = write_reactor_config(temp.path(), "retries = 2\nbackoff = 0");
+
+    let output = eden_command(&temp.path().join("home"))
+        .env("EDEN_SKILLS_GIT_BIN", &git)
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_success(&output);
+    assert_eq!(clone_count(temp.path()), 3);
+    assert!(expected_target_path(&temp.path().join("agent-skills")).exists());
+}
+
+#[test]
+fn exhausted_retries_report_attempts() {
+    let temp = tempdir().expect("tempdir");
+    let git = write_flaky_git(temp.path(), u32::MAX, false);
+    let config_path = write_reactor_config(temp.path(), "retries = 1\nbackoff = 0");
+
+    let output = eden_command(&temp.path().join("home"))
+        .env("EDEN_SKILLS_GIT_BIN", &git)
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not resolve host, failed after 2 attempts"),
+        "stderr={stderr}"
+    );
+    // A network failure does not fall back to a second, branchless clone.
+    assert_eq!(clone_count(temp.path()), 2);
+}
+
+#[test]
+fn apply_json_reports_sync_failure_attempts() {
+    let temp = tempdir().expect("tempdir");
+    let git = write_flaky_git(temp.path(), u32::MAX, false);
+    let config_path = write_reactor_config(temp.path(), "retries = 1\nbackoff = 0");
+
+    let output = eden_command(&temp.path().join("home"))
+        .env("EDEN_SKILLS_GIT_BIN", &git)
+        .args(["apply", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_eq!(output.status.code(), Some(1));
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("apply failure json");
+    assert_eq!(payload["action"], "apply");
+    assert_eq!(payload["status"], "failed");
+    let failure = &payload["sync_failures"][0];
+    assert_eq!(failure["skill_id"], common::SKILL_ID);
+    assert_eq!(failure["stage"], "clone");
+    assert_eq!(failure["attempts"], 2);
+}
+
+#[test]
+fn stalled_clones_time_out() {
+    let temp = tempdir().expect("tempdir");
+    let git = write_flaky_git(temp.path(), 0, true);
+    let config_path = write_reactor_config(temp.path(), "retries = 0\ntimeout_secs = 1");
+
+    let started = Instant::now();
+    let output = eden_command(&temp.path().join("home"))
+        .env("EDEN_SKILLS_GIT_BIN", &git)
+        .args(["apply", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run apply");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(started.elapsed() < Duration::from_secs(20));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("timed out"), "stderr={stderr}");
+}
+
+#[test]
+fn update_json_records_registry_attempts() {
+    let temp = tempdir().expect("tempdir");
+    let git = write_flaky_git(temp.path(), 1, false);
+    let registry = init_git_repo(
+        temp.path(),
+        "registry",
+        &[("manifest.toml", "format_version = 1\nname = \"official\"\n")],
+    );
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[reactor]\nbackoff = 0\n\n[registries.official]\nurl = \"{}\"\n",
+            toml_escape_string(&temp.path().join("storage").display().to_string()),
+            toml_escape_string(&path_to_file_url(&registry)),
+        ),
+    )
+    .expect("write config");
+
+    let output = eden_command(&temp.path().join("home"))
+        .env("EDEN_SKILLS_GIT_BIN", &git)
+        .args(["update", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .expect("run update");
+    assert_success(&output);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("update json payload");
+    let registry = &payload["registries"][0];
+    assert_eq!(registry["status"], "cloned");
+    assert_eq!(registry["attempts"], 2);
+}
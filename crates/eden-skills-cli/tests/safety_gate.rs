@@ -86,6 +86,8 @@ fn doctor_reports_safety_findings() {
         CommandOptions {
             strict: false,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
     )
     .expect("apply before doctor");
@@ -188,6 +190,8 @@ fn apply_mixed_skills_skips_verify_only_for_no_exec_skill() {
         CommandOptions {
             strict: false,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
     )
     .expect("apply mixed skills");
@@ -232,6 +236,8 @@ fn apply_mixed_skills_still_verifies_non_no_exec_skill() {
         CommandOptions {
             strict: false,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
     )
     .expect_err("non-no-exec skill verification should still run");
@@ -280,6 +286,8 @@ fn apply_strict_ignores_conflicts_for_no_exec_skill() {
         CommandOptions {
             strict: true,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
     )
     .expect("strict apply should ignore no-exec conflicts");
@@ -320,6 +328,8 @@ fn repair_strict_ignores_conflicts_for_no_exec_skill() {
         CommandOptions {
             strict: true,
             json: false,
+            offline: false,
+            user_config_path: None,
         },
     )
     .expect("strict repair should ignore no-exec conflicts");
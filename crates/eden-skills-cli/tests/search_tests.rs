@@ -0,0 +1,159 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::{tempdir, TempDir};
+
+use common::{assert_success, eden_command, toml_escape_path};
+
+struct SearchFixture {
+    temp: TempDir,
+    config_path: PathBuf,
+    registry_root: PathBuf,
+}
+
+#[test]
+fn search_json_ranks_matches_with_latest_version_and_status() {
+    let fixture = setup_search_fixture();
+    write_index_entry(
+        &fixture.registry_root,
+        "browser-tool",
+        "description = \"Drive a headless browser\"",
+        &[("1.0.0", false), ("1.1.0", false), ("2.0.0", true)],
+    );
+    write_index_entry(
+        &fixture.registry_root,
+        "scraper",
+        "tags = [\"browser\"]\ndeprecated = true",
+        &[("0.1.0", true)],
+    );
+    write_index_entry(&fixture.registry_root, "notes", "", &[("1.0.0", false)]);
+
+    let output = run_command(&fixture, &["search", "browser", "--json"]);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("search json");
+    assert_eq!(payload["query"], "browser");
+    let results = payload["results"].as_array().expect("results array");
+    assert_eq!(results.len(), 2, "json={payload}");
+
+    assert_eq!(results[0]["name"], "browser-tool");
+    assert_eq!(results[0]["registry"], "official");
+    assert_eq!(results[0]["version"], "1.1.0");
+    assert_eq!(results[0]["yanked"], false);
+    assert_eq!(results[0]["description"], "Drive a headless browser");
+
+    assert_eq!(results[1]["name"], "scraper");
+    assert_eq!(results[1]["version"], Value::Null);
+    assert_eq!(results[1]["yanked"], true);
+    assert_eq!(results[1]["deprecated"], true);
+    assert_eq!(results[1]["tags"][0], "browser");
+}
+
+#[test]
+fn search_prints_table_and_warns_about_unsynced_registries() {
+    let fixture = setup_search_fixture();
+    let config = fs::read_to_string(&fixture.config_path).expect("read config");
+    fs::write(
+        &fixture.config_path,
+        format!("{config}forge = {{ url = \"https://example.com/forge.git\", priority = 10 }}\n"),
+    )
+    .expect("write config");
+    write_index_entry(
+        &fixture.registry_root,
+        "browser-tool",
+        "description = \"Drive a headless browser\"",
+        &[("1.0.0", false)],
+    );
+
+    let output = run_command(&fixture, &["search", "headless"]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("1 match for \"headless\""),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains("browser-tool"), "stdout={stdout}");
+    assert!(stdout.contains("1.0.0"), "stdout={stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("registry `forge` has not been synced"),
+        "stderr={stderr}"
+    );
+}
+
+#[test]
+fn search_without_synced_registries_fails_with_update_hint() {
+    let fixture = setup_search_fixture();
+
+    let output = run_command(&fixture, &["search", "browser"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Run `eden-skills update` first"),
+        "stderr={stderr}"
+    );
+}
+
+/// A config with one `official` registry whose cache is not written yet.
+fn setup_search_fixture() -> SearchFixture {
+    let temp = tempdir().expect("tempdir");
+    let storage_root = temp.path().join("storage");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "version = 1\n\n[storage]\nroot = \"{}\"\n\n[registries]\nofficial = {{ url = \"https://example.com/official.git\", priority = 100 }}\n",
+            toml_escape_path(&storage_root),
+        ),
+    )
+    .expect("write config");
+
+    SearchFixture {
+        registry_root: storage_root.join("registries").join("official"),
+        temp,
+        config_path,
+    }
+}
+
+/// Write an index entry whose `[skill]` table carries `extra` lines;
+/// each version is `(version, yanked)`.
+fn write_index_entry(
+    registry_root: &Path,
+    skill_name: &str,
+    extra: &str,
+    versions: &[(&str, bool)],
+) {
+    fs::create_dir_all(registry_root).expect("create registry root");
+    fs::write(
+        registry_root.join("manifest.toml"),
+        "format_version = 1\nname = \"official\"\n",
+    )
+    .expect("write manifest");
+    let first = skill_name.chars().next().expect("skill name");
+    let index_dir = registry_root.join("index").join(first.to_string());
+    fs::create_dir_all(&index_dir).expect("create index dir");
+
+    let mut body = format!(
+        "[skill]\nname = \"{skill_name}\"\nrepo = \"https://example.com/{skill_name}.git\"\n{extra}\n\n"
+    );
+    for (version, yanked) in versions {
+        body.push_str(&format!(
+            "[[versions]]\nversion = \"{version}\"\nref = \"v{version}\"\ncommit = \"{}\"\nyanked = {yanked}\n\n",
+            "a".repeat(40)
+        ));
+    }
+    fs::write(index_dir.join(format!("{skill_name}.toml")), body).expect("write index entry");
+}
+
+fn run_command(fixture: &SearchFixture, args: &[&str]) -> Output {
+    eden_command(&fixture.temp.path().join("home"))
+        .current_dir(fixture.temp.path())
+        .args(args)
+        .arg("--config")
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run eden-skills")
+}
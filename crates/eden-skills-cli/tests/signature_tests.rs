@@ -0,0 +1,178 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::{tempdir, TempDir};
+
+use common::{
+    assert_success, eden_command, init_git_repo, path_to_file_url, run_git_cmd, toml_escape_path,
+};
+
+const SKILL_MD: &str = "---\nname: signed-skill\n---\n";
+
+struct SignedFixture {
+    temp: TempDir,
+    home_dir: PathBuf,
+    config_path: PathBuf,
+    skill_repo: PathBuf,
+}
+
+#[test]
+fn apply_accepts_commit_signed_by_trusted_key() {
+    let fixture = setup_signed_fixture("ref = \"main\"");
+
+    let output = run_command(&fixture, &["apply"]);
+    assert_success(&output);
+    assert!(fixture
+        .temp
+        .path()
+        .join("agent-skills/signed-skill/SKILL.md")
+        .exists());
+}
+
+#[test]
+fn update_refuses_unsigned_commit_and_doctor_reports_revoked_key() {
+    let fixture = setup_signed_fixture("ref = \"main\"");
+    assert_success(&run_command(&fixture, &["apply"]));
+
+    fs::write(fixture.skill_repo.join("README.md"), "unsigned\n").expect("write README");
+    run_git_cmd(&fixture.skill_repo, &["add", "."]);
+    run_git_cmd(
+        &fixture.skill_repo,
+        &["-c", "commit.gpgsign=false", "commit", "-m", "unsigned"],
+    );
+
+    let output = run_command(&fixture, &["update", "--apply"]);
+    assert_ne!(output.status.code(), Some(0), "unsigned commit must fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("signature — untrusted signature"),
+        "stderr={stderr}"
+    );
+
+    assert!(
+        !fixture
+            .temp
+            .path()
+            .join("agent-skills/signed-skill/README.md")
+            .exists(),
+        "the refused commit must not reach the symlinked target"
+    );
+
+    // Revoking the key leaves the installed checkout untrusted.
+    fs::write(fixture.temp.path().join("allowed_signers"), "").expect("revoke key");
+    let output = run_command(&fixture, &["doctor", "--json"]);
+    assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("doctor json");
+    let findings = payload["findings"].as_array().expect("findings array");
+    assert!(
+        findings
+            .iter()
+            .any(|finding| finding["code"] == "UNSIGNED_SOURCE"
+                && finding["skill_id"] == "signed-skill"),
+        "json={payload}"
+    );
+}
+
+#[test]
+fn signed_tag_covers_unsigned_commit() {
+    let fixture = setup_signed_fixture("version = \"^1\"");
+    fs::write(fixture.skill_repo.join("README.md"), "release\n").expect("write README");
+    run_git_cmd(&fixture.skill_repo, &["add", "."]);
+    run_git_cmd(
+        &fixture.skill_repo,
+        &["-c", "commit.gpgsign=false", "commit", "-m", "release"],
+    );
+    run_git_cmd(
+        &fixture.skill_repo,
+        &["tag", "-s", "v1.0.0", "-m", "v1.0.0"],
+    );
+
+    let output = run_command(&fixture, &["apply"]);
+    assert_success(&output);
+}
+
+/// A repo whose commits are SSH-signed by a key listed in the config's
+/// `git.allowed_signers`.
+fn setup_signed_fixture(source_ref: &str) -> SignedFixture {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    fs::create_dir_all(&home_dir).expect("create HOME");
+
+    let key = temp.path().join("signing-key");
+    let status = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "signer", "-f"])
+        .arg(&key)
+        .status()
+        .expect("spawn ssh-keygen");
+    assert!(status.success(), "ssh-keygen should succeed");
+    let public_key = fs::read_to_string(key.with_extension("pub")).expect("read public key");
+    let allowed_signers = temp.path().join("allowed_signers");
+    fs::write(&allowed_signers, format!("signer@example.com {public_key}"))
+        .expect("write allowed signers");
+
+    let skill_repo = temp.path().join("signed-origin");
+    fs::create_dir_all(&skill_repo).expect("create repo");
+    run_git_cmd(&skill_repo, &["init"]);
+    run_git_cmd(&skill_repo, &["config", "gpg.format", "ssh"]);
+    run_git_cmd(
+        &skill_repo,
+        &["config", "user.signingkey", &key.display().to_string()],
+    );
+    run_git_cmd(&skill_repo, &["config", "commit.gpgsign", "true"]);
+    let skill_repo = init_git_repo(temp.path(), "signed-origin", &[("SKILL.md", SKILL_MD)]);
+
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version = 1
+
+[storage]
+root = "{storage}"
+
+[git]
+allowed_signers = "{allowed_signers}"
+
+[[skills]]
+id = "signed-skill"
+
+[skills.source]
+repo = "{repo}"
+{source_ref}
+verify_signature = true
+
+[[skills.targets]]
+agent = "custom"
+path = "{target}"
+"#,
+            storage = toml_escape_path(&temp.path().join("storage")),
+            allowed_signers = toml_escape_path(&allowed_signers),
+            repo = path_to_file_url(&skill_repo),
+            target = toml_escape_path(&temp.path().join("agent-skills")),
+        ),
+    )
+    .expect("write config");
+
+    SignedFixture {
+        temp,
+        home_dir,
+        config_path,
+        skill_repo,
+    }
+}
+
+fn run_command(fixture: &SignedFixture, args: &[&str]) -> Output {
+    eden_command(&fixture.home_dir)
+        .current_dir(fixture.temp.path())
+        .args(args)
+        .arg("--config")
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run eden-skills")
+}
@@ -298,6 +298,173 @@ fn tm_p295_034_update_mode_a_refresh_uses_repo_cache_paths() {
     );
 }
 
+#[test]
+fn update_lists_upstream_commits_within_the_skill_subpath() {
+    let fixture = setup_mode_a_fixture("symlink", false);
+    let apply_output = run_command(&fixture, "never", false, &["apply"]);
+    common::assert_success(&apply_output);
+
+    commit_file(
+        &fixture.skill_repo,
+        "packages/browser/README.md",
+        "upstream-v2\n",
+        "rewrite browser readme",
+    );
+    commit_file(
+        &fixture.skill_repo,
+        "packages/other/README.md",
+        "unrelated\n",
+        "touch another package",
+    );
+    commit_file(
+        &fixture.skill_repo,
+        "packages/browser/tools.md",
+        "tools\n",
+        "add browser tools",
+    );
+
+    let output = run_command(&fixture, "never", false, &["update"]);
+    common::assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("Changes") && stdout.contains("(2 commits, 1 added, 0 removed, 1 changed)"),
+        "update should summarize the skill's upstream changes, stdout={stdout}"
+    );
+    assert!(
+        stdout.contains("add browser tools") && stdout.contains("rewrite browser readme"),
+        "changelog should list commits touching the subpath, stdout={stdout}"
+    );
+    assert!(
+        !stdout.contains("touch another package"),
+        "changelog should skip commits outside the subpath, stdout={stdout}"
+    );
+    let newest = stdout.find("add browser tools").expect("newest commit");
+    let oldest = stdout
+        .find("rewrite browser readme")
+        .expect("oldest commit");
+    assert!(
+        newest < oldest,
+        "commits should be newest first, stdout={stdout}"
+    );
+}
+
+#[test]
+fn update_json_carries_the_changelog_from_the_locked_commit() {
+    let fixture = setup_mode_a_fixture("symlink", false);
+    let apply_output = run_command(&fixture, "never", false, &["apply"]);
+    common::assert_success(&apply_output);
+    let locked_commit = git_head(&fixture.skill_repo);
+
+    commit_file(
+        &fixture.skill_repo,
+        "packages/browser/extra.md",
+        "extra\n",
+        "add extra notes",
+    );
+    let origin_head = git_head(&fixture.skill_repo);
+
+    let output = run_command(&fixture, "never", false, &["update", "--json"]);
+    common::assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("update json");
+    let changelog = &payload["skills"][0]["changelog"];
+
+    assert_eq!(
+        changelog["from"],
+        Value::from(locked_commit),
+        "json={payload}"
+    );
+    assert_eq!(
+        changelog["to"],
+        Value::from(origin_head.clone()),
+        "json={payload}"
+    );
+    assert_eq!(
+        changelog["commits"],
+        serde_json::json!([{ "id": origin_head, "subject": "add extra notes" }]),
+        "json={payload}"
+    );
+    assert_eq!(
+        changelog["files"],
+        serde_json::json!({ "added": 1, "removed": 0, "changed": 0 }),
+        "json={payload}"
+    );
+}
+
+#[test]
+fn update_json_changelog_is_null_when_up_to_date() {
+    let fixture = setup_mode_a_fixture("symlink", false);
+    let apply_output = run_command(&fixture, "never", false, &["apply"]);
+    common::assert_success(&apply_output);
+
+    let output = run_command(&fixture, "never", false, &["update", "--json"]);
+    common::assert_success(&output);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("update json");
+
+    assert_eq!(
+        payload["skills"][0]["status"], "up-to-date",
+        "json={payload}"
+    );
+    assert!(
+        payload["skills"][0]["changelog"].is_null(),
+        "json={payload}"
+    );
+}
+
+#[test]
+fn update_dry_run_reports_changes_without_syncing_registries() {
+    let fixture = setup_mode_a_fixture("symlink", true);
+    let apply_output = run_command(&fixture, "never", false, &["apply"]);
+    common::assert_success(&apply_output);
+
+    commit_file(
+        &fixture.skill_repo,
+        "packages/browser/README.md",
+        "upstream-v2\n",
+        "rewrite browser readme",
+    );
+    let local_head_before = git_head(&mode_a_repo_dir(&fixture));
+
+    let output = run_command(&fixture, "never", false, &["update", "--dry-run"]);
+    common::assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("rewrite browser readme"),
+        "dry run should list upstream commits, stdout={stdout}"
+    );
+    assert!(
+        !stdout.contains("registries synced"),
+        "dry run should not sync registries, stdout={stdout}"
+    );
+    assert!(
+        !fixture
+            .storage_root
+            .join("registries")
+            .join("official")
+            .exists(),
+        "dry run should not clone registries"
+    );
+    assert_eq!(
+        git_head(&mode_a_repo_dir(&fixture)),
+        local_head_before,
+        "dry run should keep the skill checkout unchanged"
+    );
+
+    let output = run_command(
+        &fixture,
+        "never",
+        false,
+        &["update", "--dry-run", "--apply"],
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "--dry-run and --apply should conflict, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 fn setup_mode_a_fixture(install_mode: &str, with_registries: bool) -> ModeAFixture {
     let temp = tempdir().expect("tempdir");
     let home_dir = temp.path().join("home");
@@ -5,6 +5,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 use common::{assert_success, eden_command, path_to_file_url, run_git_cmd};
+use eden_skills_core::git;
 use eden_skills_core::source::resolve_repo_cache_root;
 use serde_json::Value;
 use tempfile::{tempdir, TempDir};
@@ -85,7 +86,7 @@ fn tm_p297_003_update_clears_stale_index_lock_before_fetching() {
         &path_to_file_url(&fixture.source_root),
         "main",
     );
-    let index_lock = repo_dir.join(".git").join("index.lock");
+    let index_lock = git::git_dir(&repo_dir).join("index.lock");
     let warning_fragment = format!("removed stale git lock `{}`", index_lock.display());
 
     write_stale_lock(&index_lock);
@@ -126,7 +127,7 @@ fn tm_p297_004_update_clears_stale_shallow_lock_before_fetching() {
         &path_to_file_url(&fixture.source_root),
         "main",
     );
-    let shallow_lock = repo_dir.join(".git").join("shallow.lock");
+    let shallow_lock = git::common_dir(&repo_dir).join("shallow.lock");
     let warning_fragment = format!("removed stale git lock `{}`", shallow_lock.display());
 
     write_stale_lock(&shallow_lock);
@@ -0,0 +1,165 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use eden_skills_core::lock::{lock_path_for_config, read_lock_file};
+use serde_json::Value;
+use tempfile::{tempdir, TempDir};
+
+use common::{
+    assert_success, eden_command, init_git_repo, path_to_file_url, run_git_cmd, toml_escape_path,
+    toml_escape_string,
+};
+
+struct TaggedFixture {
+    temp: TempDir,
+    home_dir: PathBuf,
+    config_path: PathBuf,
+    target_root: PathBuf,
+    skill_repo: PathBuf,
+}
+
+#[test]
+fn apply_checks_out_newest_tag_matching_version_constraint() {
+    let fixture = setup_tagged_fixture("^1.0");
+
+    let output = run_command(&fixture, &["apply"]);
+    assert_success(&output);
+
+    assert_eq!(installed_readme(&fixture), "release 1.2.0\n");
+    let lock = read_lock_file(&lock_path_for_config(&fixture.config_path))
+        .expect("read lock")
+        .expect("lock exists");
+    let entry = &lock.skills[0];
+    assert_eq!(entry.source_ref, "semver:^1.0");
+    assert_eq!(entry.resolved_version.as_deref(), Some("v1.2.0"));
+}
+
+#[test]
+fn version_constraint_without_matching_tag_fails() {
+    let fixture = setup_tagged_fixture("^3");
+
+    let output = run_command(&fixture, &["apply"]);
+    assert_ne!(output.status.code(), Some(0), "apply should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no matching version tag"),
+        "stderr={stderr}"
+    );
+}
+
+#[test]
+fn update_reports_and_applies_newer_matching_tag() {
+    let fixture = setup_tagged_fixture("^1.0");
+    assert_success(&run_command(&fixture, &["apply"]));
+
+    tag_release(&fixture.skill_repo, "1.3.0");
+    tag_release(&fixture.skill_repo, "2.1.0");
+
+    let output = run_command(&fixture, &["update", "--json"]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let payload: Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|err| panic!("update --json should emit JSON, err={err} stdout={stdout}"));
+    let skill = &payload["skills"][0];
+    assert_eq!(skill["status"], "new-commit", "json={payload}");
+    assert_eq!(skill["remote_version"], "v1.3.0", "json={payload}");
+    assert_eq!(installed_readme(&fixture), "release 1.2.0\n");
+
+    let output = run_command(&fixture, &["update", "--apply"]);
+    assert_success(&output);
+    assert_eq!(installed_readme(&fixture), "release 1.3.0\n");
+    let lock = read_lock_file(&lock_path_for_config(&fixture.config_path))
+        .expect("read lock")
+        .expect("lock exists");
+    assert_eq!(lock.skills[0].resolved_version.as_deref(), Some("v1.3.0"));
+}
+
+fn setup_tagged_fixture(constraint: &str) -> TaggedFixture {
+    let temp = tempdir().expect("tempdir");
+    let home_dir = temp.path().join("home");
+    fs::create_dir_all(&home_dir).expect("create HOME");
+    let skill_repo = init_git_repo(
+        temp.path(),
+        "tagged-origin",
+        &[("SKILL.md", "---\nname: tagged\n---\n")],
+    );
+    for version in ["1.0.0", "1.2.0", "2.0.0"] {
+        tag_release(&skill_repo, version);
+    }
+    // Untagged work on the branch must never be picked up.
+    commit_readme(&skill_repo, "unreleased\n");
+
+    let storage_root = temp.path().join("storage");
+    let target_root = temp.path().join("agent-skills");
+    let config_path = temp.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version = 1
+
+[storage]
+root = "{storage_root}"
+
+[[skills]]
+id = "tagged"
+
+[skills.source]
+repo = "{repo}"
+version = "{constraint}"
+
+[skills.install]
+mode = "copy"
+
+[[skills.targets]]
+agent = "custom"
+path = "{target_root}"
+
+[skills.safety]
+no_exec_metadata_only = false
+"#,
+            storage_root = toml_escape_path(&storage_root),
+            repo = toml_escape_string(&path_to_file_url(&skill_repo)),
+            constraint = toml_escape_string(constraint),
+            target_root = toml_escape_path(&target_root),
+        ),
+    )
+    .expect("write config");
+
+    TaggedFixture {
+        temp,
+        home_dir,
+        config_path,
+        target_root,
+        skill_repo,
+    }
+}
+
+/// Commit a README for `version` and tag it `v<version>` (annotated).
+fn tag_release(repo: &Path, version: &str) {
+    commit_readme(repo, &format!("release {version}\n"));
+    let tag = format!("v{version}");
+    run_git_cmd(repo, &["tag", "-a", &tag, "-m", &tag]);
+}
+
+fn commit_readme(repo: &Path, content: &str) {
+    fs::write(repo.join("README.md"), content).expect("write README");
+    run_git_cmd(repo, &["add", "."]);
+    run_git_cmd(repo, &["commit", "-m", content.trim()]);
+}
+
+fn installed_readme(fixture: &TaggedFixture) -> String {
+    fs::read_to_string(fixture.target_root.join("tagged/README.md")).expect("read README")
+}
+
+fn run_command(fixture: &TaggedFixture, args: &[&str]) -> Output {
+    eden_command(&fixture.home_dir)
+        .current_dir(fixture.temp.path())
+        .args(args)
+        .arg("--config")
+        .arg(&fixture.config_path)
+        .output()
+        .expect("run eden-skills")
+}
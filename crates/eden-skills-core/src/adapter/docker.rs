@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use tokio::process::Command;
 
-use crate::config::{AgentKind, InstallMode, TargetConfig};
+use crate::config::{AgentKind, InstallMode, TargetConfig, TargetScope};
 use crate::error::AdapterError;
 use crate::paths::{default_agent_path, normalize_lexical};
 
@@ -404,6 +404,7 @@ impl DockerAdapter {
                     expected_path: None,
                     path: None,
                     environment: format!("docker:{}", self.container_name),
+                    scope: TargetScope::Global,
                 });
             }
         }
@@ -591,6 +592,37 @@ impl TargetAdapter for DockerAdapter {
         })
     }
 
+    async fn copy_out(&self, target: &Path, dest: &Path) -> Result<(), AdapterError> {
+        self.health_check().await?;
+        if let Some(host_target) = self.mounted_host_path_for_path(target).await? {
+            return LocalAdapter::new().copy_out(&host_target, dest).await;
+        }
+
+        let output = self
+            .run_docker(
+                vec![
+                    OsString::from("cp"),
+                    OsString::from(format!("{}:{}", self.container_name, target.display())),
+                    dest.as_os_str().to_os_string(),
+                ],
+                "copy installed target out of container",
+            )
+            .await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        Err(AdapterError::Runtime {
+            detail: format!(
+                "docker cp failed while reading target `{}` from container `{}`: status={} stderr=`{}`",
+                target.display(),
+                self.container_name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+
     async fn exec(&self, cmd: &str) -> Result<String, AdapterError> {
         self.health_check().await?;
         let output = self
@@ -61,6 +61,10 @@ impl TargetAdapter for LocalAdapter {
         remove_existing_path(target).await
     }
 
+    async fn copy_out(&self, target: &Path, dest: &Path) -> Result<(), AdapterError> {
+        copy_recursively(target, dest).await
+    }
+
     async fn exec(&self, cmd: &str) -> Result<String, AdapterError> {
         #[cfg(unix)]
         let mut command = {
@@ -83,6 +83,10 @@ pub trait TargetAdapter: Send + Sync {
 
     async fn uninstall(&self, target: &Path) -> Result<(), AdapterError>;
 
+    /// Copy the installed `target` into `dest` on the host so its content
+    /// can be inspected locally. `dest` must not already exist.
+    async fn copy_out(&self, target: &Path, dest: &Path) -> Result<(), AdapterError>;
+
     async fn exec(&self, cmd: &str) -> Result<String, AdapterError>;
 }
 
@@ -3,15 +3,17 @@
 //! Probes the user's home directory for known agent config roots and
 //! returns a list of [`TargetConfig`] values representing locally
 //! installed agents.  Used by `install --all` and `install --auto-detect`
-//! to discover targets without explicit configuration.
+//! to discover targets without explicit configuration. Project-scope
+//! installs probe the project root for project-level agent directories
+//! instead.
 
 use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
-use crate::config::{AgentKind, TargetConfig};
+use crate::config::{AgentKind, TargetConfig, TargetScope};
 use crate::error::EdenError;
-use crate::paths::default_agent_path;
+use crate::paths::{default_agent_path, default_agent_project_path};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct AgentDetectionRule {
@@ -63,6 +65,43 @@ pub fn detect_installed_agent_targets_from_home(home: &Path) -> Vec<TargetConfig
                 expected_path: None,
                 path: None,
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
+            });
+        }
+    }
+    detected
+}
+
+/// Detect agents that already have a project-level directory under
+/// `project_root` (see [`default_agent_project_path`]).
+///
+/// Several agents share one project directory (e.g. `.agents/skills`);
+/// each directory is reported once, for the first matching agent.
+/// Agents whose project directory sits directly in the project root are
+/// only detected when the `skills/` directory itself exists.
+pub fn detect_project_agent_targets(project_root: &Path) -> Vec<TargetConfig> {
+    let mut detected = Vec::new();
+    let mut seen_paths = Vec::new();
+    for rule in agent_rules() {
+        let Some(project_subpath) = default_agent_project_path(&rule.agent) else {
+            continue;
+        };
+        if seen_paths.contains(&project_subpath) {
+            continue;
+        }
+        let skills_dir = project_root.join(project_subpath);
+        let detected_by_parent = Path::new(project_subpath)
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .is_some_and(|parent| project_root.join(parent).is_dir());
+        if skills_dir.is_dir() || detected_by_parent {
+            seen_paths.push(project_subpath);
+            detected.push(TargetConfig {
+                agent: rule.agent.clone(),
+                expected_path: None,
+                path: None,
+                environment: "local".to_string(),
+                scope: TargetScope::Project,
             });
         }
     }
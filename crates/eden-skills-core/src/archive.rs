@@ -0,0 +1,199 @@
+//! Release-archive skill sources.
+//!
+//! An archive source (`[skills.source] archive = "<url>"` plus
+//! `sha256 = "<hex>"`) is downloaded into the repo cache entry for its
+//! `(url, sha256:<hex>)` pair instead of being cloned. The download is
+//! verified against the checksum before anything is written, and
+//! extraction rejects entries that would land outside the cache entry:
+//! absolute paths, `..` components, and links. Archives are immutable, so
+//! a cache entry that exists is never downloaded again; a new checksum
+//! maps to a new cache entry.
+
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+
+use crate::digest::sha256_hex;
+
+/// Container formats recognised by their URL suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from the path of `url`, ignoring any query
+    /// string or fragment.
+    pub fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let path = path.to_ascii_lowercase();
+        if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if path.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `url` looks like a downloadable release archive.
+pub fn is_archive_url(url: &str) -> bool {
+    (url.starts_with("https://") || url.starts_with("http://") || url.starts_with("file://"))
+        && ArchiveFormat::from_url(url).is_some()
+}
+
+/// Fetch the bytes of an archive from an `http(s)://` or `file://` URL,
+/// giving up on an http download once `timeout` has passed.
+pub fn download(url: &str, timeout: Option<Duration>) -> Result<Vec<u8>, String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return fs::read(path).map_err(|err| format!("failed to read `{url}`: {err}"));
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("eden-skills/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|err| format!("failed to initialize http client: {err}"))?;
+    let response = client
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| format!("failed to download `{url}`: {err}"))?;
+    response
+        .bytes()
+        .map(|bytes| bytes.to_vec())
+        .map_err(|err| format!("failed to download `{url}`: {err}"))
+}
+
+/// Check `bytes` against the expected lowercase hex SHA-256.
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual = sha256_hex(bytes);
+    if actual == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "checksum mismatch: expected sha256 `{expected}`, got `{actual}`"
+    ))
+}
+
+/// Extract `bytes` into `dest`, which must not exist yet.
+pub fn extract(bytes: &[u8], format: ArchiveFormat, dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest)
+        .map_err(|err| format!("failed to create `{}`: {err}", dest.display()))?;
+    match format {
+        ArchiveFormat::TarGz => extract_tar_gz(bytes, dest),
+        ArchiveFormat::Zip => extract_zip(bytes, dest),
+    }
+}
+
+fn extract_tar_gz(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    let entries = archive
+        .entries()
+        .map_err(|err| format!("failed to read archive: {err}"))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|err| format!("failed to read archive entry: {err}"))?;
+        let name = entry
+            .path()
+            .map_err(|err| format!("failed to read archive entry path: {err}"))?
+            .into_owned();
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(link_entry_error(&name));
+        }
+        if !(entry_type.is_file() || entry_type.is_dir() || entry_type.is_contiguous()) {
+            continue;
+        }
+        let Some(relative) = enclosed_path(&name) else {
+            return Err(unsafe_entry_error(&name));
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let path = dest.join(&relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("failed to create `{}`: {err}", parent.display()))?;
+        }
+        entry
+            .unpack(&path)
+            .map_err(|err| format!("failed to extract `{}`: {err}", name.display()))?;
+    }
+    Ok(())
+}
+
+fn extract_zip(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|err| format!("failed to read archive: {err}"))?;
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|err| format!("failed to read archive entry: {err}"))?;
+        let name = PathBuf::from(file.name());
+        if file.is_symlink() {
+            return Err(link_entry_error(&name));
+        }
+        let Some(relative) = enclosed_path(&name) else {
+            return Err(unsafe_entry_error(&name));
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let path = dest.join(&relative);
+        if file.is_dir() {
+            fs::create_dir_all(&path)
+                .map_err(|err| format!("failed to create `{}`: {err}", path.display()))?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("failed to create `{}`: {err}", parent.display()))?;
+        }
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|err| format!("failed to extract `{}`: {err}", name.display()))?;
+        fs::write(&path, content)
+            .map_err(|err| format!("failed to write `{}`: {err}", path.display()))?;
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o777))
+                .map_err(|err| format!("failed to set mode of `{}`: {err}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// `name` as a path relative to the extraction root, or `None` when it is
+/// absolute or climbs out with `..`. The root itself (`./`) maps to an
+/// empty path, which callers skip.
+fn enclosed_path(name: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in name.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(relative)
+}
+
+fn unsafe_entry_error(name: &Path) -> String {
+    format!(
+        "archive entry `{}` escapes the extraction directory",
+        name.display()
+    )
+}
+
+fn link_entry_error(name: &Path) -> String {
+    format!(
+        "archive entry `{}` is a link; links are not supported in archive sources",
+        name.display()
+    )
+}
@@ -4,42 +4,107 @@
 //! [`validate_config`] checks structural invariants (mode compatibility,
 //! path resolution, duplicate detection). [`LoadedConfig`] pairs the
 //! parsed config with non-fatal warnings collected during load.
+//! [`load_layered_from_files`] merges a project config over the
+//! user-global one (see [`crate::config_layers`]).
 
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
+use crate::archive::ArchiveFormat;
+use crate::config_layers::{merge_config_layers, ConfigLayer};
 use crate::error::EdenError;
+use crate::git::{GitBackend, SignatureTrust};
+use crate::mirrors::MirrorRule;
 use crate::paths::resolve_path_string;
-use crate::reactor::{DEFAULT_CONCURRENCY_LIMIT, MAX_CONCURRENCY_LIMIT, MIN_CONCURRENCY_LIMIT};
-
-const DEFAULT_STORAGE_ROOT: &str = "~/.eden-skills/skills";
+use crate::reactor::{
+    DEFAULT_CONCURRENCY_LIMIT, DEFAULT_HOST_CONCURRENCY_LIMIT, MAX_CONCURRENCY_LIMIT,
+    MIN_CONCURRENCY_LIMIT,
+};
+use crate::retry::{
+    RetryPolicy, DEFAULT_BACKOFF, DEFAULT_RETRIES, DEFAULT_TIMEOUT_SECS, MAX_BACKOFF, MAX_RETRIES,
+};
+
+pub const DEFAULT_STORAGE_ROOT: &str = "~/.eden-skills/skills";
 const REGISTRY_MODE_REPO_PREFIX: &str = "registry://";
+const ARCHIVE_REF_PREFIX: &str = "sha256:";
+const VERSION_REF_PREFIX: &str = "semver:";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub version: u32,
     pub storage_root: String,
     pub reactor: ReactorConfig,
+    pub git: GitConfig,
+    /// URL rewrite rules, sorted by prefix (see [`crate::mirrors`]).
+    pub mirrors: Vec<MirrorRule>,
     pub skills: Vec<SkillConfig>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ReactorConfig {
     pub concurrency: usize,
+    /// Extra attempts for a network operation that fails transiently.
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each one after it.
+    pub backoff: Duration,
+    /// Limit for one network operation; `0` disables it.
+    pub timeout_secs: u64,
+    /// Network operations allowed in flight against one host.
+    pub host_concurrency: usize,
 }
 
 impl Default for ReactorConfig {
     fn default() -> Self {
         Self {
             concurrency: DEFAULT_CONCURRENCY_LIMIT,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            host_concurrency: DEFAULT_HOST_CONCURRENCY_LIMIT,
+        }
+    }
+}
+
+impl ReactorConfig {
+    /// The retry and timeout settings for each clone, fetch, and download.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retries,
+            backoff: self.backoff,
+            timeout: (self.timeout_secs > 0).then(|| Duration::from_secs(self.timeout_secs)),
         }
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitConfig {
+    pub backend: GitBackend,
+    /// SSH allowed-signers file trusted for `verify_signature` sources.
+    pub allowed_signers: Option<String>,
+    /// GnuPG home directory trusted for `verify_signature` sources.
+    pub gpg_home: Option<String>,
+}
+
+impl GitConfig {
+    /// The trusted keys with paths resolved against `config_dir`.
+    pub fn signature_trust(&self, config_dir: &Path) -> Result<SignatureTrust, EdenError> {
+        let resolve = |path: &Option<String>| {
+            path.as_deref()
+                .map(|path| resolve_path_string(path, config_dir))
+                .transpose()
+        };
+        Ok(SignatureTrust {
+            allowed_signers: resolve(&self.allowed_signers)?,
+            gpg_home: resolve(&self.gpg_home)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SkillConfig {
     pub id: String,
@@ -48,13 +113,46 @@ pub struct SkillConfig {
     pub targets: Vec<TargetConfig>,
     pub verify: VerifyConfig,
     pub safety: SafetyConfig,
+    /// Registry skills this one needs, by name, with their version
+    /// constraints.
+    pub requires: BTreeMap<String, String>,
+    /// Pulled in by another skill's `requires` rather than configured;
+    /// only set on configs resolved for execution.
+    pub implicit: bool,
 }
 
+/// Where a skill's files come from.
+///
+/// Archive sources keep the archive URL in `repo` and its checksum in
+/// `ref` as `sha256:<hex>` (see [`encode_archive_ref`]). Git sources with
+/// a `version` constraint keep it in `ref` as `semver:<constraint>` (see
+/// [`encode_version_ref`]); it is resolved against the repo's tags.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SourceConfig {
     pub repo: String,
     pub subpath: String,
     pub r#ref: String,
+    /// Refuse checkouts whose commit, or a tag at it, is not signed by a
+    /// key trusted in `[git]`.
+    pub verify_signature: bool,
+}
+
+impl SourceConfig {
+    /// The expected SHA-256 of an archive source, or `None` for git and
+    /// registry sources.
+    pub fn archive_sha256(&self) -> Option<&str> {
+        decode_archive_ref(&self.r#ref)
+    }
+
+    pub fn is_archive(&self) -> bool {
+        self.archive_sha256().is_some()
+    }
+
+    /// The semver constraint of a git source that follows tags, or
+    /// `None` when it tracks a plain ref.
+    pub fn version_constraint(&self) -> Option<&str> {
+        decode_version_ref(&self.r#ref)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -281,12 +379,45 @@ impl AgentKind {
     }
 }
 
+/// Where a target's default agent directory lives.
+///
+/// `Global` targets resolve through [`default_agent_path`] under the
+/// user's home directory. `Project` targets resolve through
+/// [`default_agent_project_path`] relative to the directory that holds
+/// `skills.toml`, so a repository can carry its own skill set.
+///
+/// [`default_agent_path`]: crate::paths::default_agent_path
+/// [`default_agent_project_path`]: crate::paths::default_agent_project_path
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetScope {
+    #[default]
+    Global,
+    Project,
+}
+
+impl TargetScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Project => "project",
+        }
+    }
+
+    pub fn is_global(&self) -> bool {
+        matches!(self, Self::Global)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TargetConfig {
     pub agent: AgentKind,
     pub expected_path: Option<String>,
     pub path: Option<String>,
     pub environment: String,
+    pub scope: TargetScope,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -310,32 +441,123 @@ pub struct LoadOptions {
 /// Warnings cover deprecated fields, unresolved paths, or mode-B skill
 /// hints that do not block normal operation but should be surfaced to
 /// the user.
+///
+/// `skill_layers` records which layer defined each skill when the config
+/// was loaded with [`load_layered_from_files`]; it is empty for a
+/// single-file load.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoadedConfig {
     pub config: Config,
     pub warnings: Vec<String>,
+    pub skill_layers: BTreeMap<String, ConfigLayer>,
+}
+
+impl LoadedConfig {
+    /// The layer a skill came from, if the config was layered.
+    pub fn skill_layer(&self, skill_id: &str) -> Option<ConfigLayer> {
+        self.skill_layers.get(skill_id).copied()
+    }
 }
 
 pub fn load_from_file(config_path: &Path, options: LoadOptions) -> Result<LoadedConfig, EdenError> {
-    let config_raw = fs::read_to_string(config_path)?;
     let config_dir = config_path.parent().unwrap_or(Path::new("."));
-
-    let value: toml::Value = toml::from_str(&config_raw)
-        .map_err(|err| EdenError::Validation(format!("root: invalid toml: {err}")))?;
+    let value = read_config_value(config_path)?;
     let warnings = collect_top_level_unknown_key_warnings(&value)?;
+    reject_unknown_keys_in_strict_mode(&warnings, options)?;
+
+    let config = config_from_value(value, config_dir)?;
+    Ok(LoadedConfig {
+        config,
+        warnings,
+        skill_layers: BTreeMap::new(),
+    })
+}
+
+/// Load a project config layered over the user-global config.
+///
+/// Relative paths in the merged result resolve against the project
+/// config directory. Shadowed user definitions are reported in
+/// [`LoadedConfig::warnings`]; they are expected in a layered setup and
+/// do not fail strict mode.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when either file cannot be read or parsed, or
+/// when the merged config fails validation.
+pub fn load_layered_from_files(
+    user_config_path: &Path,
+    project_config_path: &Path,
+    options: LoadOptions,
+) -> Result<LoadedConfig, EdenError> {
+    let user_dir = user_config_path.parent().unwrap_or(Path::new("."));
+    let project_dir = project_config_path.parent().unwrap_or(Path::new("."));
+    let user_value = read_config_value(user_config_path)?;
+    let project_value = read_config_value(project_config_path)?;
+
+    let mut warnings = collect_top_level_unknown_key_warnings(&user_value)?;
+    warnings.extend(collect_top_level_unknown_key_warnings(&project_value)?);
+    reject_unknown_keys_in_strict_mode(&warnings, options)?;
+
+    let merged = merge_config_layers(user_value, user_dir, project_value);
+    warnings.extend(merged.warnings);
+    let config = config_from_value(merged.value, project_dir)?;
+    Ok(LoadedConfig {
+        config,
+        warnings,
+        skill_layers: merged.skill_layers,
+    })
+}
+
+/// Load the user-global layer on its own, anchored exactly as
+/// [`load_layered_from_files`] anchors it for `project_config_path`.
+///
+/// Skills the project layer leaves untouched compare equal between this
+/// config and the merged one, which lets writers keep user definitions
+/// out of the project file.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the user config cannot be read or fails
+/// validation on its own.
+pub fn load_user_layer(
+    user_config_path: &Path,
+    project_config_path: &Path,
+) -> Result<Config, EdenError> {
+    let user_dir = user_config_path.parent().unwrap_or(Path::new("."));
+    let project_dir = project_config_path.parent().unwrap_or(Path::new("."));
+    let user_value = read_config_value(user_config_path)?;
+    let anchored = merge_config_layers(
+        user_value,
+        user_dir,
+        toml::Value::Table(toml::value::Table::new()),
+    );
+    config_from_value(anchored.value, project_dir)
+}
+
+fn read_config_value(config_path: &Path) -> Result<toml::Value, EdenError> {
+    let config_raw = fs::read_to_string(config_path)?;
+    toml::from_str(&config_raw)
+        .map_err(|err| EdenError::Validation(format!("root: invalid toml: {err}")))
+}
 
+fn reject_unknown_keys_in_strict_mode(
+    warnings: &[String],
+    options: LoadOptions,
+) -> Result<(), EdenError> {
     if options.strict && !warnings.is_empty() {
         return Err(EdenError::Validation(format!(
             "root: unknown top-level keys in strict mode: {}",
             warnings.join(", ")
         )));
     }
+    Ok(())
+}
 
-    let raw: RawConfig = toml::from_str(&config_raw)
+fn config_from_value(value: toml::Value, config_dir: &Path) -> Result<Config, EdenError> {
+    let raw: RawConfig = value
+        .try_into()
         .map_err(|err| EdenError::Validation(format!("root: invalid config shape: {err}")))?;
-
-    let config = raw.into_config(config_dir)?;
-    Ok(LoadedConfig { config, warnings })
+    raw.into_config(config_dir)
 }
 
 fn collect_top_level_unknown_key_warnings(value: &toml::Value) -> Result<Vec<String>, EdenError> {
@@ -346,7 +568,15 @@ fn collect_top_level_unknown_key_warnings(value: &toml::Value) -> Result<Vec<Str
     };
 
     let mut warnings = Vec::new();
-    let allowed_keys = ["version", "storage", "registries", "reactor", "skills"];
+    let allowed_keys = [
+        "version",
+        "storage",
+        "registries",
+        "reactor",
+        "git",
+        "mirrors",
+        "skills",
+    ];
     for key in map.keys() {
         if !allowed_keys.contains(&key.as_str()) {
             warnings.push(format!("unknown top-level key `{key}`"));
@@ -361,6 +591,8 @@ struct RawConfig {
     storage: Option<RawStorageConfig>,
     registries: Option<BTreeMap<String, RawRegistryConfig>>,
     reactor: Option<RawReactorConfig>,
+    git: Option<RawGitConfig>,
+    mirrors: Option<BTreeMap<String, String>>,
     skills: Option<Vec<RawSkillConfig>>,
 }
 
@@ -402,10 +634,38 @@ impl RawConfig {
             registry_names.insert(registry_name);
         }
         let has_registries = !registry_names.is_empty();
-        let reactor = self
-            .reactor
-            .unwrap_or_default()
-            .into_reactor_config("reactor.concurrency")?;
+        let reactor = self.reactor.unwrap_or_default().into_reactor_config()?;
+        let git = match self.git {
+            Some(raw_git) => {
+                for path in [&raw_git.allowed_signers, &raw_git.gpg_home]
+                    .into_iter()
+                    .flatten()
+                {
+                    resolve_path_string(path, config_dir)?;
+                }
+                GitConfig {
+                    backend: raw_git.backend.unwrap_or_default(),
+                    allowed_signers: raw_git.allowed_signers,
+                    gpg_home: raw_git.gpg_home,
+                }
+            }
+            None => GitConfig::default(),
+        };
+        let has_trusted_keys = git.allowed_signers.is_some() || git.gpg_home.is_some();
+        let mut mirrors = Vec::new();
+        for (prefix, replacement) in self.mirrors.unwrap_or_default() {
+            let field_path = format!("mirrors.\"{prefix}\"");
+            if prefix.trim().is_empty() {
+                return Err(EdenError::Validation(
+                    "mirrors: prefix must not be empty".to_string(),
+                ));
+            }
+            validate_repo_url(&replacement, &field_path)?;
+            mirrors.push(MirrorRule {
+                prefix,
+                replacement,
+            });
+        }
 
         let raw_skills = self.skills.unwrap_or_default();
 
@@ -415,6 +675,13 @@ impl RawConfig {
             let skill_path = format!("skills[{idx}]");
             let skill =
                 raw_skill.into_skill(config_dir, &skill_path, has_registries, &registry_names)?;
+            if skill.source.verify_signature && !has_trusted_keys {
+                return Err(phase2_validation_error(
+                    "MISSING_TRUSTED_KEYS",
+                    &format!("{skill_path}.source.verify_signature"),
+                    "requires `git.allowed_signers` or `git.gpg_home`",
+                ));
+            }
             if !ids.insert(skill.id.clone()) {
                 return Err(phase2_validation_error(
                     "DUPLICATE_SKILL_ID",
@@ -429,6 +696,8 @@ impl RawConfig {
             version,
             storage_root,
             reactor,
+            git,
+            mirrors,
             skills,
         })
     }
@@ -446,24 +715,77 @@ struct RawRegistryConfig {
     auto_update: Option<bool>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct RawGitConfig {
+    backend: Option<GitBackend>,
+    allowed_signers: Option<String>,
+    gpg_home: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 struct RawReactorConfig {
     concurrency: Option<usize>,
+    retries: Option<u32>,
+    backoff: Option<f64>,
+    timeout_secs: Option<u64>,
+    host_concurrency: Option<usize>,
 }
 
 impl RawReactorConfig {
-    fn into_reactor_config(self, field_path: &str) -> Result<ReactorConfig, EdenError> {
+    fn into_reactor_config(self) -> Result<ReactorConfig, EdenError> {
         let concurrency = self.concurrency.unwrap_or(DEFAULT_CONCURRENCY_LIMIT);
         if !(MIN_CONCURRENCY_LIMIT..=MAX_CONCURRENCY_LIMIT).contains(&concurrency) {
             return Err(phase2_validation_error(
                 "INVALID_CONCURRENCY",
-                field_path,
+                "reactor.concurrency",
                 &format!(
                     "expected value in [{MIN_CONCURRENCY_LIMIT}, {MAX_CONCURRENCY_LIMIT}], got {concurrency}"
                 ),
             ));
         }
-        Ok(ReactorConfig { concurrency })
+        let retries = self.retries.unwrap_or(DEFAULT_RETRIES);
+        if retries > MAX_RETRIES {
+            return Err(phase2_validation_error(
+                "INVALID_RETRIES",
+                "reactor.retries",
+                &format!("expected value in [0, {MAX_RETRIES}], got {retries}"),
+            ));
+        }
+        let backoff = match self.backoff {
+            None => DEFAULT_BACKOFF,
+            Some(seconds) if (0.0..=MAX_BACKOFF.as_secs_f64()).contains(&seconds) => {
+                Duration::from_secs_f64(seconds)
+            }
+            Some(seconds) => {
+                return Err(phase2_validation_error(
+                    "INVALID_BACKOFF",
+                    "reactor.backoff",
+                    &format!(
+                        "expected seconds in [0, {}], got {seconds}",
+                        MAX_BACKOFF.as_secs()
+                    ),
+                ));
+            }
+        };
+        let host_concurrency = self
+            .host_concurrency
+            .unwrap_or(DEFAULT_HOST_CONCURRENCY_LIMIT);
+        if !(MIN_CONCURRENCY_LIMIT..=MAX_CONCURRENCY_LIMIT).contains(&host_concurrency) {
+            return Err(phase2_validation_error(
+                "INVALID_CONCURRENCY",
+                "reactor.host_concurrency",
+                &format!(
+                    "expected value in [{MIN_CONCURRENCY_LIMIT}, {MAX_CONCURRENCY_LIMIT}], got {host_concurrency}"
+                ),
+            ));
+        }
+        Ok(ReactorConfig {
+            concurrency,
+            retries,
+            backoff,
+            timeout_secs: self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            host_concurrency,
+        })
     }
 }
 
@@ -478,6 +800,7 @@ struct RawSkillConfig {
     targets: Option<Vec<RawTargetConfig>>,
     verify: Option<RawVerifyConfig>,
     safety: Option<RawSafetyConfig>,
+    requires: Option<BTreeMap<String, String>>,
 }
 
 impl RawSkillConfig {
@@ -536,13 +859,14 @@ impl RawSkillConfig {
                     repo: encode_registry_mode_repo(registry_name.as_deref()),
                     subpath: ".".to_string(),
                     r#ref: version_constraint,
+                    verify_signature: false,
                 },
             )
         } else {
             let id = required(self.id, &format!("{field_path}.id"))?;
             let source = required(self.source, &format!("{field_path}.source"))?
                 .into_source_config(&format!("{field_path}.source"))?;
-            validate_repo_url(&source.repo, &format!("{field_path}.source.repo"))?;
+            validate_source(&source, &format!("{field_path}.source"))?;
             (id, source)
         };
 
@@ -580,6 +904,29 @@ impl RawSkillConfig {
 
         let safety = self.safety.unwrap_or_default().into_safety_config();
 
+        let requires = self.requires.unwrap_or_default();
+        if !requires.is_empty() && !has_registries {
+            return Err(phase2_validation_error(
+                "MISSING_REGISTRIES",
+                &format!("{field_path}.requires"),
+                "`requires` needs a [registries] section",
+            ));
+        }
+        for (name, constraint) in &requires {
+            let requires_path = format!("{field_path}.requires.{name}");
+            if name.trim().is_empty() {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}.requires: skill name must not be empty"
+                )));
+            }
+            if *name == id {
+                return Err(EdenError::Validation(format!(
+                    "{requires_path}: a skill cannot require itself"
+                )));
+            }
+            validate_semver_constraint(constraint, &requires_path)?;
+        }
+
         Ok(SkillConfig {
             id,
             source,
@@ -587,6 +934,8 @@ impl RawSkillConfig {
             targets,
             verify,
             safety,
+            requires,
+            implicit: false,
         })
     }
 }
@@ -594,20 +943,89 @@ impl RawSkillConfig {
 #[derive(Debug, Clone, Deserialize)]
 struct RawSourceConfig {
     repo: Option<String>,
+    archive: Option<String>,
+    sha256: Option<String>,
     subpath: Option<String>,
     r#ref: Option<String>,
+    version: Option<String>,
+    verify_signature: Option<bool>,
 }
 
 impl RawSourceConfig {
     fn into_source_config(self, field_path: &str) -> Result<SourceConfig, EdenError> {
-        let repo = required(self.repo, &format!("{field_path}.repo"))?;
         let subpath = self.subpath.unwrap_or_else(|| ".".to_string());
+        let verify_signature = self.verify_signature.unwrap_or(false);
+        if let Some(archive) = self.archive {
+            if self.repo.is_some() {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}: `repo` and `archive` are mutually exclusive"
+                )));
+            }
+            if self.r#ref.is_some() {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}.ref: not supported for archive sources"
+                )));
+            }
+            if self.version.is_some() {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}.version: not supported for archive sources"
+                )));
+            }
+            if verify_signature {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}.verify_signature: not supported for archive sources"
+                )));
+            }
+            let sha256 = required(self.sha256, &format!("{field_path}.sha256"))?;
+            let Some(sha256) = normalize_sha256(&sha256) else {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}.sha256: expected 64 hexadecimal characters"
+                )));
+            };
+            return Ok(SourceConfig {
+                repo: archive,
+                subpath,
+                r#ref: encode_archive_ref(&sha256),
+                verify_signature,
+            });
+        }
+        if self.sha256.is_some() {
+            return Err(EdenError::Validation(format!(
+                "{field_path}.sha256: only supported for archive sources"
+            )));
+        }
+
+        let repo = required(self.repo, &format!("{field_path}.repo"))?;
+        if let Some(version) = self.version {
+            if self.r#ref.is_some() {
+                return Err(EdenError::Validation(format!(
+                    "{field_path}: `ref` and `version` are mutually exclusive"
+                )));
+            }
+            return Ok(SourceConfig {
+                repo,
+                subpath,
+                r#ref: encode_version_ref(&version),
+                verify_signature,
+            });
+        }
         let r#ref = self.r#ref.unwrap_or_else(|| "main".to_string());
+        if decode_archive_ref(&r#ref).is_some() {
+            return Err(EdenError::Validation(format!(
+                "{field_path}.ref: `{ARCHIVE_REF_PREFIX}` refs are reserved for archive sources"
+            )));
+        }
+        if decode_version_ref(&r#ref).is_some() {
+            return Err(EdenError::Validation(format!(
+                "{field_path}.ref: `{VERSION_REF_PREFIX}` refs are reserved; use `version` instead"
+            )));
+        }
 
         Ok(SourceConfig {
             repo,
             subpath,
             r#ref,
+            verify_signature,
         })
     }
 }
@@ -623,6 +1041,7 @@ struct RawTargetConfig {
     expected_path: Option<String>,
     path: Option<String>,
     environment: Option<String>,
+    scope: Option<TargetScope>,
 }
 
 impl RawTargetConfig {
@@ -646,12 +1065,15 @@ impl RawTargetConfig {
         }
         let environment = self.environment.unwrap_or_else(|| "local".to_string());
         validate_environment(&environment, &format!("{field_path}.environment"))?;
+        let scope = self.scope.unwrap_or_default();
+        validate_target_scope(scope, &environment, &format!("{field_path}.scope"))?;
 
         Ok(TargetConfig {
             agent,
             expected_path: self.expected_path,
             path: self.path,
             environment,
+            scope,
         })
     }
 }
@@ -714,6 +1136,35 @@ pub fn is_registry_mode_repo(repo: &str) -> bool {
     repo.starts_with(REGISTRY_MODE_REPO_PREFIX)
 }
 
+/// Encode an archive checksum as a source ref. `:` is not valid in git
+/// ref names, so the result never collides with a branch or tag.
+pub fn encode_archive_ref(sha256: &str) -> String {
+    format!("{ARCHIVE_REF_PREFIX}{sha256}")
+}
+
+pub fn decode_archive_ref(reference: &str) -> Option<&str> {
+    reference.strip_prefix(ARCHIVE_REF_PREFIX)
+}
+
+/// Encode a semver constraint as a source ref. Like archive refs, `:`
+/// keeps it from colliding with a branch or tag name.
+pub fn encode_version_ref(constraint: &str) -> String {
+    format!("{VERSION_REF_PREFIX}{}", constraint.trim())
+}
+
+pub fn decode_version_ref(reference: &str) -> Option<&str> {
+    reference.strip_prefix(VERSION_REF_PREFIX)
+}
+
+/// Lowercase a SHA-256 checksum, accepting an optional `sha256:` prefix.
+/// Returns `None` unless it is exactly 64 hex digits.
+pub fn normalize_sha256(value: &str) -> Option<String> {
+    let value = value.trim();
+    let hex = value.strip_prefix(ARCHIVE_REF_PREFIX).unwrap_or(value);
+    (hex.len() == 64 && hex.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .then(|| hex.to_ascii_lowercase())
+}
+
 fn validate_semver_constraint(value: &str, field_path: &str) -> Result<(), EdenError> {
     let constraint = value.trim();
     if constraint.is_empty() {
@@ -757,6 +1208,21 @@ fn validate_environment(environment: &str, field_path: &str) -> Result<(), EdenE
     ))
 }
 
+fn validate_target_scope(
+    scope: TargetScope,
+    environment: &str,
+    field_path: &str,
+) -> Result<(), EdenError> {
+    if scope == TargetScope::Project && environment != "local" {
+        return Err(phase2_validation_error(
+            "INVALID_TARGET_SCOPE",
+            field_path,
+            "project scope is only supported for `local` targets",
+        ));
+    }
+    Ok(())
+}
+
 fn default_verify_checks(install_mode: InstallMode) -> Vec<String> {
     match install_mode {
         InstallMode::Symlink => vec![
@@ -818,7 +1284,7 @@ pub fn validate_config(config: &Config, config_dir: &Path) -> Result<(), EdenErr
         if is_registry_mode_repo(&skill.source.repo) {
             validate_semver_constraint(&skill.source.r#ref, &format!("{skill_path}.version"))?;
         } else {
-            validate_repo_url(&skill.source.repo, &format!("{skill_path}.source.repo"))?;
+            validate_source(&skill.source, &format!("{skill_path}.source"))?;
         }
 
         if skill.targets.is_empty() {
@@ -847,6 +1313,11 @@ pub fn validate_config(config: &Config, config_dir: &Path) -> Result<(), EdenErr
                 })?;
             }
             validate_environment(&target.environment, &format!("{target_path}.environment"))?;
+            validate_target_scope(
+                target.scope,
+                &target.environment,
+                &format!("{target_path}.scope"),
+            )?;
         }
 
         if skill.verify.enabled && skill.verify.checks.is_empty() {
@@ -859,6 +1330,48 @@ pub fn validate_config(config: &Config, config_dir: &Path) -> Result<(), EdenErr
     Ok(())
 }
 
+fn validate_source(source: &SourceConfig, field_path: &str) -> Result<(), EdenError> {
+    if source.verify_signature && Path::new(&source.repo).is_absolute() {
+        return Err(EdenError::Validation(format!(
+            "{field_path}.verify_signature: not supported for local sources"
+        )));
+    }
+    if let Some(constraint) = source.version_constraint() {
+        if Path::new(&source.repo).is_absolute() {
+            return Err(EdenError::Validation(format!(
+                "{field_path}.version: not supported for local sources"
+            )));
+        }
+        validate_semver_constraint(constraint, &format!("{field_path}.version"))?;
+    }
+    let Some(sha256) = source.archive_sha256() else {
+        return validate_repo_url(&source.repo, &format!("{field_path}.repo"));
+    };
+    if normalize_sha256(sha256).as_deref() != Some(sha256) {
+        return Err(EdenError::Validation(format!(
+            "{field_path}.sha256: expected 64 lowercase hexadecimal characters"
+        )));
+    }
+    validate_archive_url(&source.repo, &format!("{field_path}.archive"))
+}
+
+fn validate_archive_url(url: &str, field_path: &str) -> Result<(), EdenError> {
+    if !["https://", "http://", "file://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        return Err(EdenError::Validation(format!(
+            "{field_path}: must be an https, http, or file URL"
+        )));
+    }
+    if ArchiveFormat::from_url(url).is_none() {
+        return Err(EdenError::Validation(format!(
+            "{field_path}: unsupported archive format (expected .tar.gz, .tgz, or .zip)"
+        )));
+    }
+    Ok(())
+}
+
 fn validate_repo_url(url: &str, field_path: &str) -> Result<(), EdenError> {
     if Path::new(url).is_absolute() {
         return Ok(());
@@ -0,0 +1,296 @@
+//! Layered configuration: a project `skills.toml` merged over the
+//! user-global one.
+//!
+//! Layers are merged at the TOML level before validation, so a project
+//! layer may override a single field of a user-layer skill without
+//! restating the whole entry. Precedence is project over user:
+//!
+//! - `[storage]`, `[reactor]`, `[git]`, `[mirrors]`, and each
+//!   `[registries.<name>]` table merge key by key.
+//! - `[[skills]]` entries are matched by `id` (Mode A) or `name`
+//!   (Mode B). Matching entries merge field by field, recursing into
+//!   sub-tables; arrays such as `targets` are replaced wholesale. An
+//!   entry that switches between Mode A and Mode B replaces the user
+//!   definition entirely.
+//!
+//! Relative paths in the user layer are anchored to the user config
+//! directory before merging, because the merged config is resolved
+//! against the project directory.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use toml::value::Table;
+use toml::Value;
+
+/// Which configuration layer a skill definition came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigLayer {
+    /// The user-global config (`~/.eden-skills/skills.toml`).
+    User,
+    /// The project config (`skills.toml` or `.eden-skills.toml`).
+    Project,
+}
+
+impl ConfigLayer {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Project => "project",
+        }
+    }
+}
+
+/// The merged TOML document plus per-skill provenance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedLayers {
+    pub value: Value,
+    /// Layer that last defined each skill, keyed by skill id.
+    pub skill_layers: BTreeMap<String, ConfigLayer>,
+    /// Shadowed-definition warnings, in document order.
+    pub warnings: Vec<String>,
+}
+
+/// Merge a project config document over a user-global one.
+///
+/// Both values must be top-level TOML tables; a non-table document is
+/// passed through unchanged so validation reports the error later.
+pub fn merge_config_layers(user: Value, user_dir: &Path, project: Value) -> MergedLayers {
+    let (mut user, mut project) = match (user, project) {
+        (Value::Table(user), Value::Table(project)) => (user, project),
+        (Value::Table(_), other) | (other, _) => return passthrough(other),
+    };
+    anchor_user_layer_paths(&mut user, user_dir);
+
+    let mut warnings = Vec::new();
+    let user_skills = take_skills(&mut user);
+    let project_skills = take_skills(&mut project);
+
+    for key in ["storage", "reactor", "git", "mirrors"] {
+        let Some(overlay) = project.remove(key) else {
+            continue;
+        };
+        match (user.get_mut(key), overlay) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                let mut overridden = Vec::new();
+                merge_tables(base, overlay, &mut overridden, "");
+                if !overridden.is_empty() {
+                    warnings.push(format!(
+                        "project config overrides user `[{key}]` ({})",
+                        overridden.join(", ")
+                    ));
+                }
+            }
+            (Some(existing), overlay) => {
+                if *existing != overlay {
+                    warnings.push(format!("project config overrides user `{key}`"));
+                }
+                *existing = overlay;
+            }
+            (None, overlay) => {
+                user.insert(key.to_string(), overlay);
+            }
+        }
+    }
+
+    if let Some(overlay) = project.remove("registries") {
+        match (user.get_mut("registries"), overlay) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                for (name, entry) in overlay {
+                    match (base.get_mut(&name), entry) {
+                        (Some(Value::Table(base_entry)), Value::Table(entry)) => {
+                            let mut overridden = Vec::new();
+                            merge_tables(base_entry, entry, &mut overridden, "");
+                            if !overridden.is_empty() {
+                                warnings.push(format!(
+                                    "project config overrides user registry `{name}` ({})",
+                                    overridden.join(", ")
+                                ));
+                            }
+                        }
+                        (_, entry) => {
+                            base.insert(name, entry);
+                        }
+                    }
+                }
+            }
+            (_, overlay) => {
+                user.insert("registries".to_string(), overlay);
+            }
+        }
+    }
+
+    // Remaining project keys (`version`, unknown keys) win outright.
+    for (key, value) in project {
+        user.insert(key, value);
+    }
+
+    let mut skill_layers = BTreeMap::new();
+    let mut merged_skills: Vec<Value> = Vec::with_capacity(user_skills.len());
+    for skill in user_skills {
+        if let Some(key) = skill_key(&skill) {
+            skill_layers.insert(key.to_string(), ConfigLayer::User);
+        }
+        merged_skills.push(skill);
+    }
+    for skill in project_skills {
+        let Some(key) = skill_key(&skill).map(str::to_string) else {
+            merged_skills.push(skill);
+            continue;
+        };
+        skill_layers.insert(key.clone(), ConfigLayer::Project);
+        let existing = merged_skills
+            .iter_mut()
+            .find(|candidate| skill_key(candidate) == Some(key.as_str()));
+        let Some(existing) = existing else {
+            merged_skills.push(skill);
+            continue;
+        };
+        if skill_mode_key(existing) != skill_mode_key(&skill) {
+            warnings.push(format!(
+                "project config replaces user skill `{key}` (Mode A/Mode B definitions differ)"
+            ));
+            *existing = skill;
+            continue;
+        }
+        let (Value::Table(base), Value::Table(overlay)) = (existing, skill) else {
+            continue;
+        };
+        let mut overridden = Vec::new();
+        // A git source and an archive source do not mix key by key.
+        let overlay_kind = source_kind(&overlay);
+        if overlay_kind.is_some() && overlay_kind != source_kind(base) {
+            base.remove("source");
+            overridden.push("source".to_string());
+        }
+        merge_tables(base, overlay, &mut overridden, "");
+        overridden.retain(|field| field != "id" && field != "name");
+        if !overridden.is_empty() {
+            warnings.push(format!(
+                "project config overrides user skill `{key}` ({})",
+                overridden.join(", ")
+            ));
+        }
+    }
+    if !merged_skills.is_empty() {
+        user.insert("skills".to_string(), Value::Array(merged_skills));
+    }
+
+    MergedLayers {
+        value: Value::Table(user),
+        skill_layers,
+        warnings,
+    }
+}
+
+fn passthrough(value: Value) -> MergedLayers {
+    MergedLayers {
+        value,
+        skill_layers: BTreeMap::new(),
+        warnings: Vec::new(),
+    }
+}
+
+fn take_skills(table: &mut Table) -> Vec<Value> {
+    match table.remove("skills") {
+        Some(Value::Array(skills)) => skills,
+        Some(other) => {
+            // Leave malformed values in place for validation to report.
+            table.insert("skills".to_string(), other);
+            Vec::new()
+        }
+        None => Vec::new(),
+    }
+}
+
+fn skill_key(skill: &Value) -> Option<&str> {
+    let table = skill.as_table()?;
+    table
+        .get("id")
+        .or_else(|| table.get("name"))
+        .and_then(Value::as_str)
+}
+
+fn skill_mode_key(skill: &Value) -> Option<&'static str> {
+    let table = skill.as_table()?;
+    if table.contains_key("name") {
+        Some("name")
+    } else if table.contains_key("id") {
+        Some("id")
+    } else {
+        None
+    }
+}
+
+/// `"archive"` or `"repo"`, whichever key the skill's source table sets.
+fn source_kind(skill: &Table) -> Option<&'static str> {
+    let source = skill.get("source")?.as_table()?;
+    ["archive", "repo"]
+        .into_iter()
+        .find(|key| source.contains_key(*key))
+}
+
+/// Recursively merge `overlay` into `base`. Scalars and arrays replace;
+/// tables merge. Every replaced key is recorded as a dotted path.
+fn merge_tables(base: &mut Table, overlay: Table, overridden: &mut Vec<String>, prefix: &str) {
+    for (key, value) in overlay {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_child)), Value::Table(overlay_child)) => {
+                merge_tables(base_child, overlay_child, overridden, &path);
+            }
+            (Some(existing), value) => {
+                if *existing != value {
+                    overridden.push(path);
+                }
+                *existing = value;
+            }
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn anchor_user_layer_paths(user: &mut Table, user_dir: &Path) {
+    if let Some(Value::Table(storage)) = user.get_mut("storage") {
+        anchor_path_field(storage, "root", user_dir);
+    }
+    if let Some(Value::Table(git)) = user.get_mut("git") {
+        anchor_path_field(git, "allowed_signers", user_dir);
+        anchor_path_field(git, "gpg_home", user_dir);
+    }
+    let Some(Value::Array(skills)) = user.get_mut("skills") else {
+        return;
+    };
+    for skill in skills {
+        let Some(Value::Array(targets)) = skill
+            .as_table_mut()
+            .and_then(|table| table.get_mut("targets"))
+        else {
+            continue;
+        };
+        for target in targets {
+            if let Some(target) = target.as_table_mut() {
+                anchor_path_field(target, "path", user_dir);
+                anchor_path_field(target, "expected_path", user_dir);
+            }
+        }
+    }
+}
+
+fn anchor_path_field(table: &mut Table, key: &str, base_dir: &Path) {
+    let Some(Value::String(raw)) = table.get_mut(key) else {
+        return;
+    };
+    if raw.starts_with('~') || Path::new(raw.as_str()).is_absolute() {
+        return;
+    }
+    *raw = base_dir.join(raw.as_str()).display().to_string();
+}
@@ -0,0 +1,119 @@
+//! Deterministic content digests for installed skill trees.
+//!
+//! A tree digest is SHA-256 over every entry below a root, visited in
+//! sorted relative-path order. Each regular file contributes its
+//! `/`-separated relative path, its exec bit, its length, and its bytes;
+//! each symlink contributes its path and link text. `.git` directories
+//! and eden-skills' own cache metadata files are skipped so that fetches
+//! and safety scans of cache checkouts do not change the digest. The
+//! result is rendered as `sha256:<hex>`.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+pub const DIGEST_PREFIX: &str = "sha256:";
+
+/// Entry names that never contribute to a tree digest.
+pub(crate) const IGNORED_ENTRIES: &[&str] = &[".git", ".eden-fetched-at", ".eden-safety.toml"];
+
+/// Compute the tree digest of `root`, following `root` itself if it is a
+/// symlink (so a symlink-mode target digests to its source content).
+///
+/// # Errors
+///
+/// Returns the underlying I/O error when `root` or any entry below it
+/// cannot be read.
+pub fn tree_digest(root: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let metadata = fs::metadata(root)?;
+    if metadata.is_file() {
+        hash_file(&mut hasher, "", root, &metadata)?;
+    } else {
+        hash_dir(&mut hasher, root, "")?;
+    }
+    Ok(format!("{DIGEST_PREFIX}{}", to_hex(&hasher.finalize())))
+}
+
+fn hash_dir(hasher: &mut Sha256, dir: &Path, prefix: &str) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| (entry.file_name().to_string_lossy().into_owned(), entry)))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (name, entry) in entries {
+        if IGNORED_ENTRIES.contains(&name.as_str()) {
+            continue;
+        }
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            let link = fs::read_link(&path)?;
+            hasher.update(b"L");
+            hash_field(hasher, relative.as_bytes());
+            hash_field(hasher, link.to_string_lossy().replace('\\', "/").as_bytes());
+        } else if metadata.is_dir() {
+            hasher.update(b"D");
+            hash_field(hasher, relative.as_bytes());
+            hash_dir(hasher, &path, &relative)?;
+        } else {
+            hash_file(hasher, &relative, &path, &metadata)?;
+        }
+    }
+    Ok(())
+}
+
+fn hash_file(
+    hasher: &mut Sha256,
+    relative: &str,
+    path: &Path,
+    metadata: &fs::Metadata,
+) -> io::Result<()> {
+    hasher.update(if is_executable(metadata) { b"X" } else { b"F" });
+    hash_field(hasher, relative.as_bytes());
+    hasher.update(metadata.len().to_be_bytes());
+
+    let mut file = fs::File::open(path)?;
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(())
+}
+
+/// Length-prefix variable fields so that adjacent fields cannot collide.
+fn hash_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hex-encoded SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
@@ -144,14 +144,42 @@ fn parse_skill_markdown(
     })
 }
 
+/// Read the frontmatter of the `SKILL.md` in `skill_dir` as ordered
+/// `key: value` pairs, with surrounding quotes stripped. Returns `None`
+/// when there is no `SKILL.md`; a file without frontmatter yields no
+/// pairs.
+pub fn read_skill_frontmatter(
+    skill_dir: &Path,
+) -> Result<Option<Vec<(String, String)>>, EdenError> {
+    let Some(skill_file) = resolve_skill_markdown_path(skill_dir) else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(skill_file)?;
+    Ok(Some(parse_skill_frontmatter(&content)))
+}
+
 fn parse_frontmatter_name_description(content: &str) -> (Option<String>, Option<String>) {
+    let mut name = None;
+    let mut description = None;
+    for (key, value) in parse_skill_frontmatter(content) {
+        match key.as_str() {
+            "name" if !value.is_empty() => name = Some(value),
+            "description" if !value.is_empty() => description = Some(value),
+            _ => {}
+        }
+    }
+    (name, description)
+}
+
+/// Parse the frontmatter of `SKILL.md` content as ordered `key: value`
+/// pairs; content without a leading `---` block yields no pairs.
+pub fn parse_skill_frontmatter(content: &str) -> Vec<(String, String)> {
     let mut lines = content.lines();
     if lines.next().map(str::trim) != Some("---") {
-        return (None, None);
+        return Vec::new();
     }
 
-    let mut name = None;
-    let mut description = None;
+    let mut fields = Vec::new();
     for line in lines {
         let trimmed = line.trim();
         if trimmed == "---" {
@@ -165,21 +193,9 @@ fn parse_frontmatter_name_description(content: &str) -> (Option<String>, Option<
             .trim_matches('"')
             .trim_matches('\'')
             .to_string();
-        match key.trim() {
-            "name" => {
-                if !normalized.is_empty() {
-                    name = Some(normalized);
-                }
-            }
-            "description" => {
-                if !normalized.is_empty() {
-                    description = Some(normalized);
-                }
-            }
-            _ => {}
-        }
+        fields.push((key.trim().to_string(), normalized));
     }
-    (name, description)
+    fields
 }
 
 fn dedupe_by_subpath(discovered: &mut Vec<DiscoveredSkill>) {
@@ -0,0 +1,355 @@
+//! Apply journal: numbered generations of installed state.
+//!
+//! Every successful `apply`, `install`, and `update --apply` records a
+//! generation under `<storage_root>/.generations/<N>/`: a copy of the
+//! config file, a copy of `skills.lock`, and a `generation.toml` with the
+//! timestamp and command. Generation numbers are shared by every config
+//! that uses the same storage root; each config has its own current
+//! generation, which `rollback` moves backwards.
+//!
+//! The commit of every remote skill in a generation is pinned with a ref
+//! under `refs/eden-skills/generations/` in its repo cache, so `git gc`
+//! cannot drop it while the generation exists. Only the newest
+//! [`GENERATION_RETENTION`] generations of each config are kept; pruning
+//! an older one deletes its directory and its refs.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::EdenError;
+use crate::git::{self, GitBackend};
+use crate::lock::{utc_now_iso8601, LockFile};
+use crate::source::{is_local_source_repo, repo_cache_key, resolve_repo_cache_root};
+
+/// Directory below the storage root that holds all generations.
+pub const GENERATIONS_DIR: &str = ".generations";
+
+/// Generations kept per config; older ones are pruned on record.
+pub const GENERATION_RETENTION: usize = 20;
+
+const META_FILE: &str = "generation.toml";
+const CONFIG_FILE: &str = "skills.toml";
+const LOCK_FILE: &str = "skills.lock";
+const CURRENT_FILE: &str = "current.toml";
+const PIN_REF_PREFIX: &str = "refs/eden-skills/generations";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationMeta {
+    pub number: u64,
+    pub created_at: String,
+    /// Command that produced the generation, e.g. `apply`.
+    pub command: String,
+    /// Absolute path of the config file the generation belongs to.
+    pub config_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generation {
+    pub meta: GenerationMeta,
+    /// Config file text at the time the generation was recorded.
+    pub config_text: String,
+    pub lock: LockFile,
+}
+
+/// Per-config current generation, keyed by config path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CurrentGenerations {
+    #[serde(default)]
+    current: BTreeMap<String, u64>,
+}
+
+pub fn generations_root(storage_root: &Path) -> PathBuf {
+    storage_root.join(GENERATIONS_DIR)
+}
+
+/// List the generations recorded for `config_path`, oldest first.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the generations directory or a generation's
+/// metadata cannot be read or parsed.
+pub fn list_generations(
+    storage_root: &Path,
+    config_path: &Path,
+) -> Result<Vec<GenerationMeta>, EdenError> {
+    let config_key = config_path.display().to_string();
+    Ok(list_all_generations(storage_root)?
+        .into_iter()
+        .filter(|meta| meta.config_path == config_key)
+        .collect())
+}
+
+/// List every generation below `storage_root`, across all configs.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when a generation's metadata cannot be read or
+/// parsed.
+pub fn list_all_generations(storage_root: &Path) -> Result<Vec<GenerationMeta>, EdenError> {
+    let root = generations_root(storage_root);
+    let entries = match fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(EdenError::Io(err)),
+    };
+    let mut generations = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let meta_path = entry.path().join(META_FILE);
+        if !meta_path.is_file() {
+            continue;
+        }
+        generations.push(read_toml::<GenerationMeta>(&meta_path)?);
+    }
+    generations.sort_by_key(|meta| meta.number);
+    Ok(generations)
+}
+
+/// Read generation `number` in full.
+///
+/// # Errors
+///
+/// Returns [`EdenError::InvalidArguments`] when the generation does not
+/// exist, or [`EdenError`] when its files cannot be read or parsed.
+pub fn read_generation(storage_root: &Path, number: u64) -> Result<Generation, EdenError> {
+    let dir = generations_root(storage_root).join(number.to_string());
+    if !dir.join(META_FILE).is_file() {
+        return Err(EdenError::InvalidArguments(format!(
+            "generation {number} does not exist"
+        )));
+    }
+    Ok(Generation {
+        meta: read_toml(&dir.join(META_FILE))?,
+        config_text: fs::read_to_string(dir.join(CONFIG_FILE))?,
+        lock: read_toml(&dir.join(LOCK_FILE))?,
+    })
+}
+
+/// The current generation of `config_path`: the one last recorded or
+/// rolled back to.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the current-generation index cannot be read.
+pub fn current_generation(
+    storage_root: &Path,
+    config_path: &Path,
+) -> Result<Option<u64>, EdenError> {
+    Ok(read_current(storage_root)?
+        .current
+        .get(&config_path.display().to_string())
+        .copied())
+}
+
+/// Mark `number` as the current generation of `config_path`.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the current-generation index cannot be
+/// written.
+pub fn set_current_generation(
+    storage_root: &Path,
+    config_path: &Path,
+    number: u64,
+) -> Result<(), EdenError> {
+    let mut current = read_current(storage_root)?;
+    current
+        .current
+        .insert(config_path.display().to_string(), number);
+    let root = generations_root(storage_root);
+    fs::create_dir_all(&root)?;
+    write_toml(&root.join(CURRENT_FILE), &current)
+}
+
+/// Forget the current generation of `config_path`.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the current-generation index cannot be
+/// read or written.
+pub fn clear_current_generation(storage_root: &Path, config_path: &Path) -> Result<(), EdenError> {
+    let mut current = read_current(storage_root)?;
+    if current
+        .current
+        .remove(&config_path.display().to_string())
+        .is_none()
+    {
+        return Ok(());
+    }
+    write_toml(&generations_root(storage_root).join(CURRENT_FILE), &current)
+}
+
+/// Record a new generation for `config_path` and make it current.
+///
+/// Nothing is recorded, and `None` is returned, when the config text and
+/// lock (ignoring install timestamps) equal those of the current
+/// generation. Pinning commits in the repo cache is best effort: a
+/// missing cache or failed ref update only means that generation
+/// may not be restorable offline.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when generation files cannot be written or
+/// existing generations cannot be read.
+pub fn record_generation(
+    storage_root: &Path,
+    config_path: &Path,
+    command: &str,
+    config_text: &str,
+    lock: &LockFile,
+    backend: GitBackend,
+) -> Result<Option<u64>, EdenError> {
+    if let Some(current) = current_generation(storage_root, config_path)? {
+        if let Ok(previous) = read_generation(storage_root, current) {
+            if previous.config_text == config_text && same_lock_state(&previous.lock, lock) {
+                return Ok(None);
+            }
+        }
+    }
+
+    let number = list_all_generations(storage_root)?
+        .last()
+        .map_or(1, |meta| meta.number + 1);
+    let dir = generations_root(storage_root).join(number.to_string());
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(CONFIG_FILE), config_text)?;
+    write_toml(&dir.join(LOCK_FILE), lock)?;
+    let meta = GenerationMeta {
+        number,
+        created_at: utc_now_iso8601(),
+        command: command.to_string(),
+        config_path: config_path.display().to_string(),
+    };
+    // Metadata last: a directory without it is ignored by listings.
+    write_toml(&dir.join(META_FILE), &meta)?;
+
+    for (index, entry) in lock.skills.iter().enumerate() {
+        if let Some(repo_dir) =
+            pinnable_repo_dir(storage_root, &entry.source_repo, &entry.source_ref)
+        {
+            if !entry.resolved_commit.is_empty() {
+                let _ = git::update_ref(
+                    backend,
+                    &repo_dir,
+                    &pin_ref(number, index),
+                    &entry.resolved_commit,
+                );
+            }
+        }
+    }
+
+    set_current_generation(storage_root, config_path, number)?;
+    prune_generations(storage_root, config_path, number, backend)?;
+    Ok(Some(number))
+}
+
+/// Repo cache keys of every remote source pinned by any generation, so
+/// cache cleanup keeps the commits those generations need.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when a generation cannot be read or parsed.
+pub fn generation_repo_cache_keys(storage_root: &Path) -> Result<HashSet<String>, EdenError> {
+    let mut keys = HashSet::new();
+    for meta in list_all_generations(storage_root)? {
+        let generation = read_generation(storage_root, meta.number)?;
+        keys.extend(
+            generation
+                .lock
+                .skills
+                .iter()
+                .filter(|entry| !is_local_source_repo(&entry.source_repo))
+                .map(|entry| repo_cache_key(&entry.source_repo, &entry.source_ref)),
+        );
+    }
+    Ok(keys)
+}
+
+/// Drop all but the newest [`GENERATION_RETENTION`] generations of
+/// `config_path`, never pruning `keep`.
+fn prune_generations(
+    storage_root: &Path,
+    config_path: &Path,
+    keep: u64,
+    backend: GitBackend,
+) -> Result<(), EdenError> {
+    let generations = list_generations(storage_root, config_path)?;
+    let excess = generations.len().saturating_sub(GENERATION_RETENTION);
+    for meta in generations.into_iter().take(excess) {
+        if meta.number == keep {
+            continue;
+        }
+        if let Ok(generation) = read_generation(storage_root, meta.number) {
+            for (index, entry) in generation.lock.skills.iter().enumerate() {
+                if let Some(repo_dir) =
+                    pinnable_repo_dir(storage_root, &entry.source_repo, &entry.source_ref)
+                {
+                    let _ = git::delete_ref(backend, &repo_dir, &pin_ref(meta.number, index));
+                }
+            }
+        }
+        fs::remove_dir_all(generations_root(storage_root).join(meta.number.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Whether two locks describe the same installed state, ignoring the
+/// `installed_at` timestamps that every lock write refreshes.
+fn same_lock_state(a: &LockFile, b: &LockFile) -> bool {
+    let strip = |lock: &LockFile| {
+        let mut lock = lock.clone();
+        for entry in &mut lock.skills {
+            entry.installed_at.clear();
+        }
+        lock.skills.sort_by(|x, y| x.id.cmp(&y.id));
+        lock
+    };
+    strip(a) == strip(b)
+}
+
+fn pinnable_repo_dir(storage_root: &Path, repo_url: &str, reference: &str) -> Option<PathBuf> {
+    if is_local_source_repo(repo_url) {
+        return None;
+    }
+    let repo_dir = resolve_repo_cache_root(storage_root, repo_url, reference);
+    repo_dir.join(".git").exists().then_some(repo_dir)
+}
+
+/// Ref pinning the commit of the `index`-th lock entry of a generation.
+/// Indexes rather than skill ids keep the name a valid ref.
+fn pin_ref(number: u64, index: usize) -> String {
+    format!("{PIN_REF_PREFIX}/{number}/{index}")
+}
+
+fn read_current(storage_root: &Path) -> Result<CurrentGenerations, EdenError> {
+    let path = generations_root(storage_root).join(CURRENT_FILE);
+    if !path.is_file() {
+        return Ok(CurrentGenerations::default());
+    }
+    read_toml(&path)
+}
+
+fn read_toml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, EdenError> {
+    let raw = fs::read_to_string(path)?;
+    toml::from_str(&raw).map_err(|err| {
+        EdenError::Runtime(format!(
+            "failed to parse generation file `{}`: {err}",
+            path.display()
+        ))
+    })
+}
+
+fn write_toml<T: Serialize>(path: &Path, value: &T) -> Result<(), EdenError> {
+    let content = toml::to_string_pretty(value).map_err(|err| {
+        EdenError::Runtime(format!(
+            "failed to serialize generation file `{}`: {err}",
+            path.display()
+        ))
+    })?;
+    fs::write(path, content)?;
+    Ok(())
+}
@@ -0,0 +1,1559 @@
+//! Git operations behind a selectable backend.
+//!
+//! [`GitBackend::Cli`], the default, shells out to the `git` binary
+//! (`EDEN_SKILLS_GIT_BIN` overrides the program). [`GitBackend::Embedded`]
+//! runs clone, fetch, checkout, and rev-parse in-process through
+//! gitoxide, so no `git` binary has to be installed.
+//!
+//! Both backends keep cone-mode sparse checkouts in the worktree's
+//! `info/sparse-checkout` file; the embedded backend applies it by marking
+//! the index entries outside the cone skip-worktree. Blobless clones are
+//! CLI-only, so embedded sparse clones still fetch every blob. Both
+//! backends report failures as a single message naming what they were
+//! trying to do.
+//!
+//! With [`CloneOptions::store`], each clone is checked out as a worktree
+//! of one bare object store, so several refs of a repository share their
+//! objects. The embedded backend lays the worktree out the way
+//! `git worktree add` does, so stores work with either backend.
+//!
+//! Network operations ([`clone`] and the fetches) accept a timeout: the
+//! CLI backend kills `git` when it expires, the embedded backend
+//! interrupts the transfer. Either way the failure reads "timed out".
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use gix::bstr::ByteSlice;
+use gix::index::entry::Flags as IndexFlags;
+use gix::protocol::fetch::refmap::SpecIndex;
+use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix::refs::Target;
+use gix::remote::fetch::Shallow;
+use gix::remote::Direction;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use gix::ObjectId;
+use serde::{Deserialize, Serialize};
+
+const FETCH_HEAD_FILE: &str = "FETCH_HEAD";
+const SPARSE_CHECKOUT_FILE: &str = "info/sparse-checkout";
+const REFLOG_MESSAGE: &str = "eden-skills";
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Implementation used for git operations, selected by `[git] backend`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitBackend {
+    /// The external `git` binary.
+    #[default]
+    Cli,
+    /// In-process gitoxide; no `git` binary required.
+    Embedded,
+}
+
+impl GitBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::Embedded => "embedded",
+        }
+    }
+}
+
+/// How [`clone`] should shape the new checkout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CloneOptions<'a> {
+    /// Branch or tag to check out instead of the remote `HEAD`.
+    pub reference: Option<&'a str>,
+    /// Fetch only the tip commit.
+    pub shallow: bool,
+    /// Clone with an empty sparse checkout, blobless with the CLI.
+    pub sparse: bool,
+    /// Abort the clone when it takes longer than this.
+    pub timeout: Option<Duration>,
+    /// Check out a worktree of this bare repository, created on first
+    /// use, instead of making a standalone clone.
+    pub store: Option<&'a Path>,
+}
+
+/// The program run by the CLI backend: `EDEN_SKILLS_GIT_BIN` or `git`.
+pub fn git_bin() -> String {
+    std::env::var("EDEN_SKILLS_GIT_BIN")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "git".to_string())
+}
+
+/// Clone `repo_url` into `repo_dir`. A failed clone leaves `repo_dir` as
+/// it found it (missing or empty), so the clone can be retried.
+pub fn clone(
+    backend: GitBackend,
+    repo_url: &str,
+    repo_dir: &Path,
+    options: CloneOptions<'_>,
+) -> Result<(), String> {
+    let context = match options.reference {
+        Some(reference) => format!(
+            "clone `{repo_url}` into `{}` with ref `{reference}`",
+            repo_dir.display()
+        ),
+        None => format!(
+            "clone `{repo_url}` into `{}` without branch hint",
+            repo_dir.display()
+        ),
+    };
+    let existed = repo_dir.exists();
+    let was_empty =
+        std::fs::read_dir(repo_dir).map_or(true, |mut entries| entries.next().is_none());
+    let result = match backend {
+        GitBackend::Cli => match options.store {
+            Some(store) => cli_clone_worktree(repo_url, repo_dir, store, options, &context),
+            None => {
+                let mut command = Command::new(git_bin());
+                command.arg("clone");
+                if options.sparse {
+                    command.arg("--filter=blob:none").arg("--sparse");
+                }
+                if options.shallow {
+                    command.arg("--depth").arg("1");
+                }
+                if let Some(reference) = options.reference {
+                    command.arg("--branch").arg(reference);
+                }
+                command.arg(repo_url).arg(repo_dir);
+                run_cli_with_timeout(&mut command, &context, options.timeout).map(|_| ())
+            }
+        },
+        GitBackend::Embedded => with_deadline(options.timeout, |interrupt| {
+            embedded_clone(repo_url, repo_dir, options, interrupt)
+        })
+        .map_err(|err| embedded_failure(&context, err)),
+    };
+    if result.is_err() && was_empty {
+        let _ = std::fs::remove_dir_all(repo_dir);
+        if existed {
+            let _ = std::fs::create_dir(repo_dir);
+        }
+    }
+    result
+}
+
+/// Fetch `options.reference` (or the remote `HEAD` and every branch)
+/// into the object store, then add `repo_dir` as a worktree of it. A
+/// branch is checked out as a local branch tracking `origin`, anything
+/// else as a detached `HEAD`.
+fn cli_clone_worktree(
+    repo_url: &str,
+    repo_dir: &Path,
+    store: &Path,
+    options: CloneOptions<'_>,
+    context: &str,
+) -> Result<(), String> {
+    if store.join("HEAD").exists() {
+        set_origin_url(GitBackend::Cli, store, repo_url)?;
+    } else {
+        run_cli(
+            Command::new(git_bin())
+                .arg("init")
+                .arg("--quiet")
+                .arg("--bare")
+                .arg(store),
+            context,
+        )?;
+        run_cli(
+            cli_in(store)
+                .arg("remote")
+                .arg("add")
+                .arg("origin")
+                .arg(repo_url),
+            context,
+        )?;
+    }
+    // Forget worktrees whose directories are gone, so one can be added
+    // again at the same path.
+    run_cli(cli_in(store).arg("worktree").arg("prune"), context)?;
+
+    let mut fetch = cli_in(store);
+    fetch.arg("fetch").arg("--quiet");
+    if options.sparse {
+        fetch.arg("--filter=blob:none");
+    }
+    if options.shallow {
+        fetch.arg("--depth").arg("1");
+    }
+    fetch.arg("origin");
+    match options.reference {
+        Some(reference) => fetch.arg(reference),
+        // `HEAD` first, so it is the commit FETCH_HEAD names.
+        None => fetch.arg("HEAD").arg("+refs/heads/*:refs/remotes/origin/*"),
+    };
+    run_cli_with_timeout(&mut fetch, context, options.timeout)?;
+
+    let mut add = cli_in(store);
+    add.arg("worktree")
+        .arg("add")
+        .arg("--quiet")
+        .arg("--no-checkout");
+    let tracking = options.reference.and_then(|reference| {
+        let tracking = format!("refs/remotes/origin/{reference}");
+        rev_parse(GitBackend::Cli, store, &tracking).map(|_| (reference, tracking))
+    });
+    match tracking {
+        Some((branch, tracking)) => add
+            .arg("--track")
+            .arg("-B")
+            .arg(branch)
+            .arg(repo_dir)
+            .arg(tracking),
+        None => add.arg("--detach").arg(repo_dir).arg(FETCH_HEAD_FILE),
+    };
+    run_cli(&mut add, context)?;
+    if options.sparse {
+        run_cli(
+            cli_in(repo_dir)
+                .arg("sparse-checkout")
+                .arg("set")
+                .arg("--cone"),
+            context,
+        )?;
+    }
+    run_cli(
+        cli_in(repo_dir).arg("reset").arg("--quiet").arg("--hard"),
+        context,
+    )
+    .map(|_| ())
+}
+
+/// The worktrees of the object store `store`, including ones whose
+/// directories no longer exist.
+pub fn store_worktrees(store: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(store.join("worktrees")) else {
+        return Vec::new();
+    };
+    let mut worktrees = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("gitdir")).ok())
+        .filter_map(|gitdir| Path::new(gitdir.trim()).parent().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+    worktrees.sort();
+    worktrees
+}
+
+/// Garbage-collect the object store `store`: forget removed worktrees,
+/// delete the branches no worktree has checked out, and drop every
+/// object nothing references any more. Refs pinned by generations keep
+/// their commits. Always uses the CLI, which is what creates stores.
+pub fn gc_object_store(store: &Path) -> Result<(), String> {
+    let context = format!("garbage-collect `{}`", store.display());
+    run_cli(cli_in(store).arg("worktree").arg("prune"), &context)?;
+    let branches = run_cli(
+        cli_in(store)
+            .arg("for-each-ref")
+            .arg("--format=%(refname) %(worktreepath)")
+            .arg("refs/heads"),
+        &context,
+    )?;
+    for line in branches.lines() {
+        let (name, worktree) = line.split_once(' ').unwrap_or((line, ""));
+        if !name.is_empty() && worktree.trim().is_empty() {
+            run_cli(
+                cli_in(store).arg("update-ref").arg("-d").arg(name),
+                &context,
+            )?;
+        }
+    }
+    run_cli(
+        cli_in(store).arg("gc").arg("--quiet").arg("--prune=now"),
+        &context,
+    )
+    .map(|_| ())
+}
+
+/// The git directory of `repo_dir`: `.git` itself, or the directory the
+/// `.git` file of a worktree points at.
+pub fn git_dir(repo_dir: &Path) -> PathBuf {
+    let dot_git = repo_dir.join(".git");
+    let Some(gitdir) = std::fs::read_to_string(&dot_git)
+        .ok()
+        .and_then(|content| Some(content.strip_prefix("gitdir:")?.trim().to_string()))
+    else {
+        return dot_git;
+    };
+    repo_dir.join(gitdir)
+}
+
+/// Where the objects, refs, and `shallow` file of `repo_dir` live: the
+/// object store for a worktree, [`git_dir`] otherwise.
+pub fn common_dir(repo_dir: &Path) -> PathBuf {
+    let git_dir = git_dir(repo_dir);
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()),
+        Err(_) => git_dir,
+    }
+}
+
+/// Point `origin` at `url` unless it already is. Used when mirror rules
+/// change where an existing checkout fetches from.
+pub fn set_origin_url(backend: GitBackend, repo_dir: &Path, url: &str) -> Result<(), String> {
+    let context = format!("set the origin of `{}` to `{url}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            let current = run_cli(
+                cli_in(repo_dir)
+                    .arg("config")
+                    .arg("--get")
+                    .arg("remote.origin.url"),
+                &context,
+            )
+            .ok();
+            if current.as_deref() == Some(url) {
+                return Ok(());
+            }
+            run_cli(
+                cli_in(repo_dir)
+                    .arg("remote")
+                    .arg("set-url")
+                    .arg("origin")
+                    .arg(url),
+                &context,
+            )
+            .map(|_| ())
+        }
+        GitBackend::Embedded => {
+            embedded_set_origin_url(repo_dir, url).map_err(|err| embedded_failure(&context, err))
+        }
+    }
+}
+
+/// Fetch every branch and tag of `origin`, pruning deleted branches
+/// (CLI only).
+pub fn fetch(
+    backend: GitBackend,
+    repo_dir: &Path,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    let context = format!("fetch updates for `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => run_cli_with_timeout(
+            cli_in(repo_dir).arg("fetch").arg("--all").arg("--prune"),
+            &context,
+            timeout,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => with_deadline(timeout, |interrupt| {
+            embedded_fetch(repo_dir, Shallow::NoChange, interrupt)
+        })
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Fetch the full history of `origin`, deepening a shallow clone.
+pub fn fetch_history(
+    backend: GitBackend,
+    repo_dir: &Path,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    let context = format!("fetch history for `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            let mut command = cli_in(repo_dir);
+            command.arg("fetch").arg("--all");
+            if common_dir(repo_dir).join("shallow").exists() {
+                command.arg("--unshallow");
+            }
+            run_cli_with_timeout(&mut command, &context, timeout).map(|_| ())
+        }
+        GitBackend::Embedded => with_deadline(timeout, |interrupt| {
+            embedded_fetch(repo_dir, Shallow::undo(), interrupt)
+        })
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Fetch a single commit by its id. Servers may refuse this; callers
+/// fall back to [`fetch_history`].
+pub fn fetch_commit(
+    backend: GitBackend,
+    repo_dir: &Path,
+    commit: &str,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    match backend {
+        GitBackend::Cli => run_cli_with_timeout(
+            cli_in(repo_dir).arg("fetch").arg("origin").arg(commit),
+            &format!(
+                "fetch locked commit `{commit}` for `{}`",
+                repo_dir.display()
+            ),
+            timeout,
+        )
+        .map(|_| ()),
+        // Fetching by id is not negotiated in-process; full history is.
+        GitBackend::Embedded => fetch_history(backend, repo_dir, timeout),
+    }
+}
+
+/// Fetch `reference` (or the remote `HEAD`) from `origin` without
+/// touching any branch, recording it as `FETCH_HEAD`. Returns the
+/// fetched commit. With `shallow`, only the tip commit is fetched;
+/// otherwise the history down to what the clone already has comes
+/// along, so [`log`] can walk it.
+pub fn fetch_head(
+    backend: GitBackend,
+    repo_dir: &Path,
+    reference: Option<&str>,
+    shallow: bool,
+    timeout: Option<Duration>,
+) -> Result<String, String> {
+    let context = format!(
+        "fetch `{}` for `{}`",
+        reference.unwrap_or("HEAD"),
+        repo_dir.display()
+    );
+    match backend {
+        GitBackend::Cli => {
+            let mut command = cli_in(repo_dir);
+            command.arg("fetch");
+            if shallow {
+                command.arg("--depth").arg("1");
+            }
+            command.arg("origin");
+            if let Some(reference) = reference {
+                command.arg(reference);
+            }
+            run_cli_with_timeout(&mut command, &context, timeout)?;
+        }
+        GitBackend::Embedded => {
+            with_deadline(timeout, |interrupt| {
+                embedded_fetch_head(repo_dir, reference.unwrap_or("HEAD"), shallow, interrupt)
+            })
+            .map_err(|err| embedded_failure(&context, err))?;
+        }
+    }
+    read_fetch_head(repo_dir).ok_or_else(|| "failed to read FETCH_HEAD after fetch".to_string())
+}
+
+/// A tag advertised by `origin`, as listed by [`ls_remote_tags`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTag {
+    /// Tag name without the `refs/tags/` prefix.
+    pub name: String,
+    /// The commit the tag points to, peeled through annotated tags.
+    pub commit: String,
+}
+
+/// List the tags of `origin` without fetching them.
+pub fn ls_remote_tags(
+    backend: GitBackend,
+    repo_dir: &Path,
+    timeout: Option<Duration>,
+) -> Result<Vec<RemoteTag>, String> {
+    let context = format!("list remote tags for `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            let stdout = run_cli_with_timeout(
+                cli_in(repo_dir)
+                    .arg("ls-remote")
+                    .arg("--tags")
+                    .arg("origin"),
+                &context,
+                timeout,
+            )?;
+            let mut tags = BTreeMap::new();
+            for line in stdout.lines() {
+                let Some((commit, name)) = line.split_once('\t') else {
+                    continue;
+                };
+                let Some(name) = name.strip_prefix("refs/tags/") else {
+                    continue;
+                };
+                // `<tag>^{}` carries the commit behind an annotated tag.
+                match name.strip_suffix("^{}") {
+                    Some(name) => {
+                        tags.insert(name.to_string(), commit.to_string());
+                    }
+                    None => {
+                        tags.entry(name.to_string())
+                            .or_insert_with(|| commit.to_string());
+                    }
+                }
+            }
+            Ok(tags
+                .into_iter()
+                .map(|(name, commit)| RemoteTag { name, commit })
+                .collect())
+        }
+        GitBackend::Embedded => with_deadline(timeout, |_| embedded_ls_remote_tags(repo_dir))
+            .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Keys trusted to sign source commits and tags, from `[git]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureTrust {
+    /// SSH allowed-signers file, as for `gpg.ssh.allowedSignersFile`.
+    pub allowed_signers: Option<PathBuf>,
+    /// GnuPG home directory whose keyring holds the trusted keys.
+    pub gpg_home: Option<PathBuf>,
+}
+
+/// Check that `HEAD` of `repo_dir`, or a local tag pointing at it, has a
+/// good signature by a key in `trust`. Only `trust` is consulted: the
+/// user's own keyring and allowed-signers file are never used.
+///
+/// Verification always runs the `git` CLI (with `gpg` or `ssh-keygen`),
+/// whichever backend performed the checkout.
+pub fn verify_head_signature(repo_dir: &Path, trust: &SignatureTrust) -> Result<(), String> {
+    let head = run_cli(
+        cli_in(repo_dir).arg("rev-parse").arg("HEAD"),
+        &format!("resolve `HEAD` in `{}`", repo_dir.display()),
+    )?;
+    let head = head.trim();
+    let verify = |args: &[&str]| {
+        let mut command = cli_in(repo_dir);
+        match &trust.allowed_signers {
+            Some(path) => command
+                .arg("-c")
+                .arg(format!("gpg.ssh.allowedSignersFile={}", path.display())),
+            None => command.args(["-c", "gpg.ssh.program=false"]),
+        };
+        match &trust.gpg_home {
+            Some(path) => command.env("GNUPGHOME", path),
+            None => command.args(["-c", "gpg.openpgp.program=false"]),
+        };
+        command.args(["-c", "gpg.x509.program=false"]).args(args);
+        command
+            .stdin(Stdio::null())
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+    if verify(&["verify-commit", head]) {
+        return Ok(());
+    }
+    let tags = run_cli(
+        cli_in(repo_dir).args(["tag", "--points-at", "HEAD"]),
+        &format!("list tags at `HEAD` in `{}`", repo_dir.display()),
+    )?;
+    if tags.lines().any(|tag| verify(&["verify-tag", tag.trim()])) {
+        return Ok(());
+    }
+    Err(format!(
+        "neither commit `{head}` nor a tag pointing at it is signed by a trusted key"
+    ))
+}
+
+/// The commit recorded by the last fetch, from `FETCH_HEAD`.
+pub fn read_fetch_head(repo_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(git_dir(repo_dir).join(FETCH_HEAD_FILE)).ok()?;
+    let sha = content.lines().next()?.split_whitespace().next()?;
+    (!sha.is_empty()).then(|| sha.to_string())
+}
+
+/// Switch to `reference`: a local branch, a branch of `origin` (creating
+/// the local branch), or any other revision as a detached `HEAD`.
+pub fn checkout(backend: GitBackend, repo_dir: &Path, reference: &str) -> Result<(), String> {
+    let context = format!("checkout ref `{reference}` in `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            run_cli(cli_in(repo_dir).arg("checkout").arg(reference), &context).map(|_| ())
+        }
+        GitBackend::Embedded => {
+            embedded_checkout(repo_dir, reference).map_err(|err| embedded_failure(&context, err))
+        }
+    }
+}
+
+/// Check out `commit` as a detached `HEAD`.
+pub fn checkout_detached(backend: GitBackend, repo_dir: &Path, commit: &str) -> Result<(), String> {
+    let context = format!(
+        "checkout locked commit `{commit}` in `{}`",
+        repo_dir.display()
+    );
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir).arg("checkout").arg("--detach").arg(commit),
+            &context,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => (|| {
+            let repo = embedded_open(repo_dir)?;
+            let id = resolve_commit(&repo, commit)?;
+            checkout_tree(&repo, id)?;
+            set_head(&repo, Target::Object(id), false)
+        })()
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Fast-forward the checked-out branch `reference` to `origin`.
+/// Fails when `HEAD` is detached or the branch has diverged.
+pub fn fast_forward(backend: GitBackend, repo_dir: &Path, reference: &str) -> Result<(), String> {
+    let context = format!("fast-forward pull for `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir)
+                .arg("pull")
+                .arg("--ff-only")
+                .arg("origin")
+                .arg(reference),
+            &context,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => embedded_fast_forward(repo_dir, reference)
+            .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Move `HEAD` (and the branch it points to) to `commit`, discarding
+/// worktree changes.
+pub fn reset_hard(backend: GitBackend, repo_dir: &Path, commit: &str) -> Result<(), String> {
+    let context = format!("reset `{}` to `{commit}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir).arg("reset").arg("--hard").arg(commit),
+            &context,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => (|| {
+            let repo = embedded_open(repo_dir)?;
+            let id = resolve_commit(&repo, commit)?;
+            checkout_tree(&repo, id)?;
+            set_head(&repo, Target::Object(id), true)
+        })()
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Resolve `rev` to a full commit id, or `None` when it does not exist.
+pub fn rev_parse(backend: GitBackend, repo_dir: &Path, rev: &str) -> Option<String> {
+    match backend {
+        GitBackend::Cli => {
+            let stdout = run_cli(
+                cli_in(repo_dir).arg("rev-parse").arg(rev),
+                &format!("resolve `{rev}` in `{}`", repo_dir.display()),
+            )
+            .ok()?;
+            let sha = stdout.lines().next()?.trim();
+            (!sha.is_empty()).then(|| sha.to_string())
+        }
+        GitBackend::Embedded => {
+            let repo = embedded_open(repo_dir).ok()?;
+            resolve_commit(&repo, rev).ok().map(|id| id.to_string())
+        }
+    }
+}
+
+/// Whether `commit` is present in the object database of `repo_dir`.
+pub fn commit_exists(backend: GitBackend, repo_dir: &Path, commit: &str) -> bool {
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir)
+                .arg("cat-file")
+                .arg("-e")
+                .arg(format!("{commit}^{{commit}}")),
+            &format!("look up commit `{commit}` in `{}`", repo_dir.display()),
+        )
+        .is_ok(),
+        GitBackend::Embedded => embedded_open(repo_dir)
+            .ok()
+            .and_then(|repo| {
+                let id = ObjectId::from_hex(commit.as_bytes()).ok()?;
+                let object = repo.try_find_object(id).ok()??;
+                Some(object.kind == gix::object::Kind::Commit)
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// A commit listed by [`log`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitSummary {
+    pub id: String,
+    /// First line of the commit message.
+    pub subject: String,
+}
+
+/// How many files [`diff_stat`] found added, removed, and changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DiffStat {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// Commits reachable from `to` but not from `from`, newest first. With
+/// `path`, only commits that touched it are listed.
+pub fn log(
+    backend: GitBackend,
+    repo_dir: &Path,
+    from: &str,
+    to: &str,
+    path: Option<&str>,
+) -> Result<Vec<CommitSummary>, String> {
+    let context = format!("list commits `{from}..{to}` in `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            let mut command = cli_in(repo_dir);
+            command
+                .arg("log")
+                .arg("--format=%H%x09%s")
+                .arg(format!("{from}..{to}"))
+                .arg("--")
+                .args(path);
+            let stdout = run_cli(&mut command, &context)?;
+            Ok(stdout
+                .lines()
+                .filter_map(|line| {
+                    let (id, subject) = line.split_once('\t').unwrap_or((line, ""));
+                    (!id.is_empty()).then(|| CommitSummary {
+                        id: id.to_string(),
+                        subject: subject.to_string(),
+                    })
+                })
+                .collect())
+        }
+        GitBackend::Embedded => {
+            embedded_log(repo_dir, from, to, path).map_err(|err| embedded_failure(&context, err))
+        }
+    }
+}
+
+/// Count the files that differ between the trees of `from` and `to`,
+/// limited to `path` when given. Renames count as one removal and one
+/// addition.
+pub fn diff_stat(
+    backend: GitBackend,
+    repo_dir: &Path,
+    from: &str,
+    to: &str,
+    path: Option<&str>,
+) -> Result<DiffStat, String> {
+    let context = format!("diff `{from}..{to}` in `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => {
+            let mut command = cli_in(repo_dir);
+            command
+                .arg("diff")
+                .arg("--name-status")
+                .arg("--no-renames")
+                .arg(from)
+                .arg(to)
+                .arg("--")
+                .args(path);
+            let stdout = run_cli(&mut command, &context)?;
+            let mut stat = DiffStat::default();
+            for status in stdout.lines().filter_map(|line| line.chars().next()) {
+                match status {
+                    'A' => stat.added += 1,
+                    'D' => stat.removed += 1,
+                    _ => stat.changed += 1,
+                }
+            }
+            Ok(stat)
+        }
+        GitBackend::Embedded => embedded_diff_stat(repo_dir, from, to, path)
+            .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Point the ref `name` at `commit`, creating it if needed.
+pub fn update_ref(
+    backend: GitBackend,
+    repo_dir: &Path,
+    name: &str,
+    commit: &str,
+) -> Result<(), String> {
+    let context = format!("update ref `{name}` in `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir).arg("update-ref").arg(name).arg(commit),
+            &context,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => (|| {
+            let repo = embedded_open(repo_dir)?;
+            let id = ObjectId::from_hex(commit.as_bytes())?;
+            repo.reference(name, id, PreviousValue::Any, REFLOG_MESSAGE)?;
+            Ok(())
+        })()
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// Delete the ref `name`.
+pub fn delete_ref(backend: GitBackend, repo_dir: &Path, name: &str) -> Result<(), String> {
+    let context = format!("delete ref `{name}` in `{}`", repo_dir.display());
+    match backend {
+        GitBackend::Cli => run_cli(
+            cli_in(repo_dir).arg("update-ref").arg("-d").arg(name),
+            &context,
+        )
+        .map(|_| ()),
+        GitBackend::Embedded => (|| {
+            let repo = embedded_open(repo_dir)?;
+            repo.edit_reference(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::Any,
+                    log: RefLog::AndReference,
+                },
+                name: name.try_into()?,
+                deref: false,
+            })?;
+            Ok(())
+        })()
+        .map_err(|err| embedded_failure(&context, err)),
+    }
+}
+
+/// The cone-mode sparse paths of `repo_dir`, or `None` when it checks
+/// out the full tree.
+pub fn sparse_checkout_list(backend: GitBackend, repo_dir: &Path) -> Option<BTreeSet<String>> {
+    match backend {
+        GitBackend::Cli => {
+            let stdout = run_cli(
+                cli_in(repo_dir).arg("sparse-checkout").arg("list"),
+                &format!("list sparse checkout of `{}`", repo_dir.display()),
+            )
+            .ok()?;
+            Some(
+                stdout
+                    .lines()
+                    .map(|line| line.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect(),
+            )
+        }
+        GitBackend::Embedded => read_sparse_patterns(&git_dir(repo_dir)),
+    }
+}
+
+/// Set the cone-mode sparse paths of `repo_dir`, or disable sparse
+/// checkout when `None`.
+pub fn sparse_checkout_set(
+    backend: GitBackend,
+    repo_dir: &Path,
+    sparse_paths: Option<&BTreeSet<String>>,
+) -> Result<(), String> {
+    let context = format!("update sparse checkout of `{}`", repo_dir.display());
+    if backend == GitBackend::Embedded {
+        return (|| {
+            let repo = embedded_open(repo_dir)?;
+            match sparse_paths {
+                Some(paths) => write_sparse_patterns(repo.git_dir(), paths)?,
+                None => match std::fs::remove_file(repo.git_dir().join(SPARSE_CHECKOUT_FILE)) {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(err.into())
+                    }
+                    _ => {}
+                },
+            }
+            let head = repo.head_id()?.detach();
+            checkout_tree(&repo, head)
+        })()
+        .map_err(|err| embedded_failure(&context, err));
+    }
+    let mut command = cli_in(repo_dir);
+    command.arg("sparse-checkout");
+    match sparse_paths {
+        Some(paths) => {
+            command.arg("set").arg("--cone").arg("--").args(paths);
+        }
+        None => {
+            command.arg("disable");
+        }
+    }
+    run_cli(&mut command, &context).map(|_| ())
+}
+
+fn cli_in(repo_dir: &Path) -> Command {
+    let mut command = Command::new(git_bin());
+    command.arg("-C").arg(repo_dir);
+    command
+}
+
+fn run_cli(command: &mut Command, context: &str) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|err| format!("git invocation failed while trying to {context}: {err}"))?;
+    cli_result(output.status, &output.stdout, &output.stderr, context)
+}
+
+/// Like [`run_cli`], but kills `git` once `timeout` has passed.
+fn run_cli_with_timeout(
+    command: &mut Command,
+    context: &str,
+    timeout: Option<Duration>,
+) -> Result<String, String> {
+    let Some(timeout) = timeout else {
+        return run_cli(command, context);
+    };
+    let invocation_error =
+        |err: std::io::Error| format!("git invocation failed while trying to {context}: {err}");
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(invocation_error)?;
+    let stdout = spawn_pipe_reader(child.stdout.take());
+    let stderr = spawn_pipe_reader(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(invocation_error)? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Helpers spawned by git may still hold the pipes open, so
+                // the readers are left to finish on their own.
+                return Err(format!(
+                    "git command timed out after {}s while trying to {context}",
+                    timeout.as_secs()
+                ));
+            }
+            None => std::thread::sleep(TIMEOUT_POLL_INTERVAL),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    cli_result(status, &stdout, &stderr, context)
+}
+
+fn spawn_pipe_reader(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+fn cli_result(
+    status: ExitStatus,
+    stdout: &[u8],
+    stderr: &[u8],
+    context: &str,
+) -> Result<String, String> {
+    let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+    let stdout = String::from_utf8_lossy(stdout).trim().to_string();
+
+    if status.success() {
+        return Ok(stdout);
+    }
+
+    Err(format!(
+        "git command failed while trying to {context}: status={status} stderr=`{stderr}` stdout=`{stdout}`"
+    ))
+}
+
+type EmbeddedResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Run an embedded transfer with an interrupt flag that is raised once
+/// `timeout` has passed.
+fn with_deadline<T>(
+    timeout: Option<Duration>,
+    operation: impl FnOnce(&AtomicBool) -> EmbeddedResult<T>,
+) -> EmbeddedResult<T> {
+    let interrupt = AtomicBool::new(false);
+    let Some(timeout) = timeout else {
+        return operation(&interrupt);
+    };
+    let (finished, watchdog) = mpsc::channel::<()>();
+    std::thread::scope(|scope| {
+        let interrupt = &interrupt;
+        scope.spawn(move || {
+            if watchdog.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                interrupt.store(true, Ordering::Relaxed);
+            }
+        });
+        let result = operation(interrupt);
+        drop(finished);
+        if result.is_err() && interrupt.load(Ordering::Relaxed) {
+            return Err(format!("timed out after {}s", timeout.as_secs()).into());
+        }
+        result
+    })
+}
+
+fn embedded_failure(context: &str, err: Box<dyn std::error::Error + Send + Sync>) -> String {
+    let mut detail = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        detail.push_str(": ");
+        detail.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    format!("git operation failed while trying to {context}: {detail}")
+}
+
+fn embedded_open(repo_dir: &Path) -> EmbeddedResult<gix::Repository> {
+    // Reflog entries need a committer; minimal images often have none.
+    let options = gix::open::Options::default().config_overrides([
+        "gitoxide.committer.nameFallback=eden-skills",
+        "gitoxide.committer.emailFallback=eden-skills@localhost",
+    ]);
+    Ok(gix::open_opts(repo_dir, options)?)
+}
+
+fn embedded_clone(
+    repo_url: &str,
+    repo_dir: &Path,
+    options: CloneOptions<'_>,
+    interrupt: &AtomicBool,
+) -> EmbeddedResult<()> {
+    if let Some(store) = options.store {
+        return embedded_clone_worktree(repo_url, repo_dir, store, options, interrupt);
+    }
+    let mut prepare = gix::prepare_clone(repo_url, repo_dir)?;
+    if options.shallow {
+        prepare = prepare.with_shallow(Shallow::DepthAtRemote(NonZeroU32::MIN));
+    }
+    if let Some(reference) = options.reference {
+        prepare = prepare.with_ref_name(Some(reference))?;
+    }
+    let (mut checkout, _) = prepare.fetch_then_checkout(gix::progress::Discard, interrupt)?;
+    if !options.sparse {
+        checkout.main_worktree(gix::progress::Discard, interrupt)?;
+        return Ok(());
+    }
+    let repo = checkout.persist();
+    write_sparse_patterns(repo.git_dir(), &BTreeSet::new())?;
+    let head = repo.head_id()?.detach();
+    checkout_tree(&repo, head)
+}
+
+/// Embedded counterpart of [`cli_clone_worktree`]. The worktree's admin
+/// directory under `worktrees/` and its `.git` file are written the way
+/// `git worktree add` writes them, so the CLI (and [`gc_object_store`])
+/// treat it like any other worktree of the store.
+fn embedded_clone_worktree(
+    repo_url: &str,
+    repo_dir: &Path,
+    store: &Path,
+    options: CloneOptions<'_>,
+    interrupt: &AtomicBool,
+) -> EmbeddedResult<()> {
+    if store.join("HEAD").exists() {
+        embedded_set_origin_url(store, repo_url)?;
+    } else {
+        std::fs::create_dir_all(store)?;
+        let repo = gix::init_bare(store)?;
+        let mut remote = repo.remote_at(repo_url)?.with_refspecs(
+            Some("+refs/heads/*:refs/remotes/origin/*"),
+            Direction::Fetch,
+        )?;
+        save_remote(&repo, &mut remote)?;
+    }
+    // Forget worktrees whose directories are gone, like `git worktree
+    // prune`, so one can be added again at the same path.
+    let store = std::path::absolute(store)?;
+    let worktrees = store.join("worktrees");
+    if let Ok(entries) = std::fs::read_dir(&worktrees) {
+        for entry in entries.filter_map(Result::ok) {
+            let gitdir = std::fs::read_to_string(entry.path().join("gitdir")).unwrap_or_default();
+            if gitdir.trim().is_empty() || !Path::new(gitdir.trim()).exists() {
+                std::fs::remove_dir_all(entry.path())?;
+            }
+        }
+    }
+
+    let repo = embedded_open(&store)?;
+    let shallow = if options.shallow {
+        Shallow::DepthAtRemote(NonZeroU32::MIN)
+    } else {
+        Shallow::NoChange
+    };
+    let url = repo
+        .find_remote("origin")?
+        .url(Direction::Fetch)
+        .ok_or("remote `origin` has no fetch url")?
+        .clone();
+    let (refspecs, wanted) = match options.reference {
+        Some(reference) => (vec![reference], reference),
+        // `HEAD` first, so it is the commit the worktree starts at.
+        None => (vec!["HEAD", "+refs/heads/*:refs/remotes/origin/*"], "HEAD"),
+    };
+    let outcome = repo
+        .remote_at(url)?
+        .with_refspecs(refspecs, Direction::Fetch)?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(gix::progress::Discard, Default::default())?
+        .with_shallow(shallow)
+        .receive(gix::progress::Discard, interrupt)?;
+    let mapping = outcome
+        .ref_map
+        .mappings
+        .iter()
+        .find(|mapping| {
+            mapping.spec_index == SpecIndex::ExplicitInRemote(0) && mapping.remote.as_id().is_some()
+        })
+        .ok_or_else(|| format!("couldn't find remote ref `{wanted}`"))?;
+    let id = mapping
+        .remote
+        .as_id()
+        .ok_or("remote ref has no id")?
+        .to_owned();
+    let commit = repo.find_object(id)?.peel_to_commit()?.id;
+    let branch = options.reference.filter(|reference| {
+        mapping
+            .remote
+            .as_name()
+            .is_some_and(|name| *name == format!("refs/heads/{reference}"))
+    });
+    let head = match branch {
+        Some(branch) => {
+            for name in [
+                format!("refs/remotes/origin/{branch}"),
+                format!("refs/heads/{branch}"),
+            ] {
+                repo.reference(name, commit, PreviousValue::Any, REFLOG_MESSAGE)?;
+            }
+            format!("ref: refs/heads/{branch}\n")
+        }
+        None => format!("{commit}\n"),
+    };
+
+    std::fs::create_dir_all(repo_dir)?;
+    let repo_dir = std::path::absolute(repo_dir)?;
+    let base = repo_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("worktree");
+    let mut name = base.to_string();
+    let mut suffix = 1;
+    while worktrees.join(&name).exists() {
+        name = format!("{base}{suffix}");
+        suffix += 1;
+    }
+    let admin_dir = worktrees.join(name);
+    std::fs::create_dir_all(&admin_dir)?;
+    std::fs::write(
+        admin_dir.join("gitdir"),
+        format!("{}\n", repo_dir.join(".git").display()),
+    )?;
+    std::fs::write(admin_dir.join("commondir"), "../..\n")?;
+    std::fs::write(admin_dir.join("HEAD"), head)?;
+    std::fs::write(
+        repo_dir.join(".git"),
+        format!("gitdir: {}\n", admin_dir.display()),
+    )?;
+
+    let worktree = embedded_open(&repo_dir)?;
+    if options.sparse {
+        write_sparse_patterns(worktree.git_dir(), &BTreeSet::new())?;
+    }
+    checkout_tree(&worktree, commit)
+}
+
+fn embedded_set_origin_url(repo_dir: &Path, url: &str) -> EmbeddedResult<()> {
+    let repo = embedded_open(repo_dir)?;
+    let remote = repo.find_remote("origin")?;
+    if remote
+        .url(Direction::Fetch)
+        .is_some_and(|current| current.to_bstring() == url)
+    {
+        return Ok(());
+    }
+    let mut remote = remote.with_url_without_url_rewrite(url)?;
+    save_remote(&repo, &mut remote)
+}
+
+/// Write `remote` to the config shared by every worktree of `repo` as
+/// `origin`.
+fn save_remote(repo: &gix::Repository, remote: &mut gix::Remote<'_>) -> EmbeddedResult<()> {
+    let config_path = repo.common_dir().join("config");
+    let mut config =
+        gix::config::File::from_path_no_includes(config_path.clone(), gix::config::Source::Local)?;
+    remote.save_as_to("origin", &mut config)?;
+    let mut out = std::fs::File::create(&config_path)?;
+    config.write_to(&mut out)?;
+    Ok(())
+}
+
+fn embedded_fetch(repo_dir: &Path, shallow: Shallow, interrupt: &AtomicBool) -> EmbeddedResult<()> {
+    let repo = embedded_open(repo_dir)?;
+    let shallow = if repo.is_shallow() {
+        shallow
+    } else {
+        Shallow::NoChange
+    };
+    repo.find_remote("origin")?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(gix::progress::Discard, Default::default())?
+        .with_shallow(shallow)
+        .receive(gix::progress::Discard, interrupt)?;
+    Ok(())
+}
+
+fn embedded_fetch_head(
+    repo_dir: &Path,
+    reference: &str,
+    shallow: bool,
+    interrupt: &AtomicBool,
+) -> EmbeddedResult<()> {
+    let repo = embedded_open(repo_dir)?;
+    let shallow = if shallow {
+        Shallow::DepthAtRemote(NonZeroU32::MIN)
+    } else {
+        Shallow::NoChange
+    };
+    let url = repo
+        .find_remote("origin")?
+        .url(Direction::Fetch)
+        .ok_or("remote `origin` has no fetch url")?
+        .clone();
+    // A refspec without destination fetches objects but updates no ref.
+    let outcome = repo
+        .remote_at(url.clone())?
+        .with_refspecs(Some(reference), Direction::Fetch)?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(gix::progress::Discard, Default::default())?
+        .with_shallow(shallow)
+        .receive(gix::progress::Discard, interrupt)?;
+    let id = outcome
+        .ref_map
+        .mappings
+        .iter()
+        .find_map(|mapping| mapping.remote.as_id())
+        .ok_or_else(|| format!("couldn't find remote ref `{reference}`"))?
+        .to_owned();
+    let commit = repo.find_object(id)?.peel_to_commit()?.id;
+    std::fs::write(
+        repo.git_dir().join(FETCH_HEAD_FILE),
+        format!("{commit}\t\t'{reference}' of {}\n", url.to_bstring()),
+    )?;
+    Ok(())
+}
+
+fn embedded_ls_remote_tags(repo_dir: &Path) -> EmbeddedResult<Vec<RemoteTag>> {
+    let repo = embedded_open(repo_dir)?;
+    let url = repo
+        .find_remote("origin")?
+        .url(Direction::Fetch)
+        .ok_or("remote `origin` has no fetch url")?
+        .clone();
+    let (ref_map, _) = repo
+        .remote_at(url)?
+        .with_refspecs(Some("refs/tags/*:refs/tags/*"), Direction::Fetch)?
+        .connect(Direction::Fetch)?
+        .ref_map(gix::progress::Discard, Default::default())?;
+    let mut tags = ref_map
+        .remote_refs
+        .iter()
+        .filter_map(|remote_ref| {
+            let (name, target, peeled) = remote_ref.unpack();
+            let name = name.to_str().ok()?.strip_prefix("refs/tags/")?;
+            Some(RemoteTag {
+                name: name.to_string(),
+                commit: peeled.or(target)?.to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    tags.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(tags)
+}
+
+fn embedded_checkout(repo_dir: &Path, reference: &str) -> EmbeddedResult<()> {
+    let repo = embedded_open(repo_dir)?;
+    let branch = format!("refs/heads/{reference}");
+    if let Some(id) = find_ref_id(&repo, &branch) {
+        checkout_tree(&repo, id)?;
+        return set_head(&repo, Target::Symbolic(branch.as_str().try_into()?), false);
+    }
+    if let Some(id) = find_ref_id(&repo, &format!("refs/remotes/origin/{reference}")) {
+        checkout_tree(&repo, id)?;
+        repo.reference(branch.as_str(), id, PreviousValue::Any, REFLOG_MESSAGE)?;
+        return set_head(&repo, Target::Symbolic(branch.as_str().try_into()?), false);
+    }
+    let id = resolve_commit(&repo, reference)?;
+    checkout_tree(&repo, id)?;
+    set_head(&repo, Target::Object(id), false)
+}
+
+fn embedded_fast_forward(repo_dir: &Path, reference: &str) -> EmbeddedResult<()> {
+    let repo = embedded_open(repo_dir)?;
+    let branch = format!("refs/heads/{reference}");
+    let on_branch = repo
+        .head_name()?
+        .is_some_and(|name| name.as_bstr() == branch.as_bytes().as_bstr());
+    if !on_branch {
+        return Err(format!("`HEAD` is not on branch `{reference}`").into());
+    }
+    let Some(upstream) = find_ref_id(&repo, &format!("refs/remotes/origin/{reference}")) else {
+        return Err(format!("couldn't find remote ref `{reference}`").into());
+    };
+    let head = repo.head_id()?.detach();
+    if head == upstream {
+        return Ok(());
+    }
+    if repo.merge_base(head, upstream)?.detach() != head {
+        return Err(format!("branch `{reference}` has diverged from `origin`").into());
+    }
+    checkout_tree(&repo, upstream)?;
+    set_head(&repo, Target::Object(upstream), true)
+}
+
+fn embedded_log(
+    repo_dir: &Path,
+    from: &str,
+    to: &str,
+    path: Option<&str>,
+) -> EmbeddedResult<Vec<CommitSummary>> {
+    let repo = embedded_open(repo_dir)?;
+    let from = resolve_commit(&repo, from)?;
+    let to = resolve_commit(&repo, to)?;
+    let walk = repo
+        .rev_walk([to])
+        .with_hidden([from])
+        .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+        .all()?;
+    let mut commits = Vec::new();
+    for info in walk {
+        let info = info?;
+        if let Some(path) = path {
+            // Like `git log -- <path>`: a commit counts when `path`
+            // differs from every parent.
+            let entry = path_entry_id(&repo, info.id, path)?;
+            let mut touched = true;
+            for parent in info.parent_ids.iter() {
+                if path_entry_id(&repo, *parent, path)? == entry {
+                    touched = false;
+                    break;
+                }
+            }
+            if info.parent_ids.is_empty() && entry.is_none() {
+                touched = false;
+            }
+            if !touched {
+                continue;
+            }
+        }
+        let commit = repo.find_commit(info.id)?;
+        commits.push(CommitSummary {
+            id: info.id.to_string(),
+            subject: commit.message()?.summary().to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+fn embedded_diff_stat(
+    repo_dir: &Path,
+    from: &str,
+    to: &str,
+    path: Option<&str>,
+) -> EmbeddedResult<DiffStat> {
+    let repo = embedded_open(repo_dir)?;
+    let before = tree_files(&repo, resolve_commit(&repo, from)?, path)?;
+    let after = tree_files(&repo, resolve_commit(&repo, to)?, path)?;
+    let mut stat = DiffStat::default();
+    for (file, entry) in &after {
+        match before.get(file) {
+            None => stat.added += 1,
+            Some(previous) if previous != entry => stat.changed += 1,
+            Some(_) => {}
+        }
+    }
+    stat.removed = before
+        .keys()
+        .filter(|file| !after.contains_key(*file))
+        .count();
+    Ok(stat)
+}
+
+/// The id of the tree entry at `path` in `commit`, if it exists.
+fn path_entry_id(
+    repo: &gix::Repository,
+    commit: ObjectId,
+    path: &str,
+) -> EmbeddedResult<Option<ObjectId>> {
+    let tree = repo.find_commit(commit)?.tree()?;
+    Ok(tree
+        .lookup_entry_by_path(path)?
+        .map(|entry| entry.object_id()))
+}
+
+/// Every file below `path` (or the root) in the tree of `commit`, keyed
+/// by its path relative to the repository, with its mode and blob id.
+fn tree_files(
+    repo: &gix::Repository,
+    commit: ObjectId,
+    path: Option<&str>,
+) -> EmbeddedResult<BTreeMap<String, (gix::objs::tree::EntryMode, ObjectId)>> {
+    let root = repo.find_commit(commit)?.tree()?;
+    let tree = match path {
+        None => root,
+        Some(path) => match root.lookup_entry_by_path(path)? {
+            Some(entry) if entry.mode().is_tree() => entry.object()?.into_tree(),
+            Some(entry) => {
+                let mut files = BTreeMap::new();
+                files.insert(path.to_string(), (entry.mode(), entry.object_id()));
+                return Ok(files);
+            }
+            None => return Ok(BTreeMap::new()),
+        },
+    };
+    Ok(tree
+        .traverse()
+        .breadthfirst
+        .files()?
+        .into_iter()
+        .filter(|entry| !entry.mode.is_tree())
+        .map(|entry| (entry.filepath.to_string(), (entry.mode, entry.oid)))
+        .collect())
+}
+
+fn find_ref_id(repo: &gix::Repository, name: &str) -> Option<ObjectId> {
+    let mut reference = repo.try_find_reference(name).ok()??;
+    Some(reference.peel_to_id().ok()?.detach())
+}
+
+fn resolve_commit(repo: &gix::Repository, rev: &str) -> EmbeddedResult<ObjectId> {
+    Ok(repo.rev_parse_single(rev)?.object()?.peel_to_commit()?.id)
+}
+
+/// Make the worktree and index match the tree of `commit`, deleting
+/// tracked files that the new tree no longer contains. Files outside the
+/// sparse checkout are marked skip-worktree instead of written, and
+/// deleted when an earlier checkout wrote them.
+fn checkout_tree(repo: &gix::Repository, commit: ObjectId) -> EmbeddedResult<()> {
+    let workdir = repo
+        .workdir()
+        .ok_or("repository has no worktree")?
+        .to_owned();
+    let tree = repo.find_commit(commit)?.tree_id()?;
+    let mut index = repo.index_from_tree(&tree)?;
+    if let Some(sparse_paths) = read_sparse_patterns(repo.git_dir()) {
+        for (entry, path) in index.entries_mut_with_paths() {
+            if !path
+                .to_str()
+                .is_ok_and(|path| sparse_includes(&sparse_paths, path))
+            {
+                entry.flags |= IndexFlags::SKIP_WORKTREE | IndexFlags::EXTENDED;
+            }
+        }
+    }
+
+    if let Ok(previous) = repo.open_index() {
+        for entry in previous.entries() {
+            let path = entry.path(&previous);
+            let written = index
+                .entry_by_path(path)
+                .is_some_and(|entry| !entry.flags.contains(IndexFlags::SKIP_WORKTREE));
+            if !written {
+                let file = workdir.join(gix::path::from_bstr(path));
+                let _ = std::fs::remove_file(&file);
+                let mut parent = file.parent();
+                while let Some(dir) = parent.filter(|dir| *dir != workdir) {
+                    if std::fs::remove_dir(dir).is_err() {
+                        break;
+                    }
+                    parent = dir.parent();
+                }
+            }
+        }
+    }
+
+    let mut options =
+        repo.checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)?;
+    options.destination_is_initially_empty = false;
+    options.overwrite_existing = true;
+    gix::worktree::state::checkout(
+        &mut index,
+        &workdir,
+        repo.objects.clone().into_arc()?,
+        &gix::progress::Discard,
+        &gix::progress::Discard,
+        &AtomicBool::new(false),
+        options,
+    )?;
+    index.write(Default::default())?;
+    Ok(())
+}
+
+/// The cone-mode sparse paths in `git_dir`, read back from the patterns
+/// `git sparse-checkout set --cone` (or [`write_sparse_patterns`])
+/// writes: every directory included recursively, i.e. without a
+/// `!/<dir>/*/` line excluding its subdirectories.
+fn read_sparse_patterns(git_dir: &Path) -> Option<BTreeSet<String>> {
+    let content = std::fs::read_to_string(git_dir.join(SPARSE_CHECKOUT_FILE)).ok()?;
+    let lines = content.lines().map(str::trim).collect::<BTreeSet<_>>();
+    Some(
+        lines
+            .iter()
+            .filter_map(|line| line.strip_prefix('/')?.strip_suffix('/'))
+            .filter(|dir| !dir.contains('*') && !lines.contains(format!("!/{dir}/*/").as_str()))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Write `sparse_paths` to `git_dir` as cone-mode patterns: files at the
+/// root, the files directly inside each parent of a sparse path, and
+/// everything below the sparse paths themselves.
+fn write_sparse_patterns(git_dir: &Path, sparse_paths: &BTreeSet<String>) -> std::io::Result<()> {
+    let recursive = sparse_paths
+        .iter()
+        .filter(|path| {
+            !sparse_paths
+                .iter()
+                .any(|other| path.starts_with(&format!("{other}/")))
+        })
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>();
+    let parents = recursive
+        .iter()
+        .flat_map(|path| path.match_indices('/').map(|(end, _)| &path[..end]))
+        .collect::<BTreeSet<_>>();
+    let mut patterns = String::from("/*\n!/*/\n");
+    for dir in parents.union(&recursive) {
+        patterns.push_str(&format!("/{dir}/\n"));
+        if parents.contains(dir) {
+            patterns.push_str(&format!("!/{dir}/*/\n"));
+        }
+    }
+    std::fs::create_dir_all(git_dir.join("info"))?;
+    std::fs::write(git_dir.join(SPARSE_CHECKOUT_FILE), patterns)
+}
+
+/// Whether the cone of `sparse_paths` contains the file `path`.
+fn sparse_includes(sparse_paths: &BTreeSet<String>, path: &str) -> bool {
+    let Some((dir, _)) = path.rsplit_once('/') else {
+        return true;
+    };
+    sparse_paths.iter().any(|sparse| {
+        path.strip_prefix(sparse.as_str())
+            .is_some_and(|rest| rest.starts_with('/'))
+            || sparse
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Point `HEAD` at `target`. With `deref`, a symbolic `HEAD` moves the
+/// branch it names instead.
+fn set_head(repo: &gix::Repository, target: Target, deref: bool) -> EmbeddedResult<()> {
+    repo.edit_reference(RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: REFLOG_MESSAGE.into(),
+            },
+            expected: PreviousValue::Any,
+            new: target,
+        },
+        name: "HEAD".try_into()?,
+        deref,
+    })?;
+    Ok(())
+}
@@ -1,24 +1,34 @@
 //! Domain logic layer for eden-skills.
 //!
 //! This crate contains configuration parsing, plan computation, source
-//! sync, verification, safety analysis, lock file management, adapter
-//! abstraction (local/Docker), reactor-based concurrency, registry
-//! resolution, and agent discovery. It has no dependency on CLI output
-//! formatting — all presentation is handled by the CLI crate.
+//! sync (git and release archives), verification, safety analysis, lock
+//! file management, adapter abstraction (local/Docker), reactor-based
+//! concurrency, registry resolution, and agent discovery. It has no
+//! dependency on CLI output formatting — all presentation is handled by
+//! the CLI crate.
 
 pub mod adapter;
 pub mod agents;
+pub mod archive;
 pub mod config;
+pub mod config_layers;
+pub mod digest;
 pub mod discovery;
 pub mod error;
+pub mod generations;
+pub mod git;
 pub mod lock;
 pub mod managed;
+pub mod mirrors;
 pub mod paths;
 pub mod plan;
+pub mod plan_file;
 pub mod reactor;
 pub mod registry;
+pub mod retry;
 pub mod safety;
 pub mod source;
 pub mod source_format;
 pub mod state;
+pub mod tree_diff;
 pub mod verify;
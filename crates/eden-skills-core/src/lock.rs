@@ -4,16 +4,23 @@
 //! installed skill so that `plan` and `apply` can detect drift without
 //! re-scanning the filesystem. The three-way diff algorithm compares
 //! TOML config ∩ lock entries to produce Added/Changed/Unchanged/Removed
-//! partitions.
+//! partitions. Each entry may also pin content digests (see
+//! [`crate::digest`]) of the source tree and of every installed target.
+//! Skills installed only because another skill `requires` them are
+//! recorded as `implicit` entries.
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
-use crate::config::Config;
+use crate::config::{is_registry_mode_repo, Config, TargetScope};
+use crate::digest::tree_digest;
 use crate::error::EdenError;
-use crate::paths::resolve_target_path;
+use crate::paths::{
+    portable_target_path, resolve_path_string, resolve_portable_target_path, resolve_target_path,
+};
+use crate::source::{read_resolved_tag, resolve_skill_source_path, resolve_skill_storage_root};
 
 pub const LOCK_VERSION: u32 = 1;
 
@@ -36,6 +43,15 @@ pub struct LockSkillEntry {
     pub resolved_version: Option<String>,
     pub install_mode: String,
     pub installed_at: String,
+    /// Tree digest of the source checkout at install time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
+    /// Installed because another skill requires it, not configured.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub implicit: bool,
+    /// Names of the skills this one requires.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
     pub targets: Vec<LockTarget>,
 }
 
@@ -48,6 +64,20 @@ pub struct LockTarget {
         skip_serializing_if = "is_local_environment"
     )]
     pub environment: String,
+    #[serde(default, skip_serializing_if = "TargetScope::is_global")]
+    pub scope: TargetScope,
+    /// Tree digest of the installed target at install time (local only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_digest: Option<String>,
+}
+
+impl LockTarget {
+    /// Absolute on-disk path of this target. Project-scope targets are
+    /// recorded relative to the project root and resolved against
+    /// `config_dir` here.
+    pub fn resolved_path(&self, config_dir: &Path) -> PathBuf {
+        resolve_portable_target_path(self.scope, &self.path, config_dir)
+    }
 }
 
 impl LockFile {
@@ -118,6 +148,7 @@ pub fn build_lock_from_config(
     resolved_commits: &std::collections::HashMap<String, String>,
 ) -> Result<LockFile, EdenError> {
     let now = utc_now_iso8601();
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
     let mut entries = Vec::with_capacity(config.skills.len());
 
     for skill in &config.skills {
@@ -127,8 +158,10 @@ pub fn build_lock_from_config(
             let target_path = target_root.join(&skill.id);
             targets.push(LockTarget {
                 agent: target.agent.as_str().to_string(),
-                path: target_path.display().to_string(),
+                path: portable_target_path(target.scope, &target_path, config_dir),
                 environment: target.environment.clone(),
+                scope: target.scope,
+                content_digest: None,
             });
         }
 
@@ -136,6 +169,11 @@ pub fn build_lock_from_config(
 
         let resolved_version = if crate::config::is_registry_mode_repo(&skill.source.repo) {
             Some(skill.source.r#ref.clone())
+        } else if skill.source.version_constraint().is_some() {
+            read_resolved_tag(
+                &resolve_skill_storage_root(&storage_root, skill),
+                &resolved_commit,
+            )
         } else {
             None
         };
@@ -149,6 +187,9 @@ pub fn build_lock_from_config(
             resolved_version,
             install_mode: skill.install.mode.as_str().to_string(),
             installed_at: now.clone(),
+            content_digest: None,
+            implicit: skill.implicit,
+            requires: skill.requires.keys().cloned().collect(),
             targets,
         });
     }
@@ -159,6 +200,174 @@ pub fn build_lock_from_config(
     })
 }
 
+/// Which lock entries get freshly computed content digests.
+#[derive(Debug, Clone, Copy)]
+pub enum DigestRefresh<'a> {
+    /// Recompute every digest (after a full reconcile).
+    All,
+    /// Recompute digests for these skill ids and carry the rest over.
+    Skills(&'a [String]),
+    /// Only carry digests over from the previous lock.
+    Keep,
+}
+
+impl DigestRefresh<'_> {
+    fn includes(&self, skill_id: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Skills(ids) => ids.iter().any(|id| id == skill_id),
+            Self::Keep => false,
+        }
+    }
+}
+
+/// Fill `content_digest` fields of a freshly built `lock`.
+///
+/// Skills selected by `refresh` are hashed from the filesystem as it is
+/// now, which is only correct right after they were installed. Every
+/// other skill keeps the digests from `previous` as long as its source
+/// and install mode are unchanged, so that non-installing commands never
+/// bless content that was modified after installation. Unreadable or
+/// missing trees, and non-local targets, get no digest.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] when the storage root cannot be resolved.
+pub fn record_content_digests(
+    lock: &mut LockFile,
+    config: &Config,
+    config_dir: &Path,
+    previous: Option<&LockFile>,
+    refresh: DigestRefresh<'_>,
+) -> Result<(), EdenError> {
+    let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
+    for entry in &mut lock.skills {
+        if refresh.includes(&entry.id) {
+            if let Some(skill) = config.skills.iter().find(|skill| skill.id == entry.id) {
+                let source_path = resolve_skill_source_path(&storage_root, skill);
+                entry.content_digest = tree_digest(&source_path).ok();
+            }
+            for target in &mut entry.targets {
+                if target.environment == "local" {
+                    target.content_digest = tree_digest(&target.resolved_path(config_dir)).ok();
+                }
+            }
+            continue;
+        }
+
+        let Some(previous) = previous
+            .and_then(|lock| lock.skills.iter().find(|prev| prev.id == entry.id))
+            .filter(|prev| {
+                prev.source_repo == entry.source_repo
+                    && prev.source_subpath == entry.source_subpath
+                    && prev.source_ref == entry.source_ref
+                    && prev.install_mode == entry.install_mode
+            })
+        else {
+            continue;
+        };
+        entry.content_digest = previous.content_digest.clone();
+        for target in &mut entry.targets {
+            target.content_digest = previous
+                .targets
+                .iter()
+                .find(|prev| {
+                    prev.agent == target.agent
+                        && prev.path == target.path
+                        && prev.environment == target.environment
+                })
+                .and_then(|prev| prev.content_digest.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Keep the `resolved_version` of tag-following sources whose checkout
+/// no longer records its tag (e.g. after a locked sync into a fresh
+/// cache), as long as the source and commit are unchanged.
+pub fn carry_resolved_versions(lock: &mut LockFile, previous: Option<&LockFile>) {
+    let Some(previous) = previous else {
+        return;
+    };
+    for entry in lock
+        .skills
+        .iter_mut()
+        .filter(|entry| entry.resolved_version.is_none())
+    {
+        entry.resolved_version = previous
+            .skills
+            .iter()
+            .find(|prev| {
+                prev.id == entry.id
+                    && prev.source_repo == entry.source_repo
+                    && prev.source_ref == entry.source_ref
+                    && prev.resolved_commit == entry.resolved_commit
+            })
+            .and_then(|prev| prev.resolved_version.clone());
+    }
+}
+
+/// Keep what a lock built from an unresolved config cannot know.
+///
+/// Registry skills only learn their index `requires` during resolution,
+/// so they keep the ones recorded in `previous`. Implicit entries of
+/// `previous` stay as long as an entry of `lock` still (transitively)
+/// requires them; once nothing does, they are dropped.
+pub fn carry_implicit_entries(lock: &mut LockFile, previous: Option<&LockFile>) {
+    let Some(previous) = previous else {
+        return;
+    };
+    for entry in lock
+        .skills
+        .iter_mut()
+        .filter(|entry| is_registry_mode_repo(&entry.source_repo))
+    {
+        if let Some(prev) = previous.skills.iter().find(|prev| prev.id == entry.id) {
+            entry.requires.extend(prev.requires.iter().cloned());
+            entry.requires.sort();
+            entry.requires.dedup();
+        }
+    }
+    loop {
+        let present = lock
+            .skills
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect::<HashSet<_>>();
+        let required = lock
+            .skills
+            .iter()
+            .flat_map(|entry| entry.requires.iter().map(String::as_str))
+            .collect::<HashSet<_>>();
+        let carried = previous
+            .skills
+            .iter()
+            .filter(|prev| {
+                prev.implicit
+                    && !present.contains(prev.id.as_str())
+                    && required.contains(prev.id.as_str())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if carried.is_empty() {
+            return;
+        }
+        lock.skills.extend(carried);
+    }
+}
+
+/// Ids of the entries of `lock` that require `skill_id`, sorted.
+pub fn lock_dependents(lock: &LockFile, skill_id: &str) -> Vec<String> {
+    let mut dependents = lock
+        .skills
+        .iter()
+        .filter(|entry| entry.requires.iter().any(|name| name == skill_id))
+        .map(|entry| entry.id.clone())
+        .collect::<Vec<_>>();
+    dependents.sort();
+    dependents
+}
+
 /// Skill-level diff classification between TOML config and lock file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SkillDiffStatus {
@@ -256,22 +465,31 @@ fn skill_config_differs_from_lock(
         return Ok(true);
     }
 
-    let mut config_targets: Vec<(String, String, String)> = Vec::with_capacity(skill.targets.len());
+    let mut config_targets: Vec<(String, String, String, TargetScope)> =
+        Vec::with_capacity(skill.targets.len());
     for target in &skill.targets {
         let target_root = resolve_target_path(target, config_dir)?;
         let target_path = target_root.join(&skill.id);
         config_targets.push((
             target.agent.as_str().to_string(),
-            target_path.display().to_string(),
+            portable_target_path(target.scope, &target_path, config_dir),
             target.environment.clone(),
+            target.scope,
         ));
     }
     config_targets.sort();
 
-    let mut lock_targets: Vec<(String, String, String)> = lock_entry
+    let mut lock_targets: Vec<(String, String, String, TargetScope)> = lock_entry
         .targets
         .iter()
-        .map(|t| (t.agent.clone(), t.path.clone(), t.environment.clone()))
+        .map(|t| {
+            (
+                t.agent.clone(),
+                t.path.clone(),
+                t.environment.clone(),
+                t.scope,
+            )
+        })
         .collect();
     lock_targets.sort();
 
@@ -279,7 +497,7 @@ fn skill_config_differs_from_lock(
 }
 
 /// Minimal ISO 8601 UTC timestamp formatter (avoids external datetime dependency).
-fn utc_now_iso8601() -> String {
+pub(crate) fn utc_now_iso8601() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -337,3 +555,7 @@ fn default_lock_environment() -> String {
 fn is_local_environment(environment: &String) -> bool {
     environment == "local"
 }
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
@@ -83,6 +83,12 @@ pub fn local_install_origin(environment: &str) -> String {
     )
 }
 
+/// Origin recorded for project-scope installs. The manifest lives inside
+/// the project tree, so it names the scope rather than the local host.
+pub fn project_install_origin() -> String {
+    "project".to_string()
+}
+
 fn resolve_host_identity() -> String {
     std::env::var("HOSTNAME")
         .ok()
@@ -0,0 +1,34 @@
+//! URL rewrite rules for fetching through mirrors.
+//!
+//! The `[mirrors]` table maps a URL prefix to a replacement, like git's
+//! `url.<replacement>.insteadOf = <prefix>`:
+//!
+//! ```toml
+//! [mirrors]
+//! "https://github.com/" = "https://git.corp.example/github/"
+//! ```
+//!
+//! Rules only change where bytes are fetched from. Skill sources,
+//! registries, and lock entries keep the canonical upstream URL, and
+//! cache keys are derived from it, so a lock written behind a mirror is
+//! valid without one and a cache entry survives a change of mirror.
+
+/// One `[mirrors]` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorRule {
+    pub prefix: String,
+    pub replacement: String,
+}
+
+/// The URL to fetch `url` from: the longest matching `prefix` replaced by
+/// its `replacement`, or `url` unchanged when no rule matches.
+pub fn rewrite_url(mirrors: &[MirrorRule], url: &str) -> String {
+    mirrors
+        .iter()
+        .filter(|rule| url.starts_with(&rule.prefix))
+        .max_by_key(|rule| rule.prefix.len())
+        .map_or_else(
+            || url.to_string(),
+            |rule| format!("{}{}", rule.replacement, &url[rule.prefix.len()..]),
+        )
+}
@@ -8,7 +8,7 @@
 use std::env;
 use std::path::{Component, Path, PathBuf};
 
-use crate::config::{AgentKind, TargetConfig};
+use crate::config::{AgentKind, TargetConfig, TargetScope};
 use crate::error::EdenError;
 
 /// Well-known global skill directories across all supported agents.
@@ -190,7 +190,11 @@ pub fn colocated_agent_display_label(primary: &AgentKind) -> String {
 }
 
 /// Resolve the absolute install target path for a skill target entry,
-/// trying `path` → `expected_path` → `default_agent_path` in order.
+/// trying `path` → `expected_path` → default agent path in order.
+///
+/// The default agent path is [`default_agent_path`] for global-scope
+/// targets and [`default_agent_project_path`] (joined onto `config_dir`,
+/// the project root) for project-scope targets.
 pub fn resolve_target_path(target: &TargetConfig, config_dir: &Path) -> Result<PathBuf, EdenError> {
     if let Some(path) = &target.path {
         return resolve_path_string(path, config_dir);
@@ -198,7 +202,11 @@ pub fn resolve_target_path(target: &TargetConfig, config_dir: &Path) -> Result<P
     if let Some(expected_path) = &target.expected_path {
         return resolve_path_string(expected_path, config_dir);
     }
-    let Some(default_path) = default_agent_path(&target.agent) else {
+    let default_path = match target.scope {
+        TargetScope::Global => default_agent_path(&target.agent),
+        TargetScope::Project => default_agent_project_path(&target.agent),
+    };
+    let Some(default_path) = default_path else {
         return Err(EdenError::Validation(
             "TARGET_PATH_UNRESOLVED: no path, expected_path, or default agent path".to_string(),
         ));
@@ -206,6 +214,40 @@ pub fn resolve_target_path(target: &TargetConfig, config_dir: &Path) -> Result<P
     resolve_path_string(default_path, config_dir)
 }
 
+/// Render an installed target path for persistence in `skills.lock`.
+///
+/// Project-scope targets that live under `config_dir` are recorded
+/// relative to it (with `/` separators) so the lock stays valid on any
+/// checkout of the project. Global-scope targets keep their absolute
+/// path.
+pub fn portable_target_path(scope: TargetScope, target_path: &Path, config_dir: &Path) -> String {
+    if scope == TargetScope::Project {
+        let root = normalize_lexical(config_dir);
+        if let Ok(relative) = normalize_lexical(target_path).strip_prefix(&root) {
+            return relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+    }
+    target_path.display().to_string()
+}
+
+/// Inverse of [`portable_target_path`]: resolve a recorded lock target
+/// path back into an absolute path for the current checkout.
+pub fn resolve_portable_target_path(
+    scope: TargetScope,
+    recorded: &str,
+    config_dir: &Path,
+) -> PathBuf {
+    let path = Path::new(recorded);
+    if scope == TargetScope::Project && !path.is_absolute() {
+        return normalize_lexical(&config_dir.join(path));
+    }
+    PathBuf::from(recorded)
+}
+
 /// Expand `~` and resolve relative paths against `config_dir`, then
 /// normalize the result lexically (without touching the filesystem).
 pub fn resolve_path_string(input: &str, config_dir: &Path) -> Result<PathBuf, EdenError> {
@@ -9,15 +9,16 @@ use std::io::ErrorKind;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::config::{Config, InstallMode};
+use crate::config_layers::ConfigLayer;
 use crate::error::EdenError;
 use crate::paths::{normalize_lexical, resolve_path_string, resolve_target_path};
 use crate::source::resolve_skill_source_path;
 
 /// The reconciliation action determined for a single skill target.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Action {
     Create,
@@ -27,7 +28,7 @@ pub enum Action {
     Remove,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlanItem {
     pub skill_id: String,
     pub source_path: String,
@@ -35,6 +36,9 @@ pub struct PlanItem {
     pub install_mode: InstallMode,
     pub action: Action,
     pub reasons: Vec<String>,
+    /// Config layer that defined the skill; set only for layered configs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<ConfigLayer>,
 }
 
 /// Build a reconciliation plan by comparing config targets to filesystem state.
@@ -67,6 +71,7 @@ pub fn build_plan(config: &Config, config_dir: &Path) -> Result<Vec<PlanItem>, E
                 install_mode: skill.install.mode,
                 action,
                 reasons,
+                layer: None,
             });
         }
     }
@@ -0,0 +1,157 @@
+//! Saved plans for `plan --out` and `apply <planfile>`.
+//!
+//! A [`PlanFile`] stores the plan items together with fingerprints of
+//! everything they were computed from: the effective config text, the
+//! lock file, every source tree, and the state of every target path.
+//! Applying a saved plan recomputes the fingerprints with
+//! [`fingerprint_plan`] and refuses to run when
+//! [`PlanFingerprints::changes_since`] reports any difference, so the
+//! reviewed change set is exactly what gets executed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::{sha256_hex, tree_digest, DIGEST_PREFIX};
+use crate::error::EdenError;
+use crate::plan::PlanItem;
+
+pub const PLAN_FILE_VERSION: u32 = 1;
+
+const ABSENT: &str = "absent";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanFile {
+    pub version: u32,
+    /// Absolute path of the config the plan was computed from.
+    pub config_path: String,
+    /// User-global config layered beneath `config_path`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_config_path: Option<String>,
+    pub fingerprints: PlanFingerprints,
+    pub items: Vec<PlanItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanFingerprints {
+    /// Digest of the effective (merged) config text.
+    pub config: String,
+    /// Digest of the lock file bytes, or `absent`.
+    pub lock: String,
+    /// Tree digest of each source path referenced by the plan.
+    pub sources: BTreeMap<String, String>,
+    /// State of each target path: `absent`, `symlink:<link>`, or a tree digest.
+    pub targets: BTreeMap<String, String>,
+}
+
+impl PlanFingerprints {
+    /// Describe every fingerprint that differs from `recorded`.
+    pub fn changes_since(&self, recorded: &PlanFingerprints) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.config != recorded.config {
+            changes.push("config changed".to_string());
+        }
+        if self.lock != recorded.lock {
+            changes.push("skills.lock changed".to_string());
+        }
+        for (path, digest) in &recorded.sources {
+            if self.sources.get(path) != Some(digest) {
+                changes.push(format!("source `{path}` changed"));
+            }
+        }
+        for (path, state) in &recorded.targets {
+            if self.targets.get(path) != Some(state) {
+                changes.push(format!("target `{path}` changed"));
+            }
+        }
+        changes
+    }
+}
+
+/// Fingerprint the inputs of `items`.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Io`] when the lock file exists but cannot be read.
+pub fn fingerprint_plan(
+    config_text: &str,
+    lock_path: &Path,
+    items: &[PlanItem],
+) -> Result<PlanFingerprints, EdenError> {
+    let lock = match fs::read(lock_path) {
+        Ok(bytes) => format!("{DIGEST_PREFIX}{}", sha256_hex(&bytes)),
+        Err(err) if err.kind() == ErrorKind::NotFound => ABSENT.to_string(),
+        Err(err) => return Err(EdenError::Io(err)),
+    };
+
+    let mut sources = BTreeMap::new();
+    let mut targets = BTreeMap::new();
+    for item in items {
+        if !item.source_path.is_empty() {
+            sources
+                .entry(item.source_path.clone())
+                .or_insert_with(|| path_state(Path::new(&item.source_path)));
+        }
+        targets
+            .entry(item.target_path.clone())
+            .or_insert_with(|| path_state(Path::new(&item.target_path)));
+    }
+
+    Ok(PlanFingerprints {
+        config: format!("{DIGEST_PREFIX}{}", sha256_hex(config_text.as_bytes())),
+        lock,
+        sources,
+        targets,
+    })
+}
+
+fn path_state(path: &Path) -> String {
+    match fs::symlink_metadata(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => ABSENT.to_string(),
+        Err(err) => format!("unreadable: {err}"),
+        Ok(metadata) if metadata.file_type().is_symlink() => match fs::read_link(path) {
+            Ok(link) => format!("symlink:{}", link.display()),
+            Err(err) => format!("unreadable: {err}"),
+        },
+        Ok(_) => tree_digest(path).unwrap_or_else(|err| format!("unreadable: {err}")),
+    }
+}
+
+/// Read and validate a saved plan.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Validation`] for malformed or unsupported plan
+/// files and [`EdenError::Io`] when the file cannot be read.
+pub fn read_plan_file(path: &Path) -> Result<PlanFile, EdenError> {
+    let content = fs::read_to_string(path)?;
+    let plan: PlanFile = serde_json::from_str(&content).map_err(|err| {
+        EdenError::Validation(format!(
+            "plan file `{}` is not a valid saved plan: {err}",
+            path.display()
+        ))
+    })?;
+    if plan.version != PLAN_FILE_VERSION {
+        return Err(EdenError::Validation(format!(
+            "plan file `{}` has unsupported version {} (expected {PLAN_FILE_VERSION})",
+            path.display(),
+            plan.version
+        )));
+    }
+    Ok(plan)
+}
+
+/// Serialize `plan` as pretty JSON to `path`.
+///
+/// # Errors
+///
+/// Returns [`EdenError`] on serialization or write failures.
+pub fn write_plan_file(path: &Path, plan: &PlanFile) -> Result<(), EdenError> {
+    let content = serde_json::to_string_pretty(plan)
+        .map_err(|err| EdenError::Runtime(format!("failed to serialize plan file: {err}")))?;
+    fs::write(path, format!("{content}\n"))?;
+    Ok(())
+}
@@ -4,11 +4,15 @@
 //! bounded by a [`Semaphore`]. Phase A runs IO-bound tasks (git clone,
 //! file copy) with configurable concurrency. Phase B (blocking) runs
 //! CPU-bound work on `spawn_blocking` threads.
+//!
+//! [`HostLimiter`] additionally caps how many network operations run
+//! against one remote host at a time, independent of the overall limit.
 
+use std::collections::HashMap;
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use tokio::sync::Semaphore;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 
@@ -19,6 +23,8 @@ pub const DEFAULT_CONCURRENCY_LIMIT: usize = 10;
 pub const MIN_CONCURRENCY_LIMIT: usize = 1;
 /// Upper bound prevents excessive file-descriptor pressure.
 pub const MAX_CONCURRENCY_LIMIT: usize = 100;
+/// Default cap on simultaneous connections to one host.
+pub const DEFAULT_HOST_CONCURRENCY_LIMIT: usize = 4;
 
 /// Indexed result from a reactor phase, preserving task ordering.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -214,3 +220,60 @@ impl SkillReactor {
         }
     }
 }
+
+/// Per-host concurrency cap shared by the tasks of one reactor phase.
+///
+/// Clones share a single [`Semaphore`] per host, so a config with many
+/// skills from one server opens at most `limit` connections to it.
+/// URLs without a host (`file://`, local paths) are never limited.
+#[derive(Debug, Clone)]
+pub struct HostLimiter {
+    limit: usize,
+    hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl HostLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(MIN_CONCURRENCY_LIMIT),
+            hosts: Arc::default(),
+        }
+    }
+
+    /// Wait for a slot on the host of `url`. The slot is held until the
+    /// returned permit is dropped; `None` means the URL is not limited.
+    pub async fn acquire(&self, url: &str) -> Option<OwnedSemaphorePermit> {
+        let host = url_host(url)?;
+        let semaphore = {
+            let mut hosts = self.hosts.lock().unwrap_or_else(|err| err.into_inner());
+            Arc::clone(
+                hosts
+                    .entry(host)
+                    .or_insert_with(|| Arc::new(Semaphore::new(self.limit))),
+            )
+        };
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// The lowercase host of a remote URL (`https://host/...`,
+/// `ssh://user@host:port/...`, or scp-like `user@host:path`), or `None`
+/// for local paths and `file://` URLs.
+pub fn url_host(url: &str) -> Option<String> {
+    let authority = match url.split_once("://") {
+        Some((scheme, _)) if scheme.eq_ignore_ascii_case("file") => return None,
+        Some((_, rest)) => rest.split('/').next()?,
+        None => {
+            let (authority, _) = url.split_once(':')?;
+            if authority.contains('/') || authority.contains('\\') || authority.len() < 2 {
+                return None;
+            }
+            authority
+        }
+    };
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or(host);
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
@@ -5,8 +5,22 @@
 //! back to a source repo + subpath + commit.  Multiple registries can be
 //! configured with different priorities; resolution walks them in
 //! descending-priority order and returns the first match.
+//!
+//! The index layout depends on the `format_version` in the registry's
+//! `manifest.toml` (ADR-007): version 1 buckets entries by the first
+//! character of the skill name (`index/g/google-search.toml`), version 2
+//! by the first two (`index/go/google-search.toml`).
+//!
+//! [`search_registry_sources`] scans whole indexes instead, ranking
+//! entries by how well their name, tags, and description match a query,
+//! and [`lookup_skill_in_registry_sources`] reports one skill's entry in
+//! every registry rather than only the first match.
+//!
+//! A version may declare `requires = { name = "<constraint>" }` on other
+//! skills; [`resolve_skill_set`] picks one version of every skill reachable
+//! through those requirements such that all of them hold at once.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -42,6 +56,141 @@ pub struct ResolvedSkill {
     pub version: String,
     pub git_ref: String,
     pub commit: String,
+    /// Skills the selected version requires, by name, with their
+    /// version constraints.
+    pub requires: BTreeMap<String, String>,
+}
+
+/// One requirement for [`resolve_skill_set`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillRequirement {
+    pub name: String,
+    pub constraint: String,
+    /// Skill declaring the requirement; `None` for a configured skill.
+    pub required_by: Option<String>,
+    /// Only look the skill up in this registry.
+    pub registry: Option<String>,
+}
+
+/// One skill of the set picked by [`resolve_skill_set`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    pub name: String,
+    pub skill: ResolvedSkill,
+    /// Skills whose `requires` name this one, sorted; empty when it was
+    /// only requested directly.
+    pub required_by: Vec<String>,
+}
+
+/// One index entry matching a registry search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillSearchHit {
+    pub registry_name: String,
+    pub registry_priority: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// Highest non-yanked version, picked as an unconstrained install
+    /// would; `None` when every version is yanked.
+    pub latest_version: Option<String>,
+    pub deprecated: bool,
+    /// Relevance to the query; higher ranks first.
+    pub score: u32,
+}
+
+/// Result of [`search_registry_sources`]: ranked hits plus the index
+/// entries that were skipped because they could not be read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistrySearch {
+    pub hits: Vec<SkillSearchHit>,
+    pub skipped: Vec<String>,
+}
+
+/// One registry's index entry for a skill, as shown by `info`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrySkillInfo {
+    pub registry_name: String,
+    pub registry_priority: u32,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    pub repo: String,
+    pub subpath: String,
+    /// Every listed version, newest first, yanked ones included.
+    pub versions: Vec<RegistrySkillVersion>,
+    /// Version the requested constraint selects in this registry.
+    pub selected_version: Option<String>,
+    /// Why nothing was selected, when `selected_version` is `None`.
+    pub selection_error: Option<String>,
+}
+
+/// One `[[versions]]` item of an index entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrySkillVersion {
+    pub version: String,
+    pub git_ref: String,
+    pub commit: String,
+    pub yanked: bool,
+}
+
+/// Index `format_version`s whose layout this client can read.
+pub const SUPPORTED_INDEX_FORMAT_VERSIONS: &[i64] = &[1, 2];
+
+/// Read `format_version` from the `manifest.toml` at `registry_root`.
+/// Returns `None` when the registry has no manifest.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::Config`] when the manifest is not valid TOML
+/// or lacks an integer `format_version`.
+pub fn read_index_format_version(registry_root: &Path) -> Result<Option<i64>, RegistryError> {
+    let manifest_path = registry_root.join("manifest.toml");
+    let raw = match fs::read_to_string(&manifest_path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let manifest: toml::Value = toml::from_str(&raw).map_err(|err| RegistryError::Config {
+        detail: format!("`{}` is invalid TOML: {err}", manifest_path.display()),
+    })?;
+    manifest
+        .get("format_version")
+        .and_then(toml::Value::as_integer)
+        .map(Some)
+        .ok_or_else(|| RegistryError::Config {
+            detail: format!("`{}` is missing `format_version`", manifest_path.display()),
+        })
+}
+
+/// Path of `skill_name`'s entry under `registry_root` in the index layout
+/// of `format_version`: bucketed by the ASCII-lowercased first character for
+/// version 1 and by the first two characters from version 2 on.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::Config`] for an empty skill name.
+pub fn index_entry_path(
+    registry_root: &Path,
+    skill_name: &str,
+    format_version: i64,
+) -> Result<PathBuf, RegistryError> {
+    if skill_name.is_empty() {
+        return Err(RegistryError::Config {
+            detail: "skill name must not be empty".to_string(),
+        });
+    }
+    let bucket_len = if format_version >= 2 { 2 } else { 1 };
+    let bucket = skill_name
+        .chars()
+        .take(bucket_len)
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    Ok(registry_root
+        .join("index")
+        .join(bucket)
+        .join(format!("{skill_name}.toml")))
 }
 
 /// Parse the `[registries]` table from a raw TOML config string.
@@ -120,6 +269,7 @@ pub fn resolve_skill_from_registry_sources(
             version: selected.version.to_string(),
             git_ref: selected.git_ref.clone(),
             commit: selected.commit.clone(),
+            requires: selected.requires.clone(),
         });
     }
 
@@ -131,6 +281,410 @@ pub fn resolve_skill_from_registry_sources(
     })
 }
 
+/// Upper bound on re-selection rounds in [`resolve_skill_set`]; reached
+/// only when requirements keep flipping each other's choices.
+const MAX_RESOLUTION_ROUNDS: usize = 64;
+
+/// Resolve `requirements` and everything they transitively require into
+/// one version per skill that satisfies every constraint on it.
+///
+/// Each round collects the constraints of `requirements` plus the
+/// `requires` of every version picked in the previous round, then picks
+/// the highest non-yanked version meeting all constraints on each name,
+/// until the picks stop changing. A name that is in no registry, or has no
+/// matching version, is left out of the round rather than failing at once,
+/// since it may only be wanted by a version that was just replaced; it is
+/// reported only once the picks, and with them the constraints, have
+/// settled. The resolver
+/// is greedy: it never backtracks to an older version of a skill to make
+/// room for the constraints of another. Names in `provided` are satisfied
+/// outside the registries (e.g. skills configured with an explicit source)
+/// and are neither looked up nor returned. The result is sorted by name.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::Resolution`] when a required skill is not in
+/// any registry, when no version meets all constraints on a skill once
+/// the picks have settled, or when the picks do not settle;
+/// [`RegistryError::Config`] for an invalid constraint.
+pub fn resolve_skill_set(
+    sources: &[RegistrySource],
+    requirements: &[SkillRequirement],
+    provided: &BTreeSet<String>,
+) -> Result<Vec<ResolvedDependency>, RegistryError> {
+    let mut ordered_sources = sources.to_vec();
+    ordered_sources.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut entries = BTreeMap::new();
+    let mut selected = BTreeMap::<String, ResolvedDependency>::new();
+    let mut conflict = None;
+    for _ in 0..MAX_RESOLUTION_ROUNDS {
+        let mut wanted = BTreeMap::<String, Vec<SkillRequirement>>::new();
+        let picked_requirements = selected.values().flat_map(|picked| {
+            picked
+                .skill
+                .requires
+                .iter()
+                .map(|(name, constraint)| SkillRequirement {
+                    name: name.clone(),
+                    constraint: constraint.clone(),
+                    required_by: Some(picked.name.clone()),
+                    registry: None,
+                })
+        });
+        for requirement in requirements.iter().cloned().chain(picked_requirements) {
+            if !provided.contains(&requirement.name) {
+                wanted
+                    .entry(requirement.name.clone())
+                    .or_default()
+                    .push(requirement);
+            }
+        }
+
+        let mut next = BTreeMap::new();
+        let mut round_conflict = None;
+        for (name, constraints) in &wanted {
+            let registry = constraints
+                .iter()
+                .find_map(|requirement| requirement.registry.as_deref());
+            let key = (name.clone(), registry.map(str::to_string));
+            if !entries.contains_key(&key) {
+                match find_skill_index_entry(&ordered_sources, name, registry, constraints) {
+                    Ok(found) => {
+                        entries.insert(key.clone(), found);
+                    }
+                    Err(err @ RegistryError::Resolution { .. }) => {
+                        round_conflict.get_or_insert(err);
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            let (source, entry) = &entries[&key];
+            let picked = match select_version_for_all(entry, name, constraints) {
+                Ok(picked) => picked,
+                Err(err @ RegistryError::Resolution { .. }) => {
+                    round_conflict.get_or_insert(err);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let mut required_by = constraints
+                .iter()
+                .filter_map(|requirement| requirement.required_by.clone())
+                .collect::<Vec<_>>();
+            required_by.sort();
+            required_by.dedup();
+            next.insert(
+                name.clone(),
+                ResolvedDependency {
+                    name: name.clone(),
+                    skill: ResolvedSkill {
+                        registry_name: source.name.clone(),
+                        registry_priority: source.priority,
+                        repo: entry.skill.repo.clone(),
+                        subpath: entry
+                            .skill
+                            .subpath
+                            .clone()
+                            .unwrap_or_else(|| ".".to_string()),
+                        version: picked.version.to_string(),
+                        git_ref: picked.git_ref.clone(),
+                        commit: picked.commit.clone(),
+                        requires: picked.requires.clone(),
+                    },
+                    required_by,
+                },
+            );
+        }
+        if next == selected {
+            return match round_conflict {
+                Some(err) => Err(err),
+                None => Ok(selected.into_values().collect()),
+            };
+        }
+        selected = next;
+        conflict = round_conflict;
+    }
+
+    Err(conflict.unwrap_or_else(|| RegistryError::Resolution {
+        detail: format!(
+            "dependency resolution did not settle for {}",
+            selected
+                .keys()
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }))
+}
+
+/// The first source, in priority order, listing `name`; only `registry`
+/// when one is pinned.
+fn find_skill_index_entry(
+    sources: &[RegistrySource],
+    name: &str,
+    registry: Option<&str>,
+    constraints: &[SkillRequirement],
+) -> Result<(RegistrySource, SkillIndexEntry), RegistryError> {
+    let mut searched = Vec::new();
+    for source in sources
+        .iter()
+        .filter(|source| registry.is_none_or(|registry| source.name == registry))
+    {
+        searched.push(format!("{}({})", source.name, source.priority));
+        if let Some(entry) = load_skill_index_entry(source, name)? {
+            return Ok((source.clone(), entry));
+        }
+    }
+    let required_by = constraints
+        .iter()
+        .filter_map(|requirement| requirement.required_by.as_deref())
+        .map(|dependent| format!("`{dependent}`"))
+        .collect::<Vec<_>>();
+    let subject = if required_by.is_empty() {
+        format!("skill `{name}`")
+    } else {
+        format!("skill `{name}` required by {}", required_by.join(", "))
+    };
+    Err(RegistryError::Resolution {
+        detail: format!(
+            "{subject} not found in configured registries: {}",
+            searched.join(", ")
+        ),
+    })
+}
+
+/// The highest non-yanked version of `entry` meeting every constraint.
+fn select_version_for_all<'a>(
+    entry: &'a SkillIndexEntry,
+    name: &str,
+    constraints: &[SkillRequirement],
+) -> Result<&'a IndexedVersion, RegistryError> {
+    let candidates = entry
+        .versions
+        .iter()
+        .filter(|candidate| !candidate.yanked)
+        .collect::<Vec<_>>();
+    let mut matching = Vec::new();
+    for candidate in &candidates {
+        let mut matches_all = true;
+        for requirement in constraints {
+            if !version_satisfies(&candidate.version, &requirement.constraint)? {
+                matches_all = false;
+                break;
+            }
+        }
+        if matches_all {
+            matching.push(*candidate);
+        }
+    }
+    if let Some(picked) = matching
+        .into_iter()
+        .max_by(|left, right| left.version.cmp(&right.version))
+    {
+        return Ok(picked);
+    }
+
+    let wanted = constraints
+        .iter()
+        .map(|requirement| match &requirement.required_by {
+            Some(dependent) => format!("`{}` (required by `{dependent}`)", requirement.constraint),
+            None => format!("`{}` (configured)", requirement.constraint),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(RegistryError::Resolution {
+        detail: format!(
+            "no version of `{name}` satisfies {wanted}; available versions: {}",
+            available_versions(&candidates, |candidate| &candidate.version)
+        ),
+    })
+}
+
+/// Whether `version` meets `constraint`: an exact version or a semver
+/// requirement, as in [`select_matching_version`].
+fn version_satisfies(version: &Version, constraint: &str) -> Result<bool, RegistryError> {
+    let constraint = constraint.trim();
+    if let Ok(exact) = Version::parse(constraint) {
+        return Ok(*version == exact);
+    }
+    let requirement = VersionReq::parse(constraint).map_err(|err| RegistryError::Config {
+        detail: format!("invalid version constraint `{constraint}`: {err}"),
+    })?;
+    Ok(requirement.matches(version))
+}
+
+/// Collect the index entry of `skill_name` from every source that lists
+/// it, in source order, with the version `version_constraint` selects in
+/// each (the latest non-yanked version without a constraint).
+///
+/// # Errors
+///
+/// Returns [`RegistryError::Config`] for an invalid constraint and
+/// [`RegistryError::Resolution`] when an index entry cannot be parsed.
+pub fn lookup_skill_in_registry_sources(
+    sources: &[RegistrySource],
+    skill_name: &str,
+    version_constraint: Option<&str>,
+) -> Result<Vec<RegistrySkillInfo>, RegistryError> {
+    let mut found = Vec::new();
+    for source in sources {
+        let Some(entry) = load_skill_index_entry(source, skill_name)? else {
+            continue;
+        };
+        let (selected_version, selection_error) =
+            match select_version(&entry.versions, version_constraint) {
+                Ok(selected) => (Some(selected.version.to_string()), None),
+                Err(RegistryError::Resolution { detail }) => (None, Some(detail)),
+                Err(err) => return Err(err),
+            };
+        let mut versions = entry.versions;
+        versions.sort_by(|left, right| right.version.cmp(&left.version));
+        found.push(RegistrySkillInfo {
+            registry_name: source.name.clone(),
+            registry_priority: source.priority,
+            description: entry.skill.description,
+            license: entry.skill.license,
+            tags: entry.skill.tags,
+            deprecated: entry.skill.deprecated,
+            repo: entry.skill.repo,
+            subpath: entry.skill.subpath.unwrap_or_else(|| ".".to_string()),
+            versions: versions
+                .into_iter()
+                .map(|item| RegistrySkillVersion {
+                    version: item.version.to_string(),
+                    git_ref: item.git_ref,
+                    commit: item.commit,
+                    yanked: item.yanked,
+                })
+                .collect(),
+            selected_version,
+            selection_error,
+        });
+    }
+    Ok(found)
+}
+
+/// Search every index entry of `sources` for `query`. Each
+/// whitespace-separated term must match the entry's name, one of its
+/// tags, or its description; exact and prefix name matches rank above
+/// tag matches, which rank above description matches. Ties go to the
+/// higher-priority registry, then to the name. Entries that fail to parse
+/// are skipped and reported rather than failing the search.
+pub fn search_registry_sources(
+    sources: &[RegistrySource],
+    query: &str,
+) -> Result<RegistrySearch, RegistryError> {
+    let terms = query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return Err(RegistryError::Config {
+            detail: "search query must not be empty".to_string(),
+        });
+    }
+
+    let mut search = RegistrySearch::default();
+    for source in sources {
+        for index_path in index_entry_paths(&source.root.join("index"))? {
+            let entry = match parse_skill_index_entry(&index_path) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    search.skipped.push(format!("{}: {err}", source.name));
+                    continue;
+                }
+            };
+            let Some(score) = search_score(&entry.skill, &terms) else {
+                continue;
+            };
+            let latest_version = select_version(&entry.versions, None)
+                .ok()
+                .map(|selected| selected.version.to_string());
+            search.hits.push(SkillSearchHit {
+                registry_name: source.name.clone(),
+                registry_priority: source.priority,
+                name: entry.skill.name,
+                description: entry.skill.description,
+                tags: entry.skill.tags,
+                latest_version,
+                deprecated: entry.skill.deprecated,
+                score,
+            });
+        }
+    }
+    search.hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.registry_priority.cmp(&a.registry_priority))
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.registry_name.cmp(&b.registry_name))
+    });
+    Ok(search)
+}
+
+/// Sum of the best match of every term, or `None` when a term matches
+/// nothing.
+fn search_score(skill: &RawIndexedSkill, terms: &[String]) -> Option<u32> {
+    let name = skill.name.to_lowercase();
+    let description = skill
+        .description
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    let tags = skill
+        .tags
+        .iter()
+        .map(|tag| tag.to_lowercase())
+        .collect::<Vec<_>>();
+    terms.iter().try_fold(0, |total, term| {
+        let score = if name == *term {
+            100
+        } else if name.starts_with(term.as_str()) {
+            60
+        } else if name.contains(term.as_str()) {
+            40
+        } else if tags.iter().any(|tag| tag == term) {
+            30
+        } else if tags.iter().any(|tag| tag.contains(term.as_str())) {
+            15
+        } else if description.contains(term.as_str()) {
+            10
+        } else {
+            return None;
+        };
+        Some(total + score)
+    })
+}
+
+/// Every `*.toml` file below `index_root`, whatever its bucket layout.
+fn index_entry_paths(index_root: &Path) -> Result<Vec<PathBuf>, RegistryError> {
+    let mut paths = Vec::new();
+    let mut pending = vec![index_root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "toml") {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
 #[derive(Debug, Deserialize)]
 struct RawRegistryConfigFile {
     #[serde(default)]
@@ -153,6 +707,12 @@ struct RawSkillIndexEntry {
 #[derive(Debug, Deserialize)]
 struct RawIndexedSkill {
     name: String,
+    description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    license: Option<String>,
+    #[serde(default)]
+    deprecated: bool,
     repo: String,
     subpath: Option<String>,
 }
@@ -164,6 +724,8 @@ struct RawIndexedVersion {
     git_ref: String,
     commit: String,
     yanked: Option<bool>,
+    #[serde(default)]
+    requires: BTreeMap<String, String>,
 }
 
 #[derive(Debug)]
@@ -178,6 +740,7 @@ struct IndexedVersion {
     git_ref: String,
     commit: String,
     yanked: bool,
+    requires: BTreeMap<String, String>,
 }
 
 fn load_skill_index_entry(
@@ -189,15 +752,7 @@ fn load_skill_index_entry(
         return Ok(None);
     }
 
-    let entry_raw = fs::read_to_string(&index_path)?;
-    let entry: RawSkillIndexEntry =
-        toml::from_str(&entry_raw).map_err(|err| RegistryError::Resolution {
-            detail: format!(
-                "failed to parse registry index entry `{}`: {err}",
-                index_path.display()
-            ),
-        })?;
-
+    let entry = parse_skill_index_entry(&index_path)?;
     if entry.skill.name != skill_name {
         return Err(RegistryError::Resolution {
             detail: format!(
@@ -208,6 +763,18 @@ fn load_skill_index_entry(
             ),
         });
     }
+    Ok(Some(entry))
+}
+
+fn parse_skill_index_entry(index_path: &Path) -> Result<SkillIndexEntry, RegistryError> {
+    let entry_raw = fs::read_to_string(index_path)?;
+    let entry: RawSkillIndexEntry =
+        toml::from_str(&entry_raw).map_err(|err| RegistryError::Resolution {
+            detail: format!(
+                "failed to parse registry index entry `{}`: {err}",
+                index_path.display()
+            ),
+        })?;
 
     let mut versions = Vec::with_capacity(entry.versions.len());
     for item in entry.versions {
@@ -223,25 +790,35 @@ fn load_skill_index_entry(
             git_ref: item.git_ref,
             commit: item.commit,
             yanked: item.yanked.unwrap_or(false),
+            requires: item.requires,
         });
     }
 
-    Ok(Some(SkillIndexEntry {
+    Ok(SkillIndexEntry {
         skill: entry.skill,
         versions,
-    }))
+    })
 }
 
 fn skill_index_path(registry_root: &Path, skill_name: &str) -> Result<PathBuf, RegistryError> {
-    let mut chars = skill_name.chars();
-    let first = chars.next().ok_or_else(|| RegistryError::Config {
-        detail: "skill name must not be empty".to_string(),
-    })?;
+    // The CLI reports a missing or unreadable manifest before resolving;
+    // here it just means the original layout.
+    let format_version = read_index_format_version(registry_root)
+        .ok()
+        .flatten()
+        .unwrap_or(1);
+    if SUPPORTED_INDEX_FORMAT_VERSIONS.contains(&format_version) {
+        return index_entry_path(registry_root, skill_name, format_version);
+    }
 
-    Ok(registry_root
-        .join("index")
-        .join(first.to_ascii_lowercase().to_string())
-        .join(format!("{skill_name}.toml")))
+    // Best effort for a newer format: probe the layouts we know.
+    let newest = index_entry_path(registry_root, skill_name, 2)?;
+    let oldest = index_entry_path(registry_root, skill_name, 1)?;
+    Ok(if !newest.exists() && oldest.exists() {
+        oldest
+    } else {
+        newest
+    })
 }
 
 fn select_version<'a>(
@@ -259,6 +836,26 @@ fn select_version<'a>(
         });
     }
 
+    select_matching_version(
+        candidates,
+        |candidate| &candidate.version,
+        version_constraint,
+    )
+}
+
+/// Pick the highest of `candidates` that satisfies `version_constraint`:
+/// an exact version or a semver requirement. Without a constraint the
+/// latest stable version wins, falling back to the latest pre-release.
+///
+/// Shared by registry lookups and `version` constraints on git sources,
+/// so both resolve the same way.
+pub fn select_matching_version<T>(
+    candidates: Vec<T>,
+    version_of: impl Fn(&T) -> &Version,
+    version_constraint: Option<&str>,
+) -> Result<T, RegistryError> {
+    let available = available_versions(&candidates, &version_of);
+
     if let Some(raw_constraint) = version_constraint {
         let constraint = raw_constraint.trim();
         if constraint.is_empty() {
@@ -270,12 +867,11 @@ fn select_version<'a>(
         if let Ok(exact) = Version::parse(constraint) {
             let matched = candidates
                 .into_iter()
-                .filter(|candidate| candidate.version == exact)
-                .max_by(|left, right| left.version.cmp(&right.version));
+                .filter(|candidate| *version_of(candidate) == exact)
+                .max_by(|left, right| version_of(left).cmp(version_of(right)));
             return matched.ok_or_else(|| RegistryError::Resolution {
                 detail: format!(
-                    "no version matched exact constraint `{constraint}`; available versions: {}",
-                    available_versions(versions)
+                    "no version matched exact constraint `{constraint}`; available versions: {available}"
                 ),
             });
         }
@@ -285,57 +881,53 @@ fn select_version<'a>(
         })?;
         let matched = candidates
             .into_iter()
-            .filter(|candidate| requirement.matches(&candidate.version))
-            .max_by(|left, right| left.version.cmp(&right.version));
+            .filter(|candidate| requirement.matches(version_of(candidate)))
+            .max_by(|left, right| version_of(left).cmp(version_of(right)));
         return matched.ok_or_else(|| RegistryError::Resolution {
             detail: format!(
-                "no version matched constraint `{constraint}`; available versions: {}",
-                available_versions(versions)
+                "no version matched constraint `{constraint}`; available versions: {available}"
             ),
         });
     }
 
-    let stable_candidates = candidates
-        .iter()
-        .copied()
-        .filter(|candidate| candidate.version.pre.is_empty())
-        .collect::<Vec<_>>();
-    if let Some(stable) = stable_candidates
-        .into_iter()
-        .max_by(|left, right| left.version.cmp(&right.version))
-    {
-        return Ok(stable);
-    }
-
-    candidates
+    let (stable, prerelease): (Vec<T>, Vec<T>) = candidates
         .into_iter()
-        .max_by(|left, right| left.version.cmp(&right.version))
+        .partition(|candidate| version_of(candidate).pre.is_empty());
+    let pool = if stable.is_empty() {
+        prerelease
+    } else {
+        stable
+    };
+    pool.into_iter()
+        .max_by(|left, right| version_of(left).cmp(version_of(right)))
         .ok_or_else(|| RegistryError::Resolution {
             detail: "no version candidates available".to_string(),
         })
 }
 
-fn available_versions(versions: &[IndexedVersion]) -> String {
-    let mut available = versions
-        .iter()
-        .filter(|candidate| !candidate.yanked)
-        .map(|candidate| candidate.version.clone())
-        .collect::<Vec<_>>();
+fn available_versions<T>(candidates: &[T], version_of: impl Fn(&T) -> &Version) -> String {
+    let mut available = candidates.iter().map(&version_of).collect::<Vec<_>>();
     available.sort_unstable();
     available.reverse();
     available
         .into_iter()
-        .map(|version| version.to_string())
+        .map(Version::to_string)
         .collect::<Vec<_>>()
         .join(", ")
 }
 
-fn validate_git_url(url: &str, registry_name: &str) -> Result<(), RegistryError> {
+/// Whether `url` is a git URL registries may point at: `https://`,
+/// `ssh://`, scp-like `git@host:path`, or `file://`.
+pub fn is_supported_git_url(url: &str) -> bool {
     let is_https = url.starts_with("https://");
     let is_ssh = url.starts_with("ssh://");
     let is_scp_like = url.starts_with("git@") && url.contains(':');
     let is_file = url.starts_with("file://");
-    if is_https || is_ssh || is_scp_like || is_file {
+    is_https || is_ssh || is_scp_like || is_file
+}
+
+fn validate_git_url(url: &str, registry_name: &str) -> Result<(), RegistryError> {
+    if is_supported_git_url(url) {
         return Ok(());
     }
 
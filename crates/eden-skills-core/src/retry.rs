@@ -0,0 +1,123 @@
+//! Retries and timeouts for network operations.
+//!
+//! Every clone, fetch, and download runs under a [`RetryPolicy`] built
+//! from `[reactor] retries`, `backoff`, and `timeout_secs`. Only failures
+//! that look transient (unreachable hosts, dropped connections, timeouts)
+//! are retried; a missing repository or rejected credentials fail on the
+//! first attempt. The delay before each retry doubles, starting at
+//! `backoff`.
+
+use std::time::Duration;
+
+pub const DEFAULT_RETRIES: u32 = 2;
+/// Upper bound keeps a dead remote from stalling a sync for minutes.
+pub const MAX_RETRIES: u32 = 10;
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Failure messages (lowercased) that are worth another attempt.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "timed out",
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "connection refused",
+    "connection reset",
+    "connection was reset",
+    "early eof",
+    "the remote end hung up",
+    "unexpected disconnect",
+    "rpc failed",
+    "error sending request",
+    "502 bad gateway",
+    "503 service unavailable",
+    "504 gateway timeout",
+];
+
+/// How often and how long to try a single network operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one.
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each one after it.
+    pub backoff: Duration,
+    /// Limit for one attempt; `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT_SECS)),
+        }
+    }
+}
+
+/// Result of [`RetryPolicy::run`] with the number of attempts it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryOutcome<T> {
+    pub attempts: u32,
+    pub result: Result<T, String>,
+}
+
+impl<T> RetryOutcome<T> {
+    /// The result alone, for callers that only need the detail message.
+    pub fn into_result(self) -> Result<T, String> {
+        self.result
+    }
+
+    /// The result, with a failure turned into `E` from its detail message
+    /// and the number of attempts made.
+    pub fn map_failure<E>(self, error: impl FnOnce(String, u32) -> E) -> Result<T, E> {
+        let attempts = self.attempts;
+        self.result.map_err(|detail| error(detail, attempts))
+    }
+}
+
+impl RetryPolicy {
+    /// Run `operation` until it succeeds, fails with a non-transient
+    /// error, or the retries are used up, sleeping between attempts. A
+    /// failure after more than one attempt says how many were made.
+    pub fn run<T>(&self, mut operation: impl FnMut() -> Result<T, String>) -> RetryOutcome<T> {
+        let mut attempts = 0;
+        let mut delay = self.backoff;
+        loop {
+            attempts += 1;
+            match operation() {
+                Ok(value) => {
+                    return RetryOutcome {
+                        attempts,
+                        result: Ok(value),
+                    }
+                }
+                Err(detail) if attempts <= self.retries && is_transient_failure(&detail) => {
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                Err(detail) if attempts > 1 => {
+                    return RetryOutcome {
+                        attempts,
+                        result: Err(format!("{detail} (failed after {attempts} attempts)")),
+                    }
+                }
+                Err(detail) => {
+                    return RetryOutcome {
+                        attempts,
+                        result: Err(detail),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a failure message describes a condition that may clear up on
+/// its own, like a dropped connection or a timeout.
+pub fn is_transient_failure(detail: &str) -> bool {
+    let lower = detail.to_ascii_lowercase();
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
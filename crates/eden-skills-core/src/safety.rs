@@ -11,11 +11,11 @@ use std::io::Read;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::Config;
 use crate::error::EdenError;
+use crate::git::{self, GitBackend};
 use crate::paths::resolve_path_string;
 use crate::source::{resolve_skill_source_path, resolve_skill_storage_root};
 
@@ -67,7 +67,7 @@ pub fn analyze_skills(
 
         let (license_status, license_hint) = detect_license_status(&repo_path);
         let risk_labels = detect_risk_labels(&source_path).unwrap_or_default();
-        let commit_sha = read_commit_sha(&repo_path);
+        let commit_sha = read_commit_sha(config.git.backend, &repo_path);
 
         reports.push(SkillSafetyReport {
             skill_id: skill.id.clone(),
@@ -216,28 +216,11 @@ fn detect_file_risk(
     Ok(())
 }
 
-fn read_commit_sha(repo_path: &Path) -> Option<String> {
+fn read_commit_sha(backend: GitBackend, repo_path: &Path) -> Option<String> {
     if !repo_path.join(".git").exists() {
         return None;
     }
-
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(repo_path)
-        .arg("rev-parse")
-        .arg("HEAD")
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-
-    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if sha.is_empty() {
-        None
-    } else {
-        Some(sha)
-    }
+    git::rev_parse(backend, repo_path, "HEAD")
 }
 
 fn unix_now() -> Result<u64, EdenError> {
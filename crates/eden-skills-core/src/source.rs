@@ -4,19 +4,67 @@
 //! unique `(repo_url, ref)` pair maps to a single cache directory keyed by
 //! [`repo_cache_key`].  Synchronization is parallelized through the
 //! [`SkillReactor`] and supports clone, fetch, checkout, and
-//! fast-forward pull stages.
+//! fast-forward pull stages.  Locked syncs instead check out the exact
+//! commit pinned for each cache entry, optionally without any network
+//! access.
+//!
+//! Remote sources are cloned blobless and sparse: a cache entry only
+//! checks out the subpaths its skills reference. Syncs widen the sparse
+//! set as skills sharing the entry are added but never narrow it, since
+//! a sync may cover only some of those skills (a single `install`, or
+//! another config on the same storage root); `clean` drops the paths of
+//! removed skills through [`narrow_sparse_checkouts`]. A skill whose
+//! subpath is the repository root turns the entry into a full checkout.
+//!
+//! Git operations run through the backend selected by `[git] backend`
+//! (see [`crate::git`]); the embedded backend checks out sparsely too but
+//! fetches every blob.
+//!
+//! Git sources with a `version` constraint follow tags instead of a ref:
+//! every sync lists the remote's tags, picks the newest one whose name
+//! (minus an optional `v`) satisfies the constraint, and checks it out
+//! detached. The chosen tag is remembered next to the checkout so the
+//! lock file can record it (see [`read_resolved_tag`]).
+//!
+//! Cache entries are worktrees of a shared object store: every ref of a
+//! repository is checked out of one bare repository under `.objects/`
+//! (see [`object_store_dir`]), so tracking several refs or versions of a
+//! repo fetches its objects once. Syncs of entries sharing a store take
+//! turns (see [`lock_object_store`]).
+//!
+//! Archive sources share the `.repos/` layout: their cache entry is keyed
+//! by the archive URL and checksum and holds the extracted archive
+//! instead of a checkout (see [`crate::archive`]).
+//!
+//! Clones, fetches, and downloads go through the `[mirrors]` rewrite
+//! rules (see [`crate::mirrors`]); cache keys stay on the canonical URL.
+//! Each of them is retried and timed out per `[reactor]` (see
+//! [`crate::retry`]), and at most `[reactor] host_concurrency` of them
+//! run against one host at a time.
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::config::{Config, SkillConfig};
-use crate::error::ReactorError;
+use crate::archive::{self, ArchiveFormat};
+use semver::Version;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::config::{decode_version_ref, encode_archive_ref, Config, SkillConfig};
+use crate::digest::sha256_hex;
+use crate::error::{EdenError, ReactorError, RegistryError};
+use crate::git::{self, CloneOptions, GitBackend, RemoteTag, SignatureTrust};
+use crate::mirrors::{rewrite_url, MirrorRule};
 use crate::paths::{normalize_lexical, resolve_path_string};
-use crate::reactor::SkillReactor;
+use crate::reactor::{HostLimiter, SkillReactor};
+use crate::registry::select_matching_version;
+use crate::retry::{is_transient_failure, RetryOutcome, RetryPolicy};
 
+/// Directory below the storage root that holds the shared object stores.
+pub const OBJECT_STORE_DIR: &str = ".objects";
 const FETCHED_AT_FILE: &str = ".eden-fetched-at";
+const RESOLVED_TAG_FILE: &str = ".eden-resolved-tag";
 const DEFAULT_FRESHNESS_SECS: u64 = 300;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -34,6 +82,9 @@ pub struct SyncFailure {
     pub stage: SyncFailureStage,
     pub repo_dir: String,
     pub detail: String,
+    /// How many times the failed operation was tried; above 1 when a
+    /// transient failure was retried.
+    pub attempts: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -41,6 +92,13 @@ pub enum SyncFailureStage {
     Clone,
     Fetch,
     Checkout,
+    Download,
+    Verify,
+    Extract,
+    /// The source is not cached and network access is disabled.
+    Uncached,
+    /// The checkout is not signed by a trusted key.
+    Signature,
     Runtime,
 }
 
@@ -50,6 +108,11 @@ impl SyncFailureStage {
             Self::Clone => "clone",
             Self::Fetch => "fetch",
             Self::Checkout => "checkout",
+            Self::Download => "download",
+            Self::Verify => "verify",
+            Self::Extract => "extract",
+            Self::Uncached => "uncached",
+            Self::Signature => "signature",
             Self::Runtime => "runtime",
         }
     }
@@ -66,6 +129,8 @@ enum SyncOutcome {
 struct SyncOperationError {
     stage: SyncFailureStage,
     detail: String,
+    /// How many times the failed operation was tried.
+    attempts: u32,
 }
 
 impl From<ReactorError> for SyncOperationError {
@@ -73,6 +138,7 @@ impl From<ReactorError> for SyncOperationError {
         Self {
             stage: SyncFailureStage::Runtime,
             detail: value.to_string(),
+            attempts: 1,
         }
     }
 }
@@ -85,11 +151,27 @@ struct SyncTask {
     repo_dir: PathBuf,
     skip: bool,
     force_refresh: bool,
+    pinned_commit: Option<String>,
+    allow_network: bool,
+    /// Subpaths to check out; `None` for the full tree.
+    sparse_paths: Option<BTreeSet<String>>,
+    backend: GitBackend,
+    /// Expected checksum when the source is a release archive.
+    archive_sha256: Option<String>,
+    /// Semver constraint when the source follows tags.
+    version: Option<String>,
+    /// Trusted keys when a skill on this entry sets `verify_signature`.
+    trust: Option<SignatureTrust>,
+    retry: RetryPolicy,
 }
 
-#[derive(Debug)]
-struct GitOutput {
-    stdout: String,
+/// Commits to check out during a locked sync, keyed by [`repo_cache_key`].
+/// With `allow_network` false, repos without a pin are used as cached.
+#[derive(Debug, Clone, Copy)]
+pub struct LockedSync<'a> {
+    pub pins: &'a HashMap<String, String>,
+    /// When false, a missing clone or commit fails instead of fetching.
+    pub allow_network: bool,
 }
 
 /// Normalize a git URL into a flat, lowercase, filesystem-safe string
@@ -133,13 +215,15 @@ pub fn sanitize_ref(reference: &str) -> String {
 }
 
 /// Build the canonical cache key for a `(repo_url, ref)` pair:
-/// `normalize_repo_url(url)@sanitize_ref(ref)`.
+/// `normalize_repo_url(url)@sanitize_ref(ref)`. Version constraints are
+/// keyed by a digest instead, since sanitizing would strip the operators
+/// that tell `^1.2` and `~1.2` apart.
 pub fn repo_cache_key(repo_url: &str, reference: &str) -> String {
-    format!(
-        "{}@{}",
-        normalize_repo_url(repo_url),
-        sanitize_ref(reference)
-    )
+    let reference = match decode_version_ref(reference) {
+        Some(constraint) => format!("semver-{}", &sha256_hex(constraint.as_bytes())[..12]),
+        None => sanitize_ref(reference),
+    };
+    format!("{}@{reference}", normalize_repo_url(repo_url))
 }
 
 /// Resolve the absolute path to a repo cache directory under
@@ -152,6 +236,38 @@ pub fn resolve_repo_cache_root(storage_root: &Path, repo_url: &str, reference: &
     )
 }
 
+/// The object store shared by every ref of the repository cached at
+/// `repo_dir`: `<storage_root>/.objects/<normalized_url>.git`. `None`
+/// when `repo_dir` is not named by a [`repo_cache_key`].
+pub fn object_store_dir(repo_dir: &Path) -> Option<PathBuf> {
+    let key = repo_dir.file_name()?.to_str()?;
+    let (repo, _) = key.rsplit_once('@')?;
+    Some(
+        repo_dir
+            .parent()?
+            .parent()?
+            .join(OBJECT_STORE_DIR)
+            .join(format!("{repo}.git")),
+    )
+}
+
+/// Wait until no other sync in this process works on the object store
+/// of `repo_dir`; concurrent fetches into one store would race on its
+/// refs, `shallow` file, and config. The store stays claimed until the
+/// returned guard is dropped.
+pub async fn lock_object_store(repo_dir: &Path) -> Option<OwnedMutexGuard<()>> {
+    static STORES: OnceLock<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let store = object_store_dir(repo_dir)?;
+    let lock = {
+        let mut stores = STORES
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        Arc::clone(stores.entry(store).or_default())
+    };
+    Some(lock.lock_owned().await)
+}
+
 /// Resolve the storage root for a single skill — either the repo cache
 /// directory (for remote sources) or `<storage_root>/<skill_id>` (for
 /// local absolute-path sources).
@@ -203,6 +319,7 @@ pub async fn sync_sources_async(
         SkillReactor::default(),
         &skip_repos,
         false,
+        None,
     )
     .await
 }
@@ -217,7 +334,15 @@ pub async fn sync_sources_async_with_reactor(
     force_refresh: bool,
 ) -> Result<SyncSummary, ReactorError> {
     let skip_repos = HashSet::new();
-    sync_sources_async_inner(config, config_dir, reactor, &skip_repos, force_refresh).await
+    sync_sources_async_inner(
+        config,
+        config_dir,
+        reactor,
+        &skip_repos,
+        force_refresh,
+        None,
+    )
+    .await
 }
 
 /// Full-featured async sync entry point.  Deduplicates by
@@ -229,7 +354,51 @@ pub async fn sync_sources_async_with_reactor_skipping_repos(
     reactor: SkillReactor,
     skip_repos: &HashSet<String>,
 ) -> Result<SyncSummary, ReactorError> {
-    sync_sources_async_inner(config, config_dir, reactor, skip_repos, false).await
+    sync_sources_async_inner(config, config_dir, reactor, skip_repos, false, None).await
+}
+
+/// Bring every cached repo to the commit pinned in `locked.pins` instead
+/// of following its ref.  Repos without a pin are synced normally, unless
+/// `locked.allow_network` is false, in which case they are left as-is
+/// and missing ones fail with [`SyncFailureStage::Uncached`].
+/// Already-cached commits are checked out without fetching.
+pub async fn sync_sources_locked_async(
+    config: &Config,
+    config_dir: &Path,
+    reactor: SkillReactor,
+    locked: LockedSync<'_>,
+) -> Result<SyncSummary, ReactorError> {
+    let skip_repos = HashSet::new();
+    sync_sources_async_inner(
+        config,
+        config_dir,
+        reactor,
+        &skip_repos,
+        false,
+        Some(locked),
+    )
+    .await
+}
+
+/// Sync without touching the network: cached repos and archives are used
+/// as they are, and every source missing from the cache is reported as a
+/// [`SyncFailureStage::Uncached`] failure.
+pub async fn sync_sources_offline_async(
+    config: &Config,
+    config_dir: &Path,
+    reactor: SkillReactor,
+) -> Result<SyncSummary, ReactorError> {
+    let pins = HashMap::new();
+    sync_sources_locked_async(
+        config,
+        config_dir,
+        reactor,
+        LockedSync {
+            pins: &pins,
+            allow_network: false,
+        },
+    )
+    .await
 }
 
 async fn sync_sources_async_inner(
@@ -238,6 +407,7 @@ async fn sync_sources_async_inner(
     reactor: SkillReactor,
     skip_repos: &HashSet<String>,
     force_refresh: bool,
+    locked: Option<LockedSync<'_>>,
 ) -> Result<SyncSummary, ReactorError> {
     let storage_root = resolve_path_string(&config.storage_root, config_dir).map_err(|err| {
         ReactorError::Config {
@@ -245,6 +415,12 @@ async fn sync_sources_async_inner(
         }
     })?;
     tokio::fs::create_dir_all(&storage_root).await?;
+    let trust = config
+        .git
+        .signature_trust(config_dir)
+        .map_err(|err| ReactorError::Config {
+            detail: err.to_string(),
+        })?;
 
     let mut grouped_tasks = BTreeMap::new();
     for skill in &config.skills {
@@ -252,9 +428,11 @@ async fn sync_sources_async_inner(
             continue;
         }
         let cache_key = repo_cache_key(&skill.source.repo, &skill.source.r#ref);
-        grouped_tasks.entry(cache_key).or_insert_with(|| SyncTask {
+        let pinned_commit = locked.and_then(|locked| locked.pins.get(&cache_key).cloned());
+        let allow_network = locked.is_none_or(|locked| locked.allow_network);
+        let task = grouped_tasks.entry(cache_key).or_insert_with(|| SyncTask {
             skill_id: skill.id.clone(),
-            repo_url: skill.source.repo.clone(),
+            repo_url: rewrite_url(&config.mirrors, &skill.source.repo),
             reference: skill.source.r#ref.clone(),
             repo_dir: resolve_repo_cache_root(
                 &storage_root,
@@ -263,7 +441,19 @@ async fn sync_sources_async_inner(
             ),
             skip: skip_repos.contains(&repo_cache_key(&skill.source.repo, &skill.source.r#ref)),
             force_refresh,
+            pinned_commit,
+            allow_network,
+            sparse_paths: Some(BTreeSet::new()),
+            backend: config.git.backend,
+            archive_sha256: skill.source.archive_sha256().map(str::to_string),
+            version: skill.source.version_constraint().map(str::to_string),
+            trust: None,
+            retry: config.reactor.retry_policy(),
         });
+        if skill.source.verify_signature {
+            task.trust = Some(trust.clone());
+        }
+        add_sparse_path(&mut task.sparse_paths, &skill.source.subpath);
     }
 
     if !grouped_tasks.is_empty() {
@@ -271,11 +461,13 @@ async fn sync_sources_async_inner(
     }
 
     let tasks = grouped_tasks.into_values().collect::<Vec<_>>();
+    let hosts = HostLimiter::new(config.reactor.host_concurrency);
 
     let outcomes = reactor
         .run_phase_a(tasks, move |task| {
             let reactor = reactor;
-            async move { sync_one_source(task, reactor).await }
+            let hosts = hosts.clone();
+            async move { sync_one_source(task, reactor, hosts).await }
         })
         .await?;
 
@@ -298,14 +490,92 @@ async fn sync_sources_async_inner(
 async fn sync_one_source(
     task: SyncTask,
     reactor: SkillReactor,
+    hosts: HostLimiter,
 ) -> Result<SyncOutcome, SyncFailure> {
-    if task.skip {
-        return Ok(SyncOutcome::Skipped);
+    if task.archive_sha256.is_some() {
+        return sync_one_archive_source(task, reactor, hosts).await;
+    }
+    let _store = lock_object_store(&task.repo_dir).await;
+    let Some(trust) = task.trust.clone() else {
+        return sync_one_git_source(task, reactor, hosts).await;
+    };
+    let skill_id = task.skill_id.clone();
+    let repo_dir = task.repo_dir.clone();
+    let backend = task.backend;
+    let previous_head = git::rev_parse(backend, &repo_dir, "HEAD");
+    let outcome = sync_one_git_source(task, reactor, hosts).await?;
+    // Every sync verifies, so a checkout cached before the policy was
+    // enabled is refused as well. A refused update is rolled back so
+    // symlinked targets keep pointing at the previous content.
+    let task_name = format!("verify signature of `{skill_id}`");
+    let verify_dir = repo_dir.clone();
+    reactor
+        .run_blocking(&task_name, move || {
+            git::verify_head_signature(&verify_dir, &trust).map_err(|detail| {
+                if let Some(previous_head) = &previous_head {
+                    let _ = git::checkout_detached(backend, &verify_dir, previous_head);
+                }
+                SyncOperationError {
+                    stage: SyncFailureStage::Signature,
+                    detail,
+                    attempts: 1,
+                }
+            })
+        })
+        .await
+        .map_err(|err| SyncFailure {
+            skill_id,
+            stage: err.stage,
+            repo_dir: repo_dir.display().to_string(),
+            detail: err.detail,
+            attempts: err.attempts,
+        })?;
+    Ok(outcome)
+}
+
+async fn sync_one_git_source(
+    task: SyncTask,
+    reactor: SkillReactor,
+    hosts: HostLimiter,
+) -> Result<SyncOutcome, SyncFailure> {
+    if task.pinned_commit.is_some() && !task.skip {
+        return sync_one_locked_source(task, reactor, hosts).await;
     }
 
     let repo_exists = task.repo_dir.join(".git").exists();
+    if !repo_exists && !task.skip && !task.allow_network {
+        return Err(SyncFailure {
+            skill_id: task.skill_id.clone(),
+            stage: SyncFailureStage::Uncached,
+            repo_dir: task.repo_dir.display().to_string(),
+            detail: uncached_detail(&task.repo_url, &task.repo_dir),
+            attempts: 1,
+        });
+    }
 
-    if repo_exists && !task.force_refresh && repo_is_fresh(&task.repo_dir) {
+    if task.skip
+        || !task.allow_network
+        || (repo_exists && !task.force_refresh && repo_is_fresh(&task.repo_dir))
+    {
+        // Skills sharing this entry may still have added subpaths.
+        if repo_exists {
+            let repo_dir = task.repo_dir.clone();
+            let sparse_paths = task.sparse_paths.clone();
+            let backend = task.backend;
+            let task_name = format!("update sparse checkout for `{}`", task.skill_id);
+            reactor
+                .run_blocking(&task_name, move || {
+                    widen_sparse_paths(backend, &repo_dir, sparse_paths.as_ref())
+                })
+                .await
+                .map_err(|err| SyncFailure {
+                    skill_id: task.skill_id.clone(),
+                    stage: err.stage,
+                    repo_dir: task.repo_dir.display().to_string(),
+                    detail: err.detail,
+                    attempts: err.attempts,
+                })?;
+        }
         return Ok(SyncOutcome::Skipped);
     }
 
@@ -313,11 +583,36 @@ async fn sync_one_source(
     let skill_id = task.skill_id.clone();
     let task_name = format!("sync source `{}`", task.skill_id);
 
-    let sync_result = if repo_exists {
+    let sparse_paths = task.sparse_paths.clone();
+    let backend = task.backend;
+    let retry = task.retry;
+    let _host_permit = hosts.acquire(&task.repo_url).await;
+    let sync_result = if let Some(constraint) = task.version.clone() {
+        let repo_url = task.repo_url.clone();
+        let repo_dir = task.repo_dir.clone();
+        reactor
+            .run_blocking(&task_name, move || {
+                sync_versioned_repo(
+                    backend,
+                    &repo_url,
+                    &repo_dir,
+                    &constraint,
+                    sparse_paths.as_ref(),
+                    retry,
+                )
+            })
+            .await
+    } else if repo_exists {
+        let repo_url = task.repo_url.clone();
         let repo_dir = task.repo_dir.clone();
         let reference = task.reference.clone();
         reactor
-            .run_blocking(&task_name, move || update_repo(&repo_dir, &reference))
+            .run_blocking(&task_name, move || {
+                point_origin(backend, &repo_dir, &repo_url)?;
+                let outcome = update_repo(backend, &repo_dir, &reference, retry)?;
+                widen_sparse_paths(backend, &repo_dir, sparse_paths.as_ref())?;
+                Ok(outcome)
+            })
             .await
     } else {
         let repo_url = task.repo_url.clone();
@@ -325,7 +620,14 @@ async fn sync_one_source(
         let repo_dir = task.repo_dir.clone();
         reactor
             .run_blocking(&task_name, move || {
-                clone_repo(&repo_url, &reference, &repo_dir)
+                clone_repo(
+                    backend,
+                    &repo_url,
+                    Some(&reference),
+                    &repo_dir,
+                    sparse_paths.as_ref(),
+                    retry,
+                )
             })
             .await
     };
@@ -340,14 +642,296 @@ async fn sync_one_source(
             stage: err.stage,
             repo_dir: repo_dir_display,
             detail: err.detail,
+            attempts: err.attempts,
         }),
     }
 }
 
+async fn sync_one_locked_source(
+    task: SyncTask,
+    reactor: SkillReactor,
+    hosts: HostLimiter,
+) -> Result<SyncOutcome, SyncFailure> {
+    let repo_dir_display = task.repo_dir.display().to_string();
+    let skill_id = task.skill_id.clone();
+    let task_name = format!("sync locked source `{}`", task.skill_id);
+    let repo_dir = task.repo_dir.clone();
+    let _host_permit = if task.allow_network {
+        hosts.acquire(&task.repo_url).await
+    } else {
+        None
+    };
+
+    reactor
+        .run_blocking(&task_name, move || {
+            checkout_locked_commit(&task, task.pinned_commit.as_deref().unwrap_or_default())
+        })
+        .await
+        .inspect(|outcome| {
+            if matches!(outcome, SyncOutcome::Cloned) {
+                write_fetched_at(&repo_dir);
+            }
+        })
+        .map_err(|err| SyncFailure {
+            skill_id,
+            stage: err.stage,
+            repo_dir: repo_dir_display,
+            detail: err.detail,
+            attempts: err.attempts,
+        })
+}
+
+/// Archives are pinned by their checksum, so an existing cache entry is
+/// always current; skip lists, freshness, and lock pins do not apply.
+async fn sync_one_archive_source(
+    task: SyncTask,
+    reactor: SkillReactor,
+    hosts: HostLimiter,
+) -> Result<SyncOutcome, SyncFailure> {
+    let repo_dir_display = task.repo_dir.display().to_string();
+    let skill_id = task.skill_id.clone();
+    let task_name = format!("fetch archive `{}`", task.skill_id);
+    let _host_permit = if task.allow_network && !task.repo_dir.is_dir() {
+        hosts.acquire(&task.repo_url).await
+    } else {
+        None
+    };
+
+    reactor
+        .run_blocking(&task_name, move || {
+            fetch_archive(
+                &task.repo_url,
+                task.archive_sha256.as_deref().unwrap_or_default(),
+                &task.repo_dir,
+                task.allow_network,
+                task.retry,
+            )
+        })
+        .await
+        .map_err(|err| SyncFailure {
+            skill_id,
+            stage: err.stage,
+            repo_dir: repo_dir_display,
+            detail: err.detail,
+            attempts: err.attempts,
+        })
+}
+
+/// Fetch an archive source into its repo cache entry outside a sync, e.g.
+/// to discover the skills it contains before any is configured. The
+/// download goes through `mirrors`; the cache entry, which is returned,
+/// is keyed by the canonical `url` and is retried per `retry`. Without
+/// `allow_network`, only an already-cached entry is returned.
+///
+/// # Errors
+///
+/// Returns [`EdenError::Runtime`] naming the failed stage when the
+/// download, checksum verification, or extraction fails.
+pub fn fetch_archive_source(
+    storage_root: &Path,
+    url: &str,
+    sha256: &str,
+    mirrors: &[MirrorRule],
+    allow_network: bool,
+    retry: RetryPolicy,
+) -> Result<PathBuf, EdenError> {
+    let repo_dir = resolve_repo_cache_root(storage_root, url, &encode_archive_ref(sha256));
+    std::fs::create_dir_all(storage_root.join(".repos"))?;
+    let fetch_url = rewrite_url(mirrors, url);
+    fetch_archive(&fetch_url, sha256, &repo_dir, allow_network, retry).map_err(|err| {
+        if err.stage == SyncFailureStage::Uncached {
+            return EdenError::Runtime(err.detail);
+        }
+        EdenError::Runtime(format!(
+            "archive {} failed for `{url}`: {}",
+            err.stage.as_str(),
+            err.detail
+        ))
+    })?;
+    Ok(repo_dir)
+}
+
+/// Download, verify, and extract an archive into `repo_dir`. Extraction
+/// goes to a sibling staging directory that is renamed into place, so an
+/// interrupted fetch never leaves a partial cache entry behind.
+fn fetch_archive(
+    url: &str,
+    sha256: &str,
+    repo_dir: &Path,
+    allow_network: bool,
+    retry: RetryPolicy,
+) -> Result<SyncOutcome, SyncOperationError> {
+    if repo_dir.is_dir() {
+        return Ok(SyncOutcome::Skipped);
+    }
+    if !allow_network {
+        return Err(SyncOperationError {
+            stage: SyncFailureStage::Uncached,
+            detail: uncached_detail(url, repo_dir),
+            attempts: 1,
+        });
+    }
+    let Some(format) = ArchiveFormat::from_url(url) else {
+        return Err(SyncOperationError {
+            stage: SyncFailureStage::Download,
+            detail: format!("`{url}` is not a .tar.gz, .tgz, or .zip archive"),
+            attempts: 1,
+        });
+    };
+
+    let bytes = retry
+        .run(|| archive::download(url, retry.timeout))
+        .map_failure(|detail, attempts| SyncOperationError {
+            stage: SyncFailureStage::Download,
+            detail,
+            attempts,
+        })?;
+    archive::verify_sha256(&bytes, sha256).map_err(|detail| SyncOperationError {
+        stage: SyncFailureStage::Verify,
+        detail: format!("`{url}`: {detail}"),
+        attempts: 1,
+    })?;
+
+    let mut staging = repo_dir.as_os_str().to_owned();
+    staging.push(".partial");
+    let staging = PathBuf::from(staging);
+    let extract_error = |detail: String| SyncOperationError {
+        stage: SyncFailureStage::Extract,
+        detail,
+        attempts: 1,
+    };
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|err| {
+            extract_error(format!("failed to remove `{}`: {err}", staging.display()))
+        })?;
+    }
+    if let Err(detail) = archive::extract(&bytes, format, &staging) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(extract_error(format!("`{url}`: {detail}")));
+    }
+    std::fs::rename(&staging, repo_dir).map_err(|err| {
+        let _ = std::fs::remove_dir_all(&staging);
+        extract_error(format!(
+            "failed to move extracted archive into `{}`: {err}",
+            repo_dir.display()
+        ))
+    })?;
+    Ok(SyncOutcome::Cloned)
+}
+
+/// Detail of an [`SyncFailureStage::Uncached`] failure for `url`.
+pub fn uncached_detail(url: &str, repo_dir: &Path) -> String {
+    format!(
+        "`{url}` is not cached at `{}` and network access is disabled",
+        repo_dir.display()
+    )
+}
+
+fn checkout_locked_commit(
+    task: &SyncTask,
+    commit: &str,
+) -> Result<SyncOutcome, SyncOperationError> {
+    let SyncTask {
+        repo_url,
+        reference,
+        repo_dir,
+        allow_network,
+        backend,
+        retry,
+        ..
+    } = task;
+    let (backend, retry, allow_network) = (*backend, *retry, *allow_network);
+    let sparse_paths = task.sparse_paths.as_ref();
+    let mut outcome = SyncOutcome::Skipped;
+    if !repo_dir.join(".git").exists() {
+        if !allow_network {
+            return Err(SyncOperationError {
+                stage: SyncFailureStage::Uncached,
+                detail: uncached_detail(repo_url, repo_dir),
+                attempts: 1,
+            });
+        }
+        // A version constraint is no ref; the pin is fetched afterwards.
+        let reference = task.version.is_none().then_some(reference.as_str());
+        clone_repo(backend, repo_url, reference, repo_dir, sparse_paths, retry)?;
+        outcome = SyncOutcome::Cloned;
+    }
+
+    if git::rev_parse(backend, repo_dir, "HEAD").as_deref() == Some(commit) {
+        widen_sparse_paths(backend, repo_dir, sparse_paths)?;
+        return Ok(outcome);
+    }
+    if !git::commit_exists(backend, repo_dir, commit) {
+        if !allow_network {
+            return Err(SyncOperationError {
+                stage: SyncFailureStage::Uncached,
+                detail: format!(
+                    "locked commit `{commit}` is not in the cache at `{}` and network access is disabled",
+                    repo_dir.display()
+                ),
+                attempts: 1,
+            });
+        }
+        point_origin(backend, repo_dir, repo_url)?;
+        fetch_commit(backend, repo_dir, commit, retry)?;
+    }
+
+    git::checkout_detached(backend, repo_dir, commit).map_err(|detail| SyncOperationError {
+        stage: SyncFailureStage::Checkout,
+        detail,
+        attempts: 1,
+    })?;
+    widen_sparse_paths(backend, repo_dir, sparse_paths)?;
+    if outcome == SyncOutcome::Skipped {
+        outcome = SyncOutcome::Updated;
+    }
+    Ok(outcome)
+}
+
+fn fetch_commit(
+    backend: GitBackend,
+    repo_dir: &Path,
+    commit: &str,
+    retry: RetryPolicy,
+) -> Result<(), SyncOperationError> {
+    let direct = retry.run(|| {
+        record_test_git_fetch_if_configured();
+        git::fetch_commit(backend, repo_dir, commit, retry.timeout)
+    });
+    if direct.result.is_ok() && git::commit_exists(backend, repo_dir, commit) {
+        return Ok(());
+    }
+
+    // Servers that refuse fetching by SHA still serve full history.
+    retry
+        .run(|| git::fetch_history(backend, repo_dir, retry.timeout))
+        .map_failure(|detail, attempts| SyncOperationError {
+            stage: SyncFailureStage::Fetch,
+            detail,
+            attempts,
+        })?;
+    if git::commit_exists(backend, repo_dir, commit) {
+        return Ok(());
+    }
+    Err(SyncOperationError {
+        stage: SyncFailureStage::Fetch,
+        detail: format!(
+            "locked commit `{commit}` was not found in the remote of `{}`",
+            repo_dir.display()
+        ),
+        attempts: 1,
+    })
+}
+
+/// Clone `repo_url` into `repo_dir` at `reference`, or at the remote
+/// `HEAD` when `None`.
 fn clone_repo(
+    backend: GitBackend,
     repo_url: &str,
-    reference: &str,
+    reference: Option<&str>,
     repo_dir: &Path,
+    sparse_paths: Option<&BTreeSet<String>>,
+    retry: RetryPolicy,
 ) -> Result<SyncOutcome, SyncOperationError> {
     if let Some(parent) = repo_dir.parent() {
         std::fs::create_dir_all(parent).map_err(|err| SyncOperationError {
@@ -356,145 +940,395 @@ fn clone_repo(
                 "failed to create repository parent directory `{}`: {err}",
                 parent.display()
             ),
+            attempts: 1,
         })?;
     }
 
-    record_test_git_clone_if_configured();
-    let branch_clone = run_git(
-        Command::new("git")
-            .arg("clone")
-            .arg("--depth")
-            .arg("1")
-            .arg("--branch")
-            .arg(reference)
-            .arg(repo_url)
-            .arg(repo_dir),
-        &format!(
-            "clone `{repo_url}` into `{}` with ref `{reference}`",
-            repo_dir.display()
-        ),
-    );
-    let branch_error = match branch_clone {
-        Ok(_) => return Ok(SyncOutcome::Cloned),
+    let Some(reference) = reference else {
+        return default_clone(backend, repo_url, repo_dir, sparse_paths, retry);
+    };
+    let branch_clone = retry.run(|| {
+        record_test_git_clone_if_configured();
+        git::clone(
+            backend,
+            repo_url,
+            repo_dir,
+            CloneOptions {
+                reference: Some(reference),
+                shallow: true,
+                sparse: sparse_paths.is_some(),
+                timeout: retry.timeout,
+                store: object_store_dir(repo_dir).as_deref(),
+            },
+        )
+    });
+    let branch_error = match branch_clone.result {
+        Ok(()) => {
+            widen_sparse_paths(backend, repo_dir, sparse_paths)?;
+            return Ok(SyncOutcome::Cloned);
+        }
+        // A network failure would only repeat without the branch hint.
+        Err(err) if is_transient_failure(&err) => {
+            return Err(SyncOperationError {
+                stage: SyncFailureStage::Clone,
+                detail: err,
+                attempts: branch_clone.attempts,
+            });
+        }
         Err(err) => err,
     };
 
-    record_test_git_clone_if_configured();
-    let fallback_clone = run_git(
-        Command::new("git").arg("clone").arg(repo_url).arg(repo_dir),
-        &format!(
-            "clone `{repo_url}` into `{}` without branch hint",
-            repo_dir.display()
-        ),
-    );
-    if let Err(fallback_error) = fallback_clone {
+    if let Err(fallback_error) = default_clone(backend, repo_url, repo_dir, sparse_paths, retry) {
         return Err(SyncOperationError {
             stage: SyncFailureStage::Clone,
             detail: format!(
-                "branch clone attempt failed: {branch_error}; fallback clone attempt failed: {fallback_error}"
+                "branch clone attempt failed: {branch_error}; fallback clone attempt failed: {}",
+                fallback_error.detail
             ),
+            attempts: fallback_error.attempts,
         });
     }
+    checkout_repo_ref(backend, repo_dir, reference)?;
+    Ok(SyncOutcome::Cloned)
+}
 
-    checkout_repo_ref(repo_dir, reference)?;
+/// Full-history clone of the remote `HEAD`.
+fn default_clone(
+    backend: GitBackend,
+    repo_url: &str,
+    repo_dir: &Path,
+    sparse_paths: Option<&BTreeSet<String>>,
+    retry: RetryPolicy,
+) -> Result<SyncOutcome, SyncOperationError> {
+    retry
+        .run(|| {
+            record_test_git_clone_if_configured();
+            git::clone(
+                backend,
+                repo_url,
+                repo_dir,
+                CloneOptions {
+                    sparse: sparse_paths.is_some(),
+                    timeout: retry.timeout,
+                    store: object_store_dir(repo_dir).as_deref(),
+                    ..CloneOptions::default()
+                },
+            )
+        })
+        .map_failure(|detail, attempts| SyncOperationError {
+            stage: SyncFailureStage::Clone,
+            detail,
+            attempts,
+        })?;
+    widen_sparse_paths(backend, repo_dir, sparse_paths)?;
     Ok(SyncOutcome::Cloned)
 }
 
-fn update_repo(repo_dir: &Path, reference: &str) -> Result<SyncOutcome, SyncOperationError> {
-    let head_before = read_head_sha(repo_dir);
+/// Bring a `version` source to the newest remote tag that satisfies
+/// `constraint`, cloning the remote `HEAD` first when needed.
+fn sync_versioned_repo(
+    backend: GitBackend,
+    repo_url: &str,
+    repo_dir: &Path,
+    constraint: &str,
+    sparse_paths: Option<&BTreeSet<String>>,
+    retry: RetryPolicy,
+) -> Result<SyncOutcome, SyncOperationError> {
+    let mut outcome = SyncOutcome::Skipped;
+    if repo_dir.join(".git").exists() {
+        point_origin(backend, repo_dir, repo_url)?;
+    } else {
+        clone_repo(backend, repo_url, None, repo_dir, sparse_paths, retry)?;
+        outcome = SyncOutcome::Cloned;
+    }
 
-    record_test_git_fetch_if_configured();
-    run_git(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("fetch")
-            .arg("--all")
-            .arg("--prune"),
-        &format!("fetch updates for `{}`", repo_dir.display()),
-    )
-    .map_err(|detail| SyncOperationError {
-        stage: SyncFailureStage::Fetch,
-        detail,
-    })?;
+    let tag = resolve_version_tag(backend, repo_dir, constraint, retry).map_failure(
+        |detail, attempts| SyncOperationError {
+            stage: SyncFailureStage::Fetch,
+            detail,
+            attempts,
+        },
+    )?;
+    let tag_ref = format!("refs/tags/{}", tag.name);
+    let at_tag = git::rev_parse(backend, repo_dir, "HEAD").as_deref() == Some(tag.commit.as_str());
+    // The tag itself is kept as a local ref so its signature can be checked.
+    if !at_tag || git::rev_parse(backend, repo_dir, &tag_ref).is_none() {
+        let refspec = format!("+{tag_ref}:{tag_ref}");
+        retry
+            .run(|| {
+                record_test_git_fetch_if_configured();
+                git::fetch_head(backend, repo_dir, Some(&refspec), false, retry.timeout)
+            })
+            .map_failure(|detail, attempts| SyncOperationError {
+                stage: SyncFailureStage::Fetch,
+                detail,
+                attempts,
+            })?;
+    }
+    if !at_tag {
+        git::checkout_detached(backend, repo_dir, &tag.commit).map_err(|detail| {
+            SyncOperationError {
+                stage: SyncFailureStage::Checkout,
+                detail,
+                attempts: 1,
+            }
+        })?;
+        if outcome == SyncOutcome::Skipped {
+            outcome = SyncOutcome::Updated;
+        }
+    }
+    widen_sparse_paths(backend, repo_dir, sparse_paths)?;
+    let _ = std::fs::write(
+        repo_dir.join(RESOLVED_TAG_FILE),
+        format!("{} {}\n", tag.name, tag.commit),
+    );
+    Ok(outcome)
+}
 
-    checkout_repo_ref(repo_dir, reference)?;
+/// List the tags of `origin` in `repo_dir` and pick the newest one whose
+/// name, minus an optional `v`, is a semver version satisfying
+/// `constraint`. Listing is retried like any other network operation.
+pub fn resolve_version_tag(
+    backend: GitBackend,
+    repo_dir: &Path,
+    constraint: &str,
+    retry: RetryPolicy,
+) -> RetryOutcome<RemoteTag> {
+    let listed = retry.run(|| git::ls_remote_tags(backend, repo_dir, retry.timeout));
+    RetryOutcome {
+        attempts: listed.attempts,
+        result: listed
+            .result
+            .and_then(|tags| select_version_tag(tags, constraint)),
+    }
+}
 
-    // Pull is best-effort for branch refs; if ref is detached/commit/tag this may fail and is ignored.
-    let _ = run_git(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("pull")
-            .arg("--ff-only")
-            .arg("origin")
-            .arg(reference),
-        &format!("fast-forward pull for `{}`", repo_dir.display()),
-    );
-    let head_after = read_head_sha(repo_dir);
-    if matches!(
-        (&head_before, &head_after),
-        (Some(before), Some(after)) if before == after
-    ) {
-        return Ok(SyncOutcome::Skipped);
+fn select_version_tag(tags: Vec<RemoteTag>, constraint: &str) -> Result<RemoteTag, String> {
+    let candidates = tags
+        .into_iter()
+        .filter_map(|tag| Some((tag_version(&tag.name)?, tag)))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return Err(format!(
+            "no semver tags found for version constraint `{constraint}`"
+        ));
     }
+    select_matching_version(candidates, |(version, _)| version, Some(constraint))
+        .map(|(_, tag)| tag)
+        .map_err(|err| match err {
+            RegistryError::Config { detail } | RegistryError::Resolution { detail } => detail,
+            RegistryError::Io(err) => err.to_string(),
+        })
+}
 
-    Ok(SyncOutcome::Updated)
+/// Parse a tag name like `v1.2.3` or `1.2.3` as a semver version.
+fn tag_version(name: &str) -> Option<Version> {
+    let version = name
+        .strip_prefix('v')
+        .or_else(|| name.strip_prefix('V'))
+        .unwrap_or(name);
+    Version::parse(version).ok()
 }
 
-fn checkout_repo_ref(repo_dir: &Path, reference: &str) -> Result<(), SyncOperationError> {
-    run_git(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("checkout")
-            .arg(reference),
-        &format!("checkout ref `{reference}` in `{}`", repo_dir.display()),
-    )
-    .map(|_| ())
-    .map_err(|detail| SyncOperationError {
-        stage: SyncFailureStage::Checkout,
-        detail,
+/// The tag a `version` source was last synced to, provided the checkout
+/// is still at `commit` (a locked sync may have moved it since).
+pub fn read_resolved_tag(repo_dir: &Path, commit: &str) -> Option<String> {
+    let content = std::fs::read_to_string(repo_dir.join(RESOLVED_TAG_FILE)).ok()?;
+    let (tag, tagged_commit) = content.trim().split_once(' ')?;
+    (tagged_commit == commit && !tag.is_empty()).then(|| tag.to_string())
+}
+
+/// The subpaths checked out in a repo cache entry, or `None` when it
+/// holds the full tree (or is not a git checkout).
+pub fn repo_cache_sparse_paths(backend: GitBackend, repo_dir: &Path) -> Option<BTreeSet<String>> {
+    git::sparse_checkout_list(backend, repo_dir)
+}
+
+/// Whether a repo cache entry checks out `subpath`, i.e. it holds the
+/// full tree or `subpath` lies inside one of its sparse paths.
+pub fn repo_cache_contains_subpath(backend: GitBackend, repo_dir: &Path, subpath: &str) -> bool {
+    let Some(sparse_paths) = repo_cache_sparse_paths(backend, repo_dir) else {
+        return true;
+    };
+    let Some(wanted) = sparse_path_for_subpath(subpath) else {
+        return false;
+    };
+    sparse_paths.iter().any(|path| {
+        wanted == *path
+            || wanted
+                .strip_prefix(path.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
     })
 }
 
-fn read_head_sha(repo_dir: &Path) -> Option<String> {
-    let output = run_git(
-        Command::new("git")
-            .arg("-C")
-            .arg(repo_dir)
-            .arg("rev-parse")
-            .arg("HEAD"),
-        &format!("read HEAD for `{}`", repo_dir.display()),
-    )
-    .ok()?;
+/// Narrow the sparse checkout of every repo cache entry `config` uses to
+/// exactly the subpaths its skills reference, and return the entries
+/// that held more. Syncs only ever widen an entry, so this is how the
+/// paths of removed skills are dropped. With `dry_run`, the entries are
+/// only reported.
+///
+/// # Errors
+///
+/// Returns the git error detail when a sparse checkout cannot be set.
+pub fn narrow_sparse_checkouts(
+    config: &Config,
+    storage_root: &Path,
+    dry_run: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut wanted = BTreeMap::new();
+    for skill in &config.skills {
+        if is_local_source_repo(&skill.source.repo) {
+            continue;
+        }
+        let repo_dir =
+            resolve_repo_cache_root(storage_root, &skill.source.repo, &skill.source.r#ref);
+        let paths = wanted
+            .entry(repo_dir)
+            .or_insert_with(|| Some(BTreeSet::new()));
+        add_sparse_path(paths, &skill.source.subpath);
+    }
+
+    let mut narrowed = Vec::new();
+    for (repo_dir, paths) in wanted {
+        let Some(paths) = paths else {
+            continue;
+        };
+        if !repo_dir.join(".git").exists() {
+            continue;
+        }
+        let current = repo_cache_sparse_paths(config.git.backend, &repo_dir);
+        // Entries missing some paths are widened by the next sync instead.
+        if !current
+            .as_ref()
+            .is_none_or(|current| current.is_superset(&paths) && *current != paths)
+        {
+            continue;
+        }
+        if !dry_run {
+            git::sparse_checkout_set(config.git.backend, &repo_dir, Some(&paths))?;
+        }
+        narrowed.push(repo_dir);
+    }
+    Ok(narrowed)
+}
+
+/// Add the sparse path of `subpath` to `paths`, switching to the full
+/// tree (`None`) when the subpath needs it.
+fn add_sparse_path(paths: &mut Option<BTreeSet<String>>, subpath: &str) {
+    match sparse_path_for_subpath(subpath) {
+        Some(path) => {
+            if let Some(paths) = paths {
+                paths.insert(path);
+            }
+        }
+        None => *paths = None,
+    }
+}
 
-    let head = output.stdout.lines().next()?.trim();
-    if head.is_empty() {
+/// Normalize a skill subpath into a cone-mode sparse path. Returns
+/// `None` when the subpath needs the full tree: the repository root, or
+/// a path that escapes it.
+fn sparse_path_for_subpath(subpath: &str) -> Option<String> {
+    let parts = subpath
+        .replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if parts.is_empty() || parts.iter().any(|part| part == "..") {
         return None;
     }
-    Some(head.to_string())
+    Some(parts.join("/"))
 }
 
-fn run_git(command: &mut Command, context: &str) -> Result<GitOutput, String> {
-    let output = command
-        .output()
-        .map_err(|err| format!("git invocation failed while trying to {context}: {err}"))?;
+/// Widen the sparse checkout of `repo_dir` to also cover `sparse_paths`,
+/// or disable it when `None`. Paths already checked out are kept: a sync
+/// may cover only some of the skills sharing the entry, so only
+/// [`narrow_sparse_checkouts`] drops paths again.
+fn widen_sparse_paths(
+    backend: GitBackend,
+    repo_dir: &Path,
+    sparse_paths: Option<&BTreeSet<String>>,
+) -> Result<(), SyncOperationError> {
+    let Some(current) = repo_cache_sparse_paths(backend, repo_dir) else {
+        return Ok(());
+    };
+    let widened = sparse_paths.map(|paths| current.union(paths).cloned().collect::<BTreeSet<_>>());
+    if widened.as_ref() == Some(&current) {
+        return Ok(());
+    }
+    git::sparse_checkout_set(backend, repo_dir, widened.as_ref()).map_err(|detail| {
+        SyncOperationError {
+            stage: SyncFailureStage::Checkout,
+            detail,
+            attempts: 1,
+        }
+    })
+}
+
+/// Re-point an existing checkout at the URL it should fetch from, which
+/// moves when `[mirrors]` rules are added, changed, or removed.
+fn point_origin(
+    backend: GitBackend,
+    repo_dir: &Path,
+    repo_url: &str,
+) -> Result<(), SyncOperationError> {
+    git::set_origin_url(backend, repo_dir, repo_url).map_err(|detail| SyncOperationError {
+        stage: SyncFailureStage::Fetch,
+        detail,
+        attempts: 1,
+    })
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+fn update_repo(
+    backend: GitBackend,
+    repo_dir: &Path,
+    reference: &str,
+    retry: RetryPolicy,
+) -> Result<SyncOutcome, SyncOperationError> {
+    let head_before = git::rev_parse(backend, repo_dir, "HEAD");
 
-    if output.status.success() {
-        return Ok(GitOutput { stdout });
+    retry
+        .run(|| {
+            record_test_git_fetch_if_configured();
+            git::fetch(backend, repo_dir, retry.timeout)
+        })
+        .map_failure(|detail, attempts| SyncOperationError {
+            stage: SyncFailureStage::Fetch,
+            detail,
+            attempts,
+        })?;
+
+    checkout_repo_ref(backend, repo_dir, reference)?;
+
+    // Pull is best-effort for branch refs; if ref is detached/commit/tag this may fail and is ignored.
+    let _ = git::fast_forward(backend, repo_dir, reference);
+    let head_after = git::rev_parse(backend, repo_dir, "HEAD");
+    if matches!(
+        (&head_before, &head_after),
+        (Some(before), Some(after)) if before == after
+    ) {
+        return Ok(SyncOutcome::Skipped);
     }
 
-    Err(format!(
-        "git command failed while trying to {context}: status={} stderr=`{}` stdout=`{}`",
-        output.status, stderr, stdout
-    ))
+    Ok(SyncOutcome::Updated)
+}
+
+fn checkout_repo_ref(
+    backend: GitBackend,
+    repo_dir: &Path,
+    reference: &str,
+) -> Result<(), SyncOperationError> {
+    git::checkout(backend, repo_dir, reference).map_err(|detail| SyncOperationError {
+        stage: SyncFailureStage::Checkout,
+        detail,
+        attempts: 1,
+    })
 }
 
-fn is_local_source_repo(repo_url: &str) -> bool {
+/// Whether `repo_url` names a local directory that is used in place
+/// rather than cloned into the repo cache.
+pub fn is_local_source_repo(repo_url: &str) -> bool {
     Path::new(repo_url).is_absolute()
 }
 
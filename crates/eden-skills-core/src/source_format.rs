@@ -1,18 +1,21 @@
 //! Source format detection and URL parsing pipeline.
 //!
 //! Determines whether a user-supplied source string is a registry skill
-//! name, a GitHub URL (HTTPS / SSH / tree), or a local filesystem path.
-//! Precedence: local path → GitHub tree URL → full URL → SSH URL →
-//! GitHub shorthand (`owner/repo`) → registry name.
+//! name, a release archive URL, a GitHub URL (HTTPS / SSH / tree), or a
+//! local filesystem path. Precedence: local path → archive URL → GitHub
+//! tree URL → full URL → SSH URL → GitHub shorthand (`owner/repo`) →
+//! registry name.
 
 use std::path::Path;
 
+use crate::archive::is_archive_url;
 use crate::error::EdenError;
 use crate::paths::resolve_path_string;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UrlSourceType {
     LocalPath,
+    Archive,
     GitHubTree,
     FullUrl,
     SshUrl,
@@ -36,8 +39,9 @@ pub enum DetectedInstallSource {
 
 /// Detect the install source format from a user-supplied string.
 ///
-/// Applies format checks in precedence order: local path, GitHub tree
-/// URL, full URL, SSH URL, GitHub shorthand, and finally registry name.
+/// Applies format checks in precedence order: local path, archive URL
+/// (`.tar.gz`, `.tgz`, `.zip`), GitHub tree URL, full URL, SSH URL,
+/// GitHub shorthand, and finally registry name.
 ///
 /// # Errors
 ///
@@ -54,6 +58,16 @@ pub fn detect_install_source(input: &str, cwd: &Path) -> Result<DetectedInstallS
         }));
     }
 
+    if is_archive_url(input) {
+        return Ok(DetectedInstallSource::Url(UrlInstallSource {
+            source_type: UrlSourceType::Archive,
+            repo: input.to_string(),
+            reference: None,
+            subpath: None,
+            is_local: false,
+        }));
+    }
+
     if let Some(tree_source) = parse_github_tree_url(input) {
         return Ok(DetectedInstallSource::Url(tree_source));
     }
@@ -107,6 +121,26 @@ pub fn derive_skill_id_from_source_repo(source_repo: &str) -> Result<String, Ede
         return Ok(name.to_string());
     }
 
+    if is_archive_url(source_repo) {
+        let path = source_repo.split(['?', '#']).next().unwrap_or(source_repo);
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let stem = [".tar.gz", ".tgz", ".zip"]
+            .iter()
+            .find_map(|ext| {
+                file_name
+                    .to_ascii_lowercase()
+                    .ends_with(ext)
+                    .then(|| &file_name[..file_name.len() - ext.len()])
+            })
+            .unwrap_or(file_name);
+        if stem.trim().is_empty() {
+            return Err(EdenError::InvalidArguments(format!(
+                "failed to derive skill id from archive `{source_repo}`"
+            )));
+        }
+        return Ok(stem.to_string());
+    }
+
     let tail = if let Some(rest) = source_repo.strip_prefix("git@") {
         rest.rsplit(':').next().unwrap_or(rest)
     } else {
@@ -0,0 +1,179 @@
+//! File-level comparison between a cached skill source and an installed
+//! copy-mode target.
+//!
+//! [`diff_trees`] walks both trees in sorted relative-path order and
+//! reports each file that exists only in the target ([`FileChangeKind::Added`]),
+//! only in the source ([`FileChangeKind::Removed`]), or in both with
+//! different bytes ([`FileChangeKind::Modified`]). Changes are described
+//! from the source's point of view, so hand edits made to an installed
+//! skill show up as additions. Modified UTF-8 text files carry a unified
+//! diff; binary files only report that they differ. The same entries that
+//! tree digests skip (`.git` and cache metadata) are ignored here.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use similar::TextDiff;
+
+use crate::digest::IGNORED_ENTRIES;
+
+/// Lines of unchanged context around each hunk, matching `diff -u`.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl FileChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    /// `/`-separated path relative to the skill root.
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// Unified diff for modified text files; `None` for binary files and
+    /// for added or removed entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unified_diff: Option<String>,
+}
+
+/// Compare the source tree at `source` with the installed tree at `target`.
+///
+/// Either root may be a single file, reported under the path `.`. A
+/// missing target is reported as every source file being removed.
+///
+/// # Errors
+///
+/// Returns the underlying I/O error when an entry in either tree cannot
+/// be read.
+pub fn diff_trees(source: &Path, target: &Path) -> io::Result<Vec<FileChange>> {
+    let source_files = collect_files(source)?;
+    let target_files = collect_files(target)?;
+
+    let mut changes = Vec::new();
+    for (relative, source_entry) in &source_files {
+        match target_files.get(relative) {
+            None => changes.push(FileChange {
+                path: relative.clone(),
+                kind: FileChangeKind::Removed,
+                unified_diff: None,
+            }),
+            Some(target_entry) => {
+                let before = source_entry.read()?;
+                let after = target_entry.read()?;
+                if before != after {
+                    changes.push(FileChange {
+                        path: relative.clone(),
+                        kind: FileChangeKind::Modified,
+                        unified_diff: unified_text_diff(relative, &before, &after),
+                    });
+                }
+            }
+        }
+    }
+    for relative in target_files.keys() {
+        if !source_files.contains_key(relative) {
+            changes.push(FileChange {
+                path: relative.clone(),
+                kind: FileChangeKind::Added,
+                unified_diff: None,
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+/// A leaf entry in a skill tree: a regular file or a symlink, whose
+/// comparable content is its bytes or its link text respectively.
+enum TreeEntry {
+    File(PathBuf),
+    Symlink(String),
+}
+
+impl TreeEntry {
+    fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::File(path) => fs::read(path),
+            Self::Symlink(link) => Ok(link.as_bytes().to_vec()),
+        }
+    }
+}
+
+fn collect_files(root: &Path) -> io::Result<BTreeMap<String, TreeEntry>> {
+    let mut files = BTreeMap::new();
+    let metadata = match fs::metadata(root) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(files),
+        Err(err) => return Err(err),
+    };
+    if metadata.is_file() {
+        files.insert(".".to_string(), TreeEntry::File(root.to_path_buf()));
+    } else {
+        collect_dir(root, "", &mut files)?;
+    }
+    Ok(files)
+}
+
+fn collect_dir(
+    dir: &Path,
+    prefix: &str,
+    files: &mut BTreeMap<String, TreeEntry>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if IGNORED_ENTRIES.contains(&name.as_str()) {
+            continue;
+        }
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            let link = fs::read_link(&path)?;
+            files.insert(
+                relative,
+                TreeEntry::Symlink(link.to_string_lossy().replace('\\', "/")),
+            );
+        } else if metadata.is_dir() {
+            collect_dir(&path, &relative, files)?;
+        } else {
+            files.insert(relative, TreeEntry::File(path));
+        }
+    }
+    Ok(())
+}
+
+fn unified_text_diff(relative: &str, before: &[u8], after: &[u8]) -> Option<String> {
+    if before.contains(&0) || after.contains(&0) {
+        return None;
+    }
+    let before = std::str::from_utf8(before).ok()?;
+    let after = std::str::from_utf8(after).ok()?;
+    let diff = TextDiff::from_lines(before, after);
+    Some(
+        diff.unified_diff()
+            .context_radius(CONTEXT_LINES)
+            .header(&format!("a/{relative}"), &format!("b/{relative}"))
+            .to_string(),
+    )
+}
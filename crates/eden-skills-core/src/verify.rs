@@ -2,14 +2,18 @@
 //!
 //! Runs the checks declared in each skill's `[verify]` table against
 //! the live filesystem.  Supported checks: `path-exists`, `is-symlink`,
-//! `target-resolves`, and `content-present`.  Results are collected as
-//! [`VerifyIssue`] values consumed by the `doctor` / `repair` commands.
+//! `target-resolves`, `content-present`, and `content-hash`.  Results are
+//! collected as [`VerifyIssue`] values consumed by the `doctor` / `repair`
+//! commands.  `content-hash` compares the target tree digest against the
+//! one pinned in `skills.lock` and is skipped when no lock is supplied.
 
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::{Config, InstallMode};
+use crate::digest::tree_digest;
 use crate::error::EdenError;
+use crate::lock::LockFile;
 use crate::paths::{normalize_lexical, resolve_path_string, resolve_target_path};
 use crate::source::resolve_skill_source_path;
 
@@ -27,6 +31,16 @@ pub struct VerifyIssue {
 pub fn verify_config_state(
     config: &Config,
     config_dir: &Path,
+) -> Result<Vec<VerifyIssue>, EdenError> {
+    verify_config_state_with_lock(config, config_dir, None)
+}
+
+/// Like [`verify_config_state`], but also evaluates `content-hash` checks
+/// against the target digests recorded in `lock`.
+pub fn verify_config_state_with_lock(
+    config: &Config,
+    config_dir: &Path,
+    lock: Option<&LockFile>,
 ) -> Result<Vec<VerifyIssue>, EdenError> {
     let storage_root = resolve_path_string(&config.storage_root, config_dir)?;
     let mut issues = Vec::new();
@@ -41,6 +55,7 @@ pub fn verify_config_state(
             let target_root = resolve_target_path(target, config_dir)?;
             let target_path = normalize_lexical(&target_root.join(&skill.id));
             let target_exists = fs::symlink_metadata(&target_path).is_ok();
+            let expected_digest = locked_target_digest(lock, &skill.id, &target_path, config_dir);
 
             for check in &skill.verify.checks {
                 if !target_exists && check != "path-exists" {
@@ -52,6 +67,7 @@ pub fn verify_config_state(
                     skill.install.mode,
                     &source_path,
                     &target_path,
+                    expected_digest,
                     &mut issues,
                 )?;
             }
@@ -61,12 +77,33 @@ pub fn verify_config_state(
     Ok(issues)
 }
 
+fn locked_target_digest<'a>(
+    lock: Option<&'a LockFile>,
+    skill_id: &str,
+    target_path: &Path,
+    config_dir: &Path,
+) -> Option<&'a str> {
+    lock?
+        .skills
+        .iter()
+        .find(|entry| entry.id == skill_id)?
+        .targets
+        .iter()
+        .find(|target| {
+            target.environment == "local"
+                && normalize_lexical(&target.resolved_path(config_dir)) == target_path
+        })?
+        .content_digest
+        .as_deref()
+}
+
 fn run_check(
     check: &str,
     skill_id: &str,
     install_mode: InstallMode,
     source_path: &Path,
     target_path: &Path,
+    expected_digest: Option<&str>,
     issues: &mut Vec<VerifyIssue>,
 ) -> Result<(), EdenError> {
     match check {
@@ -174,6 +211,28 @@ fn run_check(
                 ));
             }
         }
+        "content-hash" => {
+            let Some(expected) = expected_digest else {
+                return Ok(());
+            };
+            match tree_digest(target_path) {
+                Ok(actual) if actual == expected => {}
+                Ok(actual) => issues.push(issue(
+                    skill_id,
+                    target_path,
+                    check,
+                    format!(
+                        "installed content digest `{actual}` does not match locked `{expected}`"
+                    ),
+                )),
+                Err(err) => issues.push(issue(
+                    skill_id,
+                    target_path,
+                    check,
+                    format!("failed to hash installed content: {err}"),
+                )),
+            }
+        }
         unknown => {
             return Err(EdenError::Validation(format!(
                 "verify.checks: unsupported check `{unknown}`"
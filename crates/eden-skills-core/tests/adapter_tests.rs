@@ -54,6 +54,23 @@ async fn local_adapter_install_copy_and_path_exists_work() {
     assert_eq!(copied, "hello\n");
 }
 
+#[tokio::test]
+async fn local_adapter_copy_out_copies_installed_target() {
+    let temp = tempdir().expect("tempdir");
+    let target = temp.path().join("target");
+    let dest = temp.path().join("snapshot");
+    fs::create_dir_all(target.join("nested")).expect("create target");
+    fs::write(target.join("nested").join("README.md"), "hello\n").expect("write target");
+
+    LocalAdapter::new()
+        .copy_out(&target, &dest)
+        .await
+        .expect("copy out");
+
+    let copied = fs::read_to_string(dest.join("nested").join("README.md")).expect("read copy");
+    assert_eq!(copied, "hello\n");
+}
+
 #[tokio::test]
 async fn local_adapter_uninstall_removes_existing_target() {
     let temp = tempdir().expect("tempdir");
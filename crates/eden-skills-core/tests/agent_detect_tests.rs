@@ -1,7 +1,10 @@
 use std::fs;
 
-use eden_skills_core::agents::detect_installed_agent_targets_from_home;
-use eden_skills_core::config::AgentKind;
+use eden_skills_core::agents::{
+    detect_installed_agent_targets_from_home, detect_project_agent_targets,
+};
+use eden_skills_core::config::{AgentKind, TargetScope};
+use eden_skills_core::paths::default_agent_project_path;
 use tempfile::tempdir;
 
 #[test]
@@ -104,3 +107,29 @@ fn does_not_auto_detect_shared_config_agents_path() {
         "shared ~/.config/agents/skills should not create ambiguous auto-detection targets"
     );
 }
+
+#[test]
+fn detects_project_agent_directories_once_per_path() {
+    let temp = tempdir().expect("tempdir");
+    let project = temp.path();
+    fs::create_dir_all(project.join(".claude")).expect("create .claude");
+    fs::create_dir_all(project.join(".agents/skills")).expect("create .agents/skills");
+    fs::create_dir_all(project.join("skills")).expect("create skills");
+
+    let detected = detect_project_agent_targets(project);
+
+    assert!(detected
+        .iter()
+        .all(|target| target.scope == TargetScope::Project && target.path.is_none()));
+    assert!(detected
+        .iter()
+        .any(|target| target.agent == AgentKind::ClaudeCode));
+    let shared = detected
+        .iter()
+        .filter(|target| default_agent_project_path(&target.agent) == Some(".agents/skills"))
+        .count();
+    assert_eq!(
+        shared, 1,
+        "agents sharing .agents/skills must be reported once"
+    );
+}
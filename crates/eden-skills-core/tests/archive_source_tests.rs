@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use eden_skills_core::config::{
+    encode_archive_ref, load_from_file, AgentKind, Config, GitConfig, InstallConfig, InstallMode,
+    LoadOptions, ReactorConfig, SafetyConfig, SkillConfig, SourceConfig, TargetConfig, TargetScope,
+    VerifyConfig,
+};
+use eden_skills_core::digest::sha256_hex;
+use eden_skills_core::source::{resolve_skill_source_path, sync_sources, SyncFailureStage};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tempfile::tempdir;
+
+const SKILL_ID: &str = "archived-skill";
+
+#[test]
+fn archive_source_is_parsed_with_checksum_in_ref() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    let sha256 = "AB".repeat(32);
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+version = 1
+
+[[skills]]
+id = "x"
+
+[skills.source]
+archive = "https://example.com/x-1.0.tar.gz"
+sha256 = "{sha256}"
+subpath = "x"
+
+[[skills.targets]]
+agent = "claude-code"
+"#
+        ),
+    )
+    .expect("write config");
+
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    let source = &loaded.config.skills[0].source;
+    assert_eq!(source.repo, "https://example.com/x-1.0.tar.gz");
+    assert_eq!(source.subpath, "x");
+    assert_eq!(source.archive_sha256(), Some("ab".repeat(32).as_str()));
+    assert!(source.is_archive());
+}
+
+#[test]
+fn archive_source_rejects_invalid_combinations() {
+    let sha256 = "0".repeat(64);
+    let cases = [
+        (
+            format!("repo = \"https://example.com/x.git\"\narchive = \"https://example.com/x.zip\"\nsha256 = \"{sha256}\""),
+            "mutually exclusive",
+        ),
+        (
+            "archive = \"https://example.com/x.zip\"".to_string(),
+            "sha256: missing required field",
+        ),
+        (
+            "archive = \"https://example.com/x.zip\"\nsha256 = \"abc\"".to_string(),
+            "expected 64 hexadecimal characters",
+        ),
+        (
+            format!("archive = \"https://example.com/x.zip\"\nsha256 = \"{sha256}\"\nref = \"main\""),
+            "not supported for archive sources",
+        ),
+        (
+            format!("archive = \"https://example.com/x.rar\"\nsha256 = \"{sha256}\""),
+            "unsupported archive format",
+        ),
+        (
+            format!("repo = \"https://example.com/x.git\"\nsha256 = \"{sha256}\""),
+            "only supported for archive sources",
+        ),
+        (
+            format!("repo = \"https://example.com/x.git\"\nref = \"sha256:{sha256}\""),
+            "reserved for archive sources",
+        ),
+    ];
+
+    for (source, expected) in cases {
+        let dir = tempdir().expect("tempdir");
+        let config_path = dir.path().join("skills.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "version = 1\n\n[[skills]]\nid = \"x\"\n\n[skills.source]\n{source}\n\n[[skills.targets]]\nagent = \"claude-code\"\n"
+            ),
+        )
+        .expect("write config");
+
+        let err = load_from_file(&config_path, LoadOptions::default())
+            .expect_err("invalid archive source should fail");
+        assert!(
+            err.to_string().contains(expected),
+            "expected `{expected}` in `{err}`"
+        );
+    }
+}
+
+#[test]
+fn sync_downloads_verifies_and_extracts_tar_gz_archives() {
+    let temp = tempdir().expect("tempdir");
+    let archive = tar_gz(&[
+        ("pack/SKILL.md", b"# Archived\n".as_slice()),
+        ("pack/scripts/run.sh", b"echo hi\n".as_slice()),
+    ]);
+    let archive_path = temp.path().join("pack.tar.gz");
+    fs::write(&archive_path, &archive).expect("write archive");
+    let storage_root = temp.path().join("storage");
+    let config = archive_config(
+        &storage_root,
+        &as_file_url(&archive_path),
+        &sha256_hex(&archive),
+        "pack",
+    );
+
+    let first = sync_sources(&config, temp.path()).expect("first sync");
+    assert_eq!(first.cloned, 1);
+    assert_eq!(first.failed, 0);
+    let source_path = resolve_skill_source_path(&storage_root, &config.skills[0]);
+    assert_eq!(
+        fs::read_to_string(source_path.join("SKILL.md")).expect("read extracted file"),
+        "# Archived\n"
+    );
+    assert!(source_path.join("scripts/run.sh").is_file());
+
+    // The cache entry is content-addressed, so the archive is not fetched again.
+    fs::remove_file(&archive_path).expect("remove archive");
+    let second = sync_sources(&config, temp.path()).expect("second sync");
+    assert_eq!(second.skipped, 1);
+    assert_eq!(second.failed, 0);
+}
+
+#[test]
+fn sync_extracts_zip_archives() {
+    let temp = tempdir().expect("tempdir");
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer
+        .start_file("SKILL.md", zip::write::SimpleFileOptions::default())
+        .expect("start zip entry");
+    writer.write_all(b"# Zipped\n").expect("write zip entry");
+    let archive = writer.finish().expect("finish zip").into_inner();
+    let archive_path = temp.path().join("pack.zip");
+    fs::write(&archive_path, &archive).expect("write archive");
+    let storage_root = temp.path().join("storage");
+    let config = archive_config(
+        &storage_root,
+        &as_file_url(&archive_path),
+        &sha256_hex(&archive),
+        ".",
+    );
+
+    let summary = sync_sources(&config, temp.path()).expect("sync");
+    assert_eq!(summary.cloned, 1);
+    let source_path = resolve_skill_source_path(&storage_root, &config.skills[0]);
+    assert_eq!(
+        fs::read_to_string(source_path.join("SKILL.md")).expect("read extracted file"),
+        "# Zipped\n"
+    );
+}
+
+#[test]
+fn sync_rejects_archives_with_checksum_mismatch() {
+    let temp = tempdir().expect("tempdir");
+    let archive = tar_gz(&[("SKILL.md", b"# Archived\n".as_slice())]);
+    let archive_path = temp.path().join("pack.tar.gz");
+    fs::write(&archive_path, &archive).expect("write archive");
+    let storage_root = temp.path().join("storage");
+    let config = archive_config(
+        &storage_root,
+        &as_file_url(&archive_path),
+        &"0".repeat(64),
+        ".",
+    );
+
+    let summary = sync_sources(&config, temp.path()).expect("sync summary");
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.failures[0].stage, SyncFailureStage::Verify);
+    assert!(summary.failures[0].detail.contains("checksum mismatch"));
+    assert!(!resolve_skill_source_path(&storage_root, &config.skills[0]).exists());
+}
+
+#[test]
+fn sync_rejects_archive_entries_outside_the_extraction_root() {
+    let temp = tempdir().expect("tempdir");
+    let archive = tar_gz(&[
+        ("SKILL.md", b"# Archived\n".as_slice()),
+        ("../escaped.txt", b"pwned\n".as_slice()),
+    ]);
+    let archive_path = temp.path().join("pack.tar.gz");
+    fs::write(&archive_path, &archive).expect("write archive");
+    let storage_root = temp.path().join("storage");
+    let config = archive_config(
+        &storage_root,
+        &as_file_url(&archive_path),
+        &sha256_hex(&archive),
+        ".",
+    );
+
+    let summary = sync_sources(&config, temp.path()).expect("sync summary");
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.failures[0].stage, SyncFailureStage::Extract);
+    assert!(summary.failures[0]
+        .detail
+        .contains("escapes the extraction directory"));
+    assert!(!storage_root.join(".repos/escaped.txt").exists());
+    assert!(!resolve_skill_source_path(&storage_root, &config.skills[0]).exists());
+}
+
+/// Build a `.tar.gz` from raw entry names, bypassing the `tar` builder's
+/// own path checks so hostile names can be written.
+fn tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, content) in entries {
+        let mut header = tar::Header::new_old();
+        let raw_name = &mut header.as_old_mut().name;
+        raw_name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+        builder.append(&header, *content).expect("append entry");
+    }
+    builder
+        .into_inner()
+        .expect("finish tar")
+        .finish()
+        .expect("finish gzip")
+}
+
+fn as_file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+fn archive_config(storage_root: &Path, url: &str, sha256: &str, subpath: &str) -> Config {
+    Config {
+        version: 1,
+        storage_root: storage_root.display().to_string(),
+        reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
+        skills: vec![SkillConfig {
+            id: SKILL_ID.to_string(),
+            source: SourceConfig {
+                repo: url.to_string(),
+                subpath: subpath.to_string(),
+                r#ref: encode_archive_ref(sha256),
+                verify_signature: false,
+            },
+            install: InstallConfig {
+                mode: InstallMode::Symlink,
+            },
+            targets: vec![TargetConfig {
+                agent: AgentKind::Custom,
+                expected_path: None,
+                path: Some(storage_root.join("targets").display().to_string()),
+                environment: "local".to_string(),
+                scope: TargetScope::Global,
+            }],
+            verify: VerifyConfig {
+                enabled: false,
+                checks: vec![],
+            },
+            safety: SafetyConfig {
+                no_exec_metadata_only: false,
+            },
+            requires: BTreeMap::new(),
+            implicit: false,
+        }],
+    }
+}
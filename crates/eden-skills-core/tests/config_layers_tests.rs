@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::Path;
+
+use eden_skills_core::config::{load_layered_from_files, load_user_layer, LoadOptions};
+use eden_skills_core::config_layers::ConfigLayer;
+use tempfile::tempdir;
+
+const USER_CONFIG: &str = r#"
+version = 1
+
+[storage]
+root = "storage"
+
+[reactor]
+concurrency = 4
+
+[[skills]]
+id = "shared"
+
+[skills.source]
+repo = "https://github.com/example/shared.git"
+ref = "main"
+
+[[skills.targets]]
+agent = "claude-code"
+
+[[skills]]
+id = "user-only"
+
+[skills.source]
+repo = "https://github.com/example/user-only.git"
+
+[[skills.targets]]
+agent = "claude-code"
+"#;
+
+fn write_layers(base: &Path, project: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+    let user_dir = base.join("home").join(".eden-skills");
+    let project_dir = base.join("project");
+    fs::create_dir_all(&user_dir).expect("create user dir");
+    fs::create_dir_all(&project_dir).expect("create project dir");
+    let user_path = user_dir.join("skills.toml");
+    let project_path = project_dir.join("skills.toml");
+    fs::write(&user_path, USER_CONFIG).expect("write user config");
+    fs::write(&project_path, project).expect("write project config");
+    (user_path, project_path)
+}
+
+#[test]
+fn project_layer_overrides_single_field_of_user_skill() {
+    let dir = tempdir().expect("tempdir");
+    let (user_path, project_path) = write_layers(
+        dir.path(),
+        r#"
+version = 1
+
+[[skills]]
+id = "shared"
+
+[skills.source]
+ref = "v2"
+
+[[skills]]
+id = "project-only"
+
+[skills.source]
+repo = "https://github.com/example/project-only.git"
+
+[[skills.targets]]
+agent = "cursor"
+scope = "project"
+"#,
+    );
+
+    let loaded = load_layered_from_files(&user_path, &project_path, LoadOptions::default())
+        .expect("load layered config");
+    let ids = loaded
+        .config
+        .skills
+        .iter()
+        .map(|skill| skill.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["shared", "user-only", "project-only"]);
+
+    let shared = &loaded.config.skills[0];
+    assert_eq!(shared.source.r#ref, "v2");
+    assert_eq!(shared.source.repo, "https://github.com/example/shared.git");
+    assert_eq!(shared.targets.len(), 1, "user targets should be kept");
+
+    assert_eq!(loaded.skill_layer("shared"), Some(ConfigLayer::Project));
+    assert_eq!(loaded.skill_layer("user-only"), Some(ConfigLayer::User));
+    assert_eq!(
+        loaded.skill_layer("project-only"),
+        Some(ConfigLayer::Project)
+    );
+    assert_eq!(loaded.config.reactor.concurrency, 4);
+    assert!(
+        loaded
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("`shared`") && warning.contains("source.ref")),
+        "overriding a user skill should warn, warnings={:?}",
+        loaded.warnings
+    );
+}
+
+#[test]
+fn shadow_warnings_do_not_fail_strict_mode() {
+    let dir = tempdir().expect("tempdir");
+    let (user_path, project_path) = write_layers(
+        dir.path(),
+        r#"
+version = 1
+
+[[skills]]
+id = "shared"
+
+[skills.source]
+ref = "v2"
+"#,
+    );
+
+    let loaded = load_layered_from_files(&user_path, &project_path, LoadOptions { strict: true })
+        .expect("shadowing is not an error in strict mode");
+    assert_eq!(loaded.warnings.len(), 1);
+}
+
+#[test]
+fn project_layer_overriding_settings_warns_per_section() {
+    let dir = tempdir().expect("tempdir");
+    let (user_path, project_path) = write_layers(
+        dir.path(),
+        r#"
+version = 1
+
+[reactor]
+concurrency = 8
+"#,
+    );
+
+    let loaded = load_layered_from_files(&user_path, &project_path, LoadOptions::default())
+        .expect("load layered config");
+    assert_eq!(loaded.config.reactor.concurrency, 8);
+    assert_eq!(
+        loaded.warnings,
+        vec!["project config overrides user `[reactor]` (concurrency)".to_string()]
+    );
+}
+
+#[test]
+fn user_layer_relative_paths_stay_anchored_to_user_dir() {
+    let dir = tempdir().expect("tempdir");
+    let (user_path, project_path) = write_layers(dir.path(), "version = 1\n");
+
+    let loaded = load_layered_from_files(&user_path, &project_path, LoadOptions::default())
+        .expect("load layered config");
+    let user_dir = user_path.parent().expect("user dir");
+    assert_eq!(
+        Path::new(&loaded.config.storage_root),
+        user_dir.join("storage")
+    );
+
+    let user_layer = load_user_layer(&user_path, &project_path).expect("load user layer");
+    assert_eq!(user_layer.storage_root, loaded.config.storage_root);
+    assert_eq!(user_layer.skills, loaded.config.skills);
+}
+
+#[test]
+fn switching_between_mode_a_and_mode_b_replaces_user_skill() {
+    let dir = tempdir().expect("tempdir");
+    let (user_path, project_path) = write_layers(
+        dir.path(),
+        r#"
+version = 1
+
+[registries]
+official = { url = "https://example.com/official.git", priority = 100 }
+
+[[skills]]
+name = "shared"
+version = "*"
+
+[[skills.targets]]
+agent = "claude-code"
+"#,
+    );
+
+    let loaded = load_layered_from_files(&user_path, &project_path, LoadOptions::default())
+        .expect("load layered config");
+    let shared = loaded
+        .config
+        .skills
+        .iter()
+        .find(|skill| skill.id == "shared")
+        .expect("shared skill");
+    assert_ne!(shared.source.repo, "https://github.com/example/shared.git");
+    assert!(
+        loaded
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("replaces user skill `shared`")),
+        "warnings={:?}",
+        loaded.warnings
+    );
+}
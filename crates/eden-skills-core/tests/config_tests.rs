@@ -1,6 +1,8 @@
 use std::fs;
+use std::time::Duration;
 
-use eden_skills_core::config::{load_from_file, AgentKind, LoadOptions};
+use eden_skills_core::config::{load_from_file, AgentKind, LoadOptions, TargetScope};
+use eden_skills_core::mirrors::rewrite_url;
 use tempfile::tempdir;
 
 #[test]
@@ -65,6 +67,72 @@ agent = "custom"
     assert!(message.contains("skills[0].targets[0].path"));
 }
 
+#[test]
+fn target_scope_defaults_to_global_and_accepts_project() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"
+version = 1
+
+[[skills]]
+id = "x"
+
+[skills.source]
+repo = "https://github.com/vercel-labs/skills.git"
+
+[[skills.targets]]
+agent = "claude-code"
+
+[[skills.targets]]
+agent = "cursor"
+scope = "project"
+"#,
+    )
+    .expect("write config");
+
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    let targets = &loaded.config.skills[0].targets;
+    assert_eq!(targets[0].scope, TargetScope::Global);
+    assert_eq!(targets[1].scope, TargetScope::Project);
+}
+
+#[test]
+fn reject_project_scope_on_docker_target() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"
+version = 1
+
+[[skills]]
+id = "x"
+
+[skills.source]
+repo = "https://github.com/vercel-labs/skills.git"
+
+[[skills.targets]]
+agent = "claude-code"
+environment = "docker:dev"
+scope = "project"
+"#,
+    )
+    .expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("expected error");
+    let message = err.to_string();
+    assert!(
+        message.contains("INVALID_TARGET_SCOPE"),
+        "message={message}"
+    );
+    assert!(
+        message.contains("skills[0].targets[0].scope"),
+        "message={message}"
+    );
+}
+
 #[test]
 fn repo_url_allows_https_ssh_scp_like_and_file() {
     let dir = tempdir().expect("tempdir");
@@ -229,3 +297,197 @@ fn auto_detect_eligibility_excludes_shared_config_agents_aliases() {
     assert!(!AgentKind::Universal.is_auto_detect_eligible());
     assert!(!AgentKind::Custom.is_auto_detect_eligible());
 }
+
+#[test]
+fn mirror_rules_rewrite_longest_matching_prefix() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"
+version = 1
+
+[mirrors]
+"https://github.com/" = "https://git.corp.example/github/"
+"https://github.com/acme/" = "ssh://git@git.corp.example/acme/"
+"#,
+    )
+    .expect("write config");
+
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    assert!(loaded.warnings.is_empty(), "warnings={:?}", loaded.warnings);
+    let mirrors = &loaded.config.mirrors;
+    assert_eq!(mirrors.len(), 2);
+    assert_eq!(
+        rewrite_url(mirrors, "https://github.com/acme/skills.git"),
+        "ssh://git@git.corp.example/acme/skills.git"
+    );
+    assert_eq!(
+        rewrite_url(mirrors, "https://github.com/other/skills.git"),
+        "https://git.corp.example/github/other/skills.git"
+    );
+    assert_eq!(
+        rewrite_url(mirrors, "https://gitlab.com/acme/skills.git"),
+        "https://gitlab.com/acme/skills.git"
+    );
+}
+
+#[test]
+fn mirror_replacement_must_be_a_git_url() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"
+version = 1
+
+[mirrors]
+"https://github.com/" = "ftp://mirror.example/"
+"#,
+    )
+    .expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("invalid mirror");
+    assert!(
+        err.to_string().contains("mirrors.\"https://github.com/\""),
+        "err={err}"
+    );
+}
+
+#[test]
+fn reactor_retry_settings_build_the_retry_policy() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        r#"
+version = 1
+
+[reactor]
+retries = 4
+backoff = 0.25
+timeout_secs = 0
+host_concurrency = 2
+"#,
+    )
+    .expect("write config");
+
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    let reactor = loaded.config.reactor;
+    assert_eq!(reactor.host_concurrency, 2);
+    let policy = reactor.retry_policy();
+    assert_eq!(policy.retries, 4);
+    assert_eq!(policy.backoff, Duration::from_millis(250));
+    assert_eq!(policy.timeout, None);
+}
+
+#[test]
+fn reactor_retries_above_the_limit_are_rejected() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(&config_path, "version = 1\n\n[reactor]\nretries = 50\n").expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("invalid retries");
+    assert!(
+        err.to_string().contains("INVALID_RETRIES: reactor.retries"),
+        "err={err}"
+    );
+}
+
+const VERSIONED_SKILL: &str = r#"
+version = 1
+
+[[skills]]
+id = "tagged"
+
+[skills.source]
+repo = "https://github.com/example/skills.git"
+version = "^1.2"
+
+[[skills.targets]]
+agent = "claude-code"
+"#;
+
+#[test]
+fn source_version_is_stored_as_semver_ref() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(&config_path, VERSIONED_SKILL).expect("write config");
+
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    let source = &loaded.config.skills[0].source;
+    assert_eq!(source.r#ref, "semver:^1.2");
+    assert_eq!(source.version_constraint(), Some("^1.2"));
+}
+
+#[test]
+fn source_version_and_ref_are_mutually_exclusive() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        VERSIONED_SKILL.replace("version = \"^1.2\"", "version = \"^1.2\"\nref = \"main\""),
+    )
+    .expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("conflict");
+    assert!(err.to_string().contains("mutually exclusive"), "err={err}");
+}
+
+#[test]
+fn source_version_must_be_a_semver_constraint() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    fs::write(
+        &config_path,
+        VERSIONED_SKILL.replace("^1.2", "not-a-version"),
+    )
+    .expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("invalid version");
+    assert!(
+        err.to_string()
+            .contains("INVALID_SEMVER: skills[0].source.version"),
+        "err={err}"
+    );
+}
+
+#[test]
+fn verify_signature_requires_trusted_keys() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("skills.toml");
+    let signed_skill = VERSIONED_SKILL.replace(
+        "version = \"^1.2\"",
+        "version = \"^1.2\"\nverify_signature = true",
+    );
+    fs::write(&config_path, &signed_skill).expect("write config");
+
+    let err = load_from_file(&config_path, LoadOptions::default()).expect_err("no trusted keys");
+    assert!(
+        err.to_string()
+            .contains("MISSING_TRUSTED_KEYS: skills[0].source.verify_signature"),
+        "err={err}"
+    );
+
+    fs::write(
+        &config_path,
+        signed_skill.replacen(
+            "version = 1\n",
+            "version = 1\n\n[git]\nallowed_signers = \"allowed_signers\"\n",
+            1,
+        ),
+    )
+    .expect("write config");
+    let loaded = load_from_file(&config_path, LoadOptions::default()).expect("load config");
+    assert!(loaded.config.skills[0].source.verify_signature);
+    let trust = loaded
+        .config
+        .git
+        .signature_trust(dir.path())
+        .expect("resolve trust");
+    assert_eq!(
+        trust.allowed_signers,
+        Some(dir.path().join("allowed_signers"))
+    );
+    assert_eq!(trust.gpg_home, None);
+}
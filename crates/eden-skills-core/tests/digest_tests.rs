@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+
+use eden_skills_core::digest::{tree_digest, DIGEST_PREFIX};
+
+fn write_tree(root: &Path) {
+    fs::create_dir_all(root.join("scripts")).expect("create tree");
+    fs::write(root.join("SKILL.md"), "# demo\n").expect("write skill");
+    fs::write(root.join("scripts").join("run.sh"), "echo hi\n").expect("write script");
+}
+
+#[test]
+fn tree_digest_is_independent_of_location_and_ignores_cache_metadata() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let first = temp.path().join("first");
+    let second = temp.path().join("second");
+    write_tree(&first);
+    write_tree(&second);
+    fs::create_dir_all(second.join(".git")).expect("create .git");
+    fs::write(second.join(".git").join("FETCH_HEAD"), "abc\n").expect("write fetch head");
+    fs::write(second.join(".eden-fetched-at"), "1700000000").expect("write fetched-at");
+    fs::write(second.join(".eden-safety.toml"), "version = 1\n").expect("write safety");
+
+    let digest = tree_digest(&first).expect("digest first");
+    assert!(digest.starts_with(DIGEST_PREFIX));
+    assert_eq!(digest, tree_digest(&second).expect("digest second"));
+}
+
+#[test]
+fn tree_digest_changes_with_content_and_paths() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path().join("skill");
+    write_tree(&root);
+    let original = tree_digest(&root).expect("digest");
+
+    fs::write(root.join("SKILL.md"), "# edited\n").expect("edit file");
+    let edited = tree_digest(&root).expect("digest edited");
+    assert_ne!(original, edited);
+
+    fs::rename(root.join("SKILL.md"), root.join("README.md")).expect("rename file");
+    assert_ne!(edited, tree_digest(&root).expect("digest renamed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn tree_digest_tracks_exec_bit() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path().join("skill");
+    write_tree(&root);
+    let original = tree_digest(&root).expect("digest");
+
+    let script = root.join("scripts").join("run.sh");
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).expect("chmod");
+    assert_ne!(original, tree_digest(&root).expect("digest executable"));
+}
+
+#[cfg(unix)]
+#[test]
+fn tree_digest_follows_root_symlink() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path().join("skill");
+    write_tree(&root);
+    let link = temp.path().join("link");
+    std::os::unix::fs::symlink(&root, &link).expect("symlink");
+
+    assert_eq!(
+        tree_digest(&root).expect("digest"),
+        tree_digest(&link).expect("digest via link")
+    );
+}
@@ -1,12 +1,13 @@
 #[cfg(windows)]
 mod windows {
+    use std::collections::BTreeMap;
     use std::fs;
     use std::path::{Path, PathBuf};
 
     use eden_skills_core::adapter::{LocalAdapter, TargetAdapter};
     use eden_skills_core::config::{
         AgentKind, Config, InstallConfig, InstallMode, ReactorConfig, SafetyConfig, SkillConfig,
-        SourceConfig, TargetConfig, VerifyConfig,
+        SourceConfig, TargetConfig, TargetScope, VerifyConfig,
     };
     use eden_skills_core::plan::{build_plan, Action};
     use eden_skills_core::source::resolve_skill_source_path;
@@ -110,12 +111,15 @@ mod windows {
             version: 1,
             storage_root: storage_root.display().to_string(),
             reactor: ReactorConfig::default(),
+            git: GitConfig::default(),
+            mirrors: Vec::new(),
             skills: vec![SkillConfig {
                 id: skill_id.to_string(),
                 source: SourceConfig {
                     repo: "file:///tmp/placeholder".to_string(),
                     subpath: ".".to_string(),
                     r#ref: "main".to_string(),
+                    verify_signature: false,
                 },
                 install: InstallConfig {
                     mode: InstallMode::Symlink,
@@ -125,6 +129,7 @@ mod windows {
                     expected_path: None,
                     path: Some(target_root.display().to_string()),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
                 }],
                 verify: VerifyConfig {
                     enabled: false,
@@ -133,6 +138,8 @@ mod windows {
                 safety: SafetyConfig {
                     no_exec_metadata_only: false,
                 },
+                requires: BTreeMap::new(),
+                implicit: false,
             }],
         }
     }
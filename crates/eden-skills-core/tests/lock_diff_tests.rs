@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use eden_skills_core::config::*;
@@ -21,6 +22,7 @@ fn make_skill_with_target(
             repo: repo.to_string(),
             subpath: subpath.to_string(),
             r#ref: ref_.to_string(),
+            verify_signature: false,
         },
         install: InstallConfig { mode },
         targets: vec![TargetConfig {
@@ -28,6 +30,7 @@ fn make_skill_with_target(
             expected_path: None,
             path: Some(target_path.to_string()),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
         }],
         verify: VerifyConfig {
             enabled: false,
@@ -36,6 +39,8 @@ fn make_skill_with_target(
         safety: SafetyConfig {
             no_exec_metadata_only: false,
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     }
 }
 
@@ -60,11 +65,16 @@ fn make_lock_entry_with_target(
         resolved_version: None,
         install_mode: mode.to_string(),
         installed_at: "2026-02-21T10:00:00Z".to_string(),
+        content_digest: None,
         targets: vec![LockTarget {
             agent: "custom".to_string(),
             path: resolved_target.to_string(),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
+            content_digest: None,
         }],
+        implicit: false,
+        requires: Vec::new(),
     }
 }
 
@@ -73,6 +83,8 @@ fn make_config(skills: Vec<SkillConfig>) -> Config {
         version: 1,
         storage_root: "/storage".to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills,
     }
 }
@@ -1,10 +1,13 @@
 use std::fs;
 use std::path::Path;
 
+use eden_skills_core::config::{load_from_file, Config, LoadOptions, TargetScope};
 use eden_skills_core::lock::{
-    lock_path_for_config, read_lock_file, write_lock_file, LockFile, LockSkillEntry, LockTarget,
-    LOCK_VERSION,
+    build_lock_from_config, carry_implicit_entries, lock_dependents, lock_path_for_config,
+    read_lock_file, record_content_digests, write_lock_file, DigestRefresh, LockFile,
+    LockSkillEntry, LockTarget, LOCK_VERSION,
 };
+use eden_skills_core::source::resolve_skill_source_path;
 
 // ---------------------------------------------------------------------------
 // LCK-004: lock_path_for_config
@@ -58,18 +61,25 @@ fn lock_file_round_trip_serialization() {
             resolved_version: None,
             install_mode: "symlink".to_string(),
             installed_at: "2026-02-21T10:30:00Z".to_string(),
+            content_digest: None,
             targets: vec![
                 LockTarget {
                     agent: "claude-code".to_string(),
                     path: "~/.claude/skills/browser-tool".to_string(),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
+                    content_digest: None,
                 },
                 LockTarget {
                     agent: "cursor".to_string(),
                     path: "~/.cursor/skills/browser-tool".to_string(),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
+                    content_digest: None,
                 },
             ],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
 
@@ -102,11 +112,16 @@ fn lock_file_contains_all_required_fields() {
             resolved_version: Some("1.2.0".to_string()),
             install_mode: "copy".to_string(),
             installed_at: "2026-02-21T10:30:05Z".to_string(),
+            content_digest: None,
             targets: vec![LockTarget {
                 agent: "claude-code".to_string(),
                 path: "~/.claude/skills/test-skill".to_string(),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
+                content_digest: None,
             }],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
 
@@ -141,11 +156,16 @@ fn lock_file_omits_resolved_version_when_none() {
             resolved_version: None,
             install_mode: "symlink".to_string(),
             installed_at: "2026-02-21T10:30:00Z".to_string(),
+            content_digest: None,
             targets: vec![LockTarget {
                 agent: "cursor".to_string(),
                 path: "~/.cursor/skills/url-skill".to_string(),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
+                content_digest: None,
             }],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
 
@@ -212,18 +232,25 @@ fn lock_targets_sorted_alphabetically_by_agent() {
             resolved_version: None,
             install_mode: "symlink".to_string(),
             installed_at: "2026-02-21T10:30:00Z".to_string(),
+            content_digest: None,
             targets: vec![
                 LockTarget {
                     agent: "cursor".to_string(),
                     path: "/cursor/skill-a".to_string(),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
+                    content_digest: None,
                 },
                 LockTarget {
                     agent: "claude-code".to_string(),
                     path: "/claude/skill-a".to_string(),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
+                    content_digest: None,
                 },
             ],
+            implicit: false,
+            requires: Vec::new(),
         }],
     };
 
@@ -289,10 +316,184 @@ fn timestamp_format_is_iso8601() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// Content digests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn content_digest_round_trips_and_is_omitted_when_absent() {
+    let mut entry = make_entry("digested");
+    entry.content_digest = Some("sha256:abc".to_string());
+    entry.targets[0].content_digest = Some("sha256:def".to_string());
+    let lock = LockFile {
+        version: LOCK_VERSION,
+        skills: vec![entry, make_entry("plain")],
+    };
+
+    let serialized = toml::to_string_pretty(&lock).unwrap();
+    assert_eq!(serialized.matches("content_digest").count(), 2);
+    let parsed: LockFile = toml::from_str(&serialized).unwrap();
+    assert_eq!(parsed, lock);
+}
+
+#[test]
+fn carry_implicit_entries_keeps_dependencies_still_required() {
+    let mut deploy = make_entry("deploy");
+    deploy.source_repo = "registry://official".to_string();
+    deploy.requires = vec!["k8s-conventions".to_string()];
+    let mut conventions = make_entry("k8s-conventions");
+    conventions.implicit = true;
+    conventions.requires = vec!["shell-utils".to_string()];
+    let mut shell_utils = make_entry("shell-utils");
+    shell_utils.implicit = true;
+    let mut stale = make_entry("stale-helper");
+    stale.implicit = true;
+    let previous = LockFile {
+        version: LOCK_VERSION,
+        skills: vec![deploy.clone(), conventions, shell_utils, stale],
+    };
+
+    // A lock rebuilt from config knows neither the index `requires` of
+    // registry skills nor their implicit dependencies.
+    let mut rebuilt = deploy.clone();
+    rebuilt.requires.clear();
+    let mut lock = LockFile {
+        version: LOCK_VERSION,
+        skills: vec![rebuilt],
+    };
+    carry_implicit_entries(&mut lock, Some(&previous));
+    let ids = lock
+        .skills
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["deploy", "k8s-conventions", "shell-utils"]);
+    assert_eq!(lock.skills[0].requires, vec!["k8s-conventions".to_string()]);
+    assert_eq!(lock_dependents(&lock, "k8s-conventions"), vec!["deploy"]);
+
+    let mut lock = LockFile {
+        version: LOCK_VERSION,
+        skills: vec![make_entry("other")],
+    };
+    carry_implicit_entries(&mut lock, Some(&previous));
+    assert_eq!(
+        lock.skills.len(),
+        1,
+        "unrequired implicit entries are dropped"
+    );
+}
+
+#[test]
+fn implicit_and_requires_are_omitted_when_unset() {
+    let mut entry = make_entry("k8s-conventions");
+    entry.implicit = true;
+    entry.requires = vec!["shell-utils".to_string()];
+    let lock = LockFile {
+        version: LOCK_VERSION,
+        skills: vec![entry, make_entry("plain")],
+    };
+
+    let serialized = toml::to_string_pretty(&lock).unwrap();
+    assert_eq!(serialized.matches("implicit = true").count(), 1);
+    assert_eq!(serialized.matches("requires").count(), 1);
+    let parsed: LockFile = toml::from_str(&serialized).unwrap();
+    assert_eq!(parsed, lock);
+}
+
+#[test]
+fn record_content_digests_refreshes_selected_skills_and_carries_the_rest() {
+    let dir = tempfile::tempdir().unwrap();
+    let (config, config_dir) = write_digest_config(dir.path(), "main");
+    let skill = &config.skills[0];
+    let source_path = resolve_skill_source_path(&dir.path().join("storage"), skill);
+    let target_path = dir.path().join("targets").join("demo");
+    fs::create_dir_all(&source_path).unwrap();
+    fs::create_dir_all(&target_path).unwrap();
+    fs::write(source_path.join("SKILL.md"), "source\n").unwrap();
+    fs::write(target_path.join("SKILL.md"), "source\n").unwrap();
+
+    let resolved = std::collections::HashMap::new();
+    let mut installed = build_lock_from_config(&config, &config_dir, &resolved).unwrap();
+    record_content_digests(
+        &mut installed,
+        &config,
+        &config_dir,
+        None,
+        DigestRefresh::All,
+    )
+    .unwrap();
+    let source_digest = installed.skills[0].content_digest.clone();
+    let target_digest = installed.skills[0].targets[0].content_digest.clone();
+    assert!(source_digest.is_some());
+    assert_eq!(target_digest, source_digest);
+
+    fs::write(target_path.join("SKILL.md"), "tampered\n").unwrap();
+    for refresh in [
+        DigestRefresh::Keep,
+        DigestRefresh::Skills(&["other".to_string()]),
+    ] {
+        let mut rewritten = build_lock_from_config(&config, &config_dir, &resolved).unwrap();
+        record_content_digests(
+            &mut rewritten,
+            &config,
+            &config_dir,
+            Some(&installed),
+            refresh,
+        )
+        .unwrap();
+        assert_eq!(rewritten.skills[0].content_digest, source_digest);
+        assert_eq!(rewritten.skills[0].targets[0].content_digest, target_digest);
+    }
+
+    let (moved, _) = write_digest_config(dir.path(), "v2");
+    let mut rewritten = build_lock_from_config(&moved, &config_dir, &resolved).unwrap();
+    record_content_digests(
+        &mut rewritten,
+        &moved,
+        &config_dir,
+        Some(&installed),
+        DigestRefresh::Keep,
+    )
+    .unwrap();
+    assert_eq!(rewritten.skills[0].content_digest, None);
+    assert_eq!(rewritten.skills[0].targets[0].content_digest, None);
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
+fn write_digest_config(base: &Path, source_ref: &str) -> (Config, std::path::PathBuf) {
+    let config_path = base.join("skills.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version = 1
+
+[storage]
+root = "storage"
+
+[[skills]]
+id = "demo"
+
+[skills.source]
+repo = "https://example.com/demo.git"
+ref = "{source_ref}"
+
+[skills.install]
+mode = "copy"
+
+[[skills.targets]]
+agent = "custom"
+path = "targets"
+"#
+        ),
+    )
+    .unwrap();
+    let loaded = load_from_file(&config_path, LoadOptions::default()).unwrap();
+    (loaded.config, base.to_path_buf())
+}
+
 fn make_entry(id: &str) -> LockSkillEntry {
     LockSkillEntry {
         id: id.to_string(),
@@ -303,10 +504,15 @@ fn make_entry(id: &str) -> LockSkillEntry {
         resolved_version: None,
         install_mode: "symlink".to_string(),
         installed_at: "2026-02-21T10:30:00Z".to_string(),
+        content_digest: None,
         targets: vec![LockTarget {
             agent: "claude-code".to_string(),
             path: format!("/skills/{id}"),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
+            content_digest: None,
         }],
+        implicit: false,
+        requires: Vec::new(),
     }
 }
@@ -3,10 +3,11 @@ use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
-use eden_skills_core::config::{AgentKind, TargetConfig};
+use eden_skills_core::config::{AgentKind, TargetConfig, TargetScope};
 use eden_skills_core::error::EdenError;
 use eden_skills_core::paths::{
-    default_agent_project_path, normalize_lexical, resolve_path_string, resolve_target_path,
+    default_agent_project_path, normalize_lexical, portable_target_path, resolve_path_string,
+    resolve_portable_target_path, resolve_target_path,
 };
 use tempfile::tempdir;
 
@@ -48,6 +49,7 @@ fn resolve_target_path_prefers_explicit_path_over_expected_path() {
         expected_path: Some("/ignored/expected".to_string()),
         path: Some("./explicit/../final".to_string()),
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
 
     let resolved = resolve_target_path(&target, config_dir).expect("resolve target path");
@@ -64,6 +66,7 @@ fn resolve_target_path_uses_expected_path_when_path_missing() {
         expected_path: Some("nested/./expected".to_string()),
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
 
     let resolved = resolve_target_path(&target, config_dir).expect("resolve target path");
@@ -86,12 +89,47 @@ fn resolve_target_path_uses_default_agent_path_when_no_explicit_paths() {
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
 
     let resolved = resolve_target_path(&target, config_dir).expect("resolve target path");
     assert_eq!(resolved, home.path().join(".claude").join("skills"));
 }
 
+#[test]
+fn resolve_target_path_uses_project_default_for_project_scope() {
+    let dir = tempdir().expect("tempdir");
+    let project_dir = dir.path();
+
+    let target = TargetConfig {
+        agent: AgentKind::Cursor,
+        expected_path: None,
+        path: None,
+        environment: "local".to_string(),
+        scope: TargetScope::Project,
+    };
+
+    let resolved = resolve_target_path(&target, project_dir).expect("resolve target path");
+    assert_eq!(resolved, project_dir.join(".agents").join("skills"));
+}
+
+#[test]
+fn portable_target_path_round_trips_project_targets() {
+    let dir = tempdir().expect("tempdir");
+    let project_dir = dir.path();
+    let installed = project_dir.join(".claude").join("skills").join("demo");
+
+    let recorded = portable_target_path(TargetScope::Project, &installed, project_dir);
+    assert_eq!(recorded, ".claude/skills/demo");
+    assert_eq!(
+        resolve_portable_target_path(TargetScope::Project, &recorded, project_dir),
+        installed
+    );
+
+    let global = portable_target_path(TargetScope::Global, &installed, project_dir);
+    assert_eq!(global, installed.display().to_string());
+}
+
 #[test]
 fn resolve_target_path_fails_for_custom_without_paths() {
     let dir = tempdir().expect("tempdir");
@@ -102,6 +140,7 @@ fn resolve_target_path_fails_for_custom_without_paths() {
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
 
     let err = resolve_target_path(&target, config_dir).expect_err("expected error");
@@ -125,24 +164,28 @@ fn resolve_target_path_supports_extended_agent_default_paths() {
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
     let codex = TargetConfig {
         agent: AgentKind::Codex,
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
     let windsurf = TargetConfig {
         agent: AgentKind::Windsurf,
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
     let adal = TargetConfig {
         agent: AgentKind::Adal,
         expected_path: None,
         path: None,
         environment: "local".to_string(),
+        scope: TargetScope::Global,
     };
 
     assert_eq!(
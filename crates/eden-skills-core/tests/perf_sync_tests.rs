@@ -1,14 +1,17 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use eden_skills_core::config::{
-    AgentKind, Config, InstallConfig, InstallMode, ReactorConfig, SafetyConfig, SkillConfig,
-    SourceConfig, TargetConfig, VerifyConfig,
+    AgentKind, Config, GitConfig, InstallConfig, InstallMode, ReactorConfig, SafetyConfig,
+    SkillConfig, SourceConfig, TargetConfig, TargetScope, VerifyConfig,
 };
+use eden_skills_core::git;
 use eden_skills_core::plan::{build_plan, Action};
 use eden_skills_core::source::{
-    normalize_repo_url, repo_cache_key, resolve_skill_source_path, sanitize_ref, sync_sources,
+    normalize_repo_url, object_store_dir, repo_cache_key, resolve_skill_source_path, sanitize_ref,
+    sync_sources,
 };
 use tempfile::tempdir;
 
@@ -80,6 +83,8 @@ fn build_plan_uses_repo_cache_source_path_for_remote_skills() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![test_skill_config(
             "browser-tool",
             "https://github.com/AI-Eden/eden-skills",
@@ -222,6 +227,62 @@ fn sync_sources_separates_cache_directories_by_ref() {
     assert!(cache_entries.contains(&repo_cache_key(&repo_url, "v2.0")));
 }
 
+#[test]
+fn sync_sources_checks_out_refs_as_worktrees_of_one_object_store() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    run_git(&origin_repo, &["tag", "v2.0"]);
+    let storage_root = temp.path().join("storage");
+    fs::create_dir_all(&storage_root).expect("create storage");
+    let repo_url = as_file_url(&origin_repo);
+    let config = test_config(
+        &storage_root,
+        vec![
+            test_skill_config(
+                "browser-tool-main",
+                &repo_url,
+                "packages/browser",
+                "main",
+                temp.path(),
+            ),
+            test_skill_config(
+                "browser-tool-tag",
+                &repo_url,
+                "packages/browser",
+                "v2.0",
+                temp.path(),
+            ),
+        ],
+    );
+
+    let summary = sync_sources(&config, temp.path()).expect("sync sources");
+    assert_eq!(summary.cloned, 2);
+    assert_eq!(summary.failed, 0);
+
+    let store = storage_root
+        .join(".objects")
+        .join(format!("{}.git", normalize_repo_url(&repo_url)));
+    let stores = fs::read_dir(storage_root.join(".objects"))
+        .expect("read object stores")
+        .count();
+    assert_eq!(stores, 1, "expected one object store for both refs");
+    for reference in ["main", "v2.0"] {
+        let repo_dir = storage_root
+            .join(".repos")
+            .join(repo_cache_key(&repo_url, reference));
+        assert_eq!(
+            object_store_dir(&repo_dir).as_deref(),
+            Some(store.as_path())
+        );
+        assert!(
+            repo_dir.join(".git").is_file(),
+            "expected `{reference}` to be a worktree"
+        );
+        assert!(repo_dir.join("packages/browser/README.txt").exists());
+    }
+    assert_eq!(git::store_worktrees(&store).len(), 2);
+}
+
 #[test]
 fn legacy_per_skill_directories_do_not_block_repo_cache_sync() {
     let temp = tempdir().expect("tempdir");
@@ -298,6 +359,8 @@ fn test_config(storage_root: &Path, skills: Vec<SkillConfig>) -> Config {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills,
     }
 }
@@ -319,6 +382,7 @@ fn test_skill_config(
             repo: repo_url.to_string(),
             subpath: subpath.to_string(),
             r#ref: reference.to_string(),
+            verify_signature: false,
         },
         install: InstallConfig {
             mode: InstallMode::Symlink,
@@ -328,6 +392,7 @@ fn test_skill_config(
             expected_path: None,
             path: Some(target_root.join("targets").display().to_string()),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
         }],
         verify: VerifyConfig {
             enabled: false,
@@ -336,6 +401,8 @@ fn test_skill_config(
         safety: SafetyConfig {
             no_exec_metadata_only: false,
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     }
 }
 
@@ -141,6 +141,82 @@ path = "/tmp/agent-skills"
     );
 }
 
+#[test]
+fn skill_requires_are_loaded_for_both_modes() {
+    let path = write_config(
+        r#"
+version = 1
+
+[registries]
+official = { url = "https://example.com/official.git", priority = 100 }
+
+[[skills]]
+name = "deploy"
+version = "^1.0"
+requires = { k8s-conventions = "^1", shell-utils = "~0.2" }
+
+[[skills.targets]]
+agent = "custom"
+path = "/tmp/agent-skills"
+
+[[skills]]
+id = "local-deploy"
+requires = { k8s-conventions = "*" }
+
+[skills.source]
+repo = "https://example.com/local-deploy.git"
+
+[[skills.targets]]
+agent = "custom"
+path = "/tmp/agent-skills"
+"#,
+    );
+
+    let loaded = load_from_file(&path, LoadOptions::default()).expect("requires should load");
+    let deploy = &loaded.config.skills[0];
+    assert_eq!(deploy.requires.len(), 2);
+    assert_eq!(deploy.requires["k8s-conventions"], "^1");
+    assert!(!deploy.implicit);
+    assert_eq!(loaded.config.skills[1].requires["k8s-conventions"], "*");
+}
+
+#[test]
+fn invalid_skill_requires_return_validation_codes() {
+    let cases = [
+        (
+            "requires = { k8s-conventions = \"^1\" }\n",
+            false,
+            "MISSING_REGISTRIES",
+        ),
+        (
+            "requires = { k8s-conventions = \"one\" }\n",
+            true,
+            "INVALID_SEMVER",
+        ),
+        (
+            "requires = { deploy = \"^1\" }\n",
+            true,
+            "a skill cannot require itself",
+        ),
+    ];
+    for (requires, with_registries, expected) in cases {
+        let registries = if with_registries {
+            "[registries]\nofficial = { url = \"https://example.com/official.git\" }\n"
+        } else {
+            ""
+        };
+        let path = write_config(&format!(
+            "version = 1\n{registries}\n[[skills]]\nid = \"deploy\"\n{requires}\n[skills.source]\nrepo = \"https://example.com/deploy.git\"\n\n[[skills.targets]]\nagent = \"custom\"\npath = \"/tmp/agent-skills\"\n"
+        ));
+        let err =
+            load_from_file(&path, LoadOptions::default()).expect_err("expected validation error");
+        assert!(
+            err.to_string().contains(expected),
+            "expected `{expected}`, got {err}"
+        );
+    }
+}
+
 #[test]
 fn invalid_target_environment_returns_invalid_environment_code() {
     let path = write_config(
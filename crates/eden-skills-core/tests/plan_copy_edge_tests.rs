@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 #[cfg(windows)]
@@ -7,8 +8,8 @@ use std::process::Command;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use eden_skills_core::config::{
-    AgentKind, Config, InstallConfig, InstallMode, ReactorConfig, SafetyConfig, SkillConfig,
-    SourceConfig, TargetConfig, VerifyConfig,
+    AgentKind, Config, GitConfig, InstallConfig, InstallMode, ReactorConfig, SafetyConfig,
+    SkillConfig, SourceConfig, TargetConfig, TargetScope, VerifyConfig,
 };
 use eden_skills_core::plan::{build_plan, Action};
 use eden_skills_core::source::resolve_skill_source_path;
@@ -38,6 +39,7 @@ fn copy_mode_skill(skill_id: &str, target_root: &Path) -> SkillConfig {
             repo: "file:///tmp/placeholder".to_string(),
             subpath: ".".to_string(),
             r#ref: "main".to_string(),
+            verify_signature: false,
         },
         install: InstallConfig {
             mode: InstallMode::Copy,
@@ -47,6 +49,7 @@ fn copy_mode_skill(skill_id: &str, target_root: &Path) -> SkillConfig {
             expected_path: None,
             path: Some(target_root.display().to_string()),
             environment: "local".to_string(),
+            scope: TargetScope::Global,
         }],
         verify: VerifyConfig {
             enabled: false,
@@ -55,6 +58,8 @@ fn copy_mode_skill(skill_id: &str, target_root: &Path) -> SkillConfig {
         safety: SafetyConfig {
             no_exec_metadata_only: false,
         },
+        requires: BTreeMap::new(),
+        implicit: false,
     }
 }
 
@@ -123,6 +128,8 @@ fn copy_mode_plan_noop_streams_large_file() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -168,6 +175,8 @@ fn copy_mode_plan_conflict_on_unreadable_target_file() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -211,6 +220,8 @@ fn copy_mode_plan_conflict_on_symlink_in_tree() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -263,6 +274,8 @@ fn copy_mode_plan_conflict_on_unreadable_target_file() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -305,6 +318,8 @@ fn copy_mode_plan_conflict_on_symlink_in_tree() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -364,6 +379,8 @@ fn tm_p295_037_copy_mode_mtime_and_size_fast_path_avoids_byte_comparison() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
@@ -411,6 +428,8 @@ fn tm_p295_037_copy_mode_mtime_and_size_fast_path_avoids_byte_comparison() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![skill],
     };
 
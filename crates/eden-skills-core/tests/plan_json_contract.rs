@@ -11,6 +11,7 @@ fn plan_json_serialization_keeps_stable_fields() {
         install_mode: InstallMode::Symlink,
         action: Action::Create,
         reasons: vec!["target path does not exist".to_string()],
+        layer: None,
     };
 
     let payload = serde_json::to_value(vec![item]).expect("serialize plan json");
@@ -23,4 +24,8 @@ fn plan_json_serialization_keeps_stable_fields() {
         first.get("reasons"),
         Some(&json!(["target path does not exist"]))
     );
+    assert!(
+        first.get("layer").is_none(),
+        "single-file plans should not emit a layer field"
+    );
 }
@@ -1,9 +1,11 @@
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
 use eden_skills_core::registry::{
-    parse_registry_specs_from_toml, resolve_skill_from_registry_sources,
-    sort_registry_specs_by_priority, RegistrySource,
+    index_entry_path, parse_registry_specs_from_toml, read_index_format_version,
+    resolve_skill_from_registry_sources, resolve_skill_set, search_registry_sources,
+    sort_registry_specs_by_priority, RegistrySource, SkillRequirement,
 };
 use tempfile::tempdir;
 
@@ -257,6 +259,410 @@ fn resolve_skill_handles_prerelease_matching_and_default_selection() {
     assert_eq!(default_pick.version, "2.0.3");
 }
 
+#[test]
+fn index_entry_path_buckets_by_one_or_two_characters_per_format_version() {
+    let root = Path::new("registry");
+    assert_eq!(
+        index_entry_path(root, "Google-search", 1).expect("v1 path"),
+        root.join("index").join("g").join("Google-search.toml")
+    );
+    assert_eq!(
+        index_entry_path(root, "Google-search", 2).expect("v2 path"),
+        root.join("index").join("go").join("Google-search.toml")
+    );
+    assert_eq!(
+        index_entry_path(root, "x", 2).expect("one-character name"),
+        root.join("index").join("x").join("x.toml")
+    );
+    assert!(index_entry_path(root, "", 2).is_err());
+}
+
+#[test]
+fn resolve_skill_reads_layout_from_manifest_format_version() {
+    let temp = tempdir().expect("tempdir");
+    let v1_root = temp.path().join("v1");
+    let v2_root = temp.path().join("v2");
+    write_index_entry(
+        &v1_root,
+        "google-search",
+        "https://example.com/v1/google-search.git",
+        &[("1.0.0", "v1.0.0", &"a".repeat(40), false)],
+    );
+    write_index_entry(
+        &temp.path().join("staging"),
+        "google-search",
+        "https://example.com/v2/google-search.git",
+        &[("2.0.0", "v2.0.0", &"b".repeat(40), false)],
+    );
+    let v2_entry = index_entry_path(&v2_root, "google-search", 2).expect("v2 path");
+    fs::create_dir_all(v2_entry.parent().expect("bucket")).expect("create bucket");
+    fs::rename(
+        temp.path()
+            .join("staging")
+            .join("index")
+            .join("g")
+            .join("google-search.toml"),
+        &v2_entry,
+    )
+    .expect("move entry into v2 bucket");
+    fs::write(
+        v2_root.join("manifest.toml"),
+        "format_version = 2\nname = \"v2\"\n",
+    )
+    .expect("write manifest");
+
+    assert_eq!(
+        read_index_format_version(&v1_root).expect("no manifest"),
+        None
+    );
+    assert_eq!(
+        read_index_format_version(&v2_root).expect("manifest"),
+        Some(2)
+    );
+
+    let source = |name: &str, root: &Path| RegistrySource {
+        name: name.to_string(),
+        priority: 100,
+        root: root.to_path_buf(),
+    };
+    let from_v1 =
+        resolve_skill_from_registry_sources(&[source("v1", &v1_root)], "google-search", None)
+            .expect("resolve from v1 layout");
+    assert_eq!(from_v1.version, "1.0.0");
+    let from_v2 =
+        resolve_skill_from_registry_sources(&[source("v2", &v2_root)], "google-search", None)
+            .expect("resolve from v2 layout");
+    assert_eq!(from_v2.version, "2.0.0");
+
+    // A v1 bucket is not consulted once the manifest declares v2.
+    fs::write(
+        v1_root.join("manifest.toml"),
+        "format_version = 2\nname = \"v1\"\n",
+    )
+    .expect("bump manifest");
+    assert!(
+        resolve_skill_from_registry_sources(&[source("v1", &v1_root)], "google-search", None)
+            .is_err()
+    );
+}
+
+#[test]
+fn search_ranks_name_matches_above_tags_and_descriptions_across_registries() {
+    let temp = tempdir().expect("tempdir");
+    let official_root = temp.path().join("official");
+    let forge_root = temp.path().join("forge");
+
+    write_raw_index_entry(
+        &official_root,
+        "browser-tool",
+        "description = \"Drive a headless browser\"\ntags = [\"web\"]",
+        &[("1.0.0", false), ("1.2.0", false), ("2.0.0", true)],
+    );
+    write_raw_index_entry(
+        &official_root,
+        "scraper",
+        "description = \"Fetch pages\"\ntags = [\"browser\", \"web\"]\ndeprecated = true",
+        &[("0.3.0", false)],
+    );
+    write_raw_index_entry(
+        &official_root,
+        "notes",
+        "description = \"Works with any browser profile\"",
+        &[("1.0.0", true)],
+    );
+    write_raw_index_entry(&official_root, "unrelated", "", &[("1.0.0", false)]);
+    write_raw_index_entry(&forge_root, "browser-tool", "", &[("3.0.0", false)]);
+    fs::write(
+        official_root.join("index").join("b").join("broken.toml"),
+        "not toml [",
+    )
+    .expect("write broken entry");
+
+    let sources = vec![
+        RegistrySource {
+            name: "official".to_string(),
+            priority: 100,
+            root: official_root,
+        },
+        RegistrySource {
+            name: "forge".to_string(),
+            priority: 10,
+            root: forge_root,
+        },
+    ];
+
+    let search = search_registry_sources(&sources, "Browser").expect("search");
+    let ranked = search
+        .hits
+        .iter()
+        .map(|hit| (hit.name.as_str(), hit.registry_name.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranked,
+        vec![
+            ("browser-tool", "official"),
+            ("browser-tool", "forge"),
+            ("scraper", "official"),
+            ("notes", "official"),
+        ]
+    );
+    assert_eq!(search.hits[0].latest_version.as_deref(), Some("1.2.0"));
+    assert_eq!(search.hits[1].latest_version.as_deref(), Some("3.0.0"));
+    assert!(search.hits[2].deprecated);
+    assert_eq!(search.hits[3].latest_version, None);
+    assert_eq!(search.skipped.len(), 1, "skipped={:?}", search.skipped);
+
+    let narrowed = search_registry_sources(&sources, "browser web").expect("search");
+    let names = narrowed
+        .hits
+        .iter()
+        .map(|hit| hit.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["browser-tool", "scraper"]);
+
+    assert!(search_registry_sources(&sources, "  ").is_err());
+}
+
+#[test]
+fn resolve_skill_set_pulls_in_transitive_requirements() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path().join("official");
+    write_dependency_fixture(&root);
+    let sources = vec![RegistrySource {
+        name: "official".to_string(),
+        priority: 100,
+        root,
+    }];
+    let requirements = vec![configured_requirement("deploy", "*")];
+
+    let set = resolve_skill_set(&sources, &requirements, &BTreeSet::new()).expect("resolve set");
+    let picked = set
+        .iter()
+        .map(|dependency| {
+            (
+                dependency.name.as_str(),
+                dependency.skill.version.as_str(),
+                dependency.required_by.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        picked,
+        vec![
+            ("deploy", "1.0.0", Vec::new()),
+            ("k8s-conventions", "1.3.0", vec!["deploy".to_string()]),
+            ("shell-utils", "0.2.1", vec!["k8s-conventions".to_string()]),
+        ]
+    );
+    assert_eq!(set[1].skill.requires["shell-utils"], "~0.2");
+
+    let provided = BTreeSet::from(["shell-utils".to_string()]);
+    let set = resolve_skill_set(&sources, &requirements, &provided).expect("resolve set");
+    assert_eq!(
+        set.iter()
+            .map(|dependency| dependency.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["deploy", "k8s-conventions"]
+    );
+}
+
+#[test]
+fn resolve_skill_set_picks_one_version_for_every_constraint() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path().join("official");
+    write_dependency_fixture(&root);
+    let sources = vec![RegistrySource {
+        name: "official".to_string(),
+        priority: 100,
+        root,
+    }];
+
+    let set = resolve_skill_set(
+        &sources,
+        &[
+            configured_requirement("deploy", "*"),
+            configured_requirement("k8s-conventions", "<1.3"),
+        ],
+        &BTreeSet::new(),
+    )
+    .expect("resolve set");
+    assert_eq!(set[1].name, "k8s-conventions");
+    assert_eq!(set[1].skill.version, "1.0.0");
+    assert_eq!(set[1].required_by, vec!["deploy".to_string()]);
+    assert!(
+        set.iter()
+            .all(|dependency| dependency.name != "shell-utils"),
+        "1.0.0 requires nothing"
+    );
+
+    let err = resolve_skill_set(
+        &sources,
+        &[
+            configured_requirement("deploy", "*"),
+            configured_requirement("k8s-conventions", "^2"),
+        ],
+        &BTreeSet::new(),
+    )
+    .expect_err("conflicting constraints");
+    let message = err.to_string();
+    assert!(
+        message.contains("no version of `k8s-conventions` satisfies")
+            && message.contains("`^1` (required by `deploy`)")
+            && message.contains("`^2` (configured)"),
+        "message={message}"
+    );
+
+    let err = resolve_skill_set(
+        &sources,
+        &[configured_requirement("broken-deploy", "*")],
+        &BTreeSet::new(),
+    )
+    .expect_err("missing dependency");
+    assert!(
+        err.to_string()
+            .contains("skill `missing-skill` required by `broken-deploy` not found"),
+        "message={err}"
+    );
+}
+
+#[test]
+fn resolve_skill_set_drops_requirements_of_replaced_versions() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path().join("official");
+    // `release` only surfaces the `app <2` constraint after the first
+    // round picked `app` 2.0.0, whose `lib ^2` clashes with `lib ^1`.
+    write_requiring_index_entry(&root, "release", &[("1.0.0", "app = \"<2\"")]);
+    write_requiring_index_entry(
+        &root,
+        "app",
+        &[("1.0.0", "lib = \"^1\""), ("2.0.0", "lib = \"^2\"")],
+    );
+    write_requiring_index_entry(&root, "lib", &[("1.0.0", ""), ("2.0.0", "")]);
+    let sources = vec![RegistrySource {
+        name: "official".to_string(),
+        priority: 100,
+        root,
+    }];
+
+    let set = resolve_skill_set(
+        &sources,
+        &[
+            configured_requirement("release", "*"),
+            configured_requirement("app", "*"),
+            configured_requirement("lib", "^1"),
+        ],
+        &BTreeSet::new(),
+    )
+    .expect("the downgraded app no longer requires lib ^2");
+    assert_eq!(
+        set.iter()
+            .map(|dependency| (dependency.name.as_str(), dependency.skill.version.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("app", "1.0.0"), ("lib", "1.0.0"), ("release", "1.0.0")]
+    );
+    assert_eq!(set[1].required_by, vec!["app".to_string()]);
+}
+
+#[test]
+fn resolve_skill_set_ignores_missing_skills_only_wanted_by_replaced_versions() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path().join("official");
+    // The first round picks `app` 2.0.0, which requires a skill no
+    // registry lists; `release` then downgrades `app` to 1.0.0.
+    write_requiring_index_entry(&root, "release", &[("1.0.0", "app = \"<2\"")]);
+    write_requiring_index_entry(&root, "app", &[("1.0.0", ""), ("2.0.0", "ghost = \"^1\"")]);
+    let sources = vec![RegistrySource {
+        name: "official".to_string(),
+        priority: 100,
+        root,
+    }];
+
+    let set = resolve_skill_set(
+        &sources,
+        &[
+            configured_requirement("release", "*"),
+            configured_requirement("app", "*"),
+        ],
+        &BTreeSet::new(),
+    )
+    .expect("the downgraded app no longer requires ghost");
+    assert_eq!(
+        set.iter()
+            .map(|dependency| (dependency.name.as_str(), dependency.skill.version.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("app", "1.0.0"), ("release", "1.0.0")]
+    );
+}
+
+fn configured_requirement(name: &str, constraint: &str) -> SkillRequirement {
+    SkillRequirement {
+        name: name.to_string(),
+        constraint: constraint.to_string(),
+        required_by: None,
+        registry: None,
+    }
+}
+
+/// `deploy` requires `k8s-conventions ^1`, whose 1.3.0 release requires
+/// `shell-utils ~0.2`; `broken-deploy` requires a skill no index lists.
+fn write_dependency_fixture(root: &Path) {
+    write_requiring_index_entry(root, "deploy", &[("1.0.0", "k8s-conventions = \"^1\"")]);
+    write_requiring_index_entry(
+        root,
+        "k8s-conventions",
+        &[
+            ("1.0.0", ""),
+            ("1.3.0", "shell-utils = \"~0.2\""),
+            ("2.0.0", ""),
+        ],
+    );
+    write_requiring_index_entry(root, "shell-utils", &[("0.2.1", ""), ("0.3.0", "")]);
+    write_requiring_index_entry(root, "broken-deploy", &[("1.0.0", "missing-skill = \"*\"")]);
+}
+
+/// Write an index entry; each version is `(version, requires)` where
+/// `requires` is the body of its inline `requires` table.
+fn write_requiring_index_entry(registry_root: &Path, skill_name: &str, versions: &[(&str, &str)]) {
+    let first = skill_name.chars().next().expect("skill name");
+    let index_dir = registry_root.join("index").join(first.to_string());
+    fs::create_dir_all(&index_dir).expect("create index dir");
+
+    let mut body = format!(
+        "[skill]\nname = \"{skill_name}\"\nrepo = \"https://example.com/{skill_name}.git\"\n\n"
+    );
+    for (version, requires) in versions {
+        body.push_str(&format!(
+            "[[versions]]\nversion = \"{version}\"\nref = \"v{version}\"\ncommit = \"{}\"\nrequires = {{ {requires} }}\n\n",
+            "a".repeat(40)
+        ));
+    }
+    fs::write(index_dir.join(format!("{skill_name}.toml")), body).expect("write index entry");
+}
+
+/// Write an index entry whose `[skill]` table carries `extra` lines;
+/// each version is `(version, yanked)`.
+fn write_raw_index_entry(
+    registry_root: &Path,
+    skill_name: &str,
+    extra: &str,
+    versions: &[(&str, bool)],
+) {
+    let first = skill_name.chars().next().expect("skill name");
+    let index_dir = registry_root.join("index").join(first.to_string());
+    fs::create_dir_all(&index_dir).expect("create index dir");
+
+    let mut body = format!(
+        "[skill]\nname = \"{skill_name}\"\nrepo = \"https://example.com/{skill_name}.git\"\n{extra}\n\n"
+    );
+    for (version, yanked) in versions {
+        body.push_str(&format!(
+            "[[versions]]\nversion = \"{version}\"\nref = \"v{version}\"\ncommit = \"{}\"\nyanked = {yanked}\n\n",
+            "a".repeat(40)
+        ));
+    }
+    fs::write(index_dir.join(format!("{skill_name}.toml")), body).expect("write index entry");
+}
+
 fn write_index_entry(
     registry_root: &Path,
     skill_name: &str,
@@ -0,0 +1,96 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use eden_skills_core::reactor::{url_host, HostLimiter};
+use eden_skills_core::retry::{is_transient_failure, RetryPolicy};
+
+fn policy(retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        retries,
+        backoff: Duration::ZERO,
+        timeout: None,
+    }
+}
+
+#[test]
+fn transient_failures_are_retried_until_success() {
+    let calls = Cell::new(0);
+    let outcome = policy(2).run(|| {
+        calls.set(calls.get() + 1);
+        if calls.get() < 3 {
+            Err("fatal: unable to access: Could not resolve host: github.com".to_string())
+        } else {
+            Ok(calls.get())
+        }
+    });
+    assert_eq!(outcome.attempts, 3);
+    assert_eq!(outcome.result, Ok(3));
+}
+
+#[test]
+fn exhausted_retries_report_the_attempt_count() {
+    let outcome = policy(1).run(|| Err::<(), _>("connection reset by peer".to_string()));
+    assert_eq!(outcome.attempts, 2);
+    assert_eq!(
+        outcome.result,
+        Err("connection reset by peer (failed after 2 attempts)".to_string())
+    );
+}
+
+#[test]
+fn permanent_failures_are_not_retried() {
+    let outcome = policy(3).run(|| Err::<(), _>("remote: Repository not found.".to_string()));
+    assert_eq!(outcome.attempts, 1);
+    assert_eq!(
+        outcome.result,
+        Err("remote: Repository not found.".to_string())
+    );
+    assert!(is_transient_failure(
+        "git command timed out after 5s while trying to fetch"
+    ));
+}
+
+#[test]
+fn url_host_covers_remote_url_forms() {
+    assert_eq!(
+        url_host("https://GitHub.com/acme/skills.git").as_deref(),
+        Some("github.com")
+    );
+    assert_eq!(
+        url_host("ssh://git@git.example:2222/acme/skills").as_deref(),
+        Some("git.example")
+    );
+    assert_eq!(
+        url_host("git@github.com:acme/skills.git").as_deref(),
+        Some("github.com")
+    );
+    assert_eq!(url_host("file:///srv/repos/skills"), None);
+    assert_eq!(url_host("/srv/repos/skills"), None);
+    assert_eq!(url_host("C:\\repos\\skills"), None);
+}
+
+#[tokio::test]
+async fn host_limiter_caps_permits_per_host() {
+    let hosts = HostLimiter::new(1);
+    let first = hosts.acquire("https://github.com/acme/one").await;
+    assert!(first.is_some());
+
+    let other_host = hosts.acquire("https://gitlab.com/acme/two").await;
+    assert!(other_host.is_some(), "other hosts are not limited");
+    assert!(hosts.acquire("file:///srv/repos/three").await.is_none());
+
+    let blocked = tokio::time::timeout(
+        Duration::from_millis(50),
+        hosts.acquire("https://github.com/acme/two"),
+    )
+    .await;
+    assert!(blocked.is_err(), "second github.com permit should wait");
+
+    drop(first);
+    let released = tokio::time::timeout(
+        Duration::from_millis(50),
+        hosts.acquire("https://github.com/acme/two"),
+    )
+    .await;
+    assert!(matches!(released, Ok(Some(_))));
+}
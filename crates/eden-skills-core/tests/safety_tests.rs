@@ -1,10 +1,11 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
 use eden_skills_core::config::{
-    AgentKind, Config, InstallConfig, InstallMode, ReactorConfig, SafetyConfig, SkillConfig,
-    SourceConfig, TargetConfig, VerifyConfig,
+    AgentKind, Config, GitConfig, InstallConfig, InstallMode, ReactorConfig, SafetyConfig,
+    SkillConfig, SourceConfig, TargetConfig, TargetScope, VerifyConfig,
 };
 use eden_skills_core::safety::{analyze_skills, persist_reports, LicenseStatus};
 use eden_skills_core::source::resolve_repo_cache_root;
@@ -171,12 +172,15 @@ fn test_config(storage_root: &Path, repo_url: &str) -> Config {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![SkillConfig {
             id: SKILL_ID.to_string(),
             source: SourceConfig {
                 repo: repo_url.to_string(),
                 subpath: "packages/browser".to_string(),
                 r#ref: "main".to_string(),
+                verify_signature: false,
             },
             install: InstallConfig {
                 mode: InstallMode::Symlink,
@@ -186,6 +190,7 @@ fn test_config(storage_root: &Path, repo_url: &str) -> Config {
                 expected_path: None,
                 path: Some(storage_root.join("targets").display().to_string()),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
             }],
             verify: VerifyConfig {
                 enabled: false,
@@ -194,6 +199,8 @@ fn test_config(storage_root: &Path, repo_url: &str) -> Config {
             safety: SafetyConfig {
                 no_exec_metadata_only: false,
             },
+            requires: BTreeMap::new(),
+            implicit: false,
         }],
     }
 }
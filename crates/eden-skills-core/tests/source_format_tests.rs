@@ -91,3 +91,25 @@ fn auto_derived_id_uses_repo_tail_without_git_suffix() {
         .expect("derive id");
     assert_eq!(derived, "my-skill");
 }
+
+#[test]
+fn archive_url_is_detected_before_github_urls() {
+    let input = "https://github.com/acme/skills/releases/download/v1.2.0/browser-tool.tar.gz";
+    let detected = detect_install_source(input, Path::new(".")).expect("detect");
+    let url_source = match detected {
+        DetectedInstallSource::Url(source) => source,
+        DetectedInstallSource::RegistryName(_) => panic!("expected url source"),
+    };
+
+    assert_eq!(url_source.source_type, UrlSourceType::Archive);
+    assert_eq!(url_source.repo, input);
+    assert_eq!(
+        derive_skill_id_from_source_repo(input).expect("derive id"),
+        "browser-tool"
+    );
+    assert_eq!(
+        derive_skill_id_from_source_repo("https://example.com/dl/pack.zip?token=abc")
+            .expect("derive id"),
+        "pack"
+    );
+}
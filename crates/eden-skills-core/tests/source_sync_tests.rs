@@ -1,12 +1,19 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use eden_skills_core::config::{
-    AgentKind, Config, InstallConfig, InstallMode, ReactorConfig, SafetyConfig, SkillConfig,
-    SourceConfig, TargetConfig, VerifyConfig,
+    AgentKind, Config, GitConfig, InstallConfig, InstallMode, ReactorConfig, SafetyConfig,
+    SkillConfig, SourceConfig, TargetConfig, TargetScope, VerifyConfig,
+};
+use eden_skills_core::git::{self, GitBackend};
+use eden_skills_core::reactor::SkillReactor;
+use eden_skills_core::source::{
+    narrow_sparse_checkouts, object_store_dir, repo_cache_contains_subpath, repo_cache_key,
+    repo_cache_sparse_paths, resolve_repo_cache_root, sync_sources, sync_sources_locked_async,
+    LockedSync, SyncFailureStage,
 };
-use eden_skills_core::source::{resolve_repo_cache_root, sync_sources, SyncFailureStage};
 use tempfile::tempdir;
 
 const SKILL_ID: &str = "demo-skill";
@@ -123,6 +130,8 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![
             SkillConfig {
                 id: "good-skill".to_string(),
@@ -130,6 +139,7 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                     repo: good_repo_url.clone(),
                     subpath: ".".to_string(),
                     r#ref: "main".to_string(),
+                    verify_signature: false,
                 },
                 install: InstallConfig {
                     mode: InstallMode::Symlink,
@@ -139,6 +149,7 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                     expected_path: None,
                     path: Some(storage_root.join("targets").display().to_string()),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
                 }],
                 verify: VerifyConfig {
                     enabled: false,
@@ -147,6 +158,8 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                 safety: SafetyConfig {
                     no_exec_metadata_only: false,
                 },
+                requires: BTreeMap::new(),
+                implicit: false,
             },
             SkillConfig {
                 id: "bad-skill".to_string(),
@@ -154,6 +167,7 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                     repo: as_file_url(&missing_repo),
                     subpath: ".".to_string(),
                     r#ref: "main".to_string(),
+                    verify_signature: false,
                 },
                 install: InstallConfig {
                     mode: InstallMode::Symlink,
@@ -163,6 +177,7 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                     expected_path: None,
                     path: Some(storage_root.join("targets").display().to_string()),
                     environment: "local".to_string(),
+                    scope: TargetScope::Global,
                 }],
                 verify: VerifyConfig {
                     enabled: false,
@@ -171,6 +186,8 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
                 safety: SafetyConfig {
                     no_exec_metadata_only: false,
                 },
+                requires: BTreeMap::new(),
+                implicit: false,
             },
         ],
     };
@@ -191,6 +208,226 @@ fn sync_sources_continues_after_failure_and_aggregates_results() {
     );
 }
 
+#[test]
+fn sync_sources_checks_out_only_referenced_subpaths() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    fs::create_dir_all(origin_repo.join("packages/other")).expect("create other dir");
+    fs::write(origin_repo.join("packages/other/README.txt"), "other\n").expect("write other");
+    run_git(&origin_repo, &["add", "."]);
+    run_git(&origin_repo, &["commit", "-m", "add other"]);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+    let repo_dir = resolve_repo_cache_root(&storage_root, &repo_url, "main");
+
+    let mut config = test_config(&storage_root, &repo_url, "main");
+    config.skills[0].source.subpath = "packages/browser".to_string();
+    let summary = sync_sources(&config, temp.path()).expect("first sync");
+    assert_eq!(summary.failed, 0, "failures: {:?}", summary.failures);
+    assert!(repo_dir.join("packages/browser/README.txt").exists());
+    assert!(!repo_dir.join("packages/other").exists());
+    assert_eq!(
+        repo_cache_sparse_paths(GitBackend::Cli, &repo_dir)
+            .map(|paths| paths.into_iter().collect::<Vec<_>>()),
+        Some(vec!["packages/browser".to_string()])
+    );
+
+    // A second skill sharing the cache entry widens the sparse set, even
+    // within the freshness window.
+    let mut other = config.skills[0].clone();
+    other.id = "other-skill".to_string();
+    other.source.subpath = "packages/other".to_string();
+    config.skills.push(other);
+    let summary = sync_sources(&config, temp.path()).expect("widening sync");
+    assert_eq!(summary.failed, 0, "failures: {:?}", summary.failures);
+    assert!(repo_dir.join("packages/other/README.txt").exists());
+    assert!(repo_cache_contains_subpath(
+        GitBackend::Cli,
+        &repo_dir,
+        "packages/other"
+    ));
+
+    // Dropping it keeps the path on sync; only narrowing drops it.
+    config.skills.pop();
+    let summary = sync_sources(&config, temp.path()).expect("sync without the skill");
+    assert_eq!(summary.failed, 0, "failures: {:?}", summary.failures);
+    assert!(repo_dir.join("packages/other/README.txt").exists());
+    assert_eq!(
+        narrow_sparse_checkouts(&config, &storage_root, false).expect("narrow"),
+        vec![repo_dir.clone()]
+    );
+    assert!(!repo_dir.join("packages/other").exists());
+    assert!(!repo_cache_contains_subpath(
+        GitBackend::Cli,
+        &repo_dir,
+        "packages/other"
+    ));
+    assert!(repo_cache_contains_subpath(
+        GitBackend::Cli,
+        &repo_dir,
+        "packages/browser"
+    ));
+
+    assert!(narrow_sparse_checkouts(&config, &storage_root, false)
+        .expect("narrow again")
+        .is_empty());
+
+    // A root subpath needs the full tree.
+    config.skills[0].source.subpath = ".".to_string();
+    let summary = sync_sources(&config, temp.path()).expect("full sync");
+    assert_eq!(summary.failed, 0, "failures: {:?}", summary.failures);
+    assert_eq!(repo_cache_sparse_paths(GitBackend::Cli, &repo_dir), None);
+    assert!(repo_dir.join("packages/other/README.txt").exists());
+}
+
+#[test]
+fn sync_of_a_subset_of_skills_keeps_the_other_subpaths_checked_out() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    fs::create_dir_all(origin_repo.join("packages/other")).expect("create other dir");
+    fs::write(origin_repo.join("packages/other/README.txt"), "other\n").expect("write other");
+    run_git(&origin_repo, &["add", "."]);
+    run_git(&origin_repo, &["commit", "-m", "add other"]);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+    let repo_dir = resolve_repo_cache_root(&storage_root, &repo_url, "main");
+
+    // Two configs share the storage root and the cache entry, like a
+    // second config or a single-skill `install` next to existing skills.
+    let mut alpha = test_config(&storage_root, &repo_url, "main");
+    alpha.skills[0].source.subpath = "packages/browser".to_string();
+    let mut beta = test_config(&storage_root, &repo_url, "main");
+    beta.skills[0].id = "beta-skill".to_string();
+    beta.skills[0].source.subpath = "packages/other".to_string();
+
+    for config in [&alpha, &beta, &alpha] {
+        let summary = sync_sources(config, temp.path()).expect("sync");
+        assert_eq!(summary.failed, 0, "failures: {:?}", summary.failures);
+    }
+    assert!(repo_dir.join("packages/browser/README.txt").exists());
+    assert!(repo_dir.join("packages/other/README.txt").exists());
+}
+
+#[test]
+fn embedded_backend_clones_updates_and_checks_out_locked_commits() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    fs::write(origin_repo.join("packages/browser/OLD.txt"), "old\n").expect("write old file");
+    fs::create_dir_all(origin_repo.join("packages/other")).expect("create other");
+    fs::write(origin_repo.join("packages/other/README.txt"), "other\n").expect("write other");
+    run_git(&origin_repo, &["add", "."]);
+    run_git(&origin_repo, &["commit", "-m", "add old"]);
+    let first_commit = head_commit(&origin_repo);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+    let repo_dir = resolve_repo_cache_root(&storage_root, &repo_url, "main");
+
+    let mut config = test_config(&storage_root, &repo_url, "main");
+    config.git.backend = GitBackend::Embedded;
+    config.skills[0].source.subpath = "packages/browser".to_string();
+    let summary = sync_sources(&config, temp.path()).expect("clone");
+    assert_eq!(summary.cloned, 1, "failures: {:?}", summary.failures);
+    assert_eq!(
+        git::rev_parse(GitBackend::Embedded, &repo_dir, "HEAD"),
+        Some(first_commit.clone())
+    );
+    assert_eq!(
+        repo_cache_sparse_paths(GitBackend::Embedded, &repo_dir),
+        Some(BTreeSet::from(["packages/browser".to_string()]))
+    );
+    assert!(!repo_dir.join("packages/other/README.txt").exists());
+    let store = object_store_dir(&repo_dir).expect("object store");
+    assert!(repo_dir.join(".git").is_file(), "expected a worktree");
+    assert_eq!(git::store_worktrees(&store), vec![repo_dir.clone()]);
+
+    fs::write(origin_repo.join("packages/browser/README.txt"), "v2\n").expect("write update");
+    fs::remove_file(origin_repo.join("packages/browser/OLD.txt")).expect("remove old file");
+    run_git(&origin_repo, &["add", "-A"]);
+    run_git(&origin_repo, &["commit", "-m", "update"]);
+    let _ = fs::remove_file(repo_dir.join(".eden-fetched-at"));
+    let summary = sync_sources(&config, temp.path()).expect("update");
+    assert_eq!(summary.updated, 1, "failures: {:?}", summary.failures);
+    assert_eq!(
+        git::rev_parse(GitBackend::Embedded, &repo_dir, "HEAD"),
+        Some(head_commit(&origin_repo))
+    );
+    assert_eq!(
+        fs::read_to_string(repo_dir.join("packages/browser/README.txt")).expect("read"),
+        "v2\n"
+    );
+    assert!(!repo_dir.join("packages/browser/OLD.txt").exists());
+
+    let pins = HashMap::from([(repo_cache_key(&repo_url, "main"), first_commit.clone())]);
+    let runtime = tokio::runtime::Runtime::new().expect("runtime");
+    let summary = runtime
+        .block_on(sync_sources_locked_async(
+            &config,
+            temp.path(),
+            SkillReactor::default(),
+            LockedSync {
+                pins: &pins,
+                allow_network: false,
+            },
+        ))
+        .expect("locked sync");
+    assert_eq!(summary.updated, 1, "failures: {:?}", summary.failures);
+    assert_eq!(
+        git::rev_parse(GitBackend::Embedded, &repo_dir, "HEAD"),
+        Some(first_commit)
+    );
+    assert_eq!(
+        fs::read_to_string(repo_dir.join("packages/browser/OLD.txt")).expect("read"),
+        "old\n"
+    );
+    assert!(!repo_dir.join("packages/other/README.txt").exists());
+
+    git::sparse_checkout_set(GitBackend::Embedded, &repo_dir, None).expect("widen");
+    assert!(repo_dir.join("packages/other/README.txt").exists());
+    let browser = BTreeSet::from(["packages/browser".to_string()]);
+    git::sparse_checkout_set(GitBackend::Embedded, &repo_dir, Some(&browser)).expect("narrow");
+    assert!(!repo_dir.join("packages/other").exists());
+    assert!(repo_dir.join("packages/browser/OLD.txt").exists());
+}
+
+#[test]
+fn embedded_worktrees_share_the_object_store_with_the_cli() {
+    let temp = tempdir().expect("tempdir");
+    let origin_repo = init_origin_repo(temp.path());
+    run_git(&origin_repo, &["tag", "v2.0"]);
+    let storage_root = temp.path().join("storage");
+    let repo_url = as_file_url(&origin_repo);
+
+    let mut config = test_config(&storage_root, &repo_url, "main");
+    config.git.backend = GitBackend::Embedded;
+    let mut tagged = config.skills[0].clone();
+    tagged.id = "tagged-skill".to_string();
+    tagged.source.r#ref = "v2.0".to_string();
+    config.skills.push(tagged);
+    let summary = sync_sources(&config, temp.path()).expect("sync");
+    assert_eq!(summary.cloned, 2, "failures: {:?}", summary.failures);
+
+    let main_dir = resolve_repo_cache_root(&storage_root, &repo_url, "main");
+    let tag_dir = resolve_repo_cache_root(&storage_root, &repo_url, "v2.0");
+    let store = object_store_dir(&main_dir).expect("object store");
+    assert_eq!(object_store_dir(&tag_dir).as_ref(), Some(&store));
+    assert_eq!(
+        git::store_worktrees(&store),
+        vec![main_dir.clone(), tag_dir.clone()]
+    );
+
+    // The CLI sees the branch checked out and keeps it through a gc.
+    git::gc_object_store(&store).expect("gc");
+    for repo_dir in [&main_dir, &tag_dir] {
+        assert_eq!(
+            git::rev_parse(GitBackend::Cli, repo_dir, "HEAD"),
+            Some(head_commit(&origin_repo))
+        );
+        assert!(repo_dir.join("packages/browser/README.txt").exists());
+    }
+    assert!(git::rev_parse(GitBackend::Cli, &store, "refs/heads/main").is_some());
+    run_git(&main_dir, &["status", "--porcelain"]);
+}
+
 fn init_origin_repo(base: &Path) -> PathBuf {
     let repo = base.join("origin-repo");
     fs::create_dir_all(repo.join("packages/browser")).expect("create repo tree");
@@ -205,6 +442,15 @@ fn init_origin_repo(base: &Path) -> PathBuf {
     repo
 }
 
+fn head_commit(repo: &Path) -> String {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo)
+        .output()
+        .expect("spawn git");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
 fn as_file_url(path: &Path) -> String {
     format!("file://{}", path.display())
 }
@@ -214,12 +460,15 @@ fn test_config(storage_root: &Path, repo_url: &str, reference: &str) -> Config {
         version: 1,
         storage_root: storage_root.display().to_string(),
         reactor: ReactorConfig::default(),
+        git: GitConfig::default(),
+        mirrors: Vec::new(),
         skills: vec![SkillConfig {
             id: SKILL_ID.to_string(),
             source: SourceConfig {
                 repo: repo_url.to_string(),
                 subpath: ".".to_string(),
                 r#ref: reference.to_string(),
+                verify_signature: false,
             },
             install: InstallConfig {
                 mode: InstallMode::Symlink,
@@ -229,6 +478,7 @@ fn test_config(storage_root: &Path, repo_url: &str, reference: &str) -> Config {
                 expected_path: None,
                 path: Some(storage_root.join("targets").display().to_string()),
                 environment: "local".to_string(),
+                scope: TargetScope::Global,
             }],
             verify: VerifyConfig {
                 enabled: false,
@@ -237,6 +487,8 @@ fn test_config(storage_root: &Path, repo_url: &str, reference: &str) -> Config {
             safety: SafetyConfig {
                 no_exec_metadata_only: false,
             },
+            requires: BTreeMap::new(),
+            implicit: false,
         }],
     }
 }
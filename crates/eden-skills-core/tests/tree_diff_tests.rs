@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+
+use eden_skills_core::tree_diff::{diff_trees, FileChangeKind};
+use tempfile::tempdir;
+
+fn write_tree(root: &Path) {
+    fs::create_dir_all(root.join("scripts")).expect("create tree");
+    fs::write(root.join("SKILL.md"), "# demo\n\nline one\nline two\n").expect("write skill");
+    fs::write(root.join("scripts").join("run.sh"), "echo hi\n").expect("write script");
+}
+
+#[test]
+fn identical_trees_have_no_changes() {
+    let temp = tempdir().expect("tempdir");
+    let source = temp.path().join("source");
+    let target = temp.path().join("target");
+    write_tree(&source);
+    write_tree(&target);
+    fs::create_dir_all(source.join(".git")).expect("create .git");
+    fs::write(source.join(".git").join("HEAD"), "ref: main\n").expect("write HEAD");
+    fs::write(source.join(".eden-fetched-at"), "1\n").expect("write fetch marker");
+
+    assert!(diff_trees(&source, &target).expect("diff").is_empty());
+}
+
+#[test]
+fn reports_added_removed_and_modified_files_in_path_order() {
+    let temp = tempdir().expect("tempdir");
+    let source = temp.path().join("source");
+    let target = temp.path().join("target");
+    write_tree(&source);
+    write_tree(&target);
+    fs::write(target.join("SKILL.md"), "# demo\n\nline one\nline 2\n").expect("edit skill");
+    fs::write(target.join("notes.md"), "mine\n").expect("add notes");
+    fs::remove_file(target.join("scripts").join("run.sh")).expect("remove script");
+
+    let changes = diff_trees(&source, &target).expect("diff");
+    let summary = changes
+        .iter()
+        .map(|change| (change.path.as_str(), change.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("SKILL.md", FileChangeKind::Modified),
+            ("notes.md", FileChangeKind::Added),
+            ("scripts/run.sh", FileChangeKind::Removed),
+        ]
+    );
+
+    let diff = changes[0].unified_diff.as_deref().expect("text diff");
+    assert!(diff.contains("--- a/SKILL.md"), "diff={diff}");
+    assert!(diff.contains("+++ b/SKILL.md"), "diff={diff}");
+    assert!(diff.contains("-line two\n+line 2\n"), "diff={diff}");
+    assert!(changes[1].unified_diff.is_none());
+}
+
+#[test]
+fn binary_modifications_have_no_unified_diff() {
+    let temp = tempdir().expect("tempdir");
+    let source = temp.path().join("source");
+    let target = temp.path().join("target");
+    fs::create_dir_all(&source).expect("create source");
+    fs::create_dir_all(&target).expect("create target");
+    fs::write(source.join("icon.bin"), [0u8, 1, 2]).expect("write source bin");
+    fs::write(target.join("icon.bin"), [0u8, 1, 3]).expect("write target bin");
+
+    let changes = diff_trees(&source, &target).expect("diff");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, FileChangeKind::Modified);
+    assert!(changes[0].unified_diff.is_none());
+}
+
+#[test]
+fn missing_target_reports_every_source_file_removed() {
+    let temp = tempdir().expect("tempdir");
+    let source = temp.path().join("source");
+    write_tree(&source);
+
+    let changes = diff_trees(&source, &temp.path().join("missing")).expect("diff");
+    assert_eq!(changes.len(), 2);
+    assert!(changes
+        .iter()
+        .all(|change| change.kind == FileChangeKind::Removed));
+}
@@ -0,0 +1,183 @@
+//! Structural checks for one `index/<bucket>/<name>.toml` entry, shared by
+//! `lint` (every entry of a repo) and `publish` (the entry it extends).
+
+use semver::{Version, VersionReq};
+use toml_edit::{DocumentMut, Item};
+
+use eden_skills_core::registry::is_supported_git_url;
+
+/// One `[[versions]]` item that passed validation.
+#[derive(Debug, Clone)]
+pub struct EntryVersion {
+    pub version: Version,
+    pub commit: String,
+}
+
+/// Problems found in an entry, plus the versions that parsed cleanly.
+#[derive(Debug, Default)]
+pub struct EntryReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub versions: Vec<EntryVersion>,
+}
+
+/// Skill names are lowercase ASCII letters, digits, `-`, and `_`, and
+/// start with a letter or digit.
+pub fn is_valid_skill_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// A subpath must stay inside the skill repo.
+pub fn is_valid_subpath(subpath: &str) -> bool {
+    !subpath.starts_with('/')
+        && !subpath.contains('\\')
+        && !subpath.split('/').any(|segment| segment == "..")
+}
+
+/// Check `entry` against the index entry schema of SPEC_REGISTRY,
+/// expecting it to describe `expected_name`.
+pub fn check_entry(entry: &DocumentMut, expected_name: &str) -> EntryReport {
+    let mut report = EntryReport::default();
+    let Some(skill) = entry.get("skill").and_then(Item::as_table_like) else {
+        report.errors.push("missing `[skill]` table".to_string());
+        return report;
+    };
+
+    match skill.get("name").and_then(Item::as_str) {
+        None => report.errors.push("missing `skill.name`".to_string()),
+        Some(name) if name != expected_name => report.errors.push(format!(
+            "`skill.name` is `{name}` but the file is named `{expected_name}.toml`"
+        )),
+        Some(name) if !is_valid_skill_name(name) => report.errors.push(format!(
+            "skill name `{name}` must be lowercase letters, digits, `-`, or `_`"
+        )),
+        Some(_) => {}
+    }
+    match skill.get("repo").and_then(Item::as_str) {
+        None => report.errors.push("missing `skill.repo`".to_string()),
+        Some(repo) if !is_supported_git_url(repo) => report.errors.push(format!(
+            "`skill.repo` `{repo}` is not an https, ssh, or file git URL"
+        )),
+        Some(_) => {}
+    }
+    if let Some(subpath) = skill.get("subpath") {
+        match subpath.as_str() {
+            Some(subpath) if is_valid_subpath(subpath) => {}
+            _ => report
+                .errors
+                .push("`skill.subpath` must be a relative path inside the repo".to_string()),
+        }
+    }
+    for field in ["description", "license"] {
+        if skill.get(field).and_then(Item::as_str).is_none() {
+            report.warnings.push(format!("missing `skill.{field}`"));
+        }
+    }
+
+    let Some(versions) = entry.get("versions").and_then(Item::as_array_of_tables) else {
+        report
+            .errors
+            .push("missing `[[versions]]` entries".to_string());
+        return report;
+    };
+    if versions.is_empty() {
+        report
+            .errors
+            .push("missing `[[versions]]` entries".to_string());
+    }
+    for (idx, item) in versions.iter().enumerate() {
+        let label = format!("versions[{idx}]");
+        let version = match item.get("version").and_then(Item::as_str) {
+            None => {
+                report.errors.push(format!("{label}: missing `version`"));
+                None
+            }
+            Some(raw) => match Version::parse(raw) {
+                Ok(version) => Some(version),
+                Err(err) => {
+                    report
+                        .errors
+                        .push(format!("{label}: invalid version `{raw}`: {err}"));
+                    None
+                }
+            },
+        };
+        if item
+            .get("ref")
+            .and_then(Item::as_str)
+            .is_none_or(|git_ref| git_ref.trim().is_empty())
+        {
+            report.errors.push(format!("{label}: missing `ref`"));
+        }
+        let commit = item.get("commit").and_then(Item::as_str);
+        if !commit.is_some_and(is_full_commit) {
+            report
+                .errors
+                .push(format!("{label}: `commit` must be a full 40-character SHA"));
+        }
+        if item.get("yanked").is_some_and(|yanked| !yanked.is_bool()) {
+            report
+                .errors
+                .push(format!("{label}: `yanked` must be a boolean"));
+        }
+        if let Some(requires) = item.get("requires") {
+            check_requires(requires, expected_name, &label, &mut report);
+        }
+
+        let (Some(version), Some(commit)) = (version, commit) else {
+            continue;
+        };
+        if report
+            .versions
+            .iter()
+            .any(|existing| existing.version == version)
+        {
+            report
+                .errors
+                .push(format!("{label}: duplicate version `{version}`"));
+            continue;
+        }
+        report.versions.push(EntryVersion {
+            version,
+            commit: commit.to_string(),
+        });
+    }
+    report
+}
+
+/// `requires` maps skill names to semver constraints.
+fn check_requires(requires: &Item, skill_name: &str, label: &str, report: &mut EntryReport) {
+    let Some(requires) = requires.as_table_like() else {
+        report.errors.push(format!(
+            "{label}: `requires` must be a table of skill names to version constraints"
+        ));
+        return;
+    };
+    for (name, constraint) in requires.iter() {
+        if !is_valid_skill_name(name) {
+            report
+                .errors
+                .push(format!("{label}: `requires` names invalid skill `{name}`"));
+        } else if name == skill_name {
+            report
+                .errors
+                .push(format!("{label}: a skill cannot require itself"));
+        }
+        match constraint.as_str() {
+            Some(raw) if VersionReq::parse(raw).is_ok() => {}
+            Some(raw) => report.errors.push(format!(
+                "{label}: invalid constraint `{raw}` for required skill `{name}`"
+            )),
+            None => report.errors.push(format!(
+                "{label}: constraint for required skill `{name}` must be a string"
+            )),
+        }
+    }
+}
+
+fn is_full_commit(commit: &str) -> bool {
+    commit.len() == 40 && commit.chars().all(|c| c.is_ascii_hexdigit())
+}
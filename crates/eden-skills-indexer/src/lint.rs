@@ -0,0 +1,170 @@
+//! `lint`: validate a whole registry repo, for CI on the index repo.
+//!
+//! Checks the manifest, that every entry sits in the bucket its name maps
+//! to under the declared `format_version`, and each entry's schema (see
+//! [`crate::entry::check_entry`]). Errors fail the run; warnings only do
+//! with `--deny-warnings`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eden_skills_core::registry::{
+    index_entry_path, read_index_format_version, SUPPORTED_INDEX_FORMAT_VERSIONS,
+};
+use toml_edit::DocumentMut;
+
+use crate::entry::check_entry;
+use crate::relative;
+
+#[derive(Debug, Default)]
+struct Findings {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Findings {
+    fn error(&mut self, location: &str, message: &str) {
+        self.errors += 1;
+        println!("error: {location}: {message}");
+    }
+
+    fn warning(&mut self, location: &str, message: &str) {
+        self.warnings += 1;
+        println!("warning: {location}: {message}");
+    }
+}
+
+pub fn run(registry_root: &Path, deny_warnings: bool) -> Result<(), String> {
+    let mut findings = Findings::default();
+    let format_version = check_manifest(registry_root, &mut findings);
+
+    let mut entries = 0;
+    if let Some(format_version) = format_version {
+        for path in index_files(&registry_root.join("index"))? {
+            let location = relative(registry_root, &path);
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                findings.warning(&location, "not an index entry (expected `<name>.toml`)");
+                continue;
+            }
+            entries += 1;
+            check_entry_file(
+                registry_root,
+                &path,
+                format_version,
+                &location,
+                &mut findings,
+            );
+        }
+    }
+
+    println!(
+        "checked {entries} entries: {} error(s), {} warning(s)",
+        findings.errors, findings.warnings
+    );
+    if findings.errors > 0 || (deny_warnings && findings.warnings > 0) {
+        return Err("registry index failed lint".to_string());
+    }
+    Ok(())
+}
+
+/// The manifest's `format_version` when entries can be checked against it.
+fn check_manifest(registry_root: &Path, findings: &mut Findings) -> Option<i64> {
+    let format_version = match read_index_format_version(registry_root) {
+        Ok(Some(version)) => version,
+        Ok(None) => {
+            findings.error("manifest.toml", "not found");
+            return None;
+        }
+        Err(err) => {
+            findings.error("manifest.toml", &err.to_string());
+            return None;
+        }
+    };
+    if !SUPPORTED_INDEX_FORMAT_VERSIONS.contains(&format_version) {
+        findings.error(
+            "manifest.toml",
+            &format!("unsupported format_version {format_version}"),
+        );
+        return None;
+    }
+    let has_name = fs::read_to_string(registry_root.join("manifest.toml"))
+        .ok()
+        .and_then(|raw| raw.parse::<DocumentMut>().ok())
+        .is_some_and(|manifest| {
+            manifest
+                .get("name")
+                .and_then(|name| name.as_str())
+                .is_some()
+        });
+    if !has_name {
+        findings.error("manifest.toml", "missing `name`");
+    }
+    Some(format_version)
+}
+
+fn check_entry_file(
+    registry_root: &Path,
+    path: &Path,
+    format_version: i64,
+    location: &str,
+    findings: &mut Findings,
+) {
+    let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        findings.error(location, "file name is not valid UTF-8");
+        return;
+    };
+    match index_entry_path(registry_root, name, format_version) {
+        Ok(expected) if expected != path => findings.error(
+            location,
+            &format!(
+                "belongs in `{}` under format_version {format_version}",
+                relative(registry_root, &expected)
+            ),
+        ),
+        Ok(_) => {}
+        Err(err) => findings.error(location, &err.to_string()),
+    }
+
+    let entry = match fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| raw.parse::<DocumentMut>().map_err(|err| err.to_string()))
+    {
+        Ok(entry) => entry,
+        Err(err) => {
+            findings.error(location, &format!("invalid TOML: {err}"));
+            return;
+        }
+    };
+    let report = check_entry(&entry, name);
+    for error in &report.errors {
+        findings.error(location, error);
+    }
+    for warning in &report.warnings {
+        findings.warning(location, warning);
+    }
+}
+
+/// Every file below `index_root`, sorted.
+fn index_files(index_root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![index_root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("`{}`: {err}", dir.display())),
+        };
+        for entry in entries {
+            let path = entry
+                .map_err(|err| format!("`{}`: {err}", dir.display()))?
+                .path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -1,3 +1,118 @@
-fn main() {
-    println!("eden-skills-indexer: pending Phase 3 implementation");
+//! eden-skills-indexer: maintenance tooling for registry index repos.
+//!
+//! Operates on a local clone of a registry repository (the directory that
+//! holds `manifest.toml` and `index/`). `migrate` leaves its changes in the
+//! working tree for the maintainer to review and commit; `publish` commits
+//! on a new branch ready for a pull request; `lint` only reads.
+
+mod entry;
+mod lint;
+mod migrate;
+mod publish;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(name = "eden-skills-indexer")]
+#[command(version)]
+#[command(about = "Maintain eden-skills registry index repositories.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    #[command(about = "Move a format_version 1 index to two-character buckets (format_version 2)")]
+    Migrate {
+        #[arg(
+            value_name = "REGISTRY_DIR",
+            help = "Registry repo containing manifest.toml"
+        )]
+        registry: PathBuf,
+        #[arg(long, help = "List the moves without changing any file")]
+        dry_run: bool,
+    },
+    #[command(
+        about = "Add a skill release to the index on a new branch, ready for a pull request"
+    )]
+    Publish {
+        #[arg(
+            value_name = "REGISTRY_DIR",
+            help = "Registry repo containing manifest.toml"
+        )]
+        registry: PathBuf,
+        #[arg(long, help = "Git URL of the skill repo")]
+        repo: String,
+        #[arg(long = "ref", value_name = "REF", help = "Tag or commit to publish")]
+        git_ref: String,
+        #[arg(
+            long,
+            help = "Release version (default: the ref without a leading `v`)"
+        )]
+        version: Option<String>,
+        #[arg(long, default_value = ".", help = "Skill directory within the repo")]
+        subpath: String,
+        #[arg(long, help = "Branch to create (default: publish/<name>-<version>)")]
+        branch: Option<String>,
+        #[arg(long, help = "Print the resulting entry without changing the registry")]
+        dry_run: bool,
+    },
+    #[command(about = "Validate the manifest and every index entry, for CI")]
+    Lint {
+        #[arg(
+            value_name = "REGISTRY_DIR",
+            help = "Registry repo containing manifest.toml"
+        )]
+        registry: PathBuf,
+        #[arg(long, help = "Fail on warnings as well as errors")]
+        deny_warnings: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Commands::Migrate { registry, dry_run } => migrate::run(&registry, dry_run),
+        Commands::Publish {
+            registry,
+            repo,
+            git_ref,
+            version,
+            subpath,
+            branch,
+            dry_run,
+        } => publish::run(&publish::PublishRequest {
+            registry,
+            repo,
+            git_ref,
+            version,
+            subpath,
+            branch,
+            dry_run,
+        }),
+        Commands::Lint {
+            registry,
+            deny_warnings,
+        } => lint::run(&registry, deny_warnings),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// `path` relative to the registry root, with `/` separators.
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+        .replace('\\', "/")
 }
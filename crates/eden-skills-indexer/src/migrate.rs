@@ -0,0 +1,137 @@
+//! `migrate`: rewrite a format_version 1 index into the format_version 2
+//! layout of ADR-007.
+//!
+//! Every `index/<c>/<name>.toml` moves to `index/<cc>/<name>.toml`, emptied
+//! buckets are removed, and `manifest.toml` is bumped to
+//! `format_version = 2` with the rest of the file left intact. All moves
+//! are checked for collisions before the first file is touched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eden_skills_core::registry::{index_entry_path, read_index_format_version};
+
+use crate::relative;
+
+const TARGET_FORMAT_VERSION: i64 = 2;
+
+pub fn run(registry_root: &Path, dry_run: bool) -> Result<(), String> {
+    let manifest_path = registry_root.join("manifest.toml");
+    match read_index_format_version(registry_root).map_err(|err| err.to_string())? {
+        None => {
+            return Err(format!(
+                "`{}` not found; is this a registry repo?",
+                manifest_path.display()
+            ))
+        }
+        Some(TARGET_FORMAT_VERSION) => {
+            println!("index is already at format_version {TARGET_FORMAT_VERSION}; nothing to do");
+            return Ok(());
+        }
+        Some(1) => {}
+        Some(other) => {
+            return Err(format!(
+                "cannot migrate from format_version {other}; only format_version 1 is supported"
+            ))
+        }
+    }
+
+    let moves = planned_moves(registry_root)?;
+    for (from, to) in &moves {
+        println!(
+            "{} {} -> {}",
+            if dry_run { "would move" } else { "move" },
+            relative(registry_root, from),
+            relative(registry_root, to)
+        );
+    }
+    if dry_run {
+        println!("{} entries would move; no files changed", moves.len());
+        return Ok(());
+    }
+
+    for (from, to) in &moves {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|err| io_error(parent, err))?;
+        }
+        fs::rename(from, to).map_err(|err| io_error(from, err))?;
+    }
+    remove_empty_buckets(&registry_root.join("index"))?;
+    bump_manifest(&manifest_path)?;
+    println!(
+        "moved {} entries; manifest.toml now declares format_version {TARGET_FORMAT_VERSION}",
+        moves.len()
+    );
+    Ok(())
+}
+
+/// `(from, to)` for every entry whose bucket changes, sorted by source.
+fn planned_moves(registry_root: &Path) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let index_root = registry_root.join("index");
+    let mut moves = Vec::new();
+    for bucket in read_dir_sorted(&index_root)? {
+        if !bucket.is_dir() {
+            continue;
+        }
+        for entry in read_dir_sorted(&bucket)? {
+            if entry.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+            let Some(name) = entry.file_stem().and_then(|stem| stem.to_str()) else {
+                return Err(format!("`{}` is not a valid skill name", entry.display()));
+            };
+            let target = index_entry_path(registry_root, name, TARGET_FORMAT_VERSION)
+                .map_err(|err| err.to_string())?;
+            if target != entry {
+                moves.push((entry.clone(), target));
+            }
+        }
+    }
+
+    for (from, to) in &moves {
+        if to.exists() || moves.iter().filter(|(_, other)| other == to).count() > 1 {
+            return Err(format!(
+                "cannot move `{}`: `{}` is already taken",
+                relative(registry_root, from),
+                relative(registry_root, to)
+            ));
+        }
+    }
+    Ok(moves)
+}
+
+fn remove_empty_buckets(index_root: &Path) -> Result<(), String> {
+    for bucket in read_dir_sorted(index_root)? {
+        if bucket.is_dir() && read_dir_sorted(&bucket)?.is_empty() {
+            fs::remove_dir(&bucket).map_err(|err| io_error(&bucket, err))?;
+        }
+    }
+    Ok(())
+}
+
+fn bump_manifest(manifest_path: &Path) -> Result<(), String> {
+    let raw = fs::read_to_string(manifest_path).map_err(|err| io_error(manifest_path, err))?;
+    let mut manifest = raw
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|err| format!("`{}` is invalid TOML: {err}", manifest_path.display()))?;
+    manifest["format_version"] = toml_edit::value(TARGET_FORMAT_VERSION);
+    fs::write(manifest_path, manifest.to_string()).map_err(|err| io_error(manifest_path, err))
+}
+
+fn read_dir_sorted(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(io_error(dir, err)),
+    };
+    let mut paths = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| io_error(dir, err))?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> String {
+    format!("`{}`: {err}", path.display())
+}
@@ -0,0 +1,349 @@
+//! `publish`: add one release of a skill to a local registry clone.
+//!
+//! Reads the skill's `SKILL.md` at the given tag or commit, then writes a
+//! new `index/<bucket>/<name>.toml` entry or appends a `[[versions]]` item
+//! to the existing one. A version that is already listed, is not newer
+//! than every listed version, or whose commit does not descend from the
+//! latest listed version's commit is refused. The change is committed on
+//! a fresh branch of the registry clone, ready to push for review.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use eden_skills_core::discovery::parse_skill_frontmatter;
+use eden_skills_core::git::git_bin;
+use eden_skills_core::registry::{
+    index_entry_path, is_supported_git_url, read_index_format_version,
+    SUPPORTED_INDEX_FORMAT_VERSIONS,
+};
+use semver::Version;
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::entry::{check_entry, is_valid_skill_name, is_valid_subpath};
+use crate::relative;
+
+/// Parameters for `publish`.
+#[derive(Debug)]
+pub struct PublishRequest {
+    pub registry: PathBuf,
+    pub repo: String,
+    pub git_ref: String,
+    pub version: Option<String>,
+    pub subpath: String,
+    pub branch: Option<String>,
+    pub dry_run: bool,
+}
+
+/// A bare, blobless clone of the skill repo, removed on drop.
+struct SkillCheckout {
+    dir: PathBuf,
+}
+
+impl Drop for SkillCheckout {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub fn run(request: &PublishRequest) -> Result<(), String> {
+    let registry = request.registry.as_path();
+    let format_version = match read_index_format_version(registry).map_err(|err| err.to_string())? {
+        Some(version) if SUPPORTED_INDEX_FORMAT_VERSIONS.contains(&version) => version,
+        Some(version) => return Err(format!("unsupported registry format_version {version}")),
+        None => {
+            return Err(format!(
+                "`{}` not found; is this a registry repo?",
+                registry.join("manifest.toml").display()
+            ))
+        }
+    };
+    if !is_supported_git_url(&request.repo) {
+        return Err(format!(
+            "repo `{}` is not an https, ssh, or file git URL",
+            request.repo
+        ));
+    }
+    let subpath = request.subpath.trim_matches('/');
+    let subpath = if subpath.is_empty() { "." } else { subpath };
+    if !is_valid_subpath(subpath) {
+        return Err(format!(
+            "subpath `{subpath}` must be a relative path inside the repo"
+        ));
+    }
+    let version = match &request.version {
+        Some(raw) => {
+            Version::parse(raw).map_err(|err| format!("invalid version `{raw}`: {err}"))?
+        }
+        None => Version::parse(request.git_ref.trim_start_matches('v')).map_err(|_| {
+            format!(
+                "cannot derive a version from ref `{}`; pass --version",
+                request.git_ref
+            )
+        })?,
+    };
+
+    let checkout = clone_skill_repo(&request.repo)?;
+    let commit = git(
+        &checkout.dir,
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("{}^{{commit}}", request.git_ref),
+        ],
+    )
+    .map_err(|_| format!("ref `{}` not found in `{}`", request.git_ref, request.repo))?;
+    let skill_md = if subpath == "." {
+        "SKILL.md".to_string()
+    } else {
+        format!("{subpath}/SKILL.md")
+    };
+    let content = git(&checkout.dir, &["show", &format!("{commit}:{skill_md}")])
+        .map_err(|_| format!("`{skill_md}` not found at `{}`", request.git_ref))?;
+    let frontmatter = parse_skill_frontmatter(&content);
+    let field = |key: &str| {
+        frontmatter
+            .iter()
+            .find(|(field, value)| field == key && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    };
+    let Some(name) = field("name") else {
+        return Err(format!("`{skill_md}` frontmatter has no `name`"));
+    };
+    if !is_valid_skill_name(&name) {
+        return Err(format!(
+            "skill name `{name}` must be lowercase letters, digits, `-`, or `_`"
+        ));
+    }
+    let Some(description) = field("description") else {
+        return Err(format!("`{skill_md}` frontmatter has no `description`"));
+    };
+
+    let entry_path =
+        index_entry_path(registry, &name, format_version).map_err(|err| err.to_string())?;
+    let previous = if entry_path.exists() {
+        Some(
+            fs::read_to_string(&entry_path)
+                .map_err(|err| format!("`{}`: {err}", entry_path.display()))?,
+        )
+    } else {
+        None
+    };
+    let mut entry = if let Some(raw) = &previous {
+        let entry = raw
+            .parse::<DocumentMut>()
+            .map_err(|err| format!("`{}` is invalid TOML: {err}", entry_path.display()))?;
+        check_release_history(
+            &entry,
+            &name,
+            &request.repo,
+            subpath,
+            &version,
+            &commit,
+            &checkout,
+        )?;
+        entry
+    } else {
+        new_entry(
+            &name,
+            &description,
+            field("license"),
+            &request.repo,
+            subpath,
+        )
+    };
+    let mut release = Table::new();
+    release.insert("version", value(version.to_string()));
+    release.insert("ref", value(request.git_ref.as_str()));
+    release.insert("commit", value(commit.as_str()));
+    release.insert("yanked", value(false));
+    entry["versions"]
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or("`versions` must be an array of tables")?
+        .push(release);
+
+    let location = relative(registry, &entry_path);
+    if request.dry_run {
+        println!("would write {location}:\n");
+        print!("{entry}");
+        return Ok(());
+    }
+
+    let branch = request
+        .branch
+        .clone()
+        .unwrap_or_else(|| format!("publish/{name}-{version}"));
+    git(registry, &["checkout", "--quiet", "-b", &branch])?;
+    let message = format!("Publish {name} {version}");
+    if let Err(err) = commit_entry(registry, &entry_path, &location, &entry, &message) {
+        // Put the clone back the way it was: the entry as it was before,
+        // the original branch checked out, and no half-made branch.
+        let _ = git(registry, &["reset", "--quiet", "--", &location]);
+        let _ = match &previous {
+            Some(raw) => fs::write(&entry_path, raw),
+            None => fs::remove_file(&entry_path),
+        };
+        let _ = git(registry, &["checkout", "--quiet", "-"]);
+        let _ = git(registry, &["branch", "--quiet", "-D", &branch]);
+        return Err(err);
+    }
+    println!(
+        "published {name} {version} ({}) to {location} on branch `{branch}`; push it and open a pull request",
+        &commit[..12]
+    );
+    Ok(())
+}
+
+/// Write the entry and commit it on the current branch.
+fn commit_entry(
+    registry: &Path,
+    entry_path: &Path,
+    location: &str,
+    entry: &DocumentMut,
+    message: &str,
+) -> Result<(), String> {
+    if let Some(parent) = entry_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("`{}`: {err}", parent.display()))?;
+    }
+    fs::write(entry_path, entry.to_string())
+        .map_err(|err| format!("`{}`: {err}", entry_path.display()))?;
+    git(registry, &["add", "--", location])?;
+    git(registry, &["commit", "--quiet", "-m", message])?;
+    Ok(())
+}
+
+fn new_entry(
+    name: &str,
+    description: &str,
+    license: Option<String>,
+    repo: &str,
+    subpath: &str,
+) -> DocumentMut {
+    let mut skill = Table::new();
+    skill.insert("name", value(name));
+    skill.insert("description", value(description));
+    skill.insert("repo", value(repo));
+    skill.insert("subpath", value(subpath));
+    if let Some(license) = license {
+        skill.insert("license", value(license));
+    }
+    let mut entry = DocumentMut::new();
+    entry.insert("skill", Item::Table(skill));
+    entry
+}
+
+/// Refuse a release that does not extend the entry's history: the entry
+/// must be valid and for the same source, and the release must be newer
+/// than, and descend from, the latest listed version.
+fn check_release_history(
+    entry: &DocumentMut,
+    name: &str,
+    repo: &str,
+    subpath: &str,
+    version: &Version,
+    commit: &str,
+    checkout: &SkillCheckout,
+) -> Result<(), String> {
+    let report = check_entry(entry, name);
+    if !report.errors.is_empty() {
+        return Err(format!(
+            "existing entry for `{name}` is invalid: {}",
+            report.errors.join("; ")
+        ));
+    }
+    let skill = &entry["skill"];
+    let listed_repo = skill["repo"].as_str().unwrap_or_default();
+    let listed_subpath = skill.get("subpath").and_then(Item::as_str).unwrap_or(".");
+    if listed_repo != repo || listed_subpath.trim_matches('/') != subpath {
+        return Err(format!(
+            "`{name}` is published from `{listed_repo}` (subpath `{listed_subpath}`); refusing to publish it from `{repo}` (subpath `{subpath}`)"
+        ));
+    }
+    if report
+        .versions
+        .iter()
+        .any(|listed| listed.version == *version)
+    {
+        return Err(format!("`{name}` {version} is already published"));
+    }
+    let Some(latest) = report
+        .versions
+        .iter()
+        .max_by(|a, b| a.version.cmp(&b.version))
+    else {
+        return Ok(());
+    };
+    if *version < latest.version {
+        return Err(format!(
+            "`{name}` {version} is older than the latest published version {}",
+            latest.version
+        ));
+    }
+    if git(
+        &checkout.dir,
+        &["cat-file", "-e", &format!("{}^{{commit}}", latest.commit)],
+    )
+    .is_err()
+    {
+        return Err(format!(
+            "commit {} of `{name}` {} is not in `{repo}`; cannot verify history",
+            latest.commit, latest.version
+        ));
+    }
+    if git(
+        &checkout.dir,
+        &["merge-base", "--is-ancestor", &latest.commit, commit],
+    )
+    .is_err()
+    {
+        return Err(format!(
+            "non-monotonic history: {commit} does not descend from {} (`{name}` {})",
+            latest.commit, latest.version
+        ));
+    }
+    Ok(())
+}
+
+fn clone_skill_repo(repo: &str) -> Result<SkillCheckout, String> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let checkout = SkillCheckout {
+        dir: std::env::temp_dir().join(format!(
+            "eden-skills-publish-{}-{nanos}",
+            std::process::id()
+        )),
+    };
+    let output = Command::new(git_bin())
+        .args(["clone", "--quiet", "--bare", "--filter=blob:none", repo])
+        .arg(&checkout.dir)
+        .output()
+        .map_err(|err| format!("failed to run git: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to clone `{repo}`: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(checkout)
+}
+
+/// Run git in `dir` and return its trimmed stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(git_bin())
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|err| format!("failed to run git: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const MANIFEST: &str = "format_version = 2\nname = \"official\"\n";
+
+#[test]
+fn lint_passes_valid_index_and_reports_warnings() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("manifest.toml"), MANIFEST).expect("write manifest");
+    write_entry(root, "go/google-search.toml", &valid_entry("google-search"));
+
+    let output = run_lint(root, &[]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+    let report = stdout(&output);
+    assert!(
+        report.contains("warning: index/go/google-search.toml: missing `skill.license`"),
+        "stdout={report}"
+    );
+    assert!(report.contains("checked 1 entries: 0 error(s), 1 warning(s)"));
+
+    let output = run_lint(root, &["--deny-warnings"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("registry index failed lint"));
+}
+
+#[test]
+fn lint_reports_misplaced_and_malformed_entries() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("manifest.toml"), MANIFEST).expect("write manifest");
+    write_entry(root, "g/git-helper.toml", &valid_entry("git-helper"));
+    write_entry(
+        root,
+        "ba/bad.toml",
+        "[skill]\nname = \"other\"\nrepo = \"ftp://example.com/bad.git\"\n\n[[versions]]\nversion = \"one\"\nref = \"v1\"\ncommit = \"abc\"\nrequires = { k8s-conventions = \"one\" }\n",
+    );
+    write_entry(root, "br/broken.toml", "[skill\n");
+
+    let output = run_lint(root, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = stdout(&output);
+    for expected in [
+        "error: index/g/git-helper.toml: belongs in `index/gi/git-helper.toml` under format_version 2",
+        "error: index/ba/bad.toml: `skill.name` is `other` but the file is named `bad.toml`",
+        "error: index/ba/bad.toml: `skill.repo` `ftp://example.com/bad.git` is not an https, ssh, or file git URL",
+        "error: index/ba/bad.toml: versions[0]: invalid version `one`",
+        "error: index/ba/bad.toml: versions[0]: `commit` must be a full 40-character SHA",
+        "error: index/ba/bad.toml: versions[0]: invalid constraint `one` for required skill `k8s-conventions`",
+        "error: index/br/broken.toml: invalid TOML",
+    ] {
+        assert!(report.contains(expected), "missing `{expected}` in:\n{report}");
+    }
+}
+
+#[test]
+fn lint_rejects_unsupported_manifest() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("manifest.toml"), "format_version = 9\n").expect("write manifest");
+
+    let output = run_lint(root, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stdout(&output).contains("error: manifest.toml: unsupported format_version 9"),
+        "stdout={}",
+        stdout(&output)
+    );
+}
+
+fn valid_entry(name: &str) -> String {
+    format!(
+        "[skill]\nname = \"{name}\"\ndescription = \"Example\"\nrepo = \"https://example.com/{name}.git\"\n\n[[versions]]\nversion = \"1.0.0\"\nref = \"v1.0.0\"\ncommit = \"{}\"\n",
+        "a".repeat(40)
+    )
+}
+
+fn write_entry(root: &Path, relative: &str, content: &str) {
+    let path = root.join("index").join(relative);
+    fs::create_dir_all(path.parent().expect("bucket")).expect("create bucket");
+    fs::write(path, content).expect("write entry");
+}
+
+fn run_lint(registry: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_eden-skills-indexer"))
+        .arg("lint")
+        .arg(registry)
+        .args(args)
+        .output()
+        .expect("run eden-skills-indexer")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use eden_skills_core::registry::{resolve_skill_from_registry_sources, RegistrySource};
+use tempfile::tempdir;
+
+const MANIFEST: &str = "# Official registry\nformat_version = 1\nname = \"official\"\n";
+
+#[test]
+fn migrate_moves_entries_to_two_character_buckets_and_bumps_manifest() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("manifest.toml"), MANIFEST).expect("write manifest");
+    for (bucket, name) in [("g", "google-search"), ("g", "git-helper"), ("x", "x")] {
+        write_entry(root, bucket, name);
+    }
+
+    let output = run_indexer(&["migrate", "--dry-run"], root);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("would move index/g/google-search.toml -> index/go/google-search.toml"),
+        "stdout={stdout}"
+    );
+    assert!(root.join("index/g/google-search.toml").exists());
+
+    let output = run_indexer(&["migrate"], root);
+    assert_success(&output);
+    assert!(root.join("index/go/google-search.toml").exists());
+    assert!(root.join("index/gi/git-helper.toml").exists());
+    assert!(root.join("index/x/x.toml").exists());
+    assert!(!root.join("index/g").exists(), "emptied bucket is removed");
+
+    let manifest = fs::read_to_string(root.join("manifest.toml")).expect("read manifest");
+    assert_eq!(
+        manifest,
+        "# Official registry\nformat_version = 2\nname = \"official\"\n"
+    );
+
+    let resolved = resolve_skill_from_registry_sources(
+        &[RegistrySource {
+            name: "official".to_string(),
+            priority: 100,
+            root: root.to_path_buf(),
+        }],
+        "google-search",
+        None,
+    )
+    .expect("resolve from migrated index");
+    assert_eq!(resolved.version, "1.0.0");
+
+    let output = run_indexer(&["migrate"], root);
+    assert_success(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already at format_version 2"));
+}
+
+#[test]
+fn migrate_refuses_colliding_entries_without_moving_anything() {
+    let temp = tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("manifest.toml"), MANIFEST).expect("write manifest");
+    write_entry(root, "g", "google-search");
+    write_entry(root, "go", "google-search");
+
+    let output = run_indexer(&["migrate"], root);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is already taken"), "stderr={stderr}");
+    assert!(root.join("index/g/google-search.toml").exists());
+    assert!(fs::read_to_string(root.join("manifest.toml"))
+        .expect("read manifest")
+        .contains("format_version = 1"));
+}
+
+fn write_entry(root: &Path, bucket: &str, name: &str) {
+    let dir = root.join("index").join(bucket);
+    fs::create_dir_all(&dir).expect("create bucket");
+    fs::write(
+        dir.join(format!("{name}.toml")),
+        format!(
+            "[skill]\nname = \"{name}\"\nrepo = \"https://example.com/{name}.git\"\n\n[[versions]]\nversion = \"1.0.0\"\nref = \"v1.0.0\"\ncommit = \"{}\"\n",
+            "a".repeat(40)
+        ),
+    )
+    .expect("write entry");
+}
+
+fn run_indexer(args: &[&str], registry: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_eden-skills-indexer"))
+        .args(args)
+        .arg(registry)
+        .output()
+        .expect("run eden-skills-indexer")
+}
+
+fn assert_success(output: &Output) {
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "command should succeed, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
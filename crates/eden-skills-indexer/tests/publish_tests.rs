@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use eden_skills_core::registry::{resolve_skill_from_registry_sources, RegistrySource};
+use tempfile::tempdir;
+
+const SKILL_MD: &str =
+    "---\nname: git-helper\ndescription: Git workflow helpers\nlicense: MIT\n---\n\n# Git helper\n";
+
+#[test]
+fn publish_writes_entry_on_new_branch_and_appends_later_releases() {
+    let temp = tempdir().expect("tempdir");
+    let skill_repo = temp.path().join("skill");
+    let registry = temp.path().join("registry");
+    init_skill_repo(&skill_repo);
+    commit_skill(&skill_repo, "v1.0.0");
+    init_registry(&registry);
+    let repo_url = file_url(&skill_repo);
+
+    let output = run_indexer(
+        &registry,
+        &["publish", "--repo", &repo_url, "--ref", "v1.0.0"],
+    );
+    assert_success(&output);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("on branch `publish/git-helper-1.0.0`"),
+        "stdout={}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!(
+        git(&registry, &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "publish/git-helper-1.0.0"
+    );
+    assert_eq!(git(&registry, &["status", "--porcelain"]), "");
+    let entry = fs::read_to_string(registry.join("index/gi/git-helper.toml")).expect("read entry");
+    assert!(
+        entry.starts_with(&format!(
+            "[skill]\nname = \"git-helper\"\ndescription = \"Git workflow helpers\"\nrepo = \"{repo_url}\"\nsubpath = \".\"\nlicense = \"MIT\"\n\n[[versions]]\nversion = \"1.0.0\"\nref = \"v1.0.0\"\n"
+        )),
+        "entry={entry}"
+    );
+
+    commit_skill(&skill_repo, "v1.1.0");
+    let output = run_indexer(
+        &registry,
+        &[
+            "publish",
+            "--repo",
+            &repo_url,
+            "--ref",
+            "v1.1.0",
+            "--dry-run",
+        ],
+    );
+    assert_success(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("version = \"1.1.0\""));
+    assert_eq!(
+        git(&registry, &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "publish/git-helper-1.0.0",
+        "dry run leaves the registry alone"
+    );
+
+    let output = run_indexer(
+        &registry,
+        &[
+            "publish", "--repo", &repo_url, "--ref", "v1.1.0", "--branch", "release",
+        ],
+    );
+    assert_success(&output);
+    assert_eq!(
+        git(&registry, &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "release"
+    );
+    let resolved = resolve_skill_from_registry_sources(
+        &[RegistrySource {
+            name: "official".to_string(),
+            priority: 100,
+            root: registry.clone(),
+        }],
+        "git-helper",
+        Some("^1.0"),
+    )
+    .expect("resolve published skill");
+    assert_eq!(resolved.version, "1.1.0");
+    assert_eq!(resolved.commit, git(&skill_repo, &["rev-parse", "v1.1.0"]));
+
+    let output = run_indexer(&registry, &["lint", "--deny-warnings"]);
+    assert_success(&output);
+}
+
+#[test]
+fn publish_refuses_duplicate_older_and_non_descendant_versions() {
+    let temp = tempdir().expect("tempdir");
+    let skill_repo = temp.path().join("skill");
+    let registry = temp.path().join("registry");
+    init_skill_repo(&skill_repo);
+    commit_skill(&skill_repo, "v0.9.0");
+    commit_skill(&skill_repo, "v1.0.0");
+    init_registry(&registry);
+    let repo_url = file_url(&skill_repo);
+    assert_success(&run_indexer(
+        &registry,
+        &["publish", "--repo", &repo_url, "--ref", "v1.0.0"],
+    ));
+
+    let cases = [
+        ("v1.0.0", "is already published"),
+        ("v0.9.0", "is older than the latest published version 1.0.0"),
+    ];
+    for (git_ref, expected) in cases {
+        let output = run_indexer(
+            &registry,
+            &["publish", "--repo", &repo_url, "--ref", git_ref],
+        );
+        assert_eq!(output.status.code(), Some(1), "ref={git_ref}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(expected), "stderr={stderr}");
+    }
+
+    git(
+        &skill_repo,
+        &["checkout", "--quiet", "-b", "fork", "v0.9.0"],
+    );
+    commit_skill(&skill_repo, "v2.0.0");
+    let output = run_indexer(
+        &registry,
+        &["publish", "--repo", &repo_url, "--ref", "v2.0.0"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("non-monotonic history"), "stderr={stderr}");
+    assert_eq!(
+        git(&registry, &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "publish/git-helper-1.0.0",
+        "refused releases create no branch"
+    );
+}
+
+#[test]
+fn publish_requires_skill_md_frontmatter() {
+    let temp = tempdir().expect("tempdir");
+    let skill_repo = temp.path().join("skill");
+    let registry = temp.path().join("registry");
+    init_skill_repo(&skill_repo);
+    fs::write(skill_repo.join("SKILL.md"), "# No frontmatter\n").expect("write SKILL.md");
+    git(&skill_repo, &["add", "-A"]);
+    git(&skill_repo, &["commit", "--quiet", "-m", "init"]);
+    git(&skill_repo, &["tag", "v1.0.0"]);
+    init_registry(&registry);
+
+    let output = run_indexer(
+        &registry,
+        &[
+            "publish",
+            "--repo",
+            &file_url(&skill_repo),
+            "--ref",
+            "v1.0.0",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("`SKILL.md` frontmatter has no `name`"),
+        "stderr={stderr}"
+    );
+    assert!(!registry.join("index").exists());
+}
+
+#[test]
+fn publish_restores_the_registry_branch_when_the_commit_fails() {
+    let temp = tempdir().expect("tempdir");
+    let skill_repo = temp.path().join("skill");
+    let registry = temp.path().join("registry");
+    init_skill_repo(&skill_repo);
+    commit_skill(&skill_repo, "v1.0.0");
+    init_registry(&registry);
+    let hooks = temp.path().join("hooks");
+    fs::create_dir_all(&hooks).expect("create hooks dir");
+    fs::write(hooks.join("pre-commit"), "#!/bin/sh\nexit 1\n").expect("write hook");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(hooks.join("pre-commit"), fs::Permissions::from_mode(0o755))
+            .expect("make hook executable");
+    }
+    git(
+        &registry,
+        &["config", "core.hooksPath", &hooks.display().to_string()],
+    );
+
+    let output = run_indexer(
+        &registry,
+        &[
+            "publish",
+            "--repo",
+            &file_url(&skill_repo),
+            "--ref",
+            "v1.0.0",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        git(&registry, &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "main"
+    );
+    assert_eq!(git(&registry, &["branch", "--list", "publish/*"]), "");
+    assert_eq!(git(&registry, &["status", "--porcelain"]), "");
+}
+
+fn init_skill_repo(dir: &Path) {
+    fs::create_dir_all(dir).expect("create skill repo");
+    git(dir, &["init", "--quiet", "--initial-branch", "main"]);
+}
+
+/// Commit a change to the skill and tag it.
+fn commit_skill(dir: &Path, tag: &str) {
+    fs::write(dir.join("SKILL.md"), format!("{SKILL_MD}\nRelease {tag}\n"))
+        .expect("write SKILL.md");
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "--quiet", "-m", tag]);
+    git(dir, &["tag", tag]);
+}
+
+fn init_registry(dir: &Path) {
+    fs::create_dir_all(dir).expect("create registry");
+    fs::write(
+        dir.join("manifest.toml"),
+        "format_version = 2\nname = \"official\"\n",
+    )
+    .expect("write manifest");
+    git(dir, &["init", "--quiet", "--initial-branch", "main"]);
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "--quiet", "-m", "init"]);
+}
+
+fn file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = with_git_identity(Command::new("git"))
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .expect("run git");
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn run_indexer(registry: &Path, args: &[&str]) -> Output {
+    let (command, rest) = args.split_first().expect("subcommand");
+    with_git_identity(Command::new(env!("CARGO_BIN_EXE_eden-skills-indexer")))
+        .arg(command)
+        .arg(registry)
+        .args(rest)
+        .output()
+        .expect("run eden-skills-indexer")
+}
+
+fn with_git_identity(mut command: Command) -> Command {
+    command
+        .env("GIT_AUTHOR_NAME", "Eden Test")
+        .env("GIT_AUTHOR_EMAIL", "eden@example.com")
+        .env("GIT_COMMITTER_NAME", "Eden Test")
+        .env("GIT_COMMITTER_EMAIL", "eden@example.com");
+    command
+}
+
+fn assert_success(output: &Output) {
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "command should succeed, stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}